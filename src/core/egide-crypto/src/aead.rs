@@ -1,12 +1,16 @@
-//! AES-256-GCM authenticated encryption.
+//! AES-256-GCM and ChaCha20-Poly1305 authenticated encryption.
 //!
 //! Provides authenticated encryption with associated data (AEAD) using AES-256-GCM.
 //! This is the primary encryption algorithm used throughout Egide for encrypting secrets.
+//! ChaCha20-Poly1305 ([`encrypt_chacha`], [`decrypt_chacha`]) uses the same key,
+//! nonce and tag sizes and the same `nonce || ciphertext || tag` layout, for
+//! transit keys that select it.
 
 use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Nonce,
 };
+use ring::aead::{Aad, LessSafeKey, Nonce as RingNonce, UnboundKey, CHACHA20_POLY1305};
 use zeroize::Zeroizing;
 
 use crate::error::CryptoError;
@@ -39,6 +43,27 @@ pub fn encrypt(
     key: &[u8],
     plaintext: &[u8],
     associated_data: Option<&[u8]>,
+) -> Result<Vec<u8>, CryptoError> {
+    let nonce_bytes = generate_nonce()?;
+    encrypt_with_nonce(key, &nonce_bytes, plaintext, associated_data)
+}
+
+/// Encrypts plaintext using AES-256-GCM under a caller-chosen nonce.
+///
+/// Same output format as [`encrypt`], so [`decrypt`] opens it. A nonce must
+/// never be used twice under one key for different plaintexts: this exists
+/// for nonces derived from the plaintext itself with
+/// [`crate::kdf::derive_nonce`], never for counters or caller input.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKey`] if `key` is not [`KEY_SIZE`] bytes,
+/// and [`CryptoError::EncryptionFailed`] if the cipher fails.
+pub fn encrypt_with_nonce(
+    key: &[u8],
+    nonce_bytes: &[u8; NONCE_SIZE],
+    plaintext: &[u8],
+    associated_data: Option<&[u8]>,
 ) -> Result<Vec<u8>, CryptoError> {
     if key.len() != KEY_SIZE {
         return Err(CryptoError::InvalidKey(format!(
@@ -51,10 +76,9 @@ pub fn encrypt(
     let cipher =
         Aes256Gcm::new_from_slice(key).map_err(|e| CryptoError::EncryptionFailed(e.to_string()))?;
 
-    let nonce_bytes = generate_nonce()?;
-    // generate_nonce returns [u8; NONCE_SIZE], so this conversion is infallible
-    // and checked at compile time.
-    let nonce = Nonce::from(nonce_bytes);
+    // The nonce is a [u8; NONCE_SIZE], so this conversion is infallible and
+    // checked at compile time.
+    let nonce = Nonce::from(*nonce_bytes);
 
     let ciphertext = match associated_data {
         Some(aad) => cipher
@@ -72,7 +96,7 @@ pub fn encrypt(
     };
 
     let mut result = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
-    result.extend_from_slice(&nonce_bytes);
+    result.extend_from_slice(nonce_bytes);
     result.extend_from_slice(&ciphertext);
 
     Ok(result)
@@ -138,6 +162,110 @@ pub fn decrypt(
     Ok(Zeroizing::new(plaintext))
 }
 
+/// Encrypts plaintext using ChaCha20-Poly1305.
+///
+/// Same output format as [`encrypt`]: `nonce (12 bytes) || ciphertext || tag
+/// (16 bytes)`, under a fresh random nonce.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKey`] if `key` is not [`KEY_SIZE`] bytes,
+/// and [`CryptoError::EncryptionFailed`] if the cipher fails.
+pub fn encrypt_chacha(
+    key: &[u8],
+    plaintext: &[u8],
+    associated_data: Option<&[u8]>,
+) -> Result<Vec<u8>, CryptoError> {
+    let nonce_bytes = generate_nonce()?;
+    encrypt_chacha_with_nonce(key, &nonce_bytes, plaintext, associated_data)
+}
+
+/// Encrypts plaintext using ChaCha20-Poly1305 under a caller-chosen nonce.
+///
+/// The ChaCha20-Poly1305 counterpart of [`encrypt_with_nonce`], with the same
+/// restriction: only for nonces derived from the plaintext itself.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKey`] if `key` is not [`KEY_SIZE`] bytes,
+/// and [`CryptoError::EncryptionFailed`] if the cipher fails.
+pub fn encrypt_chacha_with_nonce(
+    key: &[u8],
+    nonce_bytes: &[u8; NONCE_SIZE],
+    plaintext: &[u8],
+    associated_data: Option<&[u8]>,
+) -> Result<Vec<u8>, CryptoError> {
+    let cipher = chacha_key(key)?;
+
+    // Sealed in place: the buffer holds ciphertext || tag once this returns.
+    let mut sealed = plaintext.to_vec();
+    cipher
+        .seal_in_place_append_tag(
+            RingNonce::assume_unique_for_key(*nonce_bytes),
+            Aad::from(associated_data.unwrap_or_default()),
+            &mut sealed,
+        )
+        .map_err(|_| CryptoError::EncryptionFailed("chacha20-poly1305 seal failed".into()))?;
+
+    let mut result = Vec::with_capacity(NONCE_SIZE + sealed.len());
+    result.extend_from_slice(nonce_bytes);
+    result.extend_from_slice(&sealed);
+
+    Ok(result)
+}
+
+/// Decrypts ciphertext using ChaCha20-Poly1305.
+///
+/// Expects the layout produced by [`encrypt_chacha`].
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKey`] if `key` is not [`KEY_SIZE`] bytes,
+/// [`CryptoError::InvalidInput`] if the ciphertext is too short, and
+/// [`CryptoError::DecryptionFailed`] if authentication fails.
+pub fn decrypt_chacha(
+    key: &[u8],
+    ciphertext: &[u8],
+    associated_data: Option<&[u8]>,
+) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let cipher = chacha_key(key)?;
+
+    if ciphertext.len() < NONCE_SIZE + TAG_SIZE {
+        return Err(CryptoError::InvalidInput(
+            "ciphertext too short".to_string(),
+        ));
+    }
+
+    let nonce = RingNonce::try_assume_unique_for_key(&ciphertext[..NONCE_SIZE])
+        .map_err(|_| CryptoError::InvalidInput("invalid nonce length".to_string()))?;
+    let mut in_out = Zeroizing::new(ciphertext[NONCE_SIZE..].to_vec());
+    let plaintext_len = cipher
+        .open_in_place(
+            nonce,
+            Aad::from(associated_data.unwrap_or_default()),
+            &mut in_out,
+        )
+        .map_err(|_| CryptoError::DecryptionFailed("authentication failed".to_string()))?
+        .len();
+    in_out.truncate(plaintext_len);
+
+    Ok(in_out)
+}
+
+/// Builds a ChaCha20-Poly1305 key, checking its length.
+fn chacha_key(key: &[u8]) -> Result<LessSafeKey, CryptoError> {
+    if key.len() != KEY_SIZE {
+        return Err(CryptoError::InvalidKey(format!(
+            "expected {} bytes, got {}",
+            KEY_SIZE,
+            key.len()
+        )));
+    }
+    let unbound = UnboundKey::new(&CHACHA20_POLY1305, key)
+        .map_err(|_| CryptoError::InvalidKey("invalid chacha20-poly1305 key".into()))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
 #[cfg(test)]
 #[allow(clippy::disallowed_methods)]
 mod tests {
@@ -265,4 +393,44 @@ mod tests {
 
         assert_eq!(&*plaintext, &expected_plaintext[..]);
     }
+
+    #[test]
+    fn test_chacha_roundtrip_and_wrong_aad() {
+        let key = generate_key().unwrap();
+        let ciphertext = encrypt_chacha(&*key, b"secret data", Some(b"aad")).unwrap();
+        assert_eq!(ciphertext.len(), NONCE_SIZE + 11 + TAG_SIZE);
+        assert_eq!(
+            &*decrypt_chacha(&*key, &ciphertext, Some(b"aad")).unwrap(),
+            b"secret data"
+        );
+        assert!(decrypt_chacha(&*key, &ciphertext, Some(b"other")).is_err());
+        // The two ciphers are not interchangeable.
+        assert!(decrypt(&*key, &ciphertext, Some(b"aad")).is_err());
+    }
+
+    /// RFC 8439 section 2.8.2, the `AEAD_CHACHA20_POLY1305` test vector.
+    #[test]
+    fn test_chacha_known_answer_vector_from_rfc8439() {
+        let key: Vec<u8> = (0x80..=0x9f).collect();
+        let nonce: [u8; NONCE_SIZE] = from_hex("070000004041424344454647").try_into().unwrap();
+        let associated_data = from_hex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you \
+only one tip for the future, sunscreen would be it.";
+        let expected = from_hex(concat!(
+            "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6",
+            "3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36",
+            "92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc",
+            "3ff4def08e4b7a9de576d26586cec64b6116",
+            "1ae10b594f09e26a7e902ecbd0600691",
+        ));
+
+        let sealed =
+            encrypt_chacha_with_nonce(&key, &nonce, plaintext, Some(&associated_data)).unwrap();
+        assert_eq!(&sealed[..NONCE_SIZE], &nonce);
+        assert_eq!(&sealed[NONCE_SIZE..], &expected[..]);
+        assert_eq!(
+            &*decrypt_chacha(&key, &sealed, Some(&associated_data)).unwrap(),
+            &plaintext[..]
+        );
+    }
 }
@@ -0,0 +1,143 @@
+//! Salted hash commitments to secret shares.
+//!
+//! A [`Commitment`] lets a holder of a share be checked later without the
+//! verifier keeping the share itself: it stores a random salt and
+//! `SHA-256(domain || salt || share)` over the length-prefixed field encoding.
+//! The salt makes equal shares commit to different values, so a commitment
+//! neither reveals its share nor links two commitments to the same one.
+//!
+//! Shared by the seal layer and any quorum feature that needs per-share
+//! verification.
+
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use crate::error::CryptoError;
+use crate::mac::encode_fields;
+use crate::random::generate_bytes;
+
+/// Size of the random salt in bytes.
+pub const COMMITMENT_SALT_SIZE: usize = 32;
+
+/// Size of the commitment digest in bytes.
+pub const COMMITMENT_DIGEST_SIZE: usize = 32;
+
+/// Size of a serialized [`Commitment`] (salt followed by digest).
+pub const COMMITMENT_SIZE: usize = COMMITMENT_SALT_SIZE + COMMITMENT_DIGEST_SIZE;
+
+/// Domain separation label mixed into every commitment digest.
+const COMMITMENT_DOMAIN: &[u8] = b"egide-share-commitment-v1";
+
+/// A hiding, binding commitment to a secret share.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commitment {
+    salt: [u8; COMMITMENT_SALT_SIZE],
+    digest: [u8; COMMITMENT_DIGEST_SIZE],
+}
+
+impl Commitment {
+    /// Serializes the commitment as salt followed by digest.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; COMMITMENT_SIZE] {
+        let mut out = [0u8; COMMITMENT_SIZE];
+        out[..COMMITMENT_SALT_SIZE].copy_from_slice(&self.salt);
+        out[COMMITMENT_SALT_SIZE..].copy_from_slice(&self.digest);
+        out
+    }
+
+    /// Parses a commitment produced by [`Commitment::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CryptoError::InvalidInput`] if `bytes` is not
+    /// [`COMMITMENT_SIZE`] long.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != COMMITMENT_SIZE {
+            return Err(CryptoError::InvalidInput(format!(
+                "commitment must be {COMMITMENT_SIZE} bytes, got {}",
+                bytes.len()
+            )));
+        }
+        let mut salt = [0u8; COMMITMENT_SALT_SIZE];
+        let mut digest = [0u8; COMMITMENT_DIGEST_SIZE];
+        salt.copy_from_slice(&bytes[..COMMITMENT_SALT_SIZE]);
+        digest.copy_from_slice(&bytes[COMMITMENT_SALT_SIZE..]);
+        Ok(Self { salt, digest })
+    }
+}
+
+/// Commits to `share` under a fresh random salt.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::RandomGenerationFailed`] if the salt cannot be
+/// generated, or [`CryptoError::InvalidInput`] if the share is too long to
+/// encode.
+pub fn commit(share: &[u8]) -> Result<Commitment, CryptoError> {
+    let mut salt = [0u8; COMMITMENT_SALT_SIZE];
+    salt.copy_from_slice(&generate_bytes(COMMITMENT_SALT_SIZE)?);
+    let digest = commitment_digest(&salt, share)?;
+    Ok(Commitment { salt, digest })
+}
+
+/// Returns whether `share` is the value `commitment` was made to.
+///
+/// The digest comparison is constant-time.
+#[must_use]
+pub fn verify_commitment(share: &[u8], commitment: &Commitment) -> bool {
+    commitment_digest(&commitment.salt, share)
+        .is_ok_and(|digest| bool::from(digest.ct_eq(&commitment.digest)))
+}
+
+fn commitment_digest(
+    salt: &[u8; COMMITMENT_SALT_SIZE],
+    share: &[u8],
+) -> Result<[u8; COMMITMENT_DIGEST_SIZE], CryptoError> {
+    let encoded = encode_fields(&[COMMITMENT_DOMAIN, salt, share])?;
+    Ok(Sha256::digest(&encoded).into())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_matches_its_share() {
+        let commitment = commit(b"share-one").unwrap();
+        assert!(verify_commitment(b"share-one", &commitment));
+    }
+
+    #[test]
+    fn test_commitment_rejects_other_shares() {
+        let commitment = commit(b"share-one").unwrap();
+        assert!(!verify_commitment(b"share-two", &commitment));
+        assert!(!verify_commitment(b"share-on", &commitment));
+        assert!(!verify_commitment(b"", &commitment));
+    }
+
+    #[test]
+    fn test_commitment_does_not_reveal_share() {
+        let share = [7u8; 33];
+        let first = commit(&share).unwrap();
+        let second = commit(&share).unwrap();
+
+        // Salted: the same share never commits to the same bytes, and the
+        // digest is not the bare hash of the share.
+        assert_ne!(first, second);
+        assert_ne!(first.digest, <[u8; 32]>::from(Sha256::digest(share)));
+        assert!(!first
+            .to_bytes()
+            .windows(share.len())
+            .any(|window| window == share));
+    }
+
+    #[test]
+    fn test_commitment_round_trips_through_bytes() {
+        let commitment = commit(b"share").unwrap();
+        let parsed = Commitment::from_bytes(&commitment.to_bytes()).unwrap();
+        assert_eq!(parsed, commitment);
+        assert!(verify_commitment(b"share", &parsed));
+        assert!(Commitment::from_bytes(&[0u8; 63]).is_err());
+    }
+}
@@ -0,0 +1,302 @@
+//! Timing-safe hex encoding, and hex and base64 decoding, for sensitive
+//! material.
+//!
+//! Unseal shares and tokens arrive as text. General-purpose codecs look up
+//! each character in a table or branch on its value, which leaks the secret
+//! through timing and cache state. The codecs here map every character with
+//! branch-free arithmetic and only check the accumulated validity once the
+//! whole input has been read, so their timing depends on the input length and
+//! nothing else. Error messages never echo the offending character.
+//!
+//! Decoded bytes and encoded text are returned in [`Zeroizing`] buffers. The
+//! length of the input is not treated as secret. This is the one hex codec of
+//! the workspace: engines and the seal go through it rather than keeping
+//! their own.
+
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::CryptoError;
+
+/// Encodes bytes as lowercase hex without branching on or indexing by them.
+#[must_use]
+pub fn encode_hex(bytes: &[u8]) -> Zeroizing<String> {
+    let mut out = Zeroizing::new(String::with_capacity(bytes.len() * 2));
+    for &byte in bytes {
+        out.push(char::from(hex_char(byte >> 4)));
+        out.push(char::from(hex_char(byte & 0x0f)));
+    }
+    out
+}
+
+/// Decodes a hex string (either case) without branching on its contents.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if the input has an odd length or
+/// contains a character that is not a hex digit.
+pub fn decode_hex(input: &str) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let mut out = Zeroizing::new(vec![0u8; input.len() / 2]);
+    decode_hex_into(input, &mut out)?;
+    Ok(out)
+}
+
+/// Decodes a hex string into `out` and returns the number of bytes written.
+///
+/// The input length is checked against the buffer before anything is
+/// decoded, so an oversized input is rejected without allocating.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if the input has an odd length,
+/// decodes to more than `out.len()` bytes, or contains a character that is
+/// not a hex digit. On error the written prefix of `out` is zeroized.
+pub fn decode_hex_into(input: &str, out: &mut [u8]) -> Result<usize, CryptoError> {
+    let bytes = input.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return Err(CryptoError::InvalidInput("odd length hex string".into()));
+    }
+    let len = bytes.len() / 2;
+    if len > out.len() {
+        return Err(CryptoError::InvalidInput(format!(
+            "hex string decodes to more than {} bytes",
+            out.len()
+        )));
+    }
+
+    let mut invalid = 0u8;
+    for (slot, pair) in out.iter_mut().zip(bytes.chunks_exact(2)) {
+        let (high, high_invalid) = hex_digit(pair[0]);
+        let (low, low_invalid) = hex_digit(pair[1]);
+        invalid |= high_invalid | low_invalid;
+        *slot = (high << 4) | low;
+    }
+
+    if invalid != 0 {
+        out[..len].zeroize();
+        return Err(CryptoError::InvalidInput("invalid hex digit".into()));
+    }
+    Ok(len)
+}
+
+/// Decodes a hex string of exactly `N` bytes into a fixed-size buffer.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if the input does not encode exactly
+/// `N` bytes or is not valid hex.
+pub fn decode_fixed_len<const N: usize>(input: &str) -> Result<Zeroizing<[u8; N]>, CryptoError> {
+    if input.len() != N * 2 {
+        return Err(CryptoError::InvalidInput(format!(
+            "expected {} hex characters, got {}",
+            N * 2,
+            input.len()
+        )));
+    }
+    let mut out = Zeroizing::new([0u8; N]);
+    decode_hex_into(input, &mut out[..])?;
+    Ok(out)
+}
+
+/// Decodes standard, padded base64 (RFC 4648 §4) without branching on its
+/// contents.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if the input length is not a multiple
+/// of four, a character lies outside the alphabet, or the padding is not
+/// canonical.
+pub fn decode_base64(input: &str) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let bytes = input.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return Err(CryptoError::InvalidInput(
+            "base64 length must be a multiple of 4".into(),
+        ));
+    }
+    let padding = bytes
+        .iter()
+        .rev()
+        .take(2)
+        .take_while(|&&b| b == b'=')
+        .count();
+    let body = &bytes[..bytes.len() - padding];
+
+    let mut out = Zeroizing::new(Vec::with_capacity(body.len() * 3 / 4));
+    let mut invalid = 0u8;
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    for &c in body {
+        let (value, char_invalid) = base64_sextet(c);
+        invalid |= char_invalid;
+        acc = (acc << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push(low_byte(acc >> bits));
+        }
+        acc &= (1 << bits) - 1;
+    }
+
+    // Bits left over after the last full byte must be zero, otherwise two
+    // distinct encodings would decode to the same bytes.
+    if invalid != 0 || acc != 0 {
+        return Err(CryptoError::InvalidInput("invalid base64 encoding".into()));
+    }
+    Ok(out)
+}
+
+/// Returns the lowercase hex digit of a nibble.
+fn hex_char(nibble: u8) -> u8 {
+    let n = i32::from(nibble);
+    // 10..16 make `9 - n` negative, adding the 39 that separates `:` from `a`.
+    low_byte_i32(n + 0x30 + (((9 - n) >> 8) & 0x27))
+}
+
+/// Returns the value of a hex digit and a non-zero flag if it is not one.
+fn hex_digit(c: u8) -> (u8, u8) {
+    let c = i32::from(c);
+    // 0..=9: `c ^ 0x30` lands in 0..10.
+    let num = c ^ 0x30;
+    let num_mask = ((num - 10) >> 8) & 0xff;
+    // a..=f / A..=F: clearing the case bit and subtracting 55 lands in 10..16.
+    let alpha = (c & !0x20) - 55;
+    let alpha_mask = (((alpha - 10) ^ (alpha - 16)) >> 8) & 0xff;
+    let value = (num_mask & num) | (alpha_mask & alpha);
+    (
+        low_byte_i32(value & 0x0f),
+        low_byte_i32(!(num_mask | alpha_mask) & 0xff),
+    )
+}
+
+/// Returns the value of a base64 character and a non-zero flag if it is not one.
+fn base64_sextet(c: u8) -> (u8, u8) {
+    let c = i32::from(c);
+    let upper = range_mask(c, b'A', b'Z');
+    let lower = range_mask(c, b'a', b'z');
+    let digit = range_mask(c, b'0', b'9');
+    let plus = range_mask(c, b'+', b'+');
+    let slash = range_mask(c, b'/', b'/');
+    let value = (upper & (c - 65))
+        | (lower & (c - 71))
+        | (digit & (c + 4))
+        | (plus & 0x3e)
+        | (slash & 0x3f);
+    let valid = upper | lower | digit | plus | slash;
+    (low_byte_i32(value & 0x3f), low_byte_i32(!valid & 0xff))
+}
+
+/// Returns all ones if `lo <= c <= hi`, zero otherwise, for `c` in `0..256`.
+fn range_mask(c: i32, lo: u8, hi: u8) -> i32 {
+    ((i32::from(lo) - 1 - c) & (c - i32::from(hi) - 1)) >> 8
+}
+
+// Both helpers are only ever called on values already masked to 8 bits.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn low_byte_i32(value: i32) -> u8 {
+    value as u8
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn low_byte(value: u32) -> u8 {
+    value as u8
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_hex_matches_reference() {
+        let all: Vec<u8> = (0..=255).collect();
+        let lower = hex::encode(&all);
+        assert_eq!(&*decode_hex(&lower).unwrap(), &all);
+        assert_eq!(&*decode_hex(&lower.to_uppercase()).unwrap(), &all);
+        assert!(decode_hex("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_encode_hex_matches_reference_and_round_trips() {
+        let all: Vec<u8> = (0..=255).collect();
+        let encoded = encode_hex(&all);
+        assert_eq!(encoded.as_str(), hex::encode(&all));
+        assert_eq!(&*decode_hex(&encoded).unwrap(), &all);
+        assert!(encode_hex(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_decode_hex_rejects_malformed_input() {
+        for bad in ["abc", "zz", "0g", "0\u{e9}0", "  ", "0x"] {
+            assert!(
+                matches!(decode_hex(bad), Err(CryptoError::InvalidInput(_))),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_hex_error_does_not_echo_input() {
+        let err = decode_hex("00s3cr3t").unwrap_err().to_string();
+        assert!(!err.contains('s'), "{err}");
+    }
+
+    #[test]
+    fn test_decode_hex_into_bounds_and_zeroizes_on_error() {
+        let mut buf = [0u8; 4];
+        assert_eq!(decode_hex_into("0102", &mut buf).unwrap(), 2);
+        assert_eq!(buf, [1, 2, 0, 0]);
+
+        assert!(decode_hex_into("0102030405", &mut buf).is_err());
+
+        let mut buf = [0u8; 4];
+        assert!(decode_hex_into("aabbccgg", &mut buf).is_err());
+        assert_eq!(buf, [0; 4]);
+    }
+
+    #[test]
+    fn test_decode_fixed_len_accepts_exact_length() {
+        let decoded = decode_fixed_len::<4>("deadBEEF").unwrap();
+        assert_eq!(*decoded, [0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn test_decode_fixed_len_rejects_wrong_length() {
+        assert!(decode_fixed_len::<4>("deadbe").is_err());
+        assert!(decode_fixed_len::<4>("deadbeef00").is_err());
+        assert!(decode_fixed_len::<4>("").is_err());
+        assert!(decode_fixed_len::<4>("deadbeeg").is_err());
+    }
+
+    #[test]
+    fn test_decode_fixed_len_buffer_is_zeroizing() {
+        // The return type is what guarantees wiping on drop; check that
+        // zeroizing it clears the decoded bytes.
+        let mut decoded: Zeroizing<[u8; 4]> = decode_fixed_len("01020304").unwrap();
+        decoded.zeroize();
+        assert_eq!(*decoded, [0; 4]);
+    }
+
+    #[test]
+    fn test_decode_base64_matches_reference() {
+        let cases: [(&str, &[u8]); 6] = [
+            ("", b""),
+            ("Zg==", b"f"),
+            ("Zm8=", b"fo"),
+            ("Zm9v", b"foo"),
+            ("Zm9vYmFy", b"foobar"),
+            ("+/+/", &[0xfb, 0xff, 0xbf]),
+        ];
+        for (encoded, expected) in cases {
+            assert_eq!(&*decode_base64(encoded).unwrap(), expected, "{encoded}");
+        }
+    }
+
+    #[test]
+    fn test_decode_base64_rejects_malformed_input() {
+        for bad in ["Zg", "Zg=", "Z===", "Zh==", "Zm9v!A==", "Zm-v", "=Zm9"] {
+            assert!(
+                matches!(decode_base64(bad), Err(CryptoError::InvalidInput(_))),
+                "{bad:?}"
+            );
+        }
+    }
+}
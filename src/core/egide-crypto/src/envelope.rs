@@ -0,0 +1,235 @@
+//! Versioned, self-describing header for binary ciphertext formats.
+//!
+//! Every binary layout built from these primitives starts with an
+//! [`EnvelopeHeader`], so a reader can tell which layout follows before
+//! touching it, and a later layout (a new algorithm, SIV, padding) never
+//! reads ambiguously as an older one:
+//!
+//! ```text
+//! magic "EGDE" (4) || format_version (u32, big-endian) || algo_id (1) || flags (1)
+//! ```
+//!
+//! Decoding is strict: an unknown format version, algorithm or flag bit is
+//! rejected rather than guessed at. [`seal`] and [`open`] pair the header
+//! with AES-256-GCM and bind it into the associated data, so the header of
+//! an envelope cannot be rewritten without decryption failing.
+
+use zeroize::Zeroizing;
+
+use crate::aead;
+use crate::error::CryptoError;
+use crate::mac::encode_fields;
+
+/// Magic bytes opening every envelope.
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"EGDE";
+
+/// Format version written by this build, and the only one it reads.
+pub const ENVELOPE_FORMAT_VERSION: u32 = 1;
+
+/// Size of an encoded [`EnvelopeHeader`] in bytes.
+pub const HEADER_SIZE: usize = 10;
+
+/// Algorithm of the payload following an [`EnvelopeHeader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AlgorithmId {
+    /// AES-256-GCM: `nonce || ciphertext || tag`.
+    Aes256Gcm = 1,
+    /// ECIES over X25519 with HKDF-SHA256 and AES-256-GCM, as produced by
+    /// [`crate::wrapping::KeyWrapper`].
+    X25519Aes256Gcm = 2,
+}
+
+impl TryFrom<u8> for AlgorithmId {
+    type Error = CryptoError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Aes256Gcm),
+            2 => Ok(Self::X25519Aes256Gcm),
+            other => Err(CryptoError::InvalidInput(format!(
+                "unknown envelope algorithm id {other}"
+            ))),
+        }
+    }
+}
+
+/// Header leading a binary envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    /// Layout version of the envelope.
+    pub format_version: u32,
+    /// Algorithm of the payload.
+    pub algorithm: AlgorithmId,
+    /// Layout flags; none are defined by the current format version.
+    pub flags: u8,
+}
+
+impl EnvelopeHeader {
+    /// Creates a header for `algorithm` in the current format version.
+    #[must_use]
+    pub fn new(algorithm: AlgorithmId) -> Self {
+        Self {
+            format_version: ENVELOPE_FORMAT_VERSION,
+            algorithm,
+            flags: 0,
+        }
+    }
+
+    /// Encodes the header.
+    #[must_use]
+    pub fn encode(&self) -> [u8; HEADER_SIZE] {
+        let mut out = [0u8; HEADER_SIZE];
+        out[..4].copy_from_slice(&ENVELOPE_MAGIC);
+        out[4..8].copy_from_slice(&self.format_version.to_be_bytes());
+        out[8] = self.algorithm as u8;
+        out[9] = self.flags;
+        out
+    }
+
+    /// Decodes the header at the start of `bytes` and returns it with the
+    /// payload that follows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CryptoError::InvalidInput`] if `bytes` is shorter than a
+    /// header, does not start with [`ENVELOPE_MAGIC`], or names a format
+    /// version, algorithm or flag this build does not know.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, &[u8]), CryptoError> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(CryptoError::InvalidInput("envelope too short".into()));
+        }
+        let (header, payload) = bytes.split_at(HEADER_SIZE);
+        if header[..4] != ENVELOPE_MAGIC {
+            return Err(CryptoError::InvalidInput("not an envelope".into()));
+        }
+
+        let mut version = [0u8; 4];
+        version.copy_from_slice(&header[4..8]);
+        let format_version = u32::from_be_bytes(version);
+        if format_version != ENVELOPE_FORMAT_VERSION {
+            return Err(CryptoError::InvalidInput(format!(
+                "unsupported envelope format version {format_version}"
+            )));
+        }
+
+        let algorithm = AlgorithmId::try_from(header[8])?;
+        // Format version 1 defines no flags.
+        let flags = header[9];
+        if flags != 0 {
+            return Err(CryptoError::InvalidInput(format!(
+                "unknown envelope flags {flags:#04x}"
+            )));
+        }
+
+        Ok((
+            Self {
+                format_version,
+                algorithm,
+                flags,
+            },
+            payload,
+        ))
+    }
+}
+
+/// Encrypts `plaintext` with AES-256-GCM into a self-describing envelope.
+///
+/// The header is bound into the associated data along with `associated_data`.
+///
+/// # Errors
+///
+/// Propagates [`aead::encrypt`] failures.
+pub fn seal(key: &[u8], plaintext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let header = EnvelopeHeader::new(AlgorithmId::Aes256Gcm).encode();
+    let aad = encode_fields(&[&header[..], associated_data])?;
+    let sealed = aead::encrypt(key, plaintext, Some(&aad[..]))?;
+
+    let mut out = Vec::with_capacity(HEADER_SIZE + sealed.len());
+    out.extend_from_slice(&header);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+/// Decrypts an envelope produced by [`seal`].
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if the header does not decode or
+/// names another algorithm, and propagates [`aead::decrypt`] failures.
+pub fn open(
+    key: &[u8],
+    envelope: &[u8],
+    associated_data: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let (header, payload) = EnvelopeHeader::decode(envelope)?;
+    if header.algorithm != AlgorithmId::Aes256Gcm {
+        return Err(CryptoError::InvalidInput(format!(
+            "expected an AES-256-GCM envelope, got {:?}",
+            header.algorithm
+        )));
+    }
+    let aad = encode_fields(&[&header.encode()[..], associated_data])?;
+    aead::decrypt(key, payload, Some(&aad[..]))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_round_trips() {
+        let header = EnvelopeHeader::new(AlgorithmId::X25519Aes256Gcm);
+        let mut bytes = header.encode().to_vec();
+        bytes.extend_from_slice(b"payload");
+
+        let (decoded, payload) = EnvelopeHeader::decode(&bytes).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(decoded.format_version, ENVELOPE_FORMAT_VERSION);
+        assert_eq!(payload, b"payload");
+    }
+
+    #[test]
+    fn test_rejects_unknown_format_version() {
+        let mut bytes = EnvelopeHeader::new(AlgorithmId::Aes256Gcm).encode();
+        bytes[4..8].copy_from_slice(&2u32.to_be_bytes());
+        assert!(matches!(
+            EnvelopeHeader::decode(&bytes),
+            Err(CryptoError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_bad_magic_algorithm_and_flags() {
+        let header = EnvelopeHeader::new(AlgorithmId::Aes256Gcm).encode();
+
+        let mut bad_magic = header;
+        bad_magic[0] = b'X';
+        let mut bad_algorithm = header;
+        bad_algorithm[8] = 0xff;
+        let mut bad_flags = header;
+        bad_flags[9] = 0x01;
+
+        for bytes in [
+            &bad_magic[..],
+            &bad_algorithm[..],
+            &bad_flags[..],
+            &header[..9],
+        ] {
+            assert!(EnvelopeHeader::decode(bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn test_seal_open_binds_header() {
+        let key = [7u8; aead::KEY_SIZE];
+        let mut envelope = seal(&key, b"secret", b"context").unwrap();
+        assert_eq!(&*open(&key, &envelope, b"context").unwrap(), b"secret");
+        assert!(open(&key, &envelope, b"other").is_err());
+
+        // A rewritten header is refused.
+        envelope[8] = AlgorithmId::X25519Aes256Gcm as u8;
+        assert!(open(&key, &envelope, b"context").is_err());
+    }
+}
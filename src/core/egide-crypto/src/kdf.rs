@@ -1,15 +1,20 @@
 //! Key derivation functions.
 //!
 //! Provides HKDF (HMAC-based Key Derivation Function) as specified in RFC 5869.
-//! Used to derive encryption keys from master secrets.
+//! Used to derive encryption keys from master secrets, and deterministic AEAD
+//! nonces for convergent encryption.
 
 use hkdf::Hkdf;
+use hmac::{Hmac, KeyInit, Mac};
 use sha2::Sha256;
 use zeroize::Zeroizing;
 
-use crate::aead::KEY_SIZE;
+use crate::aead::{KEY_SIZE, NONCE_SIZE};
 use crate::error::CryptoError;
 
+/// HKDF `info` for the subkey [`derive_nonce`] computes nonces under.
+const NONCE_SUBKEY_INFO: &[u8] = b"egide-nonce-v1";
+
 /// Derives a key using HKDF-SHA256.
 ///
 /// HKDF is a two-step process:
@@ -96,6 +101,136 @@ pub fn derive_multiple_keys(
         .collect()
 }
 
+/// Derives a deterministic AES-GCM nonce from a key, a plaintext and a context.
+///
+/// For convergent encryption, where encrypting the same plaintext under the
+/// same key and context must yield the same ciphertext. The nonce is
+/// HMAC-SHA256, truncated to [`NONCE_SIZE`] bytes, over the length-prefixed
+/// `context` followed by `plaintext`, keyed with an HKDF subkey of `key`.
+/// It is reproducible by the key holder but unpredictable without the key,
+/// and the subkey keeps the MAC key distinct from the encryption key.
+///
+/// Two different plaintexts only share a nonce with probability 2^-96, so the
+/// nonce never repeats across distinct messages in practice. Equal plaintexts
+/// still produce equal ciphertexts, which is the intended leak of convergent
+/// encryption. Use random nonces wherever that equality must stay hidden.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::KeyGenerationFailed`] if the subkey derivation
+/// fails, or [`CryptoError::SignatureFailed`] if the MAC cannot be keyed.
+/// Neither happens for well-formed inputs.
+pub fn derive_nonce(
+    key: &[u8],
+    plaintext: &[u8],
+    context: &[u8],
+) -> Result<[u8; NONCE_SIZE], CryptoError> {
+    let subkey = derive_key(key, None, NONCE_SUBKEY_INFO, KEY_SIZE)?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(&subkey)
+        .map_err(|e| CryptoError::SignatureFailed(e.to_string()))?;
+    // The context length prefix keeps the context/plaintext boundary
+    // unambiguous; the plaintext runs to the end.
+    mac.update(&(context.len() as u64).to_be_bytes());
+    mac.update(context);
+    mac.update(plaintext);
+    let tag = mac.finalize().into_bytes();
+
+    let mut nonce = [0u8; NONCE_SIZE];
+    nonce.copy_from_slice(&tag[..NONCE_SIZE]);
+    Ok(nonce)
+}
+
+/// Derives the key a tenant database is encrypted with at rest.
+///
+/// Each tenant file gets its own key under the `egide-storage` domain, so the
+/// page cipher never shares a key with secret or transit payloads derived from
+/// the same master key.
+pub fn derive_storage_key(
+    master_key: &[u8],
+    tenant: &str,
+) -> Result<Zeroizing<[u8; KEY_SIZE]>, CryptoError> {
+    KdfContext::new("egide-storage")
+        .field(tenant)
+        .derive_encryption_key(master_key)
+}
+
+/// A versioned HKDF `info` context: `{domain}-v{version}:{field}:{field}...`.
+///
+/// Every derivation context in Egide follows this shape (for example
+/// `egide-transit-v1:{name}:{version}`). Building it through `KdfContext`
+/// rather than a hardcoded format string makes the version an explicit,
+/// storable value: a writer derives under the current version and records it
+/// next to the ciphertext, and a reader rebuilds the context from the recorded
+/// version. A new derivation scheme can then become the default for new
+/// writes while existing items keep decrypting, with no mass re-encryption.
+///
+/// Fields are joined with `:` and are not escaped. Callers must only pass
+/// fields whose alphabet excludes `:` (key names, paths, numbers, hex), or
+/// whose position is otherwise fixed, so the encoding stays unambiguous.
+///
+/// ```
+/// use egide_crypto::kdf::KdfContext;
+///
+/// let ctx = KdfContext::new("egide-transit").version(2).field("app").field("1");
+/// assert_eq!(ctx.info(), b"egide-transit-v2:app:1");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KdfContext {
+    domain: String,
+    version: u32,
+    fields: Vec<String>,
+}
+
+impl KdfContext {
+    /// Starts a context for `domain` at version 1 with no fields.
+    #[must_use]
+    pub fn new(domain: impl Into<String>) -> Self {
+        Self {
+            domain: domain.into(),
+            version: 1,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Sets the derivation version.
+    #[must_use]
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Appends a field to the context.
+    #[must_use]
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.fields.push(field.into());
+        self
+    }
+
+    /// Returns the derivation version.
+    #[must_use]
+    pub fn derivation_version(&self) -> u32 {
+        self.version
+    }
+
+    /// Encodes the context as the HKDF `info` bytes.
+    #[must_use]
+    pub fn info(&self) -> Vec<u8> {
+        let mut info = format!("{}-v{}:", self.domain, self.version);
+        info.push_str(&self.fields.join(":"));
+        info.into_bytes()
+    }
+
+    /// Derives an AES-256 key from `master_key` under this context.
+    ///
+    /// Equivalent to [`derive_encryption_key`] with [`Self::info`].
+    pub fn derive_encryption_key(
+        &self,
+        master_key: &[u8],
+    ) -> Result<Zeroizing<[u8; KEY_SIZE]>, CryptoError> {
+        derive_encryption_key(master_key, &self.info())
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::disallowed_methods)]
 mod tests {
@@ -110,6 +245,38 @@ mod tests {
         assert_eq!(key.len(), 32);
     }
 
+    #[test]
+    fn test_derive_nonce_is_deterministic_and_input_bound() {
+        let key = [3u8; 32];
+        let nonce = derive_nonce(&key, b"plaintext", b"ctx").unwrap();
+        assert_eq!(nonce.len(), 12);
+        assert_eq!(nonce, derive_nonce(&key, b"plaintext", b"ctx").unwrap());
+
+        assert_ne!(nonce, derive_nonce(&key, b"plaintexu", b"ctx").unwrap());
+        assert_ne!(nonce, derive_nonce(&key, b"plaintext", b"ctx2").unwrap());
+        assert_ne!(
+            nonce,
+            derive_nonce(&[4u8; 32], b"plaintext", b"ctx").unwrap()
+        );
+        // Moving bytes across the context/plaintext boundary changes the nonce.
+        assert_ne!(
+            derive_nonce(&key, b"bc", b"a").unwrap(),
+            derive_nonce(&key, b"c", b"ab").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_derive_storage_key_is_per_tenant() {
+        let master = [1u8; 32];
+        let a = derive_storage_key(&master, "alpha").unwrap();
+        assert_eq!(*a, *derive_storage_key(&master, "alpha").unwrap());
+        assert_ne!(*a, *derive_storage_key(&master, "beta").unwrap());
+        assert_ne!(
+            *a,
+            *derive_encryption_key(&master, b"egide-secrets-v1:alpha").unwrap()
+        );
+    }
+
     #[test]
     fn test_derive_key_with_salt() {
         let ikm = b"input key material";
@@ -206,4 +373,29 @@ mod tests {
 
         assert_eq!(&*okm, &expected);
     }
+
+    #[test]
+    fn test_kdf_context_matches_hardcoded_info() {
+        let ctx = KdfContext::new("egide-transit").field("my-key").field("3");
+        assert_eq!(ctx.derivation_version(), 1);
+        assert_eq!(ctx.info(), b"egide-transit-v1:my-key:3");
+
+        let master = b"master secret";
+        assert_eq!(
+            *ctx.derive_encryption_key(master).unwrap(),
+            *derive_encryption_key(master, b"egide-transit-v1:my-key:3").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_kdf_context_version_changes_key() {
+        let master = b"master secret";
+        let v1 = KdfContext::new("egide-transit").field("k");
+        let v2 = v1.clone().version(2);
+        assert_eq!(v2.info(), b"egide-transit-v2:k");
+        assert_ne!(
+            *v1.derive_encryption_key(master).unwrap(),
+            *v2.derive_encryption_key(master).unwrap()
+        );
+    }
 }
@@ -3,9 +3,14 @@
 //! Core cryptographic primitives for Egide.
 //!
 //! This crate provides low-level cryptographic operations including:
-//! - Symmetric encryption (AES-256-GCM)
+//! - Symmetric encryption (AES-256-GCM, ChaCha20-Poly1305)
 //! - Key derivation (HKDF-SHA256)
 //! - Secure random generation (OS CSPRNG)
+//! - Ed25519 signature verification
+//! - Salted hash commitments to secret shares
+//! - Key wrapping to an X25519 public key (ECIES)
+//! - A versioned, self-describing header for binary ciphertext formats
+//! - Timing-safe hex and base64 decoding of secret text
 //! - Memory-safe key types with automatic zeroization
 //!
 //! ## Security
@@ -31,11 +36,16 @@
 #![forbid(unsafe_code)]
 
 pub mod aead;
+pub mod commitment;
+pub mod encoding;
+pub mod envelope;
 pub mod error;
 pub mod kdf;
 pub mod keys;
 pub mod mac;
 pub mod random;
+pub mod signing;
+pub mod wrapping;
 
 pub use error::CryptoError;
 pub use keys::{MasterKey, SymmetricKey};
@@ -7,6 +7,7 @@ use rand::TryRng;
 use zeroize::Zeroizing;
 
 use crate::aead::{KEY_SIZE, NONCE_SIZE};
+use crate::encoding;
 use crate::error::CryptoError;
 
 /// Fills `dest` with bytes from the operating system's CSPRNG.
@@ -70,18 +71,7 @@ pub fn generate_bytes(len: usize) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
 /// CSPRNG fails to produce output.
 pub fn generate_token(byte_len: usize) -> Result<Zeroizing<String>, CryptoError> {
     let bytes = generate_bytes(byte_len)?;
-    Ok(Zeroizing::new(hex_encode(&bytes)))
-}
-
-/// Encodes bytes as lowercase hexadecimal.
-fn hex_encode(bytes: &[u8]) -> String {
-    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
-    let mut hex = String::with_capacity(bytes.len() * 2);
-    for byte in bytes {
-        hex.push(HEX_CHARS[(byte >> 4) as usize] as char);
-        hex.push(HEX_CHARS[(byte & 0x0F) as usize] as char);
-    }
-    hex
+    Ok(encoding::encode_hex(&bytes))
 }
 
 #[cfg(test)]
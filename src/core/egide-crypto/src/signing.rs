@@ -0,0 +1,139 @@
+//! Public-key signatures (Ed25519).
+//!
+//! Backed by `ring`. Verification needs only the 32-byte public key, so a
+//! holder of the public key can check signatures made elsewhere without any
+//! signing capability. Signing keys are handled as their 32-byte seed, from
+//! which the key pair is rebuilt for each signature.
+
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+
+use crate::error::CryptoError;
+
+/// Ed25519 public key size in bytes.
+pub const ED25519_PUBLIC_KEY_SIZE: usize = 32;
+
+/// Ed25519 signature size in bytes.
+pub const ED25519_SIGNATURE_SIZE: usize = 64;
+
+/// Ed25519 private key seed size in bytes.
+pub const ED25519_SEED_SIZE: usize = 32;
+
+fn keypair_from_seed(seed: &[u8]) -> Result<Ed25519KeyPair, CryptoError> {
+    if seed.len() != ED25519_SEED_SIZE {
+        return Err(CryptoError::InvalidKey(format!(
+            "Ed25519 seed must be {ED25519_SEED_SIZE} bytes, got {}",
+            seed.len()
+        )));
+    }
+    Ed25519KeyPair::from_seed_unchecked(seed)
+        .map_err(|_| CryptoError::InvalidKey("invalid Ed25519 seed".into()))
+}
+
+/// Returns the public key of the Ed25519 key pair generated from `seed`.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKey`] if `seed` is not 32 bytes.
+pub fn ed25519_public_key(seed: &[u8]) -> Result<[u8; ED25519_PUBLIC_KEY_SIZE], CryptoError> {
+    let mut public_key = [0u8; ED25519_PUBLIC_KEY_SIZE];
+    public_key.copy_from_slice(keypair_from_seed(seed)?.public_key().as_ref());
+    Ok(public_key)
+}
+
+/// Signs `message` with the Ed25519 key pair generated from `seed`.
+///
+/// Ed25519 signatures are deterministic: the same seed and message always
+/// give the same signature.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKey`] if `seed` is not 32 bytes.
+pub fn sign_ed25519(
+    seed: &[u8],
+    message: &[u8],
+) -> Result<[u8; ED25519_SIGNATURE_SIZE], CryptoError> {
+    let mut signature = [0u8; ED25519_SIGNATURE_SIZE];
+    signature.copy_from_slice(keypair_from_seed(seed)?.sign(message).as_ref());
+    Ok(signature)
+}
+
+/// Verifies an Ed25519 `signature` over `message` under `public_key`.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKey`] if `public_key` is not 32 bytes, and
+/// [`CryptoError::VerificationFailed`] if the signature is malformed or does
+/// not match.
+pub fn verify_ed25519(
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), CryptoError> {
+    if public_key.len() != ED25519_PUBLIC_KEY_SIZE {
+        return Err(CryptoError::InvalidKey(format!(
+            "Ed25519 public key must be {ED25519_PUBLIC_KEY_SIZE} bytes, got {}",
+            public_key.len()
+        )));
+    }
+    if signature.len() != ED25519_SIGNATURE_SIZE {
+        return Err(CryptoError::VerificationFailed);
+    }
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(message, signature)
+        .map_err(|_| CryptoError::VerificationFailed)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    fn keypair() -> Ed25519KeyPair {
+        Ed25519KeyPair::from_seed_unchecked(&[9u8; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_verify_accepts_valid_signature() {
+        let pair = keypair();
+        let signature = pair.sign(b"message");
+        verify_ed25519(pair.public_key().as_ref(), b"message", signature.as_ref()).unwrap();
+    }
+
+    #[test]
+    fn test_sign_matches_ring_and_verifies() {
+        let seed = [9u8; 32];
+        let pair = keypair();
+        assert_eq!(
+            ed25519_public_key(&seed).unwrap(),
+            pair.public_key().as_ref()
+        );
+
+        let signature = sign_ed25519(&seed, b"message").unwrap();
+        assert_eq!(signature.as_slice(), pair.sign(b"message").as_ref());
+        verify_ed25519(&ed25519_public_key(&seed).unwrap(), b"message", &signature).unwrap();
+
+        assert!(matches!(
+            sign_ed25519(&seed[..31], b"message"),
+            Err(CryptoError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let pair = keypair();
+        let public_key = pair.public_key().as_ref();
+        let mut signature = pair.sign(b"message").as_ref().to_vec();
+
+        assert!(matches!(
+            verify_ed25519(public_key, b"messagf", &signature),
+            Err(CryptoError::VerificationFailed)
+        ));
+        assert!(verify_ed25519(public_key, b"message", &signature[..63]).is_err());
+        signature[0] ^= 1;
+        assert!(verify_ed25519(public_key, b"message", &signature).is_err());
+        assert!(matches!(
+            verify_ed25519(&public_key[..31], b"message", &signature),
+            Err(CryptoError::InvalidKey(_))
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+//! Key wrapping to a recipient's X25519 public key (ECIES).
+//!
+//! Used to hand key material to an HSM or external KMS without it ever being
+//! plaintext in transit. The sender generates an ephemeral X25519 key pair,
+//! agrees a shared secret with the recipient's public key, and derives one
+//! AES-256-GCM key per wrapped item with HKDF-SHA256:
+//!
+//! - salt: `ephemeral_public || recipient_public`
+//! - info: the caller-supplied item label, also used as AEAD associated data
+//!
+//! Each wrapped item is `ephemeral_public (32 bytes) || nonce || ciphertext ||
+//! tag`. All items wrapped by one [`KeyWrapper`] share its ephemeral key, so
+//! the recipient performs a single agreement for the whole batch. Built only
+//! from standard primitives, so any X25519 implementation can unwrap.
+
+use ring::agreement::{self, EphemeralPrivateKey, UnparsedPublicKey, X25519};
+use ring::rand::SystemRandom;
+use zeroize::Zeroizing;
+
+use crate::aead::{self, KEY_SIZE};
+use crate::error::CryptoError;
+use crate::kdf;
+
+/// X25519 public key size in bytes.
+pub const X25519_PUBLIC_KEY_SIZE: usize = 32;
+
+/// Wraps items to one recipient under a single ephemeral key agreement.
+pub struct KeyWrapper {
+    ephemeral_public: [u8; X25519_PUBLIC_KEY_SIZE],
+    recipient_public: [u8; X25519_PUBLIC_KEY_SIZE],
+    shared_secret: Zeroizing<Vec<u8>>,
+}
+
+impl KeyWrapper {
+    /// Agrees a fresh ephemeral key with `recipient_public_key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CryptoError::InvalidKey`] if the public key is not 32 bytes
+    /// or the agreement fails, and [`CryptoError::RandomGenerationFailed`] if
+    /// the ephemeral key cannot be generated.
+    pub fn new(recipient_public_key: &[u8]) -> Result<Self, CryptoError> {
+        let recipient_public: [u8; X25519_PUBLIC_KEY_SIZE] =
+            recipient_public_key.try_into().map_err(|_| {
+                CryptoError::InvalidKey(format!(
+                    "X25519 public key must be {X25519_PUBLIC_KEY_SIZE} bytes, got {}",
+                    recipient_public_key.len()
+                ))
+            })?;
+
+        let rng = SystemRandom::new();
+        let private_key = EphemeralPrivateKey::generate(&X25519, &rng)
+            .map_err(|_| CryptoError::RandomGenerationFailed("X25519 key generation".into()))?;
+        let mut ephemeral_public = [0u8; X25519_PUBLIC_KEY_SIZE];
+        ephemeral_public.copy_from_slice(
+            private_key
+                .compute_public_key()
+                .map_err(|_| CryptoError::InvalidKey("X25519 public key derivation".into()))?
+                .as_ref(),
+        );
+
+        let shared_secret = agreement::agree_ephemeral(
+            private_key,
+            &UnparsedPublicKey::new(&X25519, recipient_public),
+            |secret| Zeroizing::new(secret.to_vec()),
+        )
+        .map_err(|_| CryptoError::InvalidKey("X25519 key agreement failed".into()))?;
+
+        Ok(Self {
+            ephemeral_public,
+            recipient_public,
+            shared_secret,
+        })
+    }
+
+    /// Wraps `material` under the key derived for `label`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates key derivation and encryption failures.
+    pub fn wrap(&self, label: &[u8], material: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let key = derive_wrapping_key(
+            &self.shared_secret,
+            &self.ephemeral_public,
+            &self.recipient_public,
+            label,
+        )?;
+        let sealed = aead::encrypt(&key[..], material, Some(label))?;
+
+        let mut out = Vec::with_capacity(X25519_PUBLIC_KEY_SIZE + sealed.len());
+        out.extend_from_slice(&self.ephemeral_public);
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+}
+
+/// Derives the AES-256-GCM key protecting the item wrapped under `label`.
+///
+/// Public so that recipients holding the X25519 shared secret can unwrap with
+/// the same derivation.
+///
+/// # Errors
+///
+/// Propagates HKDF failures.
+pub fn derive_wrapping_key(
+    shared_secret: &[u8],
+    ephemeral_public: &[u8],
+    recipient_public: &[u8],
+    label: &[u8],
+) -> Result<Zeroizing<[u8; KEY_SIZE]>, CryptoError> {
+    let mut salt = Vec::with_capacity(ephemeral_public.len() + recipient_public.len());
+    salt.extend_from_slice(ephemeral_public);
+    salt.extend_from_slice(recipient_public);
+
+    let derived = kdf::derive_key(shared_secret, Some(&salt), label, KEY_SIZE)?;
+    let mut key = Zeroizing::new([0u8; KEY_SIZE]);
+    key.copy_from_slice(&derived);
+    Ok(key)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipient_unwraps_every_item() {
+        let rng = SystemRandom::new();
+        let recipient = EphemeralPrivateKey::generate(&X25519, &rng).unwrap();
+        let recipient_public = recipient.compute_public_key().unwrap();
+
+        let wrapper = KeyWrapper::new(recipient_public.as_ref()).unwrap();
+        let first = wrapper.wrap(b"item-1", b"material one").unwrap();
+        let second = wrapper.wrap(b"item-2", b"material two").unwrap();
+        assert_eq!(
+            first[..X25519_PUBLIC_KEY_SIZE],
+            second[..X25519_PUBLIC_KEY_SIZE]
+        );
+
+        let ephemeral_public = first[..X25519_PUBLIC_KEY_SIZE].to_vec();
+        let unwrapped = agreement::agree_ephemeral(
+            recipient,
+            &UnparsedPublicKey::new(&X25519, &ephemeral_public),
+            |shared| {
+                [(b"item-1", &first), (b"item-2", &second)]
+                    .iter()
+                    .map(|(label, wrapped)| {
+                        let key = derive_wrapping_key(
+                            shared,
+                            &ephemeral_public,
+                            recipient_public.as_ref(),
+                            &label[..],
+                        )
+                        .unwrap();
+                        aead::decrypt(
+                            &key[..],
+                            &wrapped[X25519_PUBLIC_KEY_SIZE..],
+                            Some(&label[..]),
+                        )
+                        .unwrap()
+                        .to_vec()
+                    })
+                    .collect::<Vec<_>>()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(unwrapped[0], b"material one");
+        assert_eq!(unwrapped[1], b"material two");
+    }
+
+    #[test]
+    fn test_wrapped_item_hides_material_and_binds_label() {
+        let rng = SystemRandom::new();
+        let recipient = EphemeralPrivateKey::generate(&X25519, &rng).unwrap();
+        let recipient_public = recipient.compute_public_key().unwrap();
+
+        let material = [0x5a; 32];
+        let wrapper = KeyWrapper::new(recipient_public.as_ref()).unwrap();
+        let wrapped = wrapper.wrap(b"item-1", &material).unwrap();
+        assert!(!wrapped.windows(material.len()).any(|w| w == material));
+
+        let ephemeral_public = wrapped[..X25519_PUBLIC_KEY_SIZE].to_vec();
+        let relabelled = agreement::agree_ephemeral(
+            recipient,
+            &UnparsedPublicKey::new(&X25519, &ephemeral_public),
+            |shared| {
+                let key =
+                    derive_wrapping_key(shared, &ephemeral_public, recipient_public.as_ref(), b"x")
+                        .unwrap();
+                aead::decrypt(
+                    &key[..],
+                    &wrapped[X25519_PUBLIC_KEY_SIZE..],
+                    Some(&b"x"[..]),
+                )
+                .is_err()
+            },
+        )
+        .unwrap();
+        assert!(relabelled);
+    }
+
+    #[test]
+    fn test_rejects_malformed_public_key() {
+        assert!(matches!(
+            KeyWrapper::new(&[1u8; 31]),
+            Err(CryptoError::InvalidKey(_))
+        ));
+    }
+}
@@ -33,10 +33,28 @@ pub enum SealError {
     #[error("duplicate share (index {0})")]
     DuplicateShare(u8),
 
+    /// The share was submitted for an unseal operation that is no longer active.
+    #[error("stale unseal nonce")]
+    StaleNonce,
+
     /// Failed to reconstruct master key.
     #[error("failed to reconstruct master key")]
     ReconstructionFailed,
 
+    /// Too many failed reconstructions; unseal is refused until the cooldown ends.
+    #[error("unseal locked out after repeated failed attempts; retry in {retry_after_secs}s")]
+    UnsealLockedOut {
+        /// Seconds until submissions are accepted again.
+        retry_after_secs: u64,
+    },
+
+    /// The root token was rotated too recently to be rotated again.
+    #[error("root token rotated too recently; retry in {retry_after_secs}s")]
+    RootRotationRateLimited {
+        /// Seconds until the next rotation is accepted.
+        retry_after_secs: u64,
+    },
+
     /// Storage error.
     #[error("storage error: {0}")]
     Storage(String),
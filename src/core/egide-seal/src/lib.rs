@@ -10,18 +10,28 @@
 //!
 //! The master key is split into N shares using Shamir's Secret Sharing.
 //! A minimum of M shares (threshold) are required to reconstruct the key.
+//!
+//! ## Auto-unseal
+//!
+//! With an [`AutoUnsealBackend`], the master key is also stored wrapped by an
+//! external key and unwrapped on startup, so restarts need no shares. Dev
+//! mode is the degenerate case where the "wrapped" key is stored in clear.
 
 #![forbid(unsafe_code)]
 
 pub mod error;
+mod share_pgp;
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
+use async_trait::async_trait;
 use blahaj::{Share as SharkShare, Sharks};
 use hmac::{Hmac, KeyInit, Mac};
 use sha2::Sha256;
@@ -31,9 +41,16 @@ use zeroize::Zeroizing;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Largest encoded share: the index byte followed by one byte per byte of
+/// the master key.
+pub const MAX_SHARE_LEN: usize = 1 + KEY_SIZE;
+
 /// Domain separation tag for master key verification.
 const SEAL_VERIFY_TAG: &[u8] = b"egide-seal-verify-v1";
 
+/// Associated data binding the pending rotation record to its purpose.
+const PENDING_ROTATION_AAD: &[u8] = b"egide-seal-pending-rotation-v1";
+
 /// Environment variable that must be set to `"1"` to explicitly allow dev
 /// mode. Dev mode stores the master key in cleartext, so activating it must
 /// never be a default or accidental outcome.
@@ -46,7 +63,11 @@ const PRODUCTION_ENV_MARKER: &str = "EGIDE_ENV";
 /// Value of [`PRODUCTION_ENV_MARKER`] that forbids dev mode.
 const PRODUCTION_ENV_VALUE: &str = "production";
 
-use egide_crypto::MasterKey;
+/// Default minimum time between two root token rotations.
+pub const DEFAULT_ROOT_ROTATION_INTERVAL: Duration = Duration::from_mins(1);
+
+use egide_crypto::commitment::{self, Commitment, COMMITMENT_SIZE};
+use egide_crypto::{aead, aead::KEY_SIZE, encoding, MasterKey};
 use egide_storage::StorageBackend;
 use egide_storage_sqlite::SqliteBackend;
 
@@ -60,6 +81,39 @@ mod keys {
     pub(crate) const INITIALIZED_AT: &str = "initialized_at";
     pub(crate) const DEV_MODE_KEY: &str = "dev_mode_master_key";
     pub(crate) const MASTER_KEY_HMAC: &str = "master_key_hmac";
+    pub(crate) const SHARE_COMMITMENTS: &str = "share_commitments";
+    pub(crate) const AUTO_UNSEAL_BLOB: &str = "auto_unseal_blob";
+    pub(crate) const PENDING_ROTATION: &str = "pending_master_key_rotation";
+}
+
+/// Wraps the master key with a key held elsewhere, so the vault can unseal
+/// itself on restart without Shamir shares.
+///
+/// A real implementation calls out to a KMS, such as a transit key on
+/// another Egide instance. The wrapped blob is stored in `system.db`; the
+/// wrapping key must never be.
+#[async_trait]
+pub trait AutoUnsealBackend: Send + Sync {
+    /// Wraps the master key, returning the blob to store.
+    async fn wrap(&self, master: &[u8]) -> Result<Vec<u8>, SealError>;
+
+    /// Recovers the master key from a blob returned by [`Self::wrap`].
+    async fn unwrap(&self, blob: &[u8]) -> Result<Zeroizing<Vec<u8>>, SealError>;
+}
+
+/// Dev mode as an auto-unseal backend: the "wrapped" master key is the
+/// master key itself, stored in clear.
+struct DevModeUnseal;
+
+#[async_trait]
+impl AutoUnsealBackend for DevModeUnseal {
+    async fn wrap(&self, master: &[u8]) -> Result<Vec<u8>, SealError> {
+        Ok(master.to_vec())
+    }
+
+    async fn unwrap(&self, blob: &[u8]) -> Result<Zeroizing<Vec<u8>>, SealError> {
+        Ok(Zeroizing::new(blob.to_vec()))
+    }
 }
 
 /// State of the vault seal.
@@ -110,29 +164,106 @@ impl Share {
     /// Encodes the share as a hex string for display.
     #[must_use]
     pub fn to_hex(&self) -> String {
-        hex_encode(&self.data)
+        encoding::encode_hex(&self.data).as_str().to_owned()
     }
 
     /// Decodes a share from a hex string.
+    ///
+    /// Decoding is timing-safe and goes through a fixed-size zeroizing buffer
+    /// of [`MAX_SHARE_LEN`] bytes, so an oversized input is rejected before
+    /// anything is allocated for it.
     pub fn from_hex(hex: &str) -> Result<Self, SealError> {
-        let data = hex_decode(hex).map_err(|e| SealError::InvalidShare(e.clone()))?;
-        if data.is_empty() {
+        let mut buf = Zeroizing::new([0u8; MAX_SHARE_LEN]);
+        let len = encoding::decode_hex_into(hex, &mut buf[..])
+            .map_err(|e| SealError::InvalidShare(e.to_string()))?;
+        if len == 0 {
             return Err(SealError::InvalidShare("empty share".into()));
         }
         // First byte is the index in sharks format
         Ok(Self {
-            index: data[0],
-            data,
+            index: buf[0],
+            data: buf[..len].to_vec(),
         })
     }
 }
 
+/// Options for [`SealManager::initialize_with`].
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    /// Armored `OpenPGP` public keys, one per share in share order. When set,
+    /// each share is returned encrypted to its holder's key instead of in
+    /// clear. Requires the `pgp` feature.
+    pub pgp_keys: Option<Vec<String>>,
+}
+
 /// Result of vault initialization.
 pub struct InitResult {
     /// Root token (shown only once).
     pub root_token: String,
-    /// Shamir shares for key holders.
+    /// Shamir shares for key holders. Empty when the shares were
+    /// PGP-encrypted, see [`InitResult::pgp_shares`].
     pub shares: Vec<Share>,
+    /// Armored PGP messages, one per share in the order of
+    /// [`InitOptions::pgp_keys`]. Empty unless keys were given.
+    pub pgp_shares: Vec<String>,
+}
+
+/// Lockout policy against brute-forcing the missing shares.
+///
+/// Every reconstruction that fails its HMAC check counts as a failed attempt.
+/// Once `max_failed_attempts` is reached, unseal submissions are refused for
+/// `cooldown`, after which the count starts over. The count lives in memory:
+/// restarting the process clears it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsealLockout {
+    /// Failed reconstructions allowed before locking out.
+    pub max_failed_attempts: u32,
+    /// How long submissions are refused once locked out.
+    pub cooldown: Duration,
+}
+
+impl Default for UnsealLockout {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 5,
+            cooldown: Duration::from_mins(5),
+        }
+    }
+}
+
+/// Seal state, Shamir configuration and unseal progress in one snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SealStatusDetail {
+    /// Current seal status.
+    pub status: SealStatus,
+    /// Total number of shares (0 before initialization).
+    pub total_shares: u8,
+    /// Threshold required to unseal (0 before initialization).
+    pub threshold: u8,
+    /// Number of shares submitted towards the pending unseal.
+    pub progress: u8,
+    /// When the vault was initialized, in seconds since the UNIX epoch.
+    pub initialized_at: Option<u64>,
+    /// Whether the vault runs in dev mode.
+    pub dev_mode: bool,
+}
+
+/// Progress of a root token regeneration, see
+/// [`SealManager::generate_root_submit`].
+pub struct GenerateRootProgress {
+    /// Threshold required to regenerate the root token.
+    pub threshold: u8,
+    /// Number of shares submitted so far.
+    pub progress: u8,
+    /// The new root token, once the threshold was reached (shown only once).
+    pub root_token: Option<String>,
+}
+
+/// Shares gathered by an attempt to regenerate the root token.
+struct GenerateRootAttempt {
+    nonce: String,
+    shares: Vec<SharkShare>,
+    indices: HashSet<u8>,
 }
 
 /// Progress of an unseal operation.
@@ -153,18 +284,71 @@ pub struct SealManager {
     data_path: PathBuf,
     status: SealStatus,
     master_key: Option<MasterKey>,
+    /// Master key replaced by [`SealManager::rotate_master_key`], kept until
+    /// the caller confirms everything was rewrapped. Also stored wrapped
+    /// under the new key and restored on unseal.
+    previous_master_key: Option<MasterKey>,
     pub(crate) pending_shares: Vec<SharkShare>,
     pub(crate) pending_indices: HashSet<u8>,
+    /// Nonce of the active unseal operation, if one was begun.
+    unseal_nonce: Option<String>,
+    /// Root token regeneration in progress, if any.
+    generate_root: Option<GenerateRootAttempt>,
     threshold: u8,
+    /// Total number of shares of the current set.
+    total_shares: u8,
+    /// Initialization time in seconds since the UNIX epoch.
+    initialized_at: Option<u64>,
     dev_mode: bool,
     /// Expected HMAC for master key verification (loaded at startup).
     expected_hmac: Option<Vec<u8>>,
+    /// Commitments to the current share set (loaded at startup). `None` for
+    /// vaults initialized before commitments were recorded.
+    share_commitments: Option<Vec<Commitment>>,
+    lockout: UnsealLockout,
+    /// Failed reconstructions since the last success or lockout.
+    failed_attempts: u32,
+    /// End of the current lockout, if any.
+    locked_until: Option<Instant>,
+    /// Backend wrapping the master key for unattended unseal, if configured.
+    auto_unseal: Option<Arc<dyn AutoUnsealBackend>>,
+    /// Minimum time between two root token rotations.
+    root_rotation_interval: Duration,
+    /// When the root token was last rotated by this process.
+    last_root_rotation: Option<Instant>,
+    /// Idle time after which the vault seals itself, if set.
+    seal_after_idle: Option<Duration>,
+    /// Last unseal or authenticated activity, see [`SealManager::touch`].
+    /// Behind a lock so request handlers can record activity under a shared
+    /// borrow of the manager.
+    last_activity: Mutex<Instant>,
 }
 
 impl SealManager {
     /// Creates a new `SealManager` with storage path.
     pub async fn new(data_path: impl AsRef<Path>) -> Result<Self, SealError> {
-        let data_path = data_path.as_ref().to_path_buf();
+        Self::open(data_path.as_ref(), None).await
+    }
+
+    /// Creates a `SealManager` that unseals itself through `backend`.
+    ///
+    /// [`Self::initialize`] stores the master key wrapped by `backend` and
+    /// leaves the vault unsealed; on every later start the stored blob is
+    /// unwrapped and checked against the master key HMAC. If that fails, for
+    /// instance because the KMS is unreachable, the vault stays sealed and
+    /// the Shamir shares from initialization still unseal it.
+    pub async fn with_auto_unseal(
+        data_path: impl AsRef<Path>,
+        backend: Arc<dyn AutoUnsealBackend>,
+    ) -> Result<Self, SealError> {
+        Self::open(data_path.as_ref(), Some(backend)).await
+    }
+
+    async fn open(
+        data_path: &Path,
+        auto_unseal: Option<Arc<dyn AutoUnsealBackend>>,
+    ) -> Result<Self, SealError> {
+        let data_path = data_path.to_path_buf();
         let storage = SqliteBackend::open(&data_path, "system").await?;
 
         let mut manager = Self {
@@ -172,11 +356,25 @@ impl SealManager {
             data_path,
             status: SealStatus::Uninitialized,
             master_key: None,
+            previous_master_key: None,
             pending_shares: Vec::new(),
             pending_indices: HashSet::new(),
+            unseal_nonce: None,
+            generate_root: None,
             threshold: 0,
+            total_shares: 0,
+            initialized_at: None,
             dev_mode: false,
             expected_hmac: None,
+            share_commitments: None,
+            lockout: UnsealLockout::default(),
+            failed_attempts: 0,
+            locked_until: None,
+            auto_unseal,
+            root_rotation_interval: DEFAULT_ROOT_ROTATION_INTERVAL,
+            last_root_rotation: None,
+            seal_after_idle: None,
+            last_activity: Mutex::new(Instant::now()),
         };
 
         manager.load_status().await?;
@@ -186,17 +384,29 @@ impl SealManager {
 
     /// Loads the seal status from storage.
     async fn load_status(&mut self) -> Result<(), SealError> {
-        let initialized = self.storage.get(keys::INITIALIZED_AT).await?.is_some();
+        let initialized_at = self.storage.get(keys::INITIALIZED_AT).await?;
 
-        if initialized {
+        if let Some(initialized_at) = initialized_at {
             self.status = SealStatus::Sealed;
+            self.initialized_at = <[u8; 8]>::try_from(initialized_at.as_slice())
+                .ok()
+                .map(u64::from_le_bytes);
 
             if let Some(threshold_bytes) = self.storage.get(keys::SHAMIR_THRESHOLD).await? {
                 self.threshold = threshold_bytes[0];
             }
+            if let Some(total_bytes) = self.storage.get(keys::SHAMIR_TOTAL).await? {
+                self.total_shares = total_bytes.first().copied().unwrap_or_default();
+            }
 
             // Load expected HMAC for master key verification
             self.expected_hmac = self.storage.get(keys::MASTER_KEY_HMAC).await?;
+            self.share_commitments = self
+                .storage
+                .get(keys::SHARE_COMMITMENTS)
+                .await?
+                .map(|bytes| decode_commitments(&bytes))
+                .transpose()?;
 
             // Check for dev mode
             if let Some(key_bytes) = self.storage.get(keys::DEV_MODE_KEY).await? {
@@ -212,12 +422,15 @@ impl SealManager {
                 )?;
 
                 warn!("⚠️  DEV MODE DETECTED - AUTO-UNSEALING - NOT FOR PRODUCTION ⚠️");
-                self.master_key = Some(
-                    MasterKey::from_bytes(&key_bytes)
-                        .map_err(|e| SealError::Crypto(e.to_string()))?,
-                );
-                self.status = SealStatus::Unsealed;
+                self.auto_unseal_with(&DevModeUnseal, &key_bytes).await?;
                 self.dev_mode = true;
+            } else if let Some(backend) = self.auto_unseal.clone() {
+                if let Some(blob) = self.storage.get(keys::AUTO_UNSEAL_BLOB).await? {
+                    match self.auto_unseal_with(backend.as_ref(), &blob).await {
+                        Ok(()) => info!("Egide auto-unsealed"),
+                        Err(e) => warn!(error = %e, "Auto-unseal failed - vault stays sealed"),
+                    }
+                }
             }
         }
 
@@ -225,25 +438,178 @@ impl SealManager {
         Ok(())
     }
 
+    /// Unwraps the master key from `blob` and unseals with it once it
+    /// matches the stored master key HMAC.
+    async fn auto_unseal_with(
+        &mut self,
+        backend: &dyn AutoUnsealBackend,
+        blob: &[u8],
+    ) -> Result<(), SealError> {
+        let key_bytes = backend.unwrap(blob).await?;
+        let expected_hmac = self
+            .expected_hmac
+            .as_ref()
+            .ok_or(SealError::ReconstructionFailed)?;
+        if !hmac_tags_match(&compute_master_key_hmac(&key_bytes)?, expected_hmac) {
+            return Err(SealError::ReconstructionFailed);
+        }
+
+        let master_key =
+            MasterKey::from_bytes(&key_bytes).map_err(|e| SealError::Crypto(e.to_string()))?;
+        self.previous_master_key = self.load_pending_rotation(&master_key).await?;
+        self.master_key = Some(master_key);
+        self.status = SealStatus::Unsealed;
+        self.touch();
+        Ok(())
+    }
+
+    /// Recovers the master key replaced by a pending rotation, stored
+    /// wrapped under `master_key`.
+    async fn load_pending_rotation(
+        &self,
+        master_key: &MasterKey,
+    ) -> Result<Option<MasterKey>, SealError> {
+        let Some(blob) = self.storage.get(keys::PENDING_ROTATION).await? else {
+            return Ok(None);
+        };
+        let key_bytes = aead::decrypt(master_key.as_bytes(), &blob, Some(PENDING_ROTATION_AAD))
+            .map_err(|e| SealError::Crypto(e.to_string()))?;
+        let previous =
+            MasterKey::from_bytes(&key_bytes).map_err(|e| SealError::Crypto(e.to_string()))?;
+
+        warn!("Master key rotation pending - rewrap engine data and finish it");
+        Ok(Some(previous))
+    }
+
     /// Returns the current seal status.
     #[must_use]
     pub fn status(&self) -> SealStatus {
         self.status
     }
 
+    /// Returns the progress of the pending unseal without submitting a share.
+    ///
+    /// `progress` counts the shares accepted since the last reset; it drops
+    /// back to zero once the vault unseals, reseals, or a reconstruction
+    /// fails.
+    #[must_use]
+    pub fn unseal_progress(&self) -> UnsealProgress {
+        // pending_shares.len() is always bounded by self.threshold (a u8), so the cast is safe.
+        #[allow(clippy::cast_possible_truncation)]
+        let progress = self.pending_shares.len() as u8;
+        UnsealProgress {
+            sealed: self.status != SealStatus::Unsealed,
+            threshold: self.threshold,
+            progress,
+        }
+    }
+
+    /// Returns the seal status together with the Shamir configuration and
+    /// unseal progress. Read-only, like [`Self::unseal_progress`].
+    #[must_use]
+    pub fn seal_status(&self) -> SealStatusDetail {
+        SealStatusDetail {
+            status: self.status,
+            total_shares: self.total_shares,
+            threshold: self.threshold,
+            progress: self.unseal_progress().progress,
+            initialized_at: self.initialized_at,
+            dev_mode: self.dev_mode,
+        }
+    }
+
     /// Returns true if running in dev mode.
     #[must_use]
     pub fn is_dev_mode(&self) -> bool {
         self.dev_mode
     }
 
+    /// Sets the unseal lockout policy.
+    pub fn set_unseal_lockout(&mut self, lockout: UnsealLockout) {
+        self.lockout = lockout;
+    }
+
+    /// Sets the idle time after which [`Self::check_idle`] seals the vault.
+    ///
+    /// `None`, the default, never seals on idle.
+    pub fn set_seal_after_idle(&mut self, idle: Option<Duration>) {
+        self.seal_after_idle = idle;
+    }
+
+    /// Records authenticated activity, pushing back the idle deadline.
+    pub fn touch(&self) {
+        *self
+            .last_activity
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Instant::now();
+    }
+
+    /// Seals the vault if it has been idle for longer than the configured
+    /// [`Self::set_seal_after_idle`] time, returning whether it did.
+    ///
+    /// The idle time counts from the last [`Self::touch`] or unseal. Never
+    /// seals in dev mode.
+    pub fn check_idle(&mut self) -> bool {
+        let Some(idle) = self.seal_after_idle else {
+            return false;
+        };
+        if self.dev_mode
+            || self.status != SealStatus::Unsealed
+            || self
+                .last_activity
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .elapsed()
+                < idle
+        {
+            return false;
+        }
+
+        info!(idle_secs = idle.as_secs(), "Sealing after idle timeout");
+        self.seal();
+        true
+    }
+
+    /// Sets the minimum time between two root token rotations.
+    pub fn set_root_rotation_interval(&mut self, interval: Duration) {
+        self.root_rotation_interval = interval;
+    }
+
     /// Initializes the vault (first time setup).
     pub async fn initialize(&mut self, config: ShamirConfig) -> Result<InitResult, SealError> {
+        self.initialize_with(config, InitOptions::default()).await
+    }
+
+    /// Initializes the vault with [`InitOptions`].
+    ///
+    /// With `pgp_keys`, every share is encrypted to the matching key before
+    /// anything is stored, and only the encrypted shares are returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::InvalidConfig`] if the number of PGP keys differs
+    /// from `config.shares`, a key cannot be used for encryption, or the
+    /// crate was built without the `pgp` feature, in addition to the errors
+    /// of [`Self::initialize`].
+    pub async fn initialize_with(
+        &mut self,
+        config: ShamirConfig,
+        options: InitOptions,
+    ) -> Result<InitResult, SealError> {
         if self.status != SealStatus::Uninitialized {
             return Err(SealError::AlreadyInitialized);
         }
 
         config.validate()?;
+        if let Some(pgp_keys) = &options.pgp_keys {
+            if pgp_keys.len() != usize::from(config.shares) {
+                return Err(SealError::InvalidConfig(format!(
+                    "expected {} pgp keys, got {}",
+                    config.shares,
+                    pgp_keys.len()
+                )));
+            }
+        }
 
         info!(
             shares = config.shares,
@@ -258,20 +624,25 @@ impl SealManager {
         let master_key_hmac = compute_master_key_hmac(master_key.as_bytes())?;
 
         // Split with Shamir
-        let sharks = Sharks(config.threshold);
-        let dealer = sharks.dealer(master_key.as_bytes());
-        let shark_shares: Vec<SharkShare> = dealer.take(config.shares as usize).collect();
+        let shares = split_master_key(master_key.as_bytes(), &config);
+        let share_commitments = commit_shares(&shares)?;
 
-        let shares: Vec<Share> = shark_shares
-            .iter()
-            .map(|s| {
-                let bytes: Vec<u8> = s.into();
-                Share {
-                    index: bytes[0],
-                    data: bytes,
-                }
-            })
-            .collect();
+        let auto_unseal_blob = match &self.auto_unseal {
+            Some(backend) => Some(backend.wrap(master_key.as_bytes()).await?),
+            None => None,
+        };
+
+        let (shares, pgp_shares) = match &options.pgp_keys {
+            Some(pgp_keys) => {
+                let encrypted = pgp_keys
+                    .iter()
+                    .zip(&shares)
+                    .map(|(key, share)| share_pgp::encrypt_share(key, share))
+                    .collect::<Result<Vec<_>, _>>()?;
+                (Vec::new(), encrypted)
+            },
+            None => (shares, Vec::new()),
+        };
 
         // Generate root token
         let root_token = egide_crypto::random::generate_token(32)
@@ -296,29 +667,103 @@ impl SealManager {
         self.storage
             .put(keys::MASTER_KEY_HMAC, &master_key_hmac)
             .await?;
+        self.storage
+            .put(
+                keys::SHARE_COMMITMENTS,
+                &encode_commitments(&share_commitments),
+            )
+            .await?;
+        if let Some(blob) = &auto_unseal_blob {
+            self.storage.put(keys::AUTO_UNSEAL_BLOB, blob).await?;
+        }
 
         self.expected_hmac = Some(master_key_hmac);
+        self.share_commitments = Some(share_commitments);
         self.status = SealStatus::Sealed;
         self.threshold = config.threshold;
+        self.total_shares = config.shares;
+        self.initialized_at = Some(now);
+        if auto_unseal_blob.is_some() {
+            self.master_key = Some(master_key);
+            self.status = SealStatus::Unsealed;
+            self.touch();
+        }
 
         info!("Egide initialized successfully");
 
         Ok(InitResult {
             root_token: root_token.as_str().to_owned(),
             shares,
+            pgp_shares,
         })
     }
 
-    /// Submits a share for unsealing.
-    // Kept async to match the public API signature expected by callers; storage calls may be
-    // added in a future version without a breaking change.
-    #[allow(clippy::unused_async)]
-    pub async fn unseal(&mut self, share: &Share) -> Result<UnsealProgress, SealError> {
+    /// Returns the nonce of the active unseal operation, beginning one if
+    /// none is active.
+    ///
+    /// Every share of an operation is submitted with its nonce. The
+    /// operation ends, and its nonce goes stale, when the vault unseals, a
+    /// reconstruction fails, the vault is sealed, or [`Self::reset_unseal`]
+    /// is called; shares still carrying the old nonce are then refused
+    /// instead of leaking into the next attempt.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::NotInitialized`] or [`SealError::AlreadyUnsealed`]
+    /// unless the vault is sealed.
+    pub fn begin_unseal(&mut self) -> Result<String, SealError> {
         match self.status {
             SealStatus::Uninitialized => return Err(SealError::NotInitialized),
             SealStatus::Unsealed => return Err(SealError::AlreadyUnsealed),
             SealStatus::Sealed => {},
         }
+        if let Some(nonce) = &self.unseal_nonce {
+            return Ok(nonce.clone());
+        }
+
+        let nonce = egide_crypto::random::generate_token(16)
+            .map_err(|e| SealError::Crypto(e.to_string()))?
+            .as_str()
+            .to_owned();
+        self.unseal_nonce = Some(nonce.clone());
+        debug!("Unseal operation begun");
+        Ok(nonce)
+    }
+
+    /// Discards the shares submitted so far and begins a new unseal
+    /// operation, returning its nonce.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Self::begin_unseal`].
+    pub fn reset_unseal(&mut self) -> Result<String, SealError> {
+        self.reset_pending();
+        self.begin_unseal()
+    }
+
+    /// Submits a share for unsealing, as part of the operation identified by
+    /// `nonce` (see [`Self::begin_unseal`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::StaleNonce`] if `nonce` is not the active
+    /// operation's nonce, in addition to share and reconstruction errors.
+    pub async fn unseal(
+        &mut self,
+        nonce: &str,
+        share: &Share,
+    ) -> Result<UnsealProgress, SealError> {
+        match self.status {
+            SealStatus::Uninitialized => return Err(SealError::NotInitialized),
+            SealStatus::Unsealed => return Err(SealError::AlreadyUnsealed),
+            SealStatus::Sealed => {},
+        }
+
+        self.check_lockout()?;
+
+        if self.unseal_nonce.as_deref() != Some(nonce) {
+            return Err(SealError::StaleNonce);
+        }
 
         // Check for duplicate
         if self.pending_indices.contains(&share.index) {
@@ -341,7 +786,13 @@ impl SealManager {
 
         // Check if we have enough shares
         if self.pending_shares.len() >= self.threshold as usize {
-            self.reconstruct_master_key()?;
+            if let Err(e) = self.reconstruct_master_key().await {
+                if matches!(e, SealError::ReconstructionFailed) {
+                    self.record_failed_attempt();
+                }
+                return Err(e);
+            }
+            self.failed_attempts = 0;
         }
 
         // pending_shares.len() is always bounded by self.threshold (a u8), so the cast is safe.
@@ -354,45 +805,431 @@ impl SealManager {
         })
     }
 
+    /// Checks whether a share belongs to this vault's current share set,
+    /// without submitting it.
+    ///
+    /// Returns `false` for a share that is malformed, or that does not match
+    /// any of the commitments recorded at initialization or the last rekey,
+    /// such as a share of another vault or of a retired set. Vaults
+    /// initialized before commitments were recorded can only be checked
+    /// structurally. Nothing is recorded: the unseal progress and lockout
+    /// are unaffected.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::NotInitialized`] before initialization.
+    pub fn verify_share(&self, share: &Share) -> Result<bool, SealError> {
+        if self.status == SealStatus::Uninitialized {
+            return Err(SealError::NotInitialized);
+        }
+
+        let well_formed = share.index != 0
+            && share.data.len() == MAX_SHARE_LEN
+            && share.data[0] == share.index
+            && SharkShare::try_from(share.data.as_slice()).is_ok();
+        if !well_formed {
+            return Ok(false);
+        }
+
+        Ok(self
+            .share_commitments
+            .as_ref()
+            .is_none_or(|commitments| is_committed(commitments, &share.data)))
+    }
+
+    /// Refuses submissions while locked out; clears an expired lockout.
+    fn check_lockout(&mut self) -> Result<(), SealError> {
+        let Some(until) = self.locked_until else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        if now < until {
+            // Round up so a client never retries a fraction of a second early.
+            let remaining = until - now;
+            let retry_after_secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+            return Err(SealError::UnsealLockedOut { retry_after_secs });
+        }
+        self.locked_until = None;
+        self.failed_attempts = 0;
+        Ok(())
+    }
+
+    /// Counts a failed reconstruction and locks out once the limit is reached.
+    fn record_failed_attempt(&mut self) {
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        if self.failed_attempts >= self.lockout.max_failed_attempts {
+            warn!(
+                attempts = self.failed_attempts,
+                cooldown_secs = self.lockout.cooldown.as_secs(),
+                "Unseal locked out after repeated failed reconstructions"
+            );
+            self.locked_until = Some(Instant::now() + self.lockout.cooldown);
+            self.reset_pending();
+        }
+    }
+
+    /// Discards the pending shares and ends the active unseal operation.
+    fn reset_pending(&mut self) {
+        self.pending_shares.clear();
+        self.pending_indices.clear();
+        self.unseal_nonce = None;
+    }
+
     /// Reconstructs the master key from pending shares.
-    fn reconstruct_master_key(&mut self) -> Result<(), SealError> {
+    async fn reconstruct_master_key(&mut self) -> Result<(), SealError> {
+        let secret = match self.recover_master_key(&self.pending_shares) {
+            Ok(secret) => secret,
+            Err(e) => {
+                self.reset_pending();
+                return Err(e);
+            },
+        };
+
+        let master_key =
+            MasterKey::from_bytes(&secret).map_err(|e| SealError::Crypto(e.to_string()))?;
+
+        // Clear pending shares (zeroize)
+        self.reset_pending();
+
+        self.previous_master_key = self.load_pending_rotation(&master_key).await?;
+        self.master_key = Some(master_key);
+        self.status = SealStatus::Unsealed;
+        self.touch();
+
+        info!("Egide unsealed successfully");
+
+        Ok(())
+    }
+
+    /// Recovers the master key from a quorum of shares and checks it against
+    /// the stored HMAC and share commitments.
+    fn recover_master_key(&self, shares: &[SharkShare]) -> Result<Zeroizing<Vec<u8>>, SealError> {
         let sharks = Sharks(self.threshold);
 
         let secret = Zeroizing::new(
             sharks
-                .recover(&self.pending_shares)
+                .recover(shares)
                 .map_err(|_| SealError::ReconstructionFailed)?,
         );
 
         // Verify the reconstructed key matches expected HMAC
-        let expected_hmac = self.expected_hmac.as_ref().ok_or_else(|| {
+        let Some(expected_hmac) = self.expected_hmac.as_ref() else {
             warn!("Master key reconstruction failed - missing expected HMAC (data corruption?)");
-            self.pending_shares.clear();
-            self.pending_indices.clear();
-            SealError::ReconstructionFailed
-        })?;
+            return Err(SealError::ReconstructionFailed);
+        };
 
         let computed_hmac = compute_master_key_hmac(&secret)?;
         if !hmac_tags_match(&computed_hmac, expected_hmac) {
             warn!("Master key reconstruction failed - HMAC mismatch (invalid shares?)");
-            // Clear pending shares before returning error
-            self.pending_shares.clear();
-            self.pending_indices.clear();
             return Err(SealError::ReconstructionFailed);
         }
 
-        let master_key =
-            MasterKey::from_bytes(&secret).map_err(|e| SealError::Crypto(e.to_string()))?;
+        // A full quorum of shares from a set replaced by `rekey` still
+        // reconstructs the right key, so each share must also belong to the
+        // current set.
+        if let Some(commitments) = &self.share_commitments {
+            let all_current = shares.iter().all(|s| {
+                let bytes: Vec<u8> = s.into();
+                is_committed(commitments, &bytes)
+            });
+            if !all_current {
+                warn!("Master key reconstruction failed - share from a retired share set");
+                return Err(SealError::ReconstructionFailed);
+            }
+        }
 
-        // Clear pending shares (zeroize)
-        self.pending_shares.clear();
-        self.pending_indices.clear();
+        Ok(secret)
+    }
 
-        self.master_key = Some(master_key);
-        self.status = SealStatus::Unsealed;
+    /// Begins regenerating the root token from a quorum of shares, returning
+    /// the nonce every share of the attempt is submitted with.
+    ///
+    /// Beginning again abandons the attempt in progress. Works whether the
+    /// vault is sealed or unsealed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::NotInitialized`] before initialization.
+    pub fn begin_generate_root(&mut self) -> Result<String, SealError> {
+        if self.status == SealStatus::Uninitialized {
+            return Err(SealError::NotInitialized);
+        }
 
-        info!("Egide unsealed successfully");
+        let nonce = egide_crypto::random::generate_token(16)
+            .map_err(|e| SealError::Crypto(e.to_string()))?
+            .as_str()
+            .to_owned();
+        self.generate_root = Some(GenerateRootAttempt {
+            nonce: nonce.clone(),
+            shares: Vec::new(),
+            indices: HashSet::new(),
+        });
+        info!("Root token generation begun");
+        Ok(nonce)
+    }
+
+    /// Submits an unseal share towards a new root token.
+    ///
+    /// Once the unseal threshold is reached and the shares reconstruct the
+    /// master key, a new root token replaces the old one, which stops
+    /// validating, and is returned in [`GenerateRootProgress::root_token`].
+    /// It is shown only this once. Failed reconstructions count towards the
+    /// unseal lockout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::StaleNonce`] unless `nonce` is the one returned by
+    /// the last [`Self::begin_generate_root`], [`SealError::UnsealLockedOut`]
+    /// while locked out, share errors like [`Self::unseal`], and
+    /// [`SealError::ReconstructionFailed`] when the quorum does not recover
+    /// the master key, which ends the attempt.
+    pub async fn generate_root_submit(
+        &mut self,
+        nonce: &str,
+        share: &Share,
+    ) -> Result<GenerateRootProgress, SealError> {
+        if self.status == SealStatus::Uninitialized {
+            return Err(SealError::NotInitialized);
+        }
+        self.check_lockout()?;
+
+        let threshold = self.threshold;
+        let attempt = self
+            .generate_root
+            .as_mut()
+            .filter(|attempt| attempt.nonce == nonce)
+            .ok_or(SealError::StaleNonce)?;
+        if attempt.indices.contains(&share.index) {
+            return Err(SealError::DuplicateShare(share.index));
+        }
+        let shark_share = SharkShare::try_from(share.data.as_slice())
+            .map_err(|_| SealError::InvalidShare("malformed share data".into()))?;
+        attempt.shares.push(shark_share);
+        attempt.indices.insert(share.index);
+
+        // shares.len() is always bounded by threshold (a u8), so the cast is safe.
+        #[allow(clippy::cast_possible_truncation)]
+        let progress = attempt.shares.len() as u8;
+        if progress < threshold {
+            return Ok(GenerateRootProgress {
+                threshold,
+                progress,
+                root_token: None,
+            });
+        }
+
+        let Some(attempt) = self.generate_root.take() else {
+            return Err(SealError::StaleNonce);
+        };
+        if let Err(e) = self.recover_master_key(&attempt.shares) {
+            if matches!(e, SealError::ReconstructionFailed) {
+                self.record_failed_attempt();
+            }
+            return Err(e);
+        }
+        self.failed_attempts = 0;
+
+        let root_token = egide_crypto::random::generate_token(32)
+            .map_err(|e| SealError::Crypto(e.to_string()))?;
+        let root_token_hash = hash_token(&root_token)?;
+        self.storage
+            .put(keys::ROOT_TOKEN_HASH, root_token_hash.as_bytes())
+            .await?;
+        self.last_root_rotation = Some(Instant::now());
+
+        info!("Root token regenerated from a share quorum");
+        Ok(GenerateRootProgress {
+            threshold,
+            progress,
+            root_token: Some(root_token.as_str().to_owned()),
+        })
+    }
+
+    /// Replaces the Shamir shares without changing the master key.
+    ///
+    /// Re-splits the in-memory master key with a fresh dealer under
+    /// `new_config`, persists the new threshold and total, and records
+    /// commitments to the new shares so that shares from any earlier set
+    /// stop reconstructing. Nothing encrypted under the master key needs to
+    /// change. The vault stays unsealed; the new shares are returned once
+    /// and are not stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::NotInitialized`] or [`SealError::Sealed`] unless
+    /// the vault is unsealed, [`SealError::InvalidConfig`] for an invalid
+    /// configuration or a dev-mode vault, and propagates storage failures.
+    pub async fn rekey(&mut self, new_config: ShamirConfig) -> Result<Vec<Share>, SealError> {
+        match self.status {
+            SealStatus::Uninitialized => return Err(SealError::NotInitialized),
+            SealStatus::Sealed => return Err(SealError::Sealed),
+            SealStatus::Unsealed => {},
+        }
+        if self.dev_mode {
+            return Err(SealError::InvalidConfig(
+                "dev mode vaults cannot be rekeyed".into(),
+            ));
+        }
+        new_config.validate()?;
+
+        let master_key = self.master_key.as_ref().ok_or(SealError::Sealed)?;
+        let shares = split_master_key(master_key.as_bytes(), &new_config);
+        let share_commitments = commit_shares(&shares)?;
+
+        // The commitments only match the new shares at the new threshold:
+        // written separately, a failure in between would leave no share set
+        // able to unseal.
+        let encoded_commitments = encode_commitments(&share_commitments);
+        self.storage
+            .put_all(&[
+                (keys::SHARE_COMMITMENTS, &encoded_commitments),
+                (keys::SHAMIR_THRESHOLD, &[new_config.threshold]),
+                (keys::SHAMIR_TOTAL, &[new_config.shares]),
+            ])
+            .await?;
+
+        self.share_commitments = Some(share_commitments);
+        self.threshold = new_config.threshold;
+        self.total_shares = new_config.shares;
+
+        info!(
+            shares = new_config.shares,
+            threshold = new_config.threshold,
+            "Vault rekeyed"
+        );
+
+        Ok(shares)
+    }
+
+    /// Replaces the master key with a freshly generated one.
+    ///
+    /// Generates a new master key, stores its verification HMAC, splits it
+    /// into a new share set with the current threshold and total, and mints
+    /// a new root token. The new shares and token are returned once; the old
+    /// shares and root token stop working immediately. The vault stays
+    /// unsealed under the new key.
+    ///
+    /// Engine keys are derived from the master key, so everything written
+    /// under the old key must be rewrapped before it becomes unreadable. The
+    /// old key is stored wrapped under the new one, in the same transaction
+    /// as the new HMAC, commitments and root token hash, and is available
+    /// through [`Self::previous_master_key`] after every unseal until
+    /// [`Self::finish_master_key_rotation`]. A second rotation is refused
+    /// while one is pending.
+    ///
+    /// The seal manager only swaps keys; it does not know the engines. The
+    /// caller (`ServiceContext::rotate_master_key` in the server) rewraps
+    /// secrets and transit keys with the old key, then calls
+    /// [`Self::finish_master_key_rotation`]. Both keys are in memory from
+    /// this call until then, and again from the next unseal if the process
+    /// stopped before the rewrap finished. Data in `SQLCipher` encrypted
+    /// storage is keyed by the master key and cannot be rekeyed yet, so the
+    /// server refuses the rotation when storage encryption is on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::NotInitialized`] or [`SealError::Sealed`] unless
+    /// the vault is unsealed, [`SealError::InvalidConfig`] for a dev-mode
+    /// vault or while a previous rotation is pending, and propagates storage
+    /// and crypto failures.
+    pub async fn rotate_master_key(&mut self) -> Result<InitResult, SealError> {
+        match self.status {
+            SealStatus::Uninitialized => return Err(SealError::NotInitialized),
+            SealStatus::Sealed => return Err(SealError::Sealed),
+            SealStatus::Unsealed => {},
+        }
+        if self.dev_mode {
+            return Err(SealError::InvalidConfig(
+                "the master key of a dev mode vault cannot be rotated".into(),
+            ));
+        }
+        if self.previous_master_key.is_some() {
+            return Err(SealError::InvalidConfig(
+                "a master key rotation is still pending".into(),
+            ));
+        }
+
+        let total = self
+            .storage
+            .get(keys::SHAMIR_TOTAL)
+            .await?
+            .and_then(|bytes| bytes.first().copied())
+            .ok_or_else(|| SealError::Storage("missing shamir total".into()))?;
+        let config = ShamirConfig {
+            shares: total,
+            threshold: self.threshold,
+        };
+        config.validate()?;
+
+        let master_key = MasterKey::generate().map_err(|e| SealError::Crypto(e.to_string()))?;
+        let master_key_hmac = compute_master_key_hmac(master_key.as_bytes())?;
+        let shares = split_master_key(master_key.as_bytes(), &config);
+        let share_commitments = commit_shares(&shares)?;
+
+        let root_token = egide_crypto::random::generate_token(32)
+            .map_err(|e| SealError::Crypto(e.to_string()))?;
+        let root_token_hash = hash_token(&root_token)?;
+        let auto_unseal_blob = match &self.auto_unseal {
+            Some(backend) => Some(backend.wrap(master_key.as_bytes()).await?),
+            None => None,
+        };
+
+        let old_key = self.master_key.as_ref().ok_or(SealError::Sealed)?;
+        let pending_rotation = aead::encrypt(
+            master_key.as_bytes(),
+            old_key.as_bytes(),
+            Some(PENDING_ROTATION_AAD),
+        )
+        .map_err(|e| SealError::Crypto(e.to_string()))?;
+
+        let encoded_commitments = encode_commitments(&share_commitments);
+        let mut entries: Vec<(&str, &[u8])> = vec![
+            (keys::MASTER_KEY_HMAC, &master_key_hmac),
+            (keys::SHARE_COMMITMENTS, &encoded_commitments),
+            (keys::ROOT_TOKEN_HASH, root_token_hash.as_bytes()),
+            (keys::PENDING_ROTATION, &pending_rotation),
+        ];
+        if let Some(blob) = &auto_unseal_blob {
+            entries.push((keys::AUTO_UNSEAL_BLOB, blob));
+        }
+        self.storage.put_all(&entries).await?;
+
+        self.expected_hmac = Some(master_key_hmac);
+        self.share_commitments = Some(share_commitments);
+        self.previous_master_key = self.master_key.replace(master_key);
+        self.last_root_rotation = Some(Instant::now());
+
+        warn!("Master key rotated - rewrap engine data, then finish the rotation");
+
+        Ok(InitResult {
+            root_token: root_token.as_str().to_owned(),
+            shares,
+            pgp_shares: Vec::new(),
+        })
+    }
+
+    /// Returns the master key replaced by the pending rotation, if any.
+    #[must_use]
+    pub fn previous_master_key(&self) -> Option<&MasterKey> {
+        self.previous_master_key.as_ref()
+    }
 
+    /// Drops the old master key once everything was rewrapped under the new
+    /// one, in memory and in storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::Sealed`] unless the vault is unsealed, since the
+    /// stored old key can only be checked under the current one, and
+    /// propagates storage failures.
+    pub async fn finish_master_key_rotation(&mut self) -> Result<(), SealError> {
+        if self.status != SealStatus::Unsealed {
+            return Err(SealError::Sealed);
+        }
+        self.storage.delete(keys::PENDING_ROTATION).await?;
+        if self.previous_master_key.take().is_some() {
+            info!("Master key rotation finished");
+        }
         Ok(())
     }
 
@@ -404,8 +1241,8 @@ impl SealManager {
         }
 
         self.master_key = None;
-        self.pending_shares.clear();
-        self.pending_indices.clear();
+        self.previous_master_key = None;
+        self.reset_pending();
         self.status = SealStatus::Sealed;
 
         info!("Egide sealed");
@@ -460,9 +1297,8 @@ impl SealManager {
         self.storage
             .put(keys::ROOT_TOKEN_HASH, root_token_hash.as_bytes())
             .await?;
-        self.storage
-            .put(keys::DEV_MODE_KEY, master_key.as_bytes())
-            .await?;
+        let dev_mode_blob = DevModeUnseal.wrap(master_key.as_bytes()).await?;
+        self.storage.put(keys::DEV_MODE_KEY, &dev_mode_blob).await?;
         self.storage.put(keys::SHAMIR_THRESHOLD, &[1]).await?;
         self.storage.put(keys::SHAMIR_TOTAL, &[1]).await?;
         self.storage
@@ -477,12 +1313,53 @@ impl SealManager {
         self.status = SealStatus::Unsealed;
         self.dev_mode = true;
         self.threshold = 1;
+        self.total_shares = 1;
+        self.initialized_at = Some(now);
 
         warn!("Dev mode enabled - root token: {}", root_token.as_str());
 
         Ok(())
     }
 
+    /// Replaces the root token with a freshly generated one.
+    ///
+    /// Stores the hash of the new token in place of the old one, which stops
+    /// validating immediately, and returns the new token. Like the token from
+    /// [`Self::initialize`], it is never stored in clear and cannot be shown
+    /// again. Rotations closer together than the configured interval (60
+    /// seconds by default) are refused; the interval is tracked in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::NotInitialized`] before initialization,
+    /// [`SealError::RootRotationRateLimited`] within the rotation interval,
+    /// and propagates storage and hashing failures.
+    pub async fn rotate_root_token(&mut self) -> Result<String, SealError> {
+        if self.status == SealStatus::Uninitialized {
+            return Err(SealError::NotInitialized);
+        }
+        if let Some(last) = self.last_root_rotation {
+            let remaining = self.root_rotation_interval.saturating_sub(last.elapsed());
+            if !remaining.is_zero() {
+                // Round up so a client never retries a fraction of a second early.
+                let retry_after_secs =
+                    remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+                return Err(SealError::RootRotationRateLimited { retry_after_secs });
+            }
+        }
+
+        let root_token = egide_crypto::random::generate_token(32)
+            .map_err(|e| SealError::Crypto(e.to_string()))?;
+        let root_token_hash = hash_token(&root_token)?;
+        self.storage
+            .put(keys::ROOT_TOKEN_HASH, root_token_hash.as_bytes())
+            .await?;
+        self.last_root_rotation = Some(Instant::now());
+
+        info!("Root token rotated");
+        Ok(root_token.to_string())
+    }
+
     /// Verifies a root token.
     pub async fn verify_root_token(&self, token: &str) -> Result<bool, SealError> {
         let stored_hash = self
@@ -516,6 +1393,53 @@ fn current_unix_secs() -> Result<u64, SealError> {
         .map_err(|e| SealError::Crypto(format!("system clock error: {e}")))
 }
 
+/// Splits the master key into `config.shares` Shamir shares.
+fn split_master_key(master_key: &[u8], config: &ShamirConfig) -> Vec<Share> {
+    let sharks = Sharks(config.threshold);
+    sharks
+        .dealer(master_key)
+        .take(config.shares as usize)
+        .map(|s| {
+            let bytes: Vec<u8> = (&s).into();
+            Share {
+                index: bytes[0],
+                data: bytes,
+            }
+        })
+        .collect()
+}
+
+/// Commits to every share of a share set.
+fn commit_shares(shares: &[Share]) -> Result<Vec<Commitment>, SealError> {
+    shares
+        .iter()
+        .map(|share| commitment::commit(&share.data).map_err(|e| SealError::Crypto(e.to_string())))
+        .collect()
+}
+
+/// Serializes share commitments for storage.
+fn encode_commitments(commitments: &[Commitment]) -> Vec<u8> {
+    commitments.iter().flat_map(Commitment::to_bytes).collect()
+}
+
+/// Parses share commitments written by [`encode_commitments`].
+fn decode_commitments(bytes: &[u8]) -> Result<Vec<Commitment>, SealError> {
+    if !bytes.len().is_multiple_of(COMMITMENT_SIZE) {
+        return Err(SealError::Storage("malformed share commitments".into()));
+    }
+    bytes
+        .chunks_exact(COMMITMENT_SIZE)
+        .map(|chunk| Commitment::from_bytes(chunk).map_err(|e| SealError::Storage(e.to_string())))
+        .collect()
+}
+
+/// Returns true if the encoded share is one of the committed shares.
+fn is_committed(commitments: &[Commitment], share_data: &[u8]) -> bool {
+    commitments
+        .iter()
+        .any(|c| commitment::verify_commitment(share_data, c))
+}
+
 /// Computes HMAC-SHA256 of the master key for verification.
 ///
 /// Returns a [`SealError::Crypto`] if the underlying MAC construction fails,
@@ -549,59 +1473,13 @@ fn hash_token(token: &str) -> Result<String, SealError> {
 }
 
 /// Verifies a token against an Argon2id hash.
-fn verify_token(token: &str, hash: &str) -> bool {
-    let Ok(parsed_hash) = PasswordHash::new(hash) else {
-        return false;
-    };
-    Argon2::default()
-        .verify_password(token.as_bytes(), &parsed_hash)
-        .is_ok()
-}
-
-/// Encodes bytes as lowercase hex.
-fn hex_encode(bytes: &[u8]) -> String {
-    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
-    let mut hex = String::with_capacity(bytes.len() * 2);
-    for byte in bytes {
-        hex.push(HEX_CHARS[(byte >> 4) as usize] as char);
-        hex.push(HEX_CHARS[(byte & 0x0F) as usize] as char);
-    }
-    hex
-}
-
-/// Decodes hex to bytes.
-///
-/// Operates on bytes rather than string slice ranges, so a non-ASCII input
-/// (which may contain multi-byte UTF-8 characters) is rejected with an error
-/// instead of panicking on a misaligned char boundary.
-fn hex_decode(hex: &str) -> Result<Vec<u8>, String> {
-    if !hex.is_ascii() {
-        return Err("hex string must contain only ASCII characters".into());
-    }
-    if !hex.len().is_multiple_of(2) {
-        return Err("odd length hex string".into());
-    }
-
-    hex.as_bytes()
-        .chunks(2)
-        .map(|pair| {
-            let high = hex_digit_value(pair[0])?;
-            let low = hex_digit_value(pair[1])?;
-            Ok((high << 4) | low)
-        })
-        .collect()
-}
-
-/// Converts a single ASCII byte into its hex digit value.
-fn hex_digit_value(byte: u8) -> Result<u8, String> {
-    // `to_digit(16)` only ever returns values in 0..16, so the truncating
-    // cast to u8 is always exact.
-    #[allow(clippy::cast_possible_truncation)]
-    let value = (byte as char)
-        .to_digit(16)
-        .map(|digit| digit as u8)
-        .ok_or_else(|| format!("invalid hex digit: {}", byte as char))?;
-    Ok(value)
+fn verify_token(token: &str, hash: &str) -> bool {
+    let Ok(parsed_hash) = PasswordHash::new(hash) else {
+        return false;
+    };
+    Argon2::default()
+        .verify_password(token.as_bytes(), &parsed_hash)
+        .is_ok()
 }
 
 /// Returns true when the operator explicitly opted into dev mode for this
@@ -659,6 +1537,12 @@ mod tests {
         (tmp, manager)
     }
 
+    /// Submits a share to the active unseal operation, beginning one if needed.
+    async fn submit(manager: &mut SealManager, share: &Share) -> Result<UnsealProgress, SealError> {
+        let nonce = manager.begin_unseal()?;
+        manager.unseal(&nonce, share).await
+    }
+
     #[tokio::test]
     async fn test_initial_status_uninitialized() {
         let (_tmp, manager) = setup().await;
@@ -681,6 +1565,47 @@ mod tests {
         assert_eq!(manager.status(), SealStatus::Sealed);
     }
 
+    #[tokio::test]
+    async fn test_initialize_rejects_pgp_key_count_mismatch() {
+        let (_tmp, mut manager) = setup().await;
+
+        let result = manager
+            .initialize_with(
+                ShamirConfig {
+                    shares: 3,
+                    threshold: 2,
+                },
+                InitOptions {
+                    pgp_keys: Some(vec!["key".into(); 2]),
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(SealError::InvalidConfig(_))));
+        assert_eq!(manager.status(), SealStatus::Uninitialized);
+    }
+
+    #[cfg(not(feature = "pgp"))]
+    #[tokio::test]
+    async fn test_pgp_keys_fail_closed_without_pgp_feature() {
+        let (_tmp, mut manager) = setup().await;
+
+        let result = manager
+            .initialize_with(
+                ShamirConfig {
+                    shares: 2,
+                    threshold: 2,
+                },
+                InitOptions {
+                    pgp_keys: Some(vec!["key".into(); 2]),
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(SealError::InvalidConfig(_))));
+        assert_eq!(manager.status(), SealStatus::Uninitialized);
+    }
+
     #[tokio::test]
     async fn test_initialize_twice_fails() {
         let (_tmp, mut manager) = setup().await;
@@ -709,7 +1634,7 @@ mod tests {
 
         // Submit 3 shares (threshold)
         for i in 0..3 {
-            let progress = manager.unseal(&init_result.shares[i]).await.unwrap();
+            let progress = submit(&mut manager, &init_result.shares[i]).await.unwrap();
 
             if i < 2 {
                 assert!(progress.sealed);
@@ -726,6 +1651,61 @@ mod tests {
         assert!(manager.master_key().is_some());
     }
 
+    #[tokio::test]
+    async fn test_unseal_progress_is_read_only() {
+        let (_tmp, mut manager) = setup().await;
+
+        let init_result = manager
+            .initialize(ShamirConfig {
+                shares: 5,
+                threshold: 3,
+            })
+            .await
+            .unwrap();
+        assert_eq!(manager.unseal_progress().progress, 0);
+
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        for _ in 0..2 {
+            let progress = manager.unseal_progress();
+            assert!(progress.sealed);
+            assert_eq!(progress.threshold, 3);
+            assert_eq!(progress.progress, 1);
+        }
+        assert_eq!(manager.status(), SealStatus::Sealed);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_root_token_replaces_old_token() {
+        let (_tmp, mut manager) = setup().await;
+        assert!(matches!(
+            manager.rotate_root_token().await,
+            Err(SealError::NotInitialized)
+        ));
+
+        let init_result = manager
+            .initialize(ShamirConfig {
+                shares: 3,
+                threshold: 2,
+            })
+            .await
+            .unwrap();
+
+        let new_token = manager.rotate_root_token().await.unwrap();
+        assert_ne!(new_token, init_result.root_token);
+        assert!(manager.verify_root_token(&new_token).await.unwrap());
+        assert!(!manager
+            .verify_root_token(&init_result.root_token)
+            .await
+            .unwrap());
+
+        assert!(matches!(
+            manager.rotate_root_token().await,
+            Err(SealError::RootRotationRateLimited { .. })
+        ));
+        manager.set_root_rotation_interval(Duration::ZERO);
+        assert!(manager.rotate_root_token().await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_unseal_duplicate_share_fails() {
         let (_tmp, mut manager) = setup().await;
@@ -737,12 +1717,416 @@ mod tests {
 
         let init_result = manager.initialize(config).await.unwrap();
 
-        manager.unseal(&init_result.shares[0]).await.unwrap();
-        let result = manager.unseal(&init_result.shares[0]).await;
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        let result = submit(&mut manager, &init_result.shares[0]).await;
 
         assert!(matches!(result, Err(SealError::DuplicateShare(_))));
     }
 
+    #[tokio::test]
+    async fn test_rekey_retires_old_shares() {
+        let (_tmp, mut manager) = setup().await;
+
+        let init_result = manager
+            .initialize(ShamirConfig {
+                shares: 3,
+                threshold: 2,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager
+                .rekey(ShamirConfig {
+                    shares: 5,
+                    threshold: 3,
+                })
+                .await,
+            Err(SealError::Sealed)
+        ));
+
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        submit(&mut manager, &init_result.shares[1]).await.unwrap();
+        let old_key = manager.master_key().unwrap().as_bytes().to_vec();
+
+        let new_shares = manager
+            .rekey(ShamirConfig {
+                shares: 5,
+                threshold: 3,
+            })
+            .await
+            .unwrap();
+        assert_eq!(new_shares.len(), 5);
+        assert_eq!(manager.status(), SealStatus::Unsealed);
+        manager.seal();
+
+        // The old quorum no longer unseals, even at the new threshold.
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        submit(&mut manager, &init_result.shares[1]).await.unwrap();
+        assert!(matches!(
+            submit(&mut manager, &init_result.shares[2]).await,
+            Err(SealError::ReconstructionFailed)
+        ));
+        assert_eq!(manager.status(), SealStatus::Sealed);
+        assert!(manager.pending_shares.is_empty());
+
+        for share in &new_shares[..3] {
+            submit(&mut manager, share).await.unwrap();
+        }
+        assert_eq!(manager.status(), SealStatus::Unsealed);
+        assert_eq!(manager.master_key().unwrap().as_bytes(), &old_key[..]);
+        assert_eq!(manager.unseal_progress().threshold, 3);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_master_key_keeps_old_key_until_finished() {
+        let (tmp, mut manager) = setup().await;
+
+        let init_result = manager
+            .initialize(ShamirConfig {
+                shares: 3,
+                threshold: 2,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.rotate_master_key().await,
+            Err(SealError::Sealed)
+        ));
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        submit(&mut manager, &init_result.shares[1]).await.unwrap();
+        let old_key = manager.master_key().unwrap().as_bytes().to_vec();
+
+        let rotated = manager.rotate_master_key().await.unwrap();
+        assert_eq!(rotated.shares.len(), 3);
+        let new_key = manager.master_key().unwrap().as_bytes().to_vec();
+        assert_ne!(new_key, old_key);
+        assert_eq!(
+            manager.previous_master_key().unwrap().as_bytes(),
+            &old_key[..]
+        );
+        assert!(matches!(
+            manager.rotate_master_key().await,
+            Err(SealError::InvalidConfig(_))
+        ));
+        assert!(manager
+            .verify_root_token(&rotated.root_token)
+            .await
+            .unwrap());
+        assert!(!manager
+            .verify_root_token(&init_result.root_token)
+            .await
+            .unwrap());
+
+        // The old key survives a seal and a restart until the rotation is
+        // finished.
+        manager.seal();
+        drop(manager);
+        let mut manager = SealManager::new(tmp.path()).await.unwrap();
+        submit(&mut manager, &rotated.shares[0]).await.unwrap();
+        submit(&mut manager, &rotated.shares[1]).await.unwrap();
+        assert_eq!(manager.master_key().unwrap().as_bytes(), &new_key[..]);
+        assert_eq!(
+            manager.previous_master_key().unwrap().as_bytes(),
+            &old_key[..]
+        );
+
+        manager.finish_master_key_rotation().await.unwrap();
+        assert!(manager.previous_master_key().is_none());
+        manager.seal();
+
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        assert!(matches!(
+            submit(&mut manager, &init_result.shares[1]).await,
+            Err(SealError::ReconstructionFailed)
+        ));
+        submit(&mut manager, &rotated.shares[1]).await.unwrap();
+        submit(&mut manager, &rotated.shares[2]).await.unwrap();
+        assert_eq!(manager.master_key().unwrap().as_bytes(), &new_key[..]);
+    }
+
+    #[tokio::test]
+    async fn test_verify_share_rejects_foreign_and_tampered_shares() {
+        let (_tmp, mut manager) = setup().await;
+        let (_other_tmp, mut other) = setup().await;
+        let config = ShamirConfig {
+            shares: 3,
+            threshold: 2,
+        };
+
+        let own = Share {
+            index: 1,
+            data: vec![1; MAX_SHARE_LEN],
+        };
+        assert!(matches!(
+            manager.verify_share(&own),
+            Err(SealError::NotInitialized)
+        ));
+
+        let init_result = manager.initialize(config.clone()).await.unwrap();
+        let foreign = other.initialize(config).await.unwrap();
+
+        for share in &init_result.shares {
+            assert!(manager.verify_share(share).unwrap());
+        }
+        assert!(!manager.verify_share(&foreign.shares[0]).unwrap());
+
+        let mut tampered = init_result.shares[0].clone();
+        tampered.data[MAX_SHARE_LEN - 1] ^= 0x01;
+        assert!(!manager.verify_share(&tampered).unwrap());
+
+        let mut truncated = init_result.shares[0].clone();
+        truncated.data.pop();
+        assert!(!manager.verify_share(&truncated).unwrap());
+
+        // Checking a share never counts toward the unseal.
+        assert_eq!(manager.unseal_progress().progress, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unseal_rejects_stale_nonce() {
+        let (_tmp, mut manager) = setup().await;
+        assert!(matches!(
+            manager.begin_unseal(),
+            Err(SealError::NotInitialized)
+        ));
+
+        let init_result = manager
+            .initialize(ShamirConfig {
+                shares: 3,
+                threshold: 2,
+            })
+            .await
+            .unwrap();
+
+        let nonce = manager.begin_unseal().unwrap();
+        assert_eq!(manager.begin_unseal().unwrap(), nonce);
+        manager
+            .unseal(&nonce, &init_result.shares[0])
+            .await
+            .unwrap();
+
+        let new_nonce = manager.reset_unseal().unwrap();
+        assert_ne!(new_nonce, nonce);
+        assert_eq!(manager.unseal_progress().progress, 0);
+        assert!(matches!(
+            manager.unseal(&nonce, &init_result.shares[1]).await,
+            Err(SealError::StaleNonce)
+        ));
+        assert_eq!(manager.unseal_progress().progress, 0);
+
+        manager
+            .unseal(&new_nonce, &init_result.shares[1])
+            .await
+            .unwrap();
+        manager
+            .unseal(&new_nonce, &init_result.shares[2])
+            .await
+            .unwrap();
+        assert_eq!(manager.status(), SealStatus::Unsealed);
+        assert!(matches!(
+            manager.begin_unseal(),
+            Err(SealError::AlreadyUnsealed)
+        ));
+    }
+
+    /// Test backend "wrapping" with a single-byte XOR key.
+    struct XorUnseal(u8);
+
+    #[async_trait]
+    impl AutoUnsealBackend for XorUnseal {
+        async fn wrap(&self, master: &[u8]) -> Result<Vec<u8>, SealError> {
+            Ok(master.iter().map(|b| b ^ self.0).collect())
+        }
+
+        async fn unwrap(&self, blob: &[u8]) -> Result<Zeroizing<Vec<u8>>, SealError> {
+            Ok(Zeroizing::new(blob.iter().map(|b| b ^ self.0).collect()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_unseal_on_restart() {
+        let tmp = TempDir::new().unwrap();
+        let config = ShamirConfig {
+            shares: 3,
+            threshold: 2,
+        };
+
+        let (shares, master) = {
+            let mut manager = SealManager::with_auto_unseal(tmp.path(), Arc::new(XorUnseal(0x5a)))
+                .await
+                .unwrap();
+            let init_result = manager.initialize(config).await.unwrap();
+            assert_eq!(manager.status(), SealStatus::Unsealed);
+            (
+                init_result.shares,
+                manager.master_key().unwrap().as_bytes().to_vec(),
+            )
+        };
+
+        let manager = SealManager::with_auto_unseal(tmp.path(), Arc::new(XorUnseal(0x5a)))
+            .await
+            .unwrap();
+        assert_eq!(manager.status(), SealStatus::Unsealed);
+        assert_eq!(manager.master_key().unwrap().as_bytes(), &master[..]);
+        assert!(!manager.is_dev_mode());
+        drop(manager);
+
+        // A backend that unwraps to the wrong key leaves the vault sealed.
+        let manager = SealManager::with_auto_unseal(tmp.path(), Arc::new(XorUnseal(0x33)))
+            .await
+            .unwrap();
+        assert_eq!(manager.status(), SealStatus::Sealed);
+        drop(manager);
+
+        // Without the backend, the Shamir shares still unseal.
+        let mut manager = SealManager::new(tmp.path()).await.unwrap();
+        assert_eq!(manager.status(), SealStatus::Sealed);
+        submit(&mut manager, &shares[0]).await.unwrap();
+        submit(&mut manager, &shares[1]).await.unwrap();
+        assert_eq!(manager.master_key().unwrap().as_bytes(), &master[..]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_root_with_share_quorum() {
+        let (_tmp, mut manager) = setup().await;
+        let (_other_tmp, mut other) = setup().await;
+        let config = ShamirConfig {
+            shares: 3,
+            threshold: 2,
+        };
+
+        let init_result = manager.initialize(config.clone()).await.unwrap();
+        let foreign = other.initialize(config).await.unwrap();
+
+        // A quorum of foreign shares ends the attempt without a token.
+        let nonce = manager.begin_generate_root().unwrap();
+        manager
+            .generate_root_submit(&nonce, &foreign.shares[0])
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager
+                .generate_root_submit(&nonce, &foreign.shares[1])
+                .await,
+            Err(SealError::ReconstructionFailed)
+        ));
+        assert!(matches!(
+            manager
+                .generate_root_submit(&nonce, &init_result.shares[0])
+                .await,
+            Err(SealError::StaleNonce)
+        ));
+
+        // Works while sealed; the old root token stops validating.
+        let nonce = manager.begin_generate_root().unwrap();
+        let progress = manager
+            .generate_root_submit(&nonce, &init_result.shares[0])
+            .await
+            .unwrap();
+        assert_eq!(progress.progress, 1);
+        assert!(progress.root_token.is_none());
+        let progress = manager
+            .generate_root_submit(&nonce, &init_result.shares[2])
+            .await
+            .unwrap();
+        let new_token = progress.root_token.unwrap();
+        assert!(manager.verify_root_token(&new_token).await.unwrap());
+        assert!(!manager
+            .verify_root_token(&init_result.root_token)
+            .await
+            .unwrap());
+        assert_eq!(manager.status(), SealStatus::Sealed);
+
+        // And while unsealed.
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        submit(&mut manager, &init_result.shares[1]).await.unwrap();
+        let nonce = manager.begin_generate_root().unwrap();
+        manager
+            .generate_root_submit(&nonce, &init_result.shares[1])
+            .await
+            .unwrap();
+        let newer_token = manager
+            .generate_root_submit(&nonce, &init_result.shares[2])
+            .await
+            .unwrap()
+            .root_token
+            .unwrap();
+        assert!(manager.verify_root_token(&newer_token).await.unwrap());
+        assert!(!manager.verify_root_token(&new_token).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_check_idle_seals_after_timeout() {
+        let (_tmp, mut manager) = setup().await;
+
+        let init_result = manager
+            .initialize(ShamirConfig {
+                shares: 3,
+                threshold: 2,
+            })
+            .await
+            .unwrap();
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        submit(&mut manager, &init_result.shares[1]).await.unwrap();
+        assert!(!manager.check_idle(), "no idle timeout by default");
+
+        manager.set_seal_after_idle(Some(Duration::from_hours(1)));
+        manager.touch();
+        assert!(!manager.check_idle());
+        assert_eq!(manager.status(), SealStatus::Unsealed);
+
+        manager.set_seal_after_idle(Some(Duration::ZERO));
+        assert!(manager.check_idle());
+        assert_eq!(manager.status(), SealStatus::Sealed);
+        assert!(manager.master_key().is_none());
+        assert!(!manager.check_idle());
+    }
+
+    #[tokio::test]
+    async fn test_check_idle_ignored_in_dev_mode() {
+        let (_tmp, mut manager) = setup().await;
+        manager.enable_dev_mode_unchecked().await.unwrap();
+
+        manager.set_seal_after_idle(Some(Duration::ZERO));
+        assert!(!manager.check_idle());
+        assert_eq!(manager.status(), SealStatus::Unsealed);
+    }
+
+    #[tokio::test]
+    async fn test_seal_status_detail() {
+        let tmp = TempDir::new().unwrap();
+        let mut manager = SealManager::new(tmp.path()).await.unwrap();
+        let detail = manager.seal_status();
+        assert_eq!(detail.status, SealStatus::Uninitialized);
+        assert_eq!(detail.total_shares, 0);
+        assert_eq!(detail.initialized_at, None);
+
+        let init_result = manager
+            .initialize(ShamirConfig {
+                shares: 5,
+                threshold: 3,
+            })
+            .await
+            .unwrap();
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        let detail = manager.seal_status();
+        assert_eq!(detail.status, SealStatus::Sealed);
+        assert_eq!(detail.total_shares, 5);
+        assert_eq!(detail.threshold, 3);
+        assert_eq!(detail.progress, 1);
+        assert!(detail.initialized_at.is_some());
+        assert!(!detail.dev_mode);
+        drop(manager);
+
+        // The configuration survives a restart; the progress does not.
+        let manager = SealManager::new(tmp.path()).await.unwrap();
+        let reloaded = manager.seal_status();
+        assert_eq!(reloaded.total_shares, 5);
+        assert_eq!(reloaded.threshold, 3);
+        assert_eq!(reloaded.progress, 0);
+        assert_eq!(reloaded.initialized_at, detail.initialized_at);
+    }
+
     #[tokio::test]
     async fn test_seal_clears_master_key() {
         let (_tmp, mut manager) = setup().await;
@@ -754,8 +2138,8 @@ mod tests {
 
         let init_result = manager.initialize(config).await.unwrap();
 
-        manager.unseal(&init_result.shares[0]).await.unwrap();
-        manager.unseal(&init_result.shares[1]).await.unwrap();
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        submit(&mut manager, &init_result.shares[1]).await.unwrap();
 
         assert!(manager.master_key().is_some());
 
@@ -879,10 +2263,33 @@ mod tests {
 
     #[test]
     fn hex_decode_rejects_non_ascii_input_without_panicking() {
-        let result = hex_decode("0\u{e9}0");
+        let mut buf = [0u8; MAX_SHARE_LEN];
+        let result = encoding::decode_hex_into("0\u{e9}0", &mut buf);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn from_hex_rejects_oversized_input() {
+        let result = Share::from_hex(&"01".repeat(MAX_SHARE_LEN + 1));
+        assert!(matches!(result, Err(SealError::InvalidShare(_))));
+    }
+
+    #[tokio::test]
+    async fn generated_shares_fit_the_share_buffer() {
+        let (_tmp, mut manager) = setup().await;
+        let init = manager
+            .initialize(ShamirConfig {
+                shares: 3,
+                threshold: 2,
+            })
+            .await
+            .unwrap();
+        for share in &init.shares {
+            assert_eq!(share.data.len(), MAX_SHARE_LEN);
+            assert_eq!(Share::from_hex(&share.to_hex()).unwrap().data, share.data);
+        }
+    }
+
     #[test]
     fn from_hex_rejects_odd_length_input() {
         let result = Share::from_hex("abc");
@@ -973,8 +2380,8 @@ mod tests {
             assert_eq!(manager.status(), SealStatus::Sealed);
 
             // Should be able to unseal with same shares
-            manager.unseal(&shares[0]).await.unwrap();
-            manager.unseal(&shares[1]).await.unwrap();
+            submit(&mut manager, &shares[0]).await.unwrap();
+            submit(&mut manager, &shares[1]).await.unwrap();
             assert_eq!(manager.status(), SealStatus::Unsealed);
 
             // Root token should still work
@@ -1002,13 +2409,76 @@ mod tests {
 
         // Try to unseal vault A with shares from vault B
         // This should fail because the reconstructed key won't match the expected HMAC
-        manager_a.unseal(&result_b.shares[0]).await.unwrap();
-        let result = manager_a.unseal(&result_b.shares[1]).await;
+        submit(&mut manager_a, &result_b.shares[0]).await.unwrap();
+        let result = submit(&mut manager_a, &result_b.shares[1]).await;
 
         assert!(matches!(result, Err(SealError::ReconstructionFailed)));
         assert_eq!(manager_a.status(), SealStatus::Sealed);
     }
 
+    #[tokio::test]
+    async fn test_repeated_failed_reconstructions_trip_lockout() {
+        let tmp_a = TempDir::new().unwrap();
+        let tmp_b = TempDir::new().unwrap();
+        let config = ShamirConfig {
+            shares: 3,
+            threshold: 2,
+        };
+
+        let mut manager = SealManager::new(tmp_a.path()).await.unwrap();
+        let valid = manager.initialize(config.clone()).await.unwrap();
+        manager.set_unseal_lockout(UnsealLockout {
+            max_failed_attempts: 2,
+            cooldown: Duration::from_hours(1),
+        });
+
+        let mut other = SealManager::new(tmp_b.path()).await.unwrap();
+        let foreign = other.initialize(config).await.unwrap();
+
+        for _ in 0..2 {
+            submit(&mut manager, &foreign.shares[0]).await.unwrap();
+            assert!(matches!(
+                submit(&mut manager, &foreign.shares[1]).await,
+                Err(SealError::ReconstructionFailed)
+            ));
+        }
+
+        // Locked out: even a valid share is refused during the cooldown.
+        let result = submit(&mut manager, &valid.shares[0]).await;
+        assert!(
+            matches!(result, Err(SealError::UnsealLockedOut { retry_after_secs }) if retry_after_secs > 3500),
+            "got {result:?}"
+        );
+        assert_eq!(manager.status(), SealStatus::Sealed);
+        assert!(manager.pending_shares.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lockout_expires_after_cooldown() {
+        let tmp_a = TempDir::new().unwrap();
+        let tmp_b = TempDir::new().unwrap();
+        let config = ShamirConfig {
+            shares: 3,
+            threshold: 2,
+        };
+
+        let mut manager = SealManager::new(tmp_a.path()).await.unwrap();
+        let valid = manager.initialize(config.clone()).await.unwrap();
+        manager.set_unseal_lockout(UnsealLockout {
+            max_failed_attempts: 1,
+            cooldown: Duration::ZERO,
+        });
+
+        let mut other = SealManager::new(tmp_b.path()).await.unwrap();
+        let foreign = other.initialize(config).await.unwrap();
+        submit(&mut manager, &foreign.shares[0]).await.unwrap();
+        assert!(submit(&mut manager, &foreign.shares[1]).await.is_err());
+
+        submit(&mut manager, &valid.shares[0]).await.unwrap();
+        submit(&mut manager, &valid.shares[1]).await.unwrap();
+        assert_eq!(manager.status(), SealStatus::Unsealed);
+    }
+
     #[tokio::test]
     async fn test_unseal_missing_hmac_fails() {
         let (tmp, mut manager) = setup().await;
@@ -1028,8 +2498,8 @@ mod tests {
         let mut manager = SealManager::new(tmp.path()).await.unwrap();
 
         // Even with enough shares, reconstruction should be rejected
-        manager.unseal(&init_result.shares[0]).await.unwrap();
-        let result = manager.unseal(&init_result.shares[1]).await;
+        submit(&mut manager, &init_result.shares[0]).await.unwrap();
+        let result = submit(&mut manager, &init_result.shares[1]).await;
 
         assert!(matches!(result, Err(SealError::ReconstructionFailed)));
         assert_eq!(manager.status(), SealStatus::Sealed);
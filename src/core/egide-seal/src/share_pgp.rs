@@ -0,0 +1,112 @@
+//! `OpenPGP` encryption of Shamir shares for their holders.
+//!
+//! Only compiled in with the `pgp` feature; without it, asking for encrypted
+//! shares fails closed rather than handing them out in clear.
+
+use crate::{SealError, Share};
+
+/// Encrypts `share` to the armored `OpenPGP` public key of its holder and
+/// returns the armored message.
+///
+/// The share is encrypted to the first encryption-capable subkey, or to the
+/// primary key if it can encrypt itself.
+#[cfg(feature = "pgp")]
+pub(crate) fn encrypt_share(armored_key: &str, share: &Share) -> Result<String, SealError> {
+    use argon2::password_hash::rand_core::OsRng;
+    use pgp::{
+        crypto::sym::SymmetricKeyAlgorithm, types::PublicKeyTrait, ArmorOptions, Deserializable,
+        Message, SignedPublicKey,
+    };
+
+    let (key, _) = SignedPublicKey::from_string(armored_key)
+        .map_err(|e| SealError::InvalidConfig(format!("pgp key: {e}")))?;
+    let message = Message::new_literal_bytes("", &share.data);
+    let encrypted = if let Some(subkey) = key
+        .public_subkeys
+        .iter()
+        .find(PublicKeyTrait::is_encryption_key)
+    {
+        message.encrypt_to_keys_seipdv1(&mut OsRng, SymmetricKeyAlgorithm::AES256, &[subkey])
+    } else if key.is_encryption_key() {
+        message.encrypt_to_keys_seipdv1(&mut OsRng, SymmetricKeyAlgorithm::AES256, &[&key])
+    } else {
+        return Err(SealError::InvalidConfig(
+            "pgp key has no encryption-capable key".into(),
+        ));
+    }
+    .map_err(|e| SealError::Crypto(e.to_string()))?;
+
+    encrypted
+        .to_armored_string(ArmorOptions::default())
+        .map_err(|e| SealError::Crypto(e.to_string()))
+}
+
+/// Refuses to encrypt: this build has no `OpenPGP` support.
+#[cfg(not(feature = "pgp"))]
+pub(crate) fn encrypt_share(_armored_key: &str, _share: &Share) -> Result<String, SealError> {
+    Err(SealError::InvalidConfig(
+        "pgp_keys requires egide-seal built with the `pgp` feature".into(),
+    ))
+}
+
+#[cfg(all(test, feature = "pgp"))]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use argon2::password_hash::rand_core::OsRng;
+    use pgp::{
+        types::SecretKeyTrait, ArmorOptions, Deserializable, KeyType, Message,
+        SecretKeyParamsBuilder, SignedSecretKey,
+    };
+
+    /// Generates an RSA key that encrypts with its primary key, returning
+    /// the secret key and the armored public key.
+    fn generate_key() -> (SignedSecretKey, String) {
+        let secret = SecretKeyParamsBuilder::default()
+            .key_type(KeyType::Rsa(2048))
+            .can_certify(true)
+            .can_encrypt(true)
+            .primary_user_id("Share Holder <holder@example.com>".into())
+            .build()
+            .unwrap()
+            .generate(OsRng)
+            .unwrap()
+            .sign(OsRng, String::new)
+            .unwrap();
+        let public = secret
+            .public_key()
+            .sign(OsRng, &secret, String::new)
+            .unwrap()
+            .to_armored_string(ArmorOptions::default())
+            .unwrap();
+        (secret, public)
+    }
+
+    #[test]
+    fn test_encrypt_share_decrypts_with_holder_key() {
+        let (secret, public) = generate_key();
+        let share = Share {
+            index: 1,
+            data: vec![1, 2, 3, 4, 5],
+        };
+
+        let armored = encrypt_share(&public, &share).unwrap();
+        assert!(armored.starts_with("-----BEGIN PGP MESSAGE-----"));
+
+        let (message, _) = Message::from_string(&armored).unwrap();
+        let (decrypted, _) = message.decrypt(String::new, &[&secret]).unwrap();
+        assert_eq!(decrypted.get_content().unwrap().unwrap(), share.data);
+    }
+
+    #[test]
+    fn test_encrypt_share_rejects_invalid_key() {
+        let share = Share {
+            index: 1,
+            data: vec![1],
+        };
+        assert!(matches!(
+            encrypt_share("not a key", &share),
+            Err(SealError::InvalidConfig(_))
+        ));
+    }
+}
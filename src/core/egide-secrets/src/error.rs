@@ -39,6 +39,28 @@ pub enum SecretsError {
         found: u32,
     },
 
+    /// Requested TTL is above the engine's configured maximum.
+    #[error("ttl of {requested_secs}s exceeds the maximum of {max_secs}s")]
+    TtlExceedsMax {
+        /// Requested TTL in seconds.
+        requested_secs: u64,
+        /// Configured maximum TTL in seconds.
+        max_secs: u64,
+    },
+
+    /// Secret data is larger than the engine's configured maximum.
+    #[error("secret data of {size} bytes exceeds the maximum of {max} bytes")]
+    ValueTooLarge {
+        /// Size of the offending value, or of the whole serialized data.
+        size: usize,
+        /// Configured maximum in bytes.
+        max: usize,
+    },
+
+    /// Metadata does not satisfy the engine's required fields.
+    #[error("invalid metadata: {0}")]
+    InvalidMetadata(String),
+
     /// Invalid secret path.
     #[error("invalid secret path: {0}")]
     InvalidPath(String),
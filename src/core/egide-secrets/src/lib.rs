@@ -20,22 +20,36 @@
 //! a path that is soft-deleted, purged, and re-created draws a fresh salt and
 //! never re-derives the key its previous generation used, even though
 //! numbering restarts at version 1. Each version row is inserted exactly
-//! once, so at most one ciphertext is ever persisted per derived key; the
-//! rare transient encryptions under a reused derivation context (CAS races)
-//! stay far below the NIST SP 800-38D bound on random 96-bit nonces (2^32
-//! messages per key), regardless of rotation rate.
+//! once, so at most one ciphertext is ever persisted per derived key, except
+//! that [`SecretsEngine::update_metadata`] and [`SecretsEngine::renew`]
+//! reseal a row in place; those reseals and the rare transient encryptions under a reused derivation
+//! context (CAS races) stay far below the NIST SP 800-38D bound on random
+//! 96-bit nonces (2^32 messages per key), regardless of rotation rate.
 //!
 //! Rows written before the generation salt existed carry no salt: they keep
 //! deriving under the prior `egide-secrets-v2:{path}:{version}` context
 //! instead of `v3`, so they remain readable without a migration pass over
 //! existing ciphertext.
 //!
+//! Each version row also records the derivation version it was written under
+//! (`kdf_version`), and is always decrypted under that recorded version. A
+//! later derivation scheme can therefore become the default for new writes
+//! while every existing row keeps decrypting, with no mass re-encryption.
+//! Rows written before the column existed carry `NULL` and are read as `v3`
+//! when salted and `v2` otherwise.
+//!
 //! The AEAD associated data is a canonical length-prefixed encoding of the
 //! domain tag, `path`, `version`, and the immutable per-version context columns
 //! `expires_at` and `metadata`. It seals each ciphertext to its storage
 //! coordinates and to that context: moving or swapping blobs between rows, or
 //! tampering with the expiry or metadata columns, fails authentication.
 //!
+//! Data fields configured as plaintext for a path (see
+//! [`SecretsEngine::with_plaintext_fields`]) are kept out of the ciphertext in
+//! the `plaintext_data` column, where they can be searched. When present, that
+//! column is bound into the associated data as well, so it cannot be altered
+//! without the version failing to decrypt.
+//!
 //! Alternatives considered and rejected: XChaCha20-Poly1305 (larger nonce
 //! but a new dependency, divergence from the AES-256-GCM doctrine used
 //! elsewhere, and no protection against cross-version splicing on its own)
@@ -45,35 +59,46 @@
 
 pub mod error;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::time::Duration;
 
 use tracing::{debug, info, warn};
+use zeroize::Zeroizing;
 
-use egide_crypto::{aead, kdf, mac, random, MasterKey};
+use egide_crypto::{aead, encoding, kdf, kdf::KdfContext, mac, random, MasterKey};
 use egide_storage::prefix_pattern;
 use egide_storage_sqlite::SqliteBackend;
 
 pub use error::SecretsError;
 
-/// Domain separation for secret encryption keys.
+/// KDF domain for secret encryption keys (`egide-secrets-v{n}:...`).
+const SECRET_KDF_DOMAIN: &str = "egide-secrets";
+
+/// Derivation version used for new writes.
 ///
-/// The `v3` bump binds a random per-generation salt into the derivation, so a
-/// path that is purged and re-created never re-derives the key it used
-/// before. Rows written under `v2` carry no salt and keep their original
-/// derivation via [`SECRET_KEY_INFO_PREFIX_V2`].
-const SECRET_KEY_INFO_PREFIX: &str = "egide-secrets-v3:";
-
-/// Domain separation for secret encryption keys derived without a generation
-/// salt.
+/// The `v3` bump binds a random per-generation salt into the derivation
+/// (`{path}:{version}:{generation_salt}`), so a path that is purged and
+/// re-created never re-derives the key it used before. Later versions keep
+/// that field layout.
+const SECRET_KDF_VERSION: u32 = 3;
+
+/// Derivation version of rows written without a generation salt.
 ///
-/// Kept alongside [`SECRET_KEY_INFO_PREFIX`] for as long as rows written
-/// under this scheme exist: it is what makes them still readable. The `v2`
-/// bump binds the secret version into the derivation, giving one key per
-/// `(path, version)` pair. Ciphertexts written under the `v1` scheme
-/// (path-only derivation) are deliberately not decryptable.
-const SECRET_KEY_INFO_PREFIX_V2: &str = "egide-secrets-v2:";
+/// Kept for as long as rows written under this scheme exist: it is what
+/// makes them still readable. The `v2` bump binds the secret version into the
+/// derivation (`{path}:{version}`), giving one key per `(path, version)` pair.
+/// Ciphertexts written under the `v1` scheme (path-only derivation) are
+/// deliberately not decryptable.
+const SECRET_KDF_VERSION_UNSALTED: u32 = 2;
+
+/// Default upper bound on a single secret value, and on the serialized data
+/// of a whole version.
+pub const DEFAULT_MAX_SECRET_BYTES: usize = 1024 * 1024;
+
+/// Secrets fetched per query when [`SecretsEngine::list`] pages through a
+/// prefix.
+const LIST_PAGE_SIZE: usize = 1000;
 
 /// Domain separation for the AEAD associated data.
 const SECRET_AAD_PREFIX: &str = "egide-secrets:";
@@ -89,7 +114,8 @@ CREATE TABLE IF NOT EXISTS secrets (
     deleted_at  INTEGER,
     created_at  INTEGER NOT NULL,
     updated_at  INTEGER NOT NULL,
-    row_mac     TEXT
+    row_mac     TEXT,
+    max_versions INTEGER
 );
 
 CREATE TABLE IF NOT EXISTS secret_versions (
@@ -102,6 +128,8 @@ CREATE TABLE IF NOT EXISTS secret_versions (
     created_at  INTEGER NOT NULL,
     created_by  TEXT,
     generation_salt TEXT,
+    kdf_version INTEGER,
+    plaintext_data TEXT,
     PRIMARY KEY (path, version)
 );
 
@@ -123,6 +151,23 @@ pub struct Secret {
     pub created_at: u64,
     /// Expiration timestamp (None = never expires).
     pub expires_at: Option<u64>,
+    /// Actor who created this version, if recorded.
+    pub created_by: Option<String>,
+    /// Set when `get` fell back to an older version because the current one
+    /// failed to decrypt; see [`SecretsEngine::with_fallback_to_last_good`].
+    pub degraded: bool,
+}
+
+impl Secret {
+    /// Time left before this version expires, or `None` if it never does.
+    ///
+    /// Rewrites pass it as the new version's `ttl` so a patch, copy, or move
+    /// keeps the lifetime it started from instead of making the secret
+    /// permanent.
+    pub fn remaining_ttl(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|expires_at| Duration::from_secs(expires_at.saturating_sub(SecretsEngine::now())))
+    }
 }
 
 /// Metadata about a secret (without decrypted data).
@@ -149,12 +194,137 @@ pub struct PutOptions {
     pub metadata: Option<serde_json::Value>,
     /// Check-and-set: only succeed if current version matches.
     pub cas: Option<u32>,
+    /// Actor recorded as the version's `created_by`; defaults to the storage
+    /// backend's current actor.
+    pub created_by: Option<String>,
+    /// Number of versions to keep, stored as the secret's default for later
+    /// writes that leave it unset. After the write, versions older than the
+    /// newest `max_versions` are deleted. `Some(0)` keeps every version
+    /// again; `None` keeps the stored default (initially unlimited).
+    pub max_versions: Option<u32>,
+}
+
+/// Options for deleting a secret.
+#[derive(Debug, Clone, Default)]
+pub struct DeleteOptions {
+    /// Check-and-set: only delete if the current version matches.
+    pub cas: Option<u32>,
+}
+
+/// Options for restoring a soft-deleted secret.
+#[derive(Debug, Clone, Default)]
+pub struct UndeleteOptions {
+    /// Check-and-set: only restore if the deleted secret's version matches.
+    pub cas: Option<u32>,
+}
+
+/// JSON type a required metadata field must hold; see
+/// [`SecretsEngine::with_required_metadata`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataType {
+    /// Any value, as long as the field is present.
+    Any,
+    /// A JSON string.
+    String,
+    /// A JSON number.
+    Number,
+    /// A JSON boolean.
+    Bool,
+    /// A JSON object.
+    Object,
+    /// A JSON array.
+    Array,
+}
+
+impl MetadataType {
+    /// Returns whether `value` has this type.
+    fn matches(self, value: &serde_json::Value) -> bool {
+        match self {
+            Self::Any => true,
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Bool => value.is_boolean(),
+            Self::Object => value.is_object(),
+            Self::Array => value.is_array(),
+        }
+    }
+}
+
+/// Which secrets [`SecretsEngine::list_with`] returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListOptions {
+    /// Include soft-deleted secrets alongside live ones (default: true).
+    pub include_deleted: bool,
+    /// Return soft-deleted secrets only, for recovery (default: false).
+    /// Takes precedence over `include_deleted`.
+    pub only_deleted: bool,
+}
+
+impl Default for ListOptions {
+    /// Every secret, live or deleted, as [`SecretsEngine::list`] returns.
+    fn default() -> Self {
+        Self {
+            include_deleted: true,
+            only_deleted: false,
+        }
+    }
+}
+
+/// What [`SecretsEngine::put`] does with a TTL above the configured maximum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaxTtlPolicy {
+    /// Refuse the write with [`SecretsError::TtlExceedsMax`].
+    #[default]
+    Reject,
+    /// Store the secret with the maximum TTL instead.
+    Clamp,
+}
+
+/// A version write checked and encrypted by [`SecretsEngine::prepare_put`],
+/// not yet committed.
+struct PreparedWrite {
+    /// Secret path.
+    path: String,
+    /// Version the write creates.
+    version: u32,
+    /// SQL statements and their parameters, in execution order.
+    statements: Vec<(&'static str, Vec<String>)>,
+}
+
+/// Stored string forms of the immutable per-version context columns bound
+/// into the AEAD associated data.
+#[derive(Debug, Clone, Copy, Default)]
+struct VersionContext<'a> {
+    /// `expires_at`, empty when the version never expires.
+    expires_at: &'a str,
+    /// `metadata`, empty when the version has none.
+    metadata: &'a str,
+    /// `plaintext_data`, empty when no field is stored in plaintext.
+    plaintext_data: &'a str,
 }
 
 /// The Secrets Engine provides secure storage for key-value secrets.
 pub struct SecretsEngine {
     storage: SqliteBackend,
     master_key: MasterKey,
+    /// Derivation version new versions are written under.
+    kdf_version: u32,
+    /// Whether `get` falls back to the last version that decrypts.
+    fallback_to_last_good: bool,
+    /// Upper bound on a client-supplied TTL, if any.
+    max_ttl: Option<Duration>,
+    /// How a TTL above `max_ttl` is handled.
+    max_ttl_policy: MaxTtlPolicy,
+    /// Data fields stored unencrypted, per path prefix.
+    plaintext_fields: Vec<(String, Vec<String>)>,
+    /// Upper bound on one value of a secret, in bytes.
+    max_value_bytes: usize,
+    /// Upper bound on the serialized data of a version, in bytes.
+    max_total_bytes: usize,
+    /// Maximum number of segments in a written path, if any.
+    max_path_depth: Option<usize>,
+    /// Metadata fields every write must carry, with their types.
+    required_metadata: Vec<(String, MetadataType)>,
 }
 
 impl SecretsEngine {
@@ -165,10 +335,42 @@ impl SecretsEngine {
         master_key: MasterKey,
     ) -> Result<Self, SecretsError> {
         let storage = SqliteBackend::open(data_path, tenant).await?;
+        Self::with_storage(storage, tenant, master_key).await
+    }
+
+    /// Creates a `SecretsEngine` whose tenant database is encrypted at rest.
+    ///
+    /// The database key is derived from `master_key` per tenant, see
+    /// [`kdf::derive_storage_key`]. Requires `egide-storage-sqlite` built with
+    /// its `sqlcipher` feature; otherwise this fails rather than writing a
+    /// plain file.
+    pub async fn new_encrypted(
+        data_path: impl AsRef<Path>,
+        tenant: &str,
+        master_key: MasterKey,
+    ) -> Result<Self, SecretsError> {
+        let storage_key = kdf::derive_storage_key(master_key.as_bytes(), tenant)?;
+        let storage = SqliteBackend::open_encrypted(data_path, tenant, &storage_key).await?;
+        Self::with_storage(storage, tenant, master_key).await
+    }
 
+    async fn with_storage(
+        storage: SqliteBackend,
+        tenant: &str,
+        master_key: MasterKey,
+    ) -> Result<Self, SecretsError> {
         let engine = Self {
             storage,
             master_key,
+            kdf_version: SECRET_KDF_VERSION,
+            fallback_to_last_good: false,
+            max_ttl: None,
+            max_ttl_policy: MaxTtlPolicy::default(),
+            plaintext_fields: Vec::new(),
+            max_value_bytes: DEFAULT_MAX_SECRET_BYTES,
+            max_total_bytes: DEFAULT_MAX_SECRET_BYTES,
+            max_path_depth: None,
+            required_metadata: Vec::new(),
         };
         engine.init_schema().await?;
 
@@ -186,31 +388,229 @@ impl SecretsEngine {
         // Egide has no versioned migration framework: the schema is applied
         // on every boot. SQLite rejects ADD COLUMN IF NOT EXISTS, so the
         // duplicate-column error is the idempotency signal here.
-        let add_column = self
-            .storage
-            .execute(
-                "ALTER TABLE secret_versions ADD COLUMN generation_salt TEXT",
-                &[],
-            )
-            .await;
+        for statement in [
+            "ALTER TABLE secrets ADD COLUMN max_versions INTEGER",
+            "ALTER TABLE secret_versions ADD COLUMN generation_salt TEXT",
+            "ALTER TABLE secret_versions ADD COLUMN kdf_version INTEGER",
+            "ALTER TABLE secret_versions ADD COLUMN plaintext_data TEXT",
+        ] {
+            if let Err(error) = self.storage.execute(statement, &[]).await {
+                let message = error.to_string();
+                if !message.contains("duplicate column") && !message.contains("already exists") {
+                    return Err(SecretsError::Storage(message));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes `get` fall back to the most recent older version that decrypts
+    /// when the current version is corrupted.
+    ///
+    /// Off by default. When enabled and the current version fails to decrypt
+    /// or its row is missing or unparsable, `get` walks down the prior
+    /// versions and returns the first one that decrypts and has not expired,
+    /// with [`Secret::degraded`] set, instead of failing. The pointer itself
+    /// is still authenticated first: a tampered or deleted pointer never
+    /// triggers a fallback. If no prior version decrypts, the original error
+    /// is returned. `get_version` is unaffected.
+    #[must_use]
+    pub fn with_fallback_to_last_good(mut self, enabled: bool) -> Self {
+        self.fallback_to_last_good = enabled;
+        self
+    }
+
+    /// Caps the TTL a client may request on [`Self::put`].
+    ///
+    /// A requested TTL above `max_ttl` is refused or lowered to `max_ttl`
+    /// depending on `policy`. Writes without a TTL are unaffected: the cap
+    /// bounds what clients ask for, it does not impose an expiry.
+    #[must_use]
+    pub fn with_max_ttl(mut self, max_ttl: Duration, policy: MaxTtlPolicy) -> Self {
+        self.max_ttl = Some(max_ttl);
+        self.max_ttl_policy = policy;
+        self
+    }
+
+    /// Caps the size of secret data accepted by [`Self::put`].
+    ///
+    /// `max_value_bytes` bounds each value and `max_total_bytes` the JSON
+    /// serialization of the whole map; both default to
+    /// [`DEFAULT_MAX_SECRET_BYTES`]. Larger writes fail with
+    /// [`SecretsError::ValueTooLarge`] before anything is encrypted.
+    #[must_use]
+    pub fn with_max_size(mut self, max_value_bytes: usize, max_total_bytes: usize) -> Self {
+        self.max_value_bytes = max_value_bytes;
+        self.max_total_bytes = max_total_bytes;
+        self
+    }
+
+    /// Limits new secrets to paths of at most `max_depth` `/`-separated
+    /// segments (default: unlimited).
+    ///
+    /// Only writes are checked, by [`Self::put`] and everything built on it:
+    /// secrets already stored deeper stay readable and deletable.
+    #[must_use]
+    pub fn with_max_path_depth(mut self, max_depth: usize) -> Self {
+        self.max_path_depth = Some(max_depth);
+        self
+    }
 
-        if let Err(error) = add_column {
-            let message = error.to_string();
-            if !message.contains("duplicate column") && !message.contains("already exists") {
-                return Err(SecretsError::Storage(message));
+    /// Validates a path about to be written: the path rules, then the
+    /// configured depth limit.
+    fn validate_write_path(&self, path: &str) -> Result<(), SecretsError> {
+        Self::validate_path(path)?;
+        let depth = path.split('/').count();
+        match self.max_path_depth {
+            Some(max) if depth > max => Err(SecretsError::InvalidPath(format!(
+                "path has {depth} segments, more than the maximum of {max}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Requires every write to carry metadata with `field` of type `kind`.
+    ///
+    /// Opt-in: without any required field, metadata stays free-form and
+    /// optional. Once one is set, [`Self::put`] and
+    /// [`Self::update_metadata`] refuse metadata that is missing, not a JSON
+    /// object, or lacks a required field, with
+    /// [`SecretsError::InvalidMetadata`]. Call once per field.
+    #[must_use]
+    pub fn with_required_metadata(mut self, field: impl Into<String>, kind: MetadataType) -> Self {
+        self.required_metadata.push((field.into(), kind));
+        self
+    }
+
+    /// Checks `metadata` against the required fields.
+    fn check_metadata(&self, metadata: Option<&serde_json::Value>) -> Result<(), SecretsError> {
+        if self.required_metadata.is_empty() {
+            return Ok(());
+        }
+        let object = metadata
+            .and_then(serde_json::Value::as_object)
+            .ok_or_else(|| SecretsError::InvalidMetadata("metadata must be an object".into()))?;
+        for (field, kind) in &self.required_metadata {
+            match object.get(field) {
+                None => {
+                    return Err(SecretsError::InvalidMetadata(format!(
+                        "missing required field {field}"
+                    )))
+                },
+                Some(value) if !kind.matches(value) => {
+                    return Err(SecretsError::InvalidMetadata(format!(
+                        "field {field} must be of type {kind:?}"
+                    )))
+                },
+                Some(_) => {},
             }
         }
+        Ok(())
+    }
 
+    /// Checks `data` against the configured size limits.
+    fn check_size(&self, data: &HashMap<String, String>) -> Result<(), SecretsError> {
+        if let Some(size) = data
+            .values()
+            .map(String::len)
+            .find(|&size| size > self.max_value_bytes)
+        {
+            return Err(SecretsError::ValueTooLarge {
+                size,
+                max: self.max_value_bytes,
+            });
+        }
+        let size = Self::serialize_data(data)?.len();
+        if size > self.max_total_bytes {
+            return Err(SecretsError::ValueTooLarge {
+                size,
+                max: self.max_total_bytes,
+            });
+        }
         Ok(())
     }
 
+    /// Stores the listed data fields of secrets under `prefix` unencrypted.
+    ///
+    /// `prefix` matches whole path segments: `apps` covers `apps` and
+    /// `apps/billing`, not `apps-legacy`; an empty prefix covers every path.
+    /// When several prefixes match, the longest wins. The listed fields are
+    /// written to a separate column, readable only through this engine like
+    /// the rest of the secret, and searchable with
+    /// [`Self::search_plaintext`]; every other field stays encrypted. Only
+    /// writes made after the call are affected.
+    #[must_use]
+    pub fn with_plaintext_fields(mut self, prefix: impl Into<String>, fields: Vec<String>) -> Self {
+        self.plaintext_fields.push((prefix.into(), fields));
+        self
+    }
+
+    /// Returns the fields stored unencrypted for `path`.
+    fn plaintext_fields_for(&self, path: &str) -> &[String] {
+        self.plaintext_fields
+            .iter()
+            .filter(|(prefix, _)| {
+                prefix.is_empty()
+                    || path
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(&[][..], |(_, fields)| fields.as_slice())
+    }
+
+    /// Splits `data` into the fields to encrypt and the stored form of the
+    /// fields kept in plaintext (empty when there are none).
+    fn split_plaintext(
+        &self,
+        path: &str,
+        mut data: HashMap<String, String>,
+    ) -> Result<(HashMap<String, String>, String), SecretsError> {
+        let plaintext: BTreeMap<String, String> = self
+            .plaintext_fields_for(path)
+            .iter()
+            .filter_map(|field| data.remove_entry(field))
+            .collect();
+        if plaintext.is_empty() {
+            return Ok((data, String::new()));
+        }
+        let repr = serde_json::to_string(&plaintext)
+            .map_err(|e| SecretsError::Storage(format!("plaintext serialization failed: {e}")))?;
+        Ok((data, repr))
+    }
+
+    /// Applies the configured TTL cap to a requested TTL.
+    fn effective_ttl(&self, requested: Option<Duration>) -> Result<Option<Duration>, SecretsError> {
+        match (requested, self.max_ttl) {
+            (Some(ttl), Some(max)) if ttl > max => match self.max_ttl_policy {
+                MaxTtlPolicy::Reject => Err(SecretsError::TtlExceedsMax {
+                    requested_secs: ttl.as_secs(),
+                    max_secs: max.as_secs(),
+                }),
+                MaxTtlPolicy::Clamp => Ok(Some(max)),
+            },
+            _ => Ok(requested),
+        }
+    }
+
+    /// Writes new versions under `kdf_version`, standing in for a future bump
+    /// of [`SECRET_KDF_VERSION`].
+    #[cfg(test)]
+    fn with_kdf_version(mut self, kdf_version: u32) -> Self {
+        self.kdf_version = kdf_version;
+        self
+    }
+
     /// Derives an encryption key for one version of a secret.
     ///
     /// Each `(path, version, generation_salt)` triple yields a distinct key,
     /// so every derived key encrypts exactly one message and the
     /// random-nonce birthday bound of AES-GCM can never be approached. When
     /// `generation_salt` is `None`, the key is derived under the legacy `v2`
-    /// context so rows written before the salt existed stay readable.
+    /// context so rows written before the salt existed stay readable;
+    /// otherwise under `kdf_version`, the derivation version recorded with
+    /// the row.
     ///
     /// `generation_salt` is the hex-encoded string as stored in the
     /// `generation_salt` column, not raw bytes: that stored form is what
@@ -219,36 +619,70 @@ impl SecretsEngine {
         &self,
         path: &str,
         version: u32,
+        kdf_version: u32,
+        generation_salt: Option<&str>,
+    ) -> Result<egide_crypto::SymmetricKey, SecretsError> {
+        Self::derive_secret_key_from(
+            &self.master_key,
+            path,
+            version,
+            kdf_version,
+            generation_salt,
+        )
+    }
+
+    /// [`Self::derive_secret_key`] under an explicit master key, for
+    /// [`Self::rewrap_secret`].
+    fn derive_secret_key_from(
+        master_key: &MasterKey,
+        path: &str,
+        version: u32,
+        kdf_version: u32,
         generation_salt: Option<&str>,
     ) -> Result<egide_crypto::SymmetricKey, SecretsError> {
-        let info = match generation_salt {
-            Some(salt) => format!("{SECRET_KEY_INFO_PREFIX}{path}:{version}:{salt}"),
-            None => format!("{SECRET_KEY_INFO_PREFIX_V2}{path}:{version}"),
+        let context = KdfContext::new(SECRET_KDF_DOMAIN)
+            .field(path)
+            .field(version.to_string());
+        let context = match generation_salt {
+            None => context.version(SECRET_KDF_VERSION_UNSALTED),
+            Some(salt) if kdf_version >= SECRET_KDF_VERSION => {
+                context.version(kdf_version).field(salt)
+            },
+            Some(_) => {
+                return Err(SecretsError::Integrity(format!(
+                    "unsupported derivation version {kdf_version} for {path} v{version}"
+                )))
+            },
         };
-        let key_bytes = kdf::derive_key(self.master_key.as_bytes(), None, info.as_bytes(), 32)?;
+        let key_bytes = kdf::derive_key(master_key.as_bytes(), None, &context.info(), 32)?;
         egide_crypto::SymmetricKey::from_bytes(&key_bytes).map_err(SecretsError::from)
     }
 
     /// Builds the AEAD associated data sealing a ciphertext to its row.
     ///
     /// Binds the storage coordinates (`path`, `version`) and the immutable
-    /// per-version context columns (`expires_at`, `metadata`) using a canonical
-    /// length-prefixed encoding. The exact stored string forms are bound, so a
-    /// tamper of either column makes decryption fail closed.
+    /// per-version context columns (`expires_at`, `metadata`, and
+    /// `plaintext_data` when not empty) using a canonical length-prefixed
+    /// encoding. The exact stored string forms are bound, so a tamper of any
+    /// column makes decryption fail closed. An empty `plaintext_data` is left
+    /// out, so rows written before the column existed keep their AAD.
     fn secret_aad(
         path: &str,
         version: u32,
-        expires_at_repr: &str,
-        metadata_repr: &str,
+        context: &VersionContext<'_>,
     ) -> Result<Vec<u8>, SecretsError> {
-        mac::encode_fields(&[
+        let version_bytes = version.to_be_bytes();
+        let mut fields = vec![
             SECRET_AAD_PREFIX.as_bytes(),
             path.as_bytes(),
-            &version.to_be_bytes(),
-            expires_at_repr.as_bytes(),
-            metadata_repr.as_bytes(),
-        ])
-        .map_err(SecretsError::from)
+            &version_bytes,
+            context.expires_at.as_bytes(),
+            context.metadata.as_bytes(),
+        ];
+        if !context.plaintext_data.is_empty() {
+            fields.push(context.plaintext_data.as_bytes());
+        }
+        mac::encode_fields(&fields).map_err(SecretsError::from)
     }
 
     /// Computes the hex-encoded keyed MAC authenticating the version pointer.
@@ -262,8 +696,17 @@ impl SecretsEngine {
         version: u32,
         deleted_at_repr: &str,
     ) -> Result<String, SecretsError> {
-        let subkey =
-            kdf::derive_encryption_key(self.master_key.as_bytes(), SECRET_POINTER_MAC_INFO)?;
+        Self::pointer_mac_from(&self.master_key, path, version, deleted_at_repr)
+    }
+
+    /// [`Self::pointer_mac`] under an explicit master key.
+    fn pointer_mac_from(
+        master_key: &MasterKey,
+        path: &str,
+        version: u32,
+        deleted_at_repr: &str,
+    ) -> Result<String, SecretsError> {
+        let subkey = kdf::derive_encryption_key(master_key.as_bytes(), SECRET_POINTER_MAC_INFO)?;
         let data = mac::encode_fields(&[
             path.as_bytes(),
             &version.to_be_bytes(),
@@ -271,7 +714,7 @@ impl SecretsEngine {
         ])
         .map_err(SecretsError::from)?;
         let tag = mac::compute_mac(&subkey[..], &data).map_err(SecretsError::from)?;
-        Ok(hex_encode(&tag))
+        Ok(encoding::encode_hex(&tag).as_str().to_owned())
     }
 
     /// Verifies the stored version-pointer MAC, failing closed on any anomaly.
@@ -296,8 +739,18 @@ impl SecretsEngine {
         deleted_at_repr: &str,
         stored_hex: &str,
     ) -> Result<(), SecretsError> {
-        let subkey =
-            kdf::derive_encryption_key(self.master_key.as_bytes(), SECRET_POINTER_MAC_INFO)?;
+        Self::verify_pointer_mac_from(&self.master_key, path, version, deleted_at_repr, stored_hex)
+    }
+
+    /// [`Self::verify_pointer_mac`] under an explicit master key.
+    fn verify_pointer_mac_from(
+        master_key: &MasterKey,
+        path: &str,
+        version: u32,
+        deleted_at_repr: &str,
+        stored_hex: &str,
+    ) -> Result<(), SecretsError> {
+        let subkey = kdf::derive_encryption_key(master_key.as_bytes(), SECRET_POINTER_MAC_INFO)?;
         let data = mac::encode_fields(&[
             path.as_bytes(),
             &version.to_be_bytes(),
@@ -316,15 +769,24 @@ impl SecretsEngine {
         path: &str,
         version: u32,
         generation_salt: Option<&str>,
-        expires_at_repr: &str,
-        metadata_repr: &str,
+        context: &VersionContext<'_>,
+        data: &HashMap<String, String>,
+    ) -> Result<(Vec<u8>, Vec<u8>), SecretsError> {
+        let key = self.derive_secret_key(path, version, self.kdf_version, generation_salt)?;
+        Self::seal_data(&key, path, version, context, data)
+    }
+
+    /// Encrypts secret data under an already derived key.
+    fn seal_data(
+        key: &egide_crypto::SymmetricKey,
+        path: &str,
+        version: u32,
+        context: &VersionContext<'_>,
         data: &HashMap<String, String>,
     ) -> Result<(Vec<u8>, Vec<u8>), SecretsError> {
-        let key = self.derive_secret_key(path, version, generation_salt)?;
-        let plaintext = serde_json::to_vec(data)
-            .map_err(|e| SecretsError::Crypto(format!("serialization failed: {e}")))?;
+        let plaintext = Self::serialize_data(data)?;
 
-        let aad = Self::secret_aad(path, version, expires_at_repr, metadata_repr)?;
+        let aad = Self::secret_aad(path, version, context)?;
         let ciphertext = aead::encrypt(key.as_bytes(), &plaintext, Some(&aad))?;
 
         // Extract nonce from ciphertext (first 12 bytes in our format)
@@ -334,29 +796,56 @@ impl SecretsEngine {
         Ok((data, nonce))
     }
 
+    /// Serializes secret data into the plaintext that gets encrypted.
+    fn serialize_data(data: &HashMap<String, String>) -> Result<Vec<u8>, SecretsError> {
+        serde_json::to_vec(data)
+            .map_err(|e| SecretsError::Crypto(format!("serialization failed: {e}")))
+    }
+
+    /// Serializes the optional custom metadata into its stored form.
+    fn serialize_metadata(options: &PutOptions) -> Result<Option<String>, SecretsError> {
+        options
+            .metadata
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| SecretsError::Storage(format!("metadata serialization failed: {e}")))
+    }
+
     /// Decrypts secret data from storage.
     ///
-    /// `sealed` is the stored `(data, nonce)` pair, grouped into a single
-    /// parameter to keep the argument count within the workspace clippy
-    /// limit.
+    /// `derivation` is the row's `(kdf_version, generation_salt)` and `sealed`
+    /// the stored `(data, nonce)` pair, each grouped into a single parameter
+    /// to keep the argument count within the workspace clippy limit.
     fn decrypt_data(
         &self,
         path: &str,
         version: u32,
-        generation_salt: Option<&str>,
-        expires_at_repr: &str,
-        metadata_repr: &str,
+        derivation: (u32, Option<&str>),
+        context: &VersionContext<'_>,
+        sealed: (&[u8], &[u8]),
+    ) -> Result<HashMap<String, String>, SecretsError> {
+        let (kdf_version, generation_salt) = derivation;
+        let key = self.derive_secret_key(path, version, kdf_version, generation_salt)?;
+        Self::open_data(&key, path, version, context, sealed)
+    }
+
+    /// Decrypts secret data under an already derived key.
+    fn open_data(
+        key: &egide_crypto::SymmetricKey,
+        path: &str,
+        version: u32,
+        context: &VersionContext<'_>,
         sealed: (&[u8], &[u8]),
     ) -> Result<HashMap<String, String>, SecretsError> {
         let (data, nonce) = sealed;
-        let key = self.derive_secret_key(path, version, generation_salt)?;
 
         // Reconstruct ciphertext with nonce prefix
         let mut ciphertext = Vec::with_capacity(nonce.len() + data.len());
         ciphertext.extend_from_slice(nonce);
         ciphertext.extend_from_slice(data);
 
-        let aad = Self::secret_aad(path, version, expires_at_repr, metadata_repr)?;
+        let aad = Self::secret_aad(path, version, context)?;
         let plaintext = aead::decrypt(key.as_bytes(), &ciphertext, Some(&aad))?;
 
         serde_json::from_slice(&plaintext)
@@ -398,27 +887,39 @@ impl SecretsEngine {
             .as_secs()
     }
 
-    /// Stores or updates a secret.
+    /// Validates a write without performing it.
     ///
-    /// Returns the new version number.
-    pub async fn put(
+    /// Runs every check [`Self::put`] runs before writing (path rules and depth,
+    /// the TTL cap, size limits, payload and metadata serialization, the deleted state and
+    /// the `cas` guard) and returns the version the write would create. Nothing is
+    /// persisted, so a concurrent writer can still change the outcome of the
+    /// real `put`; pass the returned version minus one as `cas` to make it
+    /// conditional on the validated state.
+    pub async fn validate_put(
         &self,
         path: &str,
-        data: HashMap<String, String>,
-        options: PutOptions,
+        data: &HashMap<String, String>,
+        options: &PutOptions,
     ) -> Result<u32, SecretsError> {
-        Self::validate_path(path)?;
-
-        let now = Self::now();
-        let expires_at = options.ttl.map(|ttl| now + ttl.as_secs());
-        let metadata_json = options
-            .metadata
-            .as_ref()
-            .map(serde_json::to_string)
-            .transpose()
-            .map_err(|e| SecretsError::Storage(format!("metadata serialization failed: {e}")))?;
+        self.validate_write_path(path)?;
+        self.effective_ttl(options.ttl)?;
+        self.check_size(data)?;
+        self.check_metadata(options.metadata.as_ref())?;
+        let (encrypted, _) = self.split_plaintext(path, data.clone())?;
+        Self::serialize_data(&encrypted)?;
+        Self::serialize_metadata(options)?;
+        let current = self.current_version_for_write(path, options.cas).await?;
+        Ok(current.map_or(1, |version| version + 1))
+    }
 
-        // Check if secret exists
+    /// Returns the current version a write at `path` would follow, or `None`
+    /// for a new secret, after authenticating the pointer and enforcing the
+    /// deleted state and the `cas` guard.
+    async fn current_version_for_write(
+        &self,
+        path: &str,
+        cas: Option<u32>,
+    ) -> Result<Option<u32>, SecretsError> {
         let existing = self
             .storage
             .query_one::<(i64, Option<i64>, String)>(
@@ -428,29 +929,108 @@ impl SecretsEngine {
             .await
             .map_err(|e| SecretsError::Storage(e.to_string()))?;
 
-        let new_version: u32;
-        let existing_generation_salt: Option<String>;
+        let Some((current_version, deleted_at, row_mac)) = existing else {
+            // A CAS guard on a secret that does not exist yet cannot match.
+            if cas.is_some() {
+                return Err(SecretsError::NotFound(path.to_string()));
+            }
+            return Ok(None);
+        };
 
-        if let Some((current_version, deleted_at, row_mac)) = existing {
-            // Secret exists: authenticate the pointer before trusting its version.
-            let current_version = u32::try_from(current_version).unwrap_or(0);
-            let deleted_at_repr = deleted_at.map(|d| d.to_string()).unwrap_or_default();
-            self.verify_pointer_mac(path, current_version, &deleted_at_repr, &row_mac)?;
+        // Secret exists: authenticate the pointer before trusting its version.
+        let current_version = u32::try_from(current_version).unwrap_or(0);
+        let deleted_at_repr = deleted_at.map(|d| d.to_string()).unwrap_or_default();
+        self.verify_pointer_mac(path, current_version, &deleted_at_repr, &row_mac)?;
 
-            if deleted_at.is_some() {
-                return Err(SecretsError::Deleted(path.to_string()));
+        if deleted_at.is_some() {
+            return Err(SecretsError::Deleted(path.to_string()));
+        }
+
+        if let Some(expected) = cas {
+            if current_version != expected {
+                return Err(SecretsError::VersionMismatch {
+                    expected,
+                    found: current_version,
+                });
             }
+        }
 
-            // Check CAS if provided
-            if let Some(expected) = options.cas {
-                if current_version != expected {
-                    return Err(SecretsError::VersionMismatch {
-                        expected,
-                        found: current_version,
-                    });
-                }
+        Ok(Some(current_version))
+    }
+
+    /// Stores or updates a secret.
+    ///
+    /// Returns the new version number.
+    pub async fn put(
+        &self,
+        path: &str,
+        data: HashMap<String, String>,
+        options: PutOptions,
+    ) -> Result<u32, SecretsError> {
+        let write = self.prepare_put(path, data, options).await?;
+        self.commit_writes(std::slice::from_ref(&write)).await?;
+
+        debug!(path = path, version = write.version, "Secret stored");
+        Ok(write.version)
+    }
+
+    /// Stores several secrets atomically: either every entry is written or,
+    /// on any failure, none is.
+    ///
+    /// Each entry is checked as by [`Self::put`], including its `cas` guard,
+    /// and all writes are committed in one transaction. A path may appear
+    /// only once. Returns the new version of each entry, in order.
+    pub async fn put_many(
+        &self,
+        entries: Vec<(String, HashMap<String, String>, PutOptions)>,
+    ) -> Result<Vec<u32>, SecretsError> {
+        let mut writes: Vec<PreparedWrite> = Vec::with_capacity(entries.len());
+        for (path, data, options) in entries {
+            if writes.iter().any(|write| write.path == path) {
+                return Err(SecretsError::InvalidPath(format!(
+                    "duplicate path in batch: {path}"
+                )));
             }
+            writes.push(self.prepare_put(&path, data, options).await?);
+        }
+        self.commit_writes(&writes).await?;
+
+        debug!(count = writes.len(), "Secrets stored");
+        Ok(writes.iter().map(|write| write.version).collect())
+    }
+
+    /// Runs every check of [`Self::put`] and encrypts the new version,
+    /// returning the statements that write it without executing them.
+    async fn prepare_put(
+        &self,
+        path: &str,
+        data: HashMap<String, String>,
+        options: PutOptions,
+    ) -> Result<PreparedWrite, SecretsError> {
+        self.validate_write_path(path)?;
+        self.check_size(&data)?;
+        self.check_metadata(options.metadata.as_ref())?;
+
+        let now_secs = Self::now();
+        let now = now_secs.to_string();
+        let expires_at = self
+            .effective_ttl(options.ttl)?
+            .map(|ttl| now_secs + ttl.as_secs());
+        let metadata_json = Self::serialize_metadata(&options)?;
+        let created_by = options
+            .created_by
+            .or_else(|| self.storage.current_actor())
+            .unwrap_or_default();
+        let max_versions_repr = options
+            .max_versions
+            .map(|max| max.to_string())
+            .unwrap_or_default();
+
+        let new_version: u32;
+        let existing_generation_salt: Option<String>;
+        let mut statements = Vec::with_capacity(3);
 
+        if let Some(current_version) = self.current_version_for_write(path, options.cas).await? {
             new_version = current_version + 1;
 
             // Read the previous version's generation salt so this new version
@@ -469,42 +1049,34 @@ impl SecretsEngine {
 
             // Update secrets table
             let row_mac = self.pointer_mac(path, new_version, "")?;
-            self.storage
-                .execute(
-                    "UPDATE secrets SET version = ?, updated_at = ?, row_mac = ? WHERE path = ?",
-                    &[
-                        &i64::from(new_version).to_string(),
-                        &now.to_string(),
-                        &row_mac,
-                        path,
-                    ],
-                )
-                .await
-                .map_err(|e| SecretsError::Storage(e.to_string()))?;
+            statements.push((
+                "UPDATE secrets SET version = ?, updated_at = ?, row_mac = ?, max_versions = COALESCE(CAST(NULLIF(?, '') AS INTEGER), max_versions) WHERE path = ?",
+                vec![
+                    new_version.to_string(),
+                    now.clone(),
+                    row_mac,
+                    max_versions_repr,
+                    path.to_string(),
+                ],
+            ));
         } else {
             // New secret
-            if options.cas.is_some() {
-                return Err(SecretsError::NotFound(path.to_string()));
-            }
-
             new_version = 1;
             existing_generation_salt = None;
 
             // Insert into secrets table
             let row_mac = self.pointer_mac(path, new_version, "")?;
-            self.storage
-                .execute(
-                    "INSERT INTO secrets (path, version, created_at, updated_at, row_mac) VALUES (?, ?, ?, ?, ?)",
-                    &[
-                        path,
-                        &new_version.to_string(),
-                        &now.to_string(),
-                        &now.to_string(),
-                        &row_mac,
-                    ],
-                )
-                .await
-                .map_err(|e| SecretsError::Storage(e.to_string()))?;
+            statements.push((
+                "INSERT INTO secrets (path, version, created_at, updated_at, row_mac, max_versions) VALUES (?, ?, ?, ?, ?, CAST(NULLIF(?, '') AS INTEGER))",
+                vec![
+                    path.to_string(),
+                    new_version.to_string(),
+                    now.clone(),
+                    now.clone(),
+                    row_mac,
+                    max_versions_repr,
+                ],
+            ));
         }
 
         // A generation salt is drawn once per generation and reused by every
@@ -513,44 +1085,83 @@ impl SecretsEngine {
         // does.
         let generation_salt = match existing_generation_salt {
             Some(salt) => salt,
-            None => hex_encode(random::generate_key()?.as_ref()),
+            None => encoding::encode_hex(random::generate_key()?.as_ref())
+                .as_str()
+                .to_owned(),
         };
 
         // The exact stored string forms of the immutable per-version context,
         // bound into the AAD so a later tamper of either column fails closed.
         let expires_at_repr = expires_at.map(|e| e.to_string()).unwrap_or_default();
         let metadata_repr = metadata_json.unwrap_or_default();
+        let (data, plaintext_repr) = self.split_plaintext(path, data)?;
+        let context = VersionContext {
+            expires_at: &expires_at_repr,
+            metadata: &metadata_repr,
+            plaintext_data: &plaintext_repr,
+        };
 
         // Encrypt and store version data
         let (encrypted_data, nonce) = self.encrypt_data(
             path,
             new_version,
             Some(generation_salt.as_str()),
-            &expires_at_repr,
-            &metadata_repr,
+            &context,
             &data,
         )?;
 
+        statements.push((
+            "INSERT INTO secret_versions (path, version, data, nonce, expires_at, metadata, created_at, created_by, generation_salt, kdf_version, plaintext_data) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULLIF(?, ''))",
+            vec![
+                path.to_string(),
+                new_version.to_string(),
+                encoding::encode_hex(&encrypted_data).as_str().to_owned(),
+                encoding::encode_hex(&nonce).as_str().to_owned(),
+                expires_at_repr,
+                metadata_repr,
+                now,
+                created_by,
+                generation_salt,
+                self.kdf_version.to_string(),
+                plaintext_repr,
+            ],
+        ));
+
+        // Drop the versions older than the newest `max_versions` stored for
+        // the path, as set by the pointer statement above. A limit is at
+        // least one, so the new version is never deleted.
+        statements.push((
+            "DELETE FROM secret_versions WHERE path = ? AND (SELECT COALESCE(max_versions, 0) FROM secrets WHERE path = ?) > 0 AND version <= CAST(? AS INTEGER) - (SELECT max_versions FROM secrets WHERE path = ?)",
+            vec![
+                path.to_string(),
+                path.to_string(),
+                new_version.to_string(),
+                path.to_string(),
+            ],
+        ));
+
+        Ok(PreparedWrite {
+            path: path.to_string(),
+            version: new_version,
+            statements,
+        })
+    }
+
+    /// Executes the statements of `writes` in a single transaction.
+    async fn commit_writes(&self, writes: &[PreparedWrite]) -> Result<(), SecretsError> {
+        let params: Vec<(&str, Vec<&str>)> = writes
+            .iter()
+            .flat_map(|write| &write.statements)
+            .map(|(sql, values)| (*sql, values.iter().map(String::as_str).collect()))
+            .collect();
+        let statements: Vec<(&str, &[&str])> = params
+            .iter()
+            .map(|(sql, values)| (*sql, values.as_slice()))
+            .collect();
         self.storage
-            .execute(
-                "INSERT INTO secret_versions (path, version, data, nonce, expires_at, metadata, created_at, created_by, generation_salt) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                &[
-                    path,
-                    &new_version.to_string(),
-                    &hex_encode(&encrypted_data),
-                    &hex_encode(&nonce),
-                    &expires_at_repr,
-                    &metadata_repr,
-                    &now.to_string(),
-                    &self.storage.current_actor().unwrap_or_default(),
-                    &generation_salt,
-                ],
-            )
+            .execute_transaction(&statements)
             .await
-            .map_err(|e| SecretsError::Storage(e.to_string()))?;
-
-        debug!(path = path, version = new_version, "Secret stored");
-        Ok(new_version)
+            .map_err(|e| SecretsError::Storage(e.to_string()))
     }
 
     /// Retrieves the current version of a secret.
@@ -577,18 +1188,91 @@ impl SecretsEngine {
             return Err(SecretsError::Deleted(path.to_string()));
         }
 
-        self.get_version(path, version).await
+        match self.get_version(path, version).await {
+            Err(
+                error @ (SecretsError::Crypto(_)
+                | SecretsError::Integrity(_)
+                | SecretsError::VersionNotFound { .. }),
+            ) if self.fallback_to_last_good => self.get_last_good(path, version, error).await,
+            result => result,
+        }
     }
 
-    /// Retrieves a specific version of a secret.
-    pub async fn get_version(&self, path: &str, version: u32) -> Result<Secret, SecretsError> {
-        Self::validate_path(path)?;
+    /// Returns one field of the current version of a secret, or `None` if
+    /// the secret has no such field.
+    ///
+    /// The version is decrypted whole, as by [`Self::get`], with the same
+    /// expiry and deletion checks; only the requested value is returned.
+    pub async fn get_field(&self, path: &str, field: &str) -> Result<Option<String>, SecretsError> {
+        let mut secret = self.get(path).await?;
+        Ok(secret.data.remove(field))
+    }
 
-        // Check the version pointer (current version, deleted_at) is intact before trusting it.
-        // The pointer MAC authenticates the CURRENT version, independent of the requested
-        // `version` argument below, which only selects the blob to fetch; that blob is
-        // separately authenticated by its own AEAD/AAD.
-        let pointer = self
+    /// Returns the field names of the current version of a secret, sorted,
+    /// without their values.
+    ///
+    /// Subject to the same expiry and deletion checks as [`Self::get`].
+    pub async fn list_fields(&self, path: &str) -> Result<Vec<String>, SecretsError> {
+        let secret = self.get(path).await?;
+        let mut fields: Vec<String> = secret.data.into_keys().collect();
+        fields.sort_unstable();
+        Ok(fields)
+    }
+
+    /// Retrieves the current version of several secrets.
+    ///
+    /// Returns one entry per distinct path, holding what [`Self::get`] would
+    /// return for it, so a missing, deleted or expired secret does not fail
+    /// the others. The outer error is reserved for failures of the batch as
+    /// a whole; none exist yet. Paths are still read one at a time.
+    pub async fn get_many(
+        &self,
+        paths: &[&str],
+    ) -> Result<HashMap<String, Result<Secret, SecretsError>>, SecretsError> {
+        let mut results = HashMap::with_capacity(paths.len());
+        for &path in paths {
+            if results.contains_key(path) {
+                continue;
+            }
+            let result = self.get(path).await;
+            results.insert(path.to_string(), result);
+        }
+        Ok(results)
+    }
+
+    /// Returns the most recent version below `current` that decrypts, flagged
+    /// as degraded, or `error` if there is none.
+    async fn get_last_good(
+        &self,
+        path: &str,
+        current: u32,
+        error: SecretsError,
+    ) -> Result<Secret, SecretsError> {
+        for version in (1..current).rev() {
+            if let Ok(mut secret) = self.get_version(path, version).await {
+                warn!(
+                    path = path,
+                    current = current,
+                    served = version,
+                    error = %error,
+                    "Current secret version unreadable, serving last good version"
+                );
+                secret.degraded = true;
+                return Ok(secret);
+            }
+        }
+        Err(error)
+    }
+
+    /// Retrieves a specific version of a secret.
+    pub async fn get_version(&self, path: &str, version: u32) -> Result<Secret, SecretsError> {
+        Self::validate_path(path)?;
+
+        // Check the version pointer (current version, deleted_at) is intact before trusting it.
+        // The pointer MAC authenticates the CURRENT version, independent of the requested
+        // `version` argument below, which only selects the blob to fetch; that blob is
+        // separately authenticated by its own AEAD/AAD.
+        let pointer = self
             .storage
             .query_one::<(i64, Option<i64>, String)>(
                 "SELECT version, deleted_at, COALESCE(row_mac, '') FROM secrets WHERE path = ?",
@@ -611,8 +1295,8 @@ impl SecretsEngine {
 
         let row = self
             .storage
-            .query_one::<(String, String, String, String, String, String)>(
-                "SELECT data, nonce, COALESCE(CAST(expires_at AS TEXT), ''), COALESCE(metadata, ''), CAST(created_at AS TEXT), COALESCE(generation_salt, '') FROM secret_versions WHERE path = ? AND version = ?",
+            .query_one::<(String, String, String, String, String, String, String, String, String)>(
+                "SELECT data, nonce, COALESCE(CAST(expires_at AS TEXT), ''), COALESCE(metadata, ''), CAST(created_at AS TEXT), COALESCE(generation_salt, ''), COALESCE(CAST(kdf_version AS TEXT), ''), COALESCE(plaintext_data, ''), COALESCE(created_by, '') FROM secret_versions WHERE path = ? AND version = ?",
                 &[path, &version.to_string()],
             )
             .await
@@ -622,12 +1306,23 @@ impl SecretsEngine {
                 version,
             })?;
 
-        let (data_hex, nonce_hex, expires_at_str, metadata_json, created_at_str, salt_repr) = row;
+        let (
+            data_hex,
+            nonce_hex,
+            expires_at_str,
+            metadata_json,
+            created_at_str,
+            salt_repr,
+            kdf_version_repr,
+            plaintext_repr,
+            created_by,
+        ) = row;
         let generation_salt = if salt_repr.is_empty() {
             None
         } else {
             Some(salt_repr.as_str())
         };
+        let kdf_version = Self::parse_kdf_version(&kdf_version_repr, path, version)?;
 
         // Parse timestamps
         let created_at: u64 = created_at_str.parse().unwrap_or(0);
@@ -653,15 +1348,26 @@ impl SecretsEngine {
         let nonce_bytes = hex_decode(&nonce_hex)
             .map_err(|e| SecretsError::Storage(format!("invalid nonce encoding: {e}")))?;
 
-        let data = self.decrypt_data(
+        let mut data = self.decrypt_data(
             path,
             version,
-            generation_salt,
-            &expires_at_str,
-            &metadata_json,
+            (kdf_version, generation_salt),
+            &VersionContext {
+                expires_at: &expires_at_str,
+                metadata: &metadata_json,
+                plaintext_data: &plaintext_repr,
+            },
             (&data_bytes, &nonce_bytes),
         )?;
 
+        // Plaintext fields are only trusted once the AEAD has authenticated
+        // them along with the rest of the row.
+        if !plaintext_repr.is_empty() {
+            let plaintext: HashMap<String, String> = serde_json::from_str(&plaintext_repr)
+                .map_err(|e| SecretsError::Storage(format!("invalid plaintext data: {e}")))?;
+            data.extend(plaintext);
+        }
+
         let metadata = if metadata_json.is_empty() {
             None
         } else {
@@ -678,11 +1384,30 @@ impl SecretsEngine {
             metadata,
             created_at,
             expires_at,
+            created_by: (!created_by.is_empty()).then_some(created_by),
+            degraded: false,
+        })
+    }
+
+    /// Parses the stored `kdf_version` of a version row.
+    fn parse_kdf_version(repr: &str, path: &str, version: u32) -> Result<u32, SecretsError> {
+        // Rows written before `kdf_version` existed are all `v3` when salted
+        // (the unsalted case is `v2` regardless, see `derive_secret_key`).
+        if repr.is_empty() {
+            return Ok(SECRET_KDF_VERSION);
+        }
+        repr.parse().map_err(|_| {
+            SecretsError::Integrity(format!("unparsable kdf_version for {path} v{version}"))
         })
     }
 
     /// Soft-deletes a secret.
-    pub async fn delete(&self, path: &str) -> Result<(), SecretsError> {
+    ///
+    /// With [`DeleteOptions::cas`] set, the delete only happens if the current
+    /// version still matches, so a secret rewritten since it was read is not
+    /// deleted by mistake; a mismatch fails with
+    /// [`SecretsError::VersionMismatch`].
+    pub async fn delete(&self, path: &str, options: DeleteOptions) -> Result<(), SecretsError> {
         Self::validate_path(path)?;
 
         let row = self
@@ -702,6 +1427,14 @@ impl SecretsEngine {
         if deleted_at.is_some() {
             return Err(SecretsError::Deleted(path.to_string()));
         }
+        if let Some(expected) = options.cas {
+            if version != expected {
+                return Err(SecretsError::VersionMismatch {
+                    expected,
+                    found: version,
+                });
+            }
+        }
 
         let now = Self::now();
         let row_mac = self.pointer_mac(path, version, &now.to_string())?;
@@ -718,7 +1451,12 @@ impl SecretsEngine {
     }
 
     /// Restores a soft-deleted secret.
-    pub async fn undelete(&self, path: &str) -> Result<(), SecretsError> {
+    ///
+    /// With [`UndeleteOptions::cas`] set, the restore only happens if the
+    /// deleted secret is still at that version, so a secret purged and
+    /// recreated since it was observed is not restored by mistake; a
+    /// mismatch fails with [`SecretsError::VersionMismatch`].
+    pub async fn undelete(&self, path: &str, options: UndeleteOptions) -> Result<(), SecretsError> {
         Self::validate_path(path)?;
 
         let row = self
@@ -738,6 +1476,14 @@ impl SecretsEngine {
         if deleted_at.is_none() {
             return Err(SecretsError::NotDeleted(path.to_string()));
         }
+        if let Some(expected) = options.cas {
+            if version != expected {
+                return Err(SecretsError::VersionMismatch {
+                    expected,
+                    found: version,
+                });
+            }
+        }
 
         let row_mac = self.pointer_mac(path, version, "")?;
         self.storage
@@ -752,34 +1498,238 @@ impl SecretsEngine {
         Ok(())
     }
 
-    /// Lists secrets matching a prefix.
+    /// Lists secrets matching a prefix, soft-deleted ones included, in path
+    /// order.
+    ///
+    /// Reads through [`Self::list_page`] one page at a time but returns every
+    /// match at once; to render a large namespace, page through it directly.
     pub async fn list(&self, prefix: &str) -> Result<Vec<SecretMetadata>, SecretsError> {
+        let mut secrets = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let (page, next) = self
+                .list_page(prefix, cursor.as_deref(), LIST_PAGE_SIZE)
+                .await?;
+            secrets.extend(page);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => return Ok(secrets),
+            }
+        }
+    }
+
+    /// Lists secrets matching a prefix, filtered by deleted state.
+    ///
+    /// With [`ListOptions::only_deleted`] this enumerates the secrets that
+    /// [`Self::undelete`] can still recover.
+    pub async fn list_with(
+        &self,
+        prefix: &str,
+        options: ListOptions,
+    ) -> Result<Vec<SecretMetadata>, SecretsError> {
         let pattern = prefix_pattern(prefix);
+        let sql = if options.only_deleted {
+            "SELECT path, CAST(version AS TEXT), CAST(created_at AS TEXT), CAST(updated_at AS TEXT), COALESCE(CAST(deleted_at AS TEXT), '') FROM secrets WHERE path LIKE ? ESCAPE '\\' AND deleted_at IS NOT NULL ORDER BY path"
+        } else if options.include_deleted {
+            "SELECT path, CAST(version AS TEXT), CAST(created_at AS TEXT), CAST(updated_at AS TEXT), COALESCE(CAST(deleted_at AS TEXT), '') FROM secrets WHERE path LIKE ? ESCAPE '\\' ORDER BY path"
+        } else {
+            "SELECT path, CAST(version AS TEXT), CAST(created_at AS TEXT), CAST(updated_at AS TEXT), COALESCE(CAST(deleted_at AS TEXT), '') FROM secrets WHERE path LIKE ? ESCAPE '\\' AND deleted_at IS NULL ORDER BY path"
+        };
         let rows = self
             .storage
-            .query_all::<(String, String, String, String, String)>(
-                "SELECT path, CAST(version AS TEXT), CAST(created_at AS TEXT), CAST(updated_at AS TEXT), COALESCE(CAST(deleted_at AS TEXT), '') FROM secrets WHERE path LIKE ? ESCAPE '\\' ORDER BY path",
-                &[&pattern],
-            )
+            .query_all::<(String, String, String, String, String)>(sql, &[&pattern])
             .await
             .map_err(|e| SecretsError::Storage(e.to_string()))?;
 
-        let results = rows
+        Ok(rows.into_iter().map(Self::metadata_from_row).collect())
+    }
+
+    /// Lists secrets whose path matches a glob pattern, soft-deleted ones
+    /// included, in path order.
+    ///
+    /// In the pattern, `*` matches any characters within one segment and a
+    /// `**` segment matches any number of segments, none included:
+    /// `*/database/*` matches `billing/database/password` but not
+    /// `billing/eu/database/password`, which `**/database/*` matches. The
+    /// literal part before the first wildcard narrows the query; the rest is
+    /// matched segment by segment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SecretsError::InvalidPath`] for a pattern that breaks the
+    /// path rules once wildcards are allowed, or uses `**` inside a segment.
+    pub async fn list_glob(&self, pattern: &str) -> Result<Vec<SecretMetadata>, SecretsError> {
+        Self::validate_glob(pattern)?;
+        let literal = pattern.split('*').next().unwrap_or_default();
+        let segments: Vec<&str> = pattern.split('/').collect();
+        Ok(self
+            .list(literal)
+            .await?
             .into_iter()
-            .map(
-                |(path, version_str, created_at_str, updated_at_str, deleted_at_str)| {
-                    SecretMetadata {
-                        path,
-                        version: version_str.parse().unwrap_or(0),
-                        created_at: created_at_str.parse().unwrap_or(0),
-                        updated_at: updated_at_str.parse().unwrap_or(0),
-                        deleted: !deleted_at_str.is_empty(),
-                    }
-                },
+            .filter(|secret| {
+                let path: Vec<&str> = secret.path.split('/').collect();
+                glob_matches(&segments, &path)
+            })
+            .collect())
+    }
+
+    /// Validates a glob pattern for [`Self::list_glob`].
+    fn validate_glob(pattern: &str) -> Result<(), SecretsError> {
+        if pattern
+            .split('/')
+            .any(|segment| segment.contains("**") && segment != "**")
+        {
+            return Err(SecretsError::InvalidPath(
+                "** must be a whole path segment".into(),
+            ));
+        }
+        // Stand a path character in for each wildcard to reuse the path
+        // rules; a lone `*` segment then reads as a valid name.
+        Self::validate_path(&pattern.replace('*', "x"))
+    }
+
+    /// Lists the immediate children of a folder, like `ls` rather than
+    /// `ls -R`.
+    ///
+    /// `prefix` names the folder, with or without a trailing `/`; an empty
+    /// prefix is the root. Returns the distinct next path segments below it,
+    /// sorted: a secret at that level as its bare name, a subfolder with a
+    /// trailing `/`. A name can appear as both. Soft-deleted secrets are left
+    /// out, so a folder holding only deleted secrets does not show.
+    pub async fn list_children(&self, prefix: &str) -> Result<Vec<String>, SecretsError> {
+        let folder = prefix.trim_end_matches('/');
+        let folder = if folder.is_empty() {
+            String::new()
+        } else {
+            format!("{folder}/")
+        };
+        // SQLite's substr counts characters, not bytes.
+        let start = (folder.chars().count() + 1).to_string();
+        let pattern = prefix_pattern(&folder);
+        let rows = self
+            .storage
+            .query_all::<(String,)>(
+                "SELECT DISTINCT CASE WHEN instr(substr(path, CAST(? AS INTEGER)), '/') > 0 THEN substr(path, CAST(? AS INTEGER), instr(substr(path, CAST(? AS INTEGER)), '/')) ELSE substr(path, CAST(? AS INTEGER)) END AS child FROM secrets WHERE path LIKE ? ESCAPE '\\' AND deleted_at IS NULL ORDER BY child",
+                &[&start, &start, &start, &start, &pattern],
             )
-            .collect();
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?;
 
-        Ok(results)
+        Ok(rows.into_iter().map(|(child,)| child).collect())
+    }
+
+    /// Lists one page of secrets matching a prefix, in path order.
+    ///
+    /// Keyset pagination: returns at most `limit` secrets whose path sorts
+    /// after `after` (from the start when `None`), with the cursor to pass
+    /// as `after` for the next page, or `None` on the last page.
+    /// Soft-deleted secrets are included, as in [`Self::list`].
+    /// Unlike an offset, the cursor is stable under concurrent writes: a
+    /// secret inserted before the cursor is never visited and does not shift
+    /// later pages, and the cost of a page does not grow with its position.
+    /// A `limit` of 0 is treated as 1.
+    pub async fn list_page(
+        &self,
+        prefix: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<(Vec<SecretMetadata>, Option<String>), SecretsError> {
+        let pattern = prefix_pattern(prefix);
+        let limit = limit.max(1);
+        // One row past the page tells whether another page follows.
+        let fetch = limit.saturating_add(1).to_string();
+        let rows = match after {
+            Some(after) => self
+                .storage
+                .query_all::<(String, String, String, String, String)>(
+                    "SELECT path, CAST(version AS TEXT), CAST(created_at AS TEXT), CAST(updated_at AS TEXT), COALESCE(CAST(deleted_at AS TEXT), '') FROM secrets WHERE path LIKE ? ESCAPE '\\' AND path > ? ORDER BY path LIMIT CAST(? AS INTEGER)",
+                    &[&pattern, after, &fetch],
+                )
+                .await,
+            None => self
+                .storage
+                .query_all::<(String, String, String, String, String)>(
+                    "SELECT path, CAST(version AS TEXT), CAST(created_at AS TEXT), CAST(updated_at AS TEXT), COALESCE(CAST(deleted_at AS TEXT), '') FROM secrets WHERE path LIKE ? ESCAPE '\\' ORDER BY path LIMIT CAST(? AS INTEGER)",
+                    &[&pattern, &fetch],
+                )
+                .await,
+        }
+        .map_err(|e| SecretsError::Storage(e.to_string()))?;
+
+        let mut page: Vec<SecretMetadata> = rows.into_iter().map(Self::metadata_from_row).collect();
+        let next = if page.len() > limit {
+            page.truncate(limit);
+            page.last().map(|last| last.path.clone())
+        } else {
+            None
+        };
+        Ok((page, next))
+    }
+
+    /// Builds metadata from a `(path, version, created_at, updated_at,
+    /// deleted_at)` row of the `secrets` table.
+    fn metadata_from_row(
+        (path, version_str, created_at_str, updated_at_str, deleted_at_str): (
+            String,
+            String,
+            String,
+            String,
+            String,
+        ),
+    ) -> SecretMetadata {
+        SecretMetadata {
+            path,
+            version: version_str.parse().unwrap_or(0),
+            created_at: created_at_str.parse().unwrap_or(0),
+            updated_at: updated_at_str.parse().unwrap_or(0),
+            deleted: !deleted_at_str.is_empty(),
+        }
+    }
+
+    /// Finds secrets whose plaintext `field` starts with `value_prefix`.
+    ///
+    /// Only fields configured with [`Self::with_plaintext_fields`] are
+    /// searchable; encrypted fields never match. Searches the current version
+    /// of live secrets under `prefix` and returns their paths, sorted. The
+    /// matched values are not authenticated until the secret is read with
+    /// [`Self::get`].
+    pub async fn search_plaintext(
+        &self,
+        prefix: &str,
+        field: &str,
+        value_prefix: &str,
+    ) -> Result<Vec<String>, SecretsError> {
+        let path_pattern = prefix_pattern(prefix);
+        let value_pattern = prefix_pattern(value_prefix);
+        let rows = self
+            .storage
+            .query_all::<(String,)>(
+                "SELECT s.path FROM secrets s JOIN secret_versions v ON v.path = s.path AND v.version = s.version WHERE s.deleted_at IS NULL AND s.path LIKE ? ESCAPE '\\' AND EXISTS (SELECT 1 FROM json_each(v.plaintext_data) f WHERE f.key = ? AND f.value LIKE ? ESCAPE '\\') ORDER BY s.path",
+                &[&path_pattern, field, &value_pattern],
+            )
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|(path,)| path).collect())
+    }
+
+    /// Counts secrets matching a prefix.
+    ///
+    /// Soft-deleted secrets are only counted when `include_deleted` is set.
+    pub async fn count(&self, prefix: &str, include_deleted: bool) -> Result<u64, SecretsError> {
+        let pattern = prefix_pattern(prefix);
+        let sql = if include_deleted {
+            "SELECT CAST(COUNT(*) AS TEXT) FROM secrets WHERE path LIKE ? ESCAPE '\\'"
+        } else {
+            "SELECT CAST(COUNT(*) AS TEXT) FROM secrets WHERE path LIKE ? ESCAPE '\\' AND deleted_at IS NULL"
+        };
+        let row = self
+            .storage
+            .query_one::<(String,)>(sql, &[&pattern])
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?;
+
+        Ok(row.and_then(|(count,)| count.parse().ok()).unwrap_or(0))
     }
 
     /// Lists all versions of a secret.
@@ -797,6 +1747,23 @@ impl SecretsEngine {
             return Err(SecretsError::NotFound(path.to_string()));
         }
 
+        self.version_history(path).await
+    }
+
+    /// Retrieves the current version of a secret together with its version
+    /// list, as [`Self::get`] and [`Self::versions`] would return them.
+    pub async fn get_with_history(
+        &self,
+        path: &str,
+    ) -> Result<(Secret, Vec<SecretVersionInfo>), SecretsError> {
+        let secret = self.get(path).await?;
+        let history = self.version_history(path).await?;
+        Ok((secret, history))
+    }
+
+    /// Lists the version rows of a secret already known to exist, newest
+    /// first.
+    async fn version_history(&self, path: &str) -> Result<Vec<SecretVersionInfo>, SecretsError> {
         let rows = self
             .storage
             .query_all::<(String, String, String, String)>(
@@ -841,17 +1808,396 @@ impl SecretsEngine {
         Ok(results)
     }
 
-    /// Rolls back to a previous version (creates a new version with old data).
+    /// Applies field changes to the current version and stores the result as
+    /// a new version.
+    ///
+    /// A `Some` value sets its field and `None` removes it; fields not named
+    /// in `changes` are carried over, and so are the current metadata and
+    /// remaining lifetime unless `options` sets its own. The write is check-and-set against the
+    /// version that was read, so a concurrent write fails the patch with
+    /// [`SecretsError::VersionMismatch`] instead of being overwritten;
+    /// `options.cas`, when set, must also match that version. Returns the new
+    /// version number.
+    pub async fn patch(
+        &self,
+        path: &str,
+        changes: HashMap<String, Option<String>>,
+        mut options: PutOptions,
+    ) -> Result<u32, SecretsError> {
+        Self::validate_path(path)?;
+        let current = self
+            .current_version_for_write(path, options.cas)
+            .await?
+            .ok_or_else(|| SecretsError::NotFound(path.to_string()))?;
+        let secret = self.get_version(path, current).await?;
+        if options.ttl.is_none() {
+            options.ttl = secret.remaining_ttl();
+        }
+        if options.metadata.is_none() {
+            options.metadata = secret.metadata;
+        }
+        let mut data = secret.data;
+        for (field, value) in changes {
+            match value {
+                Some(value) => data.insert(field, value),
+                None => data.remove(&field),
+            };
+        }
+
+        options.cas = Some(current);
+        self.put(path, data, options).await
+    }
+
+    /// Replaces the metadata of the current version in place, without
+    /// creating a new version.
+    ///
+    /// The secret data is unchanged, but since metadata is bound into the
+    /// AEAD associated data, the version is resealed under its own derived
+    /// key with the new metadata. `updated_at` is touched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SecretsError::NotFound`] or [`SecretsError::Deleted`] for a
+    /// missing or soft-deleted secret, and any error [`Self::get_version`]
+    /// returns for a current version that cannot be read.
+    pub async fn update_metadata(
+        &self,
+        path: &str,
+        metadata: serde_json::Value,
+    ) -> Result<(), SecretsError> {
+        self.check_metadata(Some(&metadata))?;
+        let metadata_repr = Self::serialize_metadata(&PutOptions {
+            metadata: Some(metadata),
+            ..PutOptions::default()
+        })?
+        .unwrap_or_default();
+        let version = self.reseal_current(path, None, Some(metadata_repr)).await?;
+
+        debug!(path = path, version = version, "Secret metadata updated");
+        Ok(())
+    }
+
+    /// Moves the expiry of the current version to `new_ttl` from now, without
+    /// creating a new version, and returns the new expiry.
+    ///
+    /// A TTL shorter than the remaining lifetime shortens it. The configured
+    /// TTL cap applies as on [`Self::put`]. Like [`Self::update_metadata`],
+    /// the version is resealed in place, since its expiry is authenticated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SecretsError::Expired`] for a secret that has already
+    /// expired, [`SecretsError::NotFound`] or [`SecretsError::Deleted`] for a
+    /// missing or soft-deleted one, and [`SecretsError::TtlExceedsMax`] for a
+    /// TTL the cap rejects.
+    pub async fn renew(&self, path: &str, new_ttl: Duration) -> Result<u64, SecretsError> {
+        let ttl = self.effective_ttl(Some(new_ttl))?.unwrap_or(new_ttl);
+        let expires_at = Self::now() + ttl.as_secs();
+        let version = self
+            .reseal_current(path, Some(expires_at.to_string()), None)
+            .await?;
+
+        debug!(
+            path = path,
+            version = version,
+            expires_at = expires_at,
+            "Secret renewed"
+        );
+        Ok(expires_at)
+    }
+
+    /// Rewrites the current version of `path` in place with new stored forms
+    /// of `expires_at` and `metadata` (`None` keeps the stored one), and
+    /// returns that version.
+    ///
+    /// Both columns are bound into the associated data, so the data is
+    /// decrypted, checked like [`Self::get_version`] (which refuses an expired
+    /// version), and encrypted again under the same derived key.
+    async fn reseal_current(
+        &self,
+        path: &str,
+        expires_at: Option<String>,
+        metadata: Option<String>,
+    ) -> Result<u32, SecretsError> {
+        Self::validate_path(path)?;
+        let version = self
+            .current_version_for_write(path, None)
+            .await?
+            .ok_or_else(|| SecretsError::NotFound(path.to_string()))?;
+        let data = self.get_version(path, version).await?.data;
+        let version_repr = version.to_string();
+        let (stored_expires_at, stored_metadata, salt_repr) = self
+            .storage
+            .query_one::<(String, String, String)>(
+                "SELECT COALESCE(CAST(expires_at AS TEXT), ''), COALESCE(metadata, ''), COALESCE(generation_salt, '') FROM secret_versions WHERE path = ? AND version = ?",
+                &[path, &version_repr],
+            )
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?
+            .ok_or_else(|| SecretsError::VersionNotFound {
+                path: path.to_string(),
+                version,
+            })?;
+
+        let expires_at_repr = expires_at.unwrap_or(stored_expires_at);
+        let metadata_repr = metadata.unwrap_or(stored_metadata);
+        let (data, plaintext_repr) = self.split_plaintext(path, data)?;
+        let context = VersionContext {
+            expires_at: &expires_at_repr,
+            metadata: &metadata_repr,
+            plaintext_data: &plaintext_repr,
+        };
+        let generation_salt = (!salt_repr.is_empty()).then_some(salt_repr.as_str());
+        let (encrypted_data, nonce) =
+            self.encrypt_data(path, version, generation_salt, &context, &data)?;
+
+        let data_hex = encoding::encode_hex(&encrypted_data);
+        let nonce_hex = encoding::encode_hex(&nonce);
+        let kdf_version = self.kdf_version.to_string();
+        let now = Self::now().to_string();
+        let statements: [(&str, &[&str]); 2] = [
+            (
+                "UPDATE secret_versions SET data = ?, nonce = ?, expires_at = ?, metadata = ?, kdf_version = ?, plaintext_data = NULLIF(?, '') WHERE path = ? AND version = ?",
+                &[
+                    &data_hex,
+                    &nonce_hex,
+                    &expires_at_repr,
+                    &metadata_repr,
+                    &kdf_version,
+                    &plaintext_repr,
+                    path,
+                    &version_repr,
+                ],
+            ),
+            (
+                "UPDATE secrets SET updated_at = ? WHERE path = ?",
+                &[&now, path],
+            ),
+        ];
+        self.storage
+            .execute_transaction(&statements)
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?;
+        Ok(version)
+    }
+
+    /// Copies the current version of `from` to `to`.
+    ///
+    /// The data is decrypted under the source path and written as a new
+    /// version of `to` with `options`, so it is encrypted under the
+    /// destination's own derived key; the source metadata and remaining
+    /// lifetime are carried over unless `options` sets its own. History is not copied. Returns the
+    /// destination version.
+    pub async fn copy(
+        &self,
+        from: &str,
+        to: &str,
+        mut options: PutOptions,
+    ) -> Result<u32, SecretsError> {
+        Self::validate_path(from)?;
+        Self::validate_path(to)?;
+        let source = self.get(from).await?;
+        if options.ttl.is_none() {
+            options.ttl = source.remaining_ttl();
+        }
+        if options.metadata.is_none() {
+            options.metadata = source.metadata;
+        }
+        self.put(to, source.data, options).await
+    }
+
+    /// Moves the current version of `from` to `to`, then soft-deletes `from`.
+    ///
+    /// Works like [`Self::copy`] with default options. The source delete is
+    /// check-and-set against the version that was copied: if `from` was
+    /// written in between, the move fails with
+    /// [`SecretsError::VersionMismatch`] after the copy, leaving both paths
+    /// in place. Returns the destination version.
+    pub async fn move_secret(&self, from: &str, to: &str) -> Result<u32, SecretsError> {
+        Self::validate_path(from)?;
+        Self::validate_path(to)?;
+        let source = self.get(from).await?;
+        let version = self
+            .put(
+                to,
+                source.data,
+                PutOptions {
+                    ttl: source.remaining_ttl(),
+                    metadata: source.metadata,
+                    ..PutOptions::default()
+                },
+            )
+            .await?;
+        self.delete(
+            from,
+            DeleteOptions {
+                cas: Some(source.version),
+            },
+        )
+        .await?;
+
+        info!(from = from, to = to, version = version, "Secret moved");
+        Ok(version)
+    }
+
+    /// Re-encrypts every version of `path` from `old_master` to the engine's
+    /// master key, after a master key rotation, and returns how many versions
+    /// were rewrapped.
+    ///
+    /// Each version is decrypted under its recorded derivation with
+    /// `old_master` and encrypted again under the engine's master key and
+    /// current derivation version, keeping its generation salt and context
+    /// columns; expired versions and soft-deleted secrets are rewrapped too.
+    /// The version pointer MAC is recomputed under the new key, and all rows
+    /// are written in one transaction. A secret whose pointer already
+    /// authenticates under the engine's key is left alone and reports 0, so
+    /// the call can be retried over every path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SecretsError::Integrity`] if the pointer authenticates under
+    /// neither key, and [`SecretsError::Crypto`] if a version does not
+    /// decrypt under `old_master`; nothing is written in either case.
+    pub async fn rewrap_secret(
+        &self,
+        path: &str,
+        old_master: &MasterKey,
+    ) -> Result<u32, SecretsError> {
+        Self::validate_path(path)?;
+        let (version, deleted_at, row_mac) = self
+            .storage
+            .query_one::<(i64, Option<i64>, String)>(
+                "SELECT version, deleted_at, COALESCE(row_mac, '') FROM secrets WHERE path = ?",
+                &[path],
+            )
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?
+            .ok_or_else(|| SecretsError::NotFound(path.to_string()))?;
+        let version = u32::try_from(version).unwrap_or(0);
+        let deleted_at_repr = deleted_at.map(|d| d.to_string()).unwrap_or_default();
+        if self
+            .verify_pointer_mac(path, version, &deleted_at_repr, &row_mac)
+            .is_ok()
+        {
+            return Ok(0);
+        }
+        Self::verify_pointer_mac_from(old_master, path, version, &deleted_at_repr, &row_mac)?;
+
+        let rows = self
+            .storage
+            .query_all::<(String, String, String, String, String, String, String, String)>(
+                "SELECT CAST(version AS TEXT), data, nonce, COALESCE(CAST(expires_at AS TEXT), ''), COALESCE(metadata, ''), COALESCE(generation_salt, ''), COALESCE(CAST(kdf_version AS TEXT), ''), COALESCE(plaintext_data, '') FROM secret_versions WHERE path = ? ORDER BY version",
+                &[path],
+            )
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?;
+
+        let mut rewrapped = Vec::with_capacity(rows.len());
+        for (
+            version_repr,
+            data_hex,
+            nonce_hex,
+            expires_at_repr,
+            metadata_repr,
+            salt_repr,
+            kdf_version_repr,
+            plaintext_repr,
+        ) in rows
+        {
+            let row_version: u32 = version_repr
+                .parse()
+                .map_err(|_| SecretsError::Integrity(format!("unparsable version for {path}")))?;
+            let kdf_version = Self::parse_kdf_version(&kdf_version_repr, path, row_version)?;
+            let generation_salt = (!salt_repr.is_empty()).then_some(salt_repr.as_str());
+            let context = VersionContext {
+                expires_at: &expires_at_repr,
+                metadata: &metadata_repr,
+                plaintext_data: &plaintext_repr,
+            };
+            let data_bytes = hex_decode(&data_hex)
+                .map_err(|e| SecretsError::Storage(format!("invalid data encoding: {e}")))?;
+            let nonce_bytes = hex_decode(&nonce_hex)
+                .map_err(|e| SecretsError::Storage(format!("invalid nonce encoding: {e}")))?;
+
+            let old_key = Self::derive_secret_key_from(
+                old_master,
+                path,
+                row_version,
+                kdf_version,
+                generation_salt,
+            )?;
+            let data = Self::open_data(
+                &old_key,
+                path,
+                row_version,
+                &context,
+                (&data_bytes, &nonce_bytes),
+            )?;
+            let (encrypted_data, nonce) =
+                self.encrypt_data(path, row_version, generation_salt, &context, &data)?;
+            rewrapped.push((
+                version_repr,
+                encoding::encode_hex(&encrypted_data),
+                encoding::encode_hex(&nonce),
+            ));
+        }
+
+        let kdf_version = self.kdf_version.to_string();
+        let pointer_mac = self.pointer_mac(path, version, &deleted_at_repr)?;
+        let version_params: Vec<[&str; 5]> = rewrapped
+            .iter()
+            .map(|(version_repr, data_hex, nonce_hex)| {
+                [
+                    data_hex.as_str(),
+                    nonce_hex.as_str(),
+                    kdf_version.as_str(),
+                    path,
+                    version_repr.as_str(),
+                ]
+            })
+            .collect();
+        let pointer_params = [pointer_mac.as_str(), path];
+        let mut statements: Vec<(&str, &[&str])> = version_params
+            .iter()
+            .map(|params| {
+                (
+                    "UPDATE secret_versions SET data = ?, nonce = ?, kdf_version = ? WHERE path = ? AND version = ?",
+                    &params[..],
+                )
+            })
+            .collect();
+        statements.push((
+            "UPDATE secrets SET row_mac = ? WHERE path = ?",
+            &pointer_params[..],
+        ));
+        self.storage
+            .execute_transaction(&statements)
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?;
+
+        let count = u32::try_from(rewrapped.len()).unwrap_or(u32::MAX);
+        info!(
+            path = path,
+            versions = count,
+            "Secret rewrapped under new master key"
+        );
+        Ok(count)
+    }
+
+    /// Rolls back to a previous version (creates a new version with the old
+    /// data and metadata).
     ///
     /// Returns the new version number.
     pub async fn rollback(&self, path: &str, version: u32) -> Result<u32, SecretsError> {
         // Get the old version's data
         let old_secret = self.get_version(path, version).await?;
 
-        // Put it as a new version
-        let new_version = self
-            .put(path, old_secret.data, PutOptions::default())
-            .await?;
+        // Put it as a new version, with the old version's metadata
+        let options = PutOptions {
+            metadata: old_secret.metadata,
+            ..PutOptions::default()
+        };
+        let new_version = self.put(path, old_secret.data, options).await?;
 
         info!(
             path = path,
@@ -915,16 +2261,40 @@ impl SecretsEngine {
 
         Ok(count)
     }
-}
 
-/// Information about a specific secret version.
-#[derive(Debug, Clone)]
-pub struct SecretVersionInfo {
-    /// Version number.
-    pub version: u32,
-    /// Creation timestamp.
-    pub created_at: u64,
-    /// Expiration timestamp.
+    /// Permanently deletes expired secret versions.
+    ///
+    /// Returns the number of versions purged. The version a secret's pointer
+    /// references is kept even once expired, so reading it still reports
+    /// [`SecretsError::Expired`] rather than a missing version.
+    pub async fn purge_expired(&self) -> Result<u32, SecretsError> {
+        let now = Self::now().to_string();
+        let purged = self
+            .storage
+            .query_all::<(String,)>(
+                "DELETE FROM secret_versions WHERE expires_at < CAST(? AS INTEGER) AND version <> COALESCE((SELECT s.version FROM secrets s WHERE s.path = secret_versions.path), 0) RETURNING path",
+                &[&now],
+            )
+            .await
+            .map_err(|e| SecretsError::Storage(e.to_string()))?;
+
+        let count = u32::try_from(purged.len()).unwrap_or(u32::MAX);
+        if count > 0 {
+            info!(count = count, "Purged expired secret versions");
+        }
+
+        Ok(count)
+    }
+}
+
+/// Information about a specific secret version.
+#[derive(Debug, Clone)]
+pub struct SecretVersionInfo {
+    /// Version number.
+    pub version: u32,
+    /// Creation timestamp.
+    pub created_at: u64,
+    /// Expiration timestamp.
     pub expires_at: Option<u64>,
     /// Actor who created this version.
     pub created_by: Option<String>,
@@ -932,30 +2302,43 @@ pub struct SecretVersionInfo {
     pub expired: bool,
 }
 
-/// Encodes bytes as lowercase hex.
-fn hex_encode(bytes: &[u8]) -> String {
-    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
-    let mut hex = String::with_capacity(bytes.len() * 2);
-    for byte in bytes {
-        hex.push(HEX_CHARS[(byte >> 4) as usize] as char);
-        hex.push(HEX_CHARS[(byte & 0x0F) as usize] as char);
+/// Matches path segments against glob segments, where `**` spans any
+/// number of segments.
+fn glob_matches(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| glob_matches(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(segment, tail)| {
+            segment_matches(first, segment) && glob_matches(rest, tail)
+        }),
+    }
+}
+
+/// Matches one path segment against a pattern where `*` spans any run of
+/// characters.
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = segment.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: the segment must equal the pattern.
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
     }
-    hex
+    rest.len() >= last.len() && rest.ends_with(last)
 }
 
 /// Decodes hex to bytes.
-fn hex_decode(hex: &str) -> Result<Vec<u8>, String> {
-    let bytes = hex.as_bytes();
-    if !bytes.len().is_multiple_of(2) {
-        return Err("odd length hex string".into());
-    }
-    bytes
-        .chunks_exact(2)
-        .map(|pair| {
-            let s = std::str::from_utf8(pair).map_err(|_| "invalid hex".to_string())?;
-            u8::from_str_radix(s, 16).map_err(|e| e.to_string())
-        })
-        .collect()
+fn hex_decode(hex: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+    encoding::decode_hex(hex).map_err(|e| e.to_string())
 }
 
 #[cfg(test)]
@@ -1140,12 +2523,18 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(engine.get("app/life").await.unwrap().version, 2);
-        engine.delete("app/life").await.unwrap();
+        engine
+            .delete("app/life", DeleteOptions::default())
+            .await
+            .unwrap();
         assert!(matches!(
             engine.get("app/life").await,
             Err(SecretsError::Deleted(_))
         ));
-        engine.undelete("app/life").await.unwrap();
+        engine
+            .undelete("app/life", UndeleteOptions::default())
+            .await
+            .unwrap();
         assert_eq!(engine.get("app/life").await.unwrap().version, 2);
     }
 
@@ -1156,6 +2545,7 @@ mod tests {
             ttl: Some(std::time::Duration::from_hours(1)),
             metadata: Some(serde_json::json!({"env": "prod"})),
             cas: None,
+            ..Default::default()
         };
         engine.put("app/full", test_data(), opts).await.unwrap();
 
@@ -1183,6 +2573,7 @@ mod tests {
             ttl: None,
             metadata: Some(serde_json::json!({"role": "admin"})),
             cas: None,
+            ..Default::default()
         };
         engine.put("app/meta", test_data(), opts).await.unwrap();
 
@@ -1203,6 +2594,85 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_plaintext_fields_are_searchable_and_rest_stays_encrypted() {
+        let tmp = TempDir::new().unwrap();
+        let engine = SecretsEngine::new(tmp.path(), "test", MasterKey::generate().unwrap())
+            .await
+            .unwrap()
+            .with_plaintext_fields("apps", vec!["description".to_string()]);
+
+        let mut data = HashMap::new();
+        data.insert("description".to_string(), "billing database".to_string());
+        data.insert("password".to_string(), "hunter2-secret".to_string());
+        engine
+            .put("apps/billing", data.clone(), PutOptions::default())
+            .await
+            .unwrap();
+        // Outside the configured prefix, every field is encrypted.
+        engine
+            .put("apps-legacy/billing", data.clone(), PutOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            engine
+                .search_plaintext("", "description", "billing")
+                .await
+                .unwrap(),
+            vec!["apps/billing".to_string()]
+        );
+        assert!(engine
+            .search_plaintext("", "password", "hunter2")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let (data_hex, plaintext) = engine
+            .storage
+            .query_one::<(String, String)>(
+                "SELECT data, COALESCE(plaintext_data, '') FROM secret_versions WHERE path = ?",
+                &["apps/billing"],
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(plaintext.contains("billing database"));
+        assert!(!plaintext.contains("hunter2-secret"));
+        assert!(!data_hex.contains(encoding::encode_hex(b"hunter2-secret").as_str()));
+
+        let secret = engine.get("apps/billing").await.unwrap();
+        assert_eq!(secret.data, data);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_plaintext_fields_fail_decryption() {
+        let tmp = TempDir::new().unwrap();
+        let engine = SecretsEngine::new(tmp.path(), "test", MasterKey::generate().unwrap())
+            .await
+            .unwrap()
+            .with_plaintext_fields("", vec!["description".to_string()]);
+
+        let mut data = HashMap::new();
+        data.insert("description".to_string(), "primary".to_string());
+        data.insert("password".to_string(), "s3cret".to_string());
+        engine
+            .put("app/plain", data, PutOptions::default())
+            .await
+            .unwrap();
+
+        engine
+            .storage
+            .execute(
+                "UPDATE secret_versions SET plaintext_data = ? WHERE path = ? AND version = 1",
+                &[r#"{"description":"replica"}"#, "app/plain"],
+            )
+            .await
+            .unwrap();
+
+        assert!(engine.get("app/plain").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_tampered_expires_at_fails_decryption() {
         let (_tmp, engine) = setup().await;
@@ -1210,6 +2680,7 @@ mod tests {
             ttl: Some(std::time::Duration::from_hours(1)),
             metadata: None,
             cas: None,
+            ..Default::default()
         };
         engine.put("app/exp", test_data(), opts).await.unwrap();
 
@@ -1277,114 +2748,952 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_cas_success() {
+    async fn test_max_versions_prunes_old_versions() {
         let (_tmp, engine) = setup().await;
-
+        let keep_three = PutOptions {
+            max_versions: Some(3),
+            ..Default::default()
+        };
         engine
-            .put("app/secret", test_data(), PutOptions::default())
+            .put("app/rotated", test_data(), keep_three)
             .await
             .unwrap();
+        // Later writes inherit the stored limit.
+        for _ in 0..4 {
+            engine
+                .put("app/rotated", test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
 
-        let opts = PutOptions {
-            cas: Some(1),
+        let versions: Vec<u32> = engine
+            .versions("app/rotated")
+            .await
+            .unwrap()
+            .iter()
+            .map(|info| info.version)
+            .collect();
+        assert_eq!(versions, vec![5, 4, 3]);
+        assert!(matches!(
+            engine.get_version("app/rotated", 2).await,
+            Err(SecretsError::VersionNotFound { version: 2, .. })
+        ));
+        assert_eq!(engine.get("app/rotated").await.unwrap().version, 5);
+
+        // Lowering the limit prunes down to it on the next write.
+        let keep_one = PutOptions {
+            max_versions: Some(1),
             ..Default::default()
         };
-
-        let v2 = engine.put("app/secret", test_data(), opts).await.unwrap();
-        assert_eq!(v2, 2);
+        let v6 = engine
+            .put("app/rotated", test_data(), keep_one)
+            .await
+            .unwrap();
+        let versions = engine.versions("app/rotated").await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, v6);
+        assert_eq!(engine.get("app/rotated").await.unwrap().version, v6);
+
+        // Zero lifts the limit again.
+        let keep_all = PutOptions {
+            max_versions: Some(0),
+            ..Default::default()
+        };
+        engine
+            .put("app/rotated", test_data(), keep_all)
+            .await
+            .unwrap();
+        engine
+            .put("app/rotated", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(engine.versions("app/rotated").await.unwrap().len(), 3);
     }
 
     #[tokio::test]
-    async fn test_cas_failure() {
+    async fn test_patch_merges_changes_into_a_new_version() {
         let (_tmp, engine) = setup().await;
-
+        let mut data = test_data();
+        data.insert("host".to_string(), "db.internal".to_string());
         engine
-            .put("app/secret", test_data(), PutOptions::default())
+            .put("app/db", data, PutOptions::default())
             .await
             .unwrap();
 
-        let opts = PutOptions {
-            cas: Some(99), // Wrong version
+        let changes = HashMap::from([
+            ("password".to_string(), Some("rotated".to_string())),
+            ("host".to_string(), None),
+            ("port".to_string(), Some("5432".to_string())),
+        ]);
+        let version = engine
+            .patch("app/db", changes, PutOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(version, 2);
+
+        let secret = engine.get("app/db").await.unwrap();
+        assert_eq!(secret.data.get("username").unwrap(), "admin");
+        assert_eq!(secret.data.get("password").unwrap(), "rotated");
+        assert_eq!(secret.data.get("port").unwrap(), "5432");
+        assert!(!secret.data.contains_key("host"));
+
+        let stale = PutOptions {
+            cas: Some(1),
             ..Default::default()
         };
-
-        let result = engine.put("app/secret", test_data(), opts).await;
         assert!(matches!(
-            result,
+            engine.patch("app/db", HashMap::new(), stale).await,
             Err(SecretsError::VersionMismatch {
-                expected: 99,
-                found: 1
+                expected: 1,
+                found: 2
             })
         ));
+        assert!(matches!(
+            engine
+                .patch("app/missing", HashMap::new(), PutOptions::default())
+                .await,
+            Err(SecretsError::NotFound(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_delete_and_undelete() {
+    async fn test_patch_copy_and_move_keep_the_remaining_ttl() {
         let (_tmp, engine) = setup().await;
+        let opts = PutOptions {
+            ttl: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        engine.put("app/lease", test_data(), opts).await.unwrap();
+        let original = engine.get("app/lease").await.unwrap().expires_at.unwrap();
 
+        let changes = HashMap::from([("password".to_string(), Some("rotated".to_string()))]);
         engine
-            .put("app/temp", test_data(), PutOptions::default())
+            .patch("app/lease", changes, PutOptions::default())
             .await
             .unwrap();
+        let patched = engine.get("app/lease").await.unwrap();
+        assert_eq!(patched.version, 2);
+        let expires_at = patched
+            .expires_at
+            .expect("patched secret must still expire");
+        assert!(expires_at <= original);
 
-        // Delete
-        engine.delete("app/temp").await.unwrap();
-
-        // Should not be accessible
-        let result = engine.get("app/temp").await;
-        assert!(matches!(result, Err(SecretsError::Deleted(_))));
-
-        // Undelete
-        engine.undelete("app/temp").await.unwrap();
+        engine
+            .copy("app/lease", "app/lease-copy", PutOptions::default())
+            .await
+            .unwrap();
+        let copied = engine.get("app/lease-copy").await.unwrap();
+        assert!(copied.expires_at.is_some_and(|e| e <= expires_at));
 
-        // Should be accessible again
-        let secret = engine.get("app/temp").await.unwrap();
-        assert_eq!(secret.data.get("username").unwrap(), "admin");
+        engine
+            .move_secret("app/lease-copy", "app/lease-moved")
+            .await
+            .unwrap();
+        let moved = engine.get("app/lease-moved").await.unwrap();
+        assert!(moved.expires_at.is_some_and(|e| e <= expires_at));
     }
 
     #[tokio::test]
-    async fn test_get_version_respects_soft_delete() {
+    async fn test_update_metadata_keeps_version_and_data() {
         let (_tmp, engine) = setup().await;
+        let opts = PutOptions {
+            metadata: Some(serde_json::json!({"rotation": "monthly"})),
+            ..Default::default()
+        };
+        engine.put("app/meta", test_data(), opts).await.unwrap();
+        let before = engine.list("app/meta").await.unwrap()[0].updated_at;
 
         engine
-            .put("app/deleted", test_data(), PutOptions::default())
+            .update_metadata("app/meta", serde_json::json!({"rotation": "weekly"}))
             .await
             .unwrap();
 
-        // Soft delete
-        engine.delete("app/deleted").await.unwrap();
-
-        // get_version should also return Deleted error (regression test)
-        let result = engine.get_version("app/deleted", 1).await;
-        assert!(matches!(result, Err(SecretsError::Deleted(_))));
-
-        // After undelete, get_version should work
-        engine.undelete("app/deleted").await.unwrap();
-        let secret = engine.get_version("app/deleted", 1).await.unwrap();
+        let secret = engine.get("app/meta").await.unwrap();
         assert_eq!(secret.version, 1);
+        assert_eq!(secret.data, test_data());
+        assert_eq!(
+            secret.metadata,
+            Some(serde_json::json!({"rotation": "weekly"}))
+        );
+        assert_eq!(engine.versions("app/meta").await.unwrap().len(), 1);
+        assert!(engine.list("app/meta").await.unwrap()[0].updated_at >= before);
+
+        assert!(matches!(
+            engine
+                .update_metadata("app/missing", serde_json::json!({}))
+                .await,
+            Err(SecretsError::NotFound(_))
+        ));
+        engine
+            .delete("app/meta", DeleteOptions::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine
+                .update_metadata("app/meta", serde_json::json!({}))
+                .await,
+            Err(SecretsError::Deleted(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_purge_deleted_does_not_purge_when_older_than_exceeds_now() {
+    async fn test_get_many_returns_per_path_results() {
         let (_tmp, engine) = setup().await;
+        engine
+            .put("app/one", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        engine
+            .put("app/gone", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        engine
+            .delete("app/gone", DeleteOptions::default())
+            .await
+            .unwrap();
 
+        let results = engine
+            .get_many(&["app/one", "app/gone", "app/missing", "app/one"])
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results["app/one"].as_ref().unwrap().data, test_data());
+        assert!(matches!(results["app/gone"], Err(SecretsError::Deleted(_))));
+        assert!(matches!(
+            results["app/missing"],
+            Err(SecretsError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_with_filters_by_deleted_state() {
+        let (_tmp, engine) = setup().await;
+        for path in ["bin/live", "bin/trashed"] {
+            engine
+                .put(path, test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
         engine
-            .put("app/retained", test_data(), PutOptions::default())
+            .delete("bin/trashed", DeleteOptions::default())
             .await
             .unwrap();
-        engine.delete("app/retained").await.unwrap();
 
-        // A retention duration longer than the current Unix time must not
-        // underflow the cutoff computation, and must not purge anything.
-        let purged = engine
-            .purge_deleted(Duration::from_secs(u64::MAX))
+        let paths = |secrets: Vec<SecretMetadata>| -> Vec<String> {
+            secrets.into_iter().map(|secret| secret.path).collect()
+        };
+        assert_eq!(
+            paths(
+                engine
+                    .list_with("bin", ListOptions::default())
+                    .await
+                    .unwrap()
+            ),
+            paths(engine.list("bin").await.unwrap())
+        );
+        let live = ListOptions {
+            include_deleted: false,
+            only_deleted: false,
+        };
+        assert_eq!(
+            paths(engine.list_with("bin", live).await.unwrap()),
+            vec!["bin/live"]
+        );
+        let recycle_bin = ListOptions {
+            only_deleted: true,
+            ..ListOptions::default()
+        };
+        assert_eq!(
+            paths(engine.list_with("bin", recycle_bin).await.unwrap()),
+            vec!["bin/trashed"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_children_returns_one_level() {
+        let (_tmp, engine) = setup().await;
+        for path in [
+            "myapp/db",
+            "myapp/api/key",
+            "myapp/api/deep/token",
+            "myapp/cache",
+            "myapp/cache/redis",
+            "other/x",
+        ] {
+            engine
+                .put(path, test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
+        engine
+            .put("myapp/old/gone", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        engine
+            .delete("myapp/old/gone", DeleteOptions::default())
             .await
             .unwrap();
 
-        assert_eq!(purged, 0);
+        let expected = vec!["api/", "cache", "cache/", "db"];
+        assert_eq!(engine.list_children("myapp/").await.unwrap(), expected);
+        assert_eq!(engine.list_children("myapp").await.unwrap(), expected);
+        assert_eq!(
+            engine.list_children("myapp/api").await.unwrap(),
+            vec!["deep/", "key"]
+        );
+        assert_eq!(
+            engine.list_children("").await.unwrap(),
+            vec!["myapp/", "other/"]
+        );
+        assert!(engine.list_children("nothing").await.unwrap().is_empty());
+    }
 
-        // The secret must still be recoverable.
-        engine.undelete("app/retained").await.unwrap();
+    #[tokio::test]
+    async fn test_copy_and_move_reencrypt_under_destination() {
+        let (_tmp, engine) = setup().await;
+        let opts = PutOptions {
+            metadata: Some(serde_json::json!({"owner": "team-a"})),
+            ..Default::default()
+        };
+        engine.put("old/db", test_data(), opts).await.unwrap();
+
+        assert_eq!(
+            engine
+                .copy("old/db", "new/db", PutOptions::default())
+                .await
+                .unwrap(),
+            1
+        );
+        let copied = engine.get("new/db").await.unwrap();
+        assert_eq!(copied.data, test_data());
+        assert_eq!(
+            copied.metadata,
+            Some(serde_json::json!({"owner": "team-a"}))
+        );
+        assert!(engine.get("old/db").await.is_ok());
+
+        // Each path's row decrypts only under its own derived key.
+        let source_blob = engine
+            .storage
+            .query_one::<(String,)>(
+                "SELECT data FROM secret_versions WHERE path = ?",
+                &["old/db"],
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let copied_blob = engine
+            .storage
+            .query_one::<(String,)>(
+                "SELECT data FROM secret_versions WHERE path = ?",
+                &["new/db"],
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(source_blob, copied_blob);
+
+        assert_eq!(engine.move_secret("old/db", "moved/db").await.unwrap(), 1);
+        assert_eq!(engine.get("moved/db").await.unwrap().data, test_data());
+        assert!(matches!(
+            engine.get("old/db").await,
+            Err(SecretsError::Deleted(_))
+        ));
+
+        assert!(matches!(
+            engine.copy("new/db", "/bad", PutOptions::default()).await,
+            Err(SecretsError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            engine.move_secret("missing/db", "other/db").await,
+            Err(SecretsError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rewrap_secret_moves_versions_to_new_master() {
+        let tmp = TempDir::new().unwrap();
+        let old_master = MasterKey::generate().unwrap();
+        let old_engine = SecretsEngine::new(tmp.path(), "test", old_master.clone())
+            .await
+            .unwrap();
+        old_engine
+            .put("app/db", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        let mut data2 = test_data();
+        data2.insert("password".to_string(), "newp4ss".to_string());
+        old_engine
+            .put("app/db", data2, PutOptions::default())
+            .await
+            .unwrap();
+        drop(old_engine);
+
+        let engine = SecretsEngine::new(tmp.path(), "test", MasterKey::generate().unwrap())
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.get("app/db").await,
+            Err(SecretsError::Integrity(_))
+        ));
+        let wrong = MasterKey::generate().unwrap();
+        assert!(matches!(
+            engine.rewrap_secret("app/db", &wrong).await,
+            Err(SecretsError::Integrity(_))
+        ));
+
+        assert_eq!(
+            engine.rewrap_secret("app/db", &old_master).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            engine
+                .get("app/db")
+                .await
+                .unwrap()
+                .data
+                .get("password")
+                .unwrap(),
+            "newp4ss"
+        );
+        assert_eq!(
+            engine.get_version("app/db", 1).await.unwrap().data,
+            test_data()
+        );
+        assert_eq!(
+            engine.rewrap_secret("app/db", &old_master).await.unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_many_is_all_or_nothing() {
+        let (_tmp, engine) = setup().await;
+        engine
+            .put("svc/existing", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+
+        let versions = engine
+            .put_many(vec![
+                ("svc/a".to_string(), test_data(), PutOptions::default()),
+                (
+                    "svc/existing".to_string(),
+                    test_data(),
+                    PutOptions::default(),
+                ),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(versions, vec![1, 2]);
+        assert_eq!(engine.get("svc/a").await.unwrap().data, test_data());
+
+        // A failing entry rolls back the whole batch.
+        let stale = PutOptions {
+            cas: Some(1),
+            ..Default::default()
+        };
+        assert!(matches!(
+            engine
+                .put_many(vec![
+                    ("svc/b".to_string(), test_data(), PutOptions::default()),
+                    ("svc/existing".to_string(), test_data(), stale),
+                ])
+                .await,
+            Err(SecretsError::VersionMismatch { .. })
+        ));
+        assert!(matches!(
+            engine.get("svc/b").await,
+            Err(SecretsError::NotFound(_))
+        ));
+
+        // So does a conflict only the transaction sees: here a version row
+        // that already exists for the next version of `svc/existing`.
+        engine
+            .storage
+            .execute(
+                "INSERT INTO secret_versions (path, version, data, nonce, created_at) VALUES ('svc/existing', 3, '', '', 0)",
+                &[],
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine
+                .put_many(vec![
+                    ("svc/c".to_string(), test_data(), PutOptions::default()),
+                    (
+                        "svc/existing".to_string(),
+                        test_data(),
+                        PutOptions::default()
+                    ),
+                ])
+                .await,
+            Err(SecretsError::Storage(_))
+        ));
+        assert!(matches!(
+            engine.get("svc/c").await,
+            Err(SecretsError::NotFound(_))
+        ));
+        assert_eq!(engine.get("svc/existing").await.unwrap().version, 2);
+
+        assert!(matches!(
+            engine
+                .put_many(vec![
+                    ("svc/d".to_string(), test_data(), PutOptions::default()),
+                    ("svc/d".to_string(), test_data(), PutOptions::default()),
+                ])
+                .await,
+            Err(SecretsError::InvalidPath(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_data_is_rejected_before_writing() {
+        let (_tmp, engine) = setup().await;
+        let engine = engine.with_max_size(8, 64);
+
+        let mut big_value = HashMap::new();
+        big_value.insert("blob".to_string(), "x".repeat(9));
+        assert!(matches!(
+            engine
+                .put("app/big", big_value, PutOptions::default())
+                .await,
+            Err(SecretsError::ValueTooLarge { size: 9, max: 8 })
+        ));
+
+        let many: HashMap<String, String> = (0..5)
+            .map(|i| (format!("k{i}"), "12345678".to_string()))
+            .collect();
+        assert!(matches!(
+            engine
+                .validate_put("app/big", &many, &PutOptions::default())
+                .await,
+            Err(SecretsError::ValueTooLarge { max: 64, .. })
+        ));
+        assert!(matches!(
+            engine.get("app/big").await,
+            Err(SecretsError::NotFound(_))
+        ));
+
+        engine
+            .put("app/big", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        let grow = HashMap::from([("note".to_string(), Some("x".repeat(9)))]);
+        assert!(matches!(
+            engine.patch("app/big", grow, PutOptions::default()).await,
+            Err(SecretsError::ValueTooLarge { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_field_and_list_fields() {
+        let (_tmp, engine) = setup().await;
+        engine
+            .put("app/fields", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            engine.get_field("app/fields", "username").await.unwrap(),
+            Some("admin".to_string())
+        );
+        assert_eq!(engine.get_field("app/fields", "port").await.unwrap(), None);
+        assert_eq!(
+            engine.list_fields("app/fields").await.unwrap(),
+            vec!["password", "username"]
+        );
+
+        engine
+            .delete("app/fields", DeleteOptions::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.get_field("app/fields", "username").await,
+            Err(SecretsError::Deleted(_))
+        ));
+        assert!(matches!(
+            engine.list_fields("app/fields").await,
+            Err(SecretsError::Deleted(_))
+        ));
+    }
+
+    #[test]
+    fn test_glob_segment_rules() {
+        let matches = |pattern: &str, path: &str| {
+            let pattern: Vec<&str> = pattern.split('/').collect();
+            let path: Vec<&str> = path.split('/').collect();
+            glob_matches(&pattern, &path)
+        };
+        assert!(matches("*/database/*", "billing/database/password"));
+        assert!(!matches("*/database/*", "billing/eu/database/password"));
+        assert!(matches("**/database/*", "billing/eu/database/password"));
+        assert!(matches("**/database/*", "database/password"));
+        assert!(matches("app/**", "app/a/b/c"));
+        assert!(matches("app/db-*", "app/db-primary"));
+        assert!(!matches("app/db-*", "app/cache-primary"));
+        assert!(matches("app/*-key", "app/api-key"));
+        assert!(!matches("app/*-key*x", "app/api-key"));
+        assert!(matches("app/a*b*c", "app/abc"));
+    }
+
+    #[tokio::test]
+    async fn test_list_glob_filters_by_segment() {
+        let (_tmp, engine) = setup().await;
+        for path in [
+            "billing/database/password",
+            "billing/eu/database/password",
+            "shop/database/user",
+            "shop/cache/url",
+        ] {
+            engine
+                .put(path, test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
+
+        let paths = |secrets: Vec<SecretMetadata>| -> Vec<String> {
+            secrets.into_iter().map(|secret| secret.path).collect()
+        };
+        assert_eq!(
+            paths(engine.list_glob("*/database/*").await.unwrap()),
+            vec!["billing/database/password", "shop/database/user"]
+        );
+        assert_eq!(
+            paths(engine.list_glob("billing/**/password").await.unwrap()),
+            vec!["billing/database/password", "billing/eu/database/password"]
+        );
+        for invalid in ["", "/app/*", "app//*", "app/**x", "app/$*"] {
+            assert!(
+                matches!(
+                    engine.list_glob(invalid).await,
+                    Err(SecretsError::InvalidPath(_))
+                ),
+                "{invalid:?} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_keeps_current_version() {
+        let (_tmp, engine) = setup().await;
+        for _ in 0..3 {
+            engine
+                .put("app/leases", test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
+        engine
+            .put("app/stale", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        // Versions 1 and 2 of `app/leases` and the only version of
+        // `app/stale` are past their expiry.
+        engine
+            .storage
+            .execute(
+                "UPDATE secret_versions SET expires_at = 1 WHERE (path = 'app/leases' AND version < 3) OR path = 'app/stale'",
+                &[],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(engine.purge_expired().await.unwrap(), 2);
+        let versions = engine.versions("app/leases").await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, 3);
+        assert!(matches!(
+            engine.get_version("app/leases", 1).await,
+            Err(SecretsError::VersionNotFound { .. })
+        ));
+        assert_eq!(engine.get("app/leases").await.unwrap().version, 3);
+        assert!(matches!(
+            engine.get("app/stale").await,
+            Err(SecretsError::Expired(_))
+        ));
+        assert_eq!(engine.purge_expired().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_with_history_returns_secret_and_versions() {
+        let (_tmp, engine) = setup().await;
+        for _ in 0..2 {
+            engine
+                .put("app/detail", test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
+
+        let (secret, history) = engine.get_with_history("app/detail").await.unwrap();
+        assert_eq!(secret.version, 2);
+        assert_eq!(secret.data, test_data());
+        let versions: Vec<u32> = history.iter().map(|info| info.version).collect();
+        assert_eq!(versions, vec![2, 1]);
+
+        assert!(matches!(
+            engine.get_with_history("app/none").await,
+            Err(SecretsError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_undelete_with_cas() {
+        let (_tmp, engine) = setup().await;
+        for _ in 0..2 {
+            engine
+                .put("app/restore", test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
+        engine
+            .delete("app/restore", DeleteOptions::default())
+            .await
+            .unwrap();
+
+        let stale = UndeleteOptions { cas: Some(1) };
+        assert!(matches!(
+            engine.undelete("app/restore", stale).await,
+            Err(SecretsError::VersionMismatch {
+                expected: 1,
+                found: 2
+            })
+        ));
+        assert!(matches!(
+            engine.get("app/restore").await,
+            Err(SecretsError::Deleted(_))
+        ));
+
+        let observed = UndeleteOptions { cas: Some(2) };
+        engine
+            .undelete("app/restore", observed.clone())
+            .await
+            .unwrap();
+        assert_eq!(engine.get("app/restore").await.unwrap().version, 2);
+        assert!(matches!(
+            engine.undelete("app/restore", observed).await,
+            Err(SecretsError::NotDeleted(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_max_path_depth_rejects_deep_writes() {
+        let (_tmp, engine) = setup().await;
+        engine
+            .put("a/b/c/d", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        let engine = engine.with_max_path_depth(3);
+
+        engine
+            .put("a/b/c", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        let result = engine
+            .put("a/b/c/e", test_data(), PutOptions::default())
+            .await;
+        assert!(
+            matches!(&result, Err(SecretsError::InvalidPath(msg)) if msg.contains("maximum of 3")),
+            "{result:?}"
+        );
+        assert!(matches!(
+            engine
+                .validate_put("a/b/c/e", &test_data(), &PutOptions::default())
+                .await,
+            Err(SecretsError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            engine.get("a/b/c/e").await,
+            Err(SecretsError::NotFound(_))
+        ));
+
+        // A secret stored before the limit stays readable.
+        assert!(engine.get("a/b/c/d").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_required_metadata_is_enforced() {
+        let (_tmp, engine) = setup().await;
+        let engine = engine
+            .with_required_metadata("owner", MetadataType::String)
+            .with_required_metadata("rotation_days", MetadataType::Number);
+
+        let missing = PutOptions {
+            metadata: Some(serde_json::json!({"owner": "team-a"})),
+            ..Default::default()
+        };
+        assert!(matches!(
+            engine.put("app/owned", test_data(), missing).await,
+            Err(SecretsError::InvalidMetadata(_))
+        ));
+        assert!(matches!(
+            engine
+                .put("app/owned", test_data(), PutOptions::default())
+                .await,
+            Err(SecretsError::InvalidMetadata(_))
+        ));
+        let mistyped = PutOptions {
+            metadata: Some(serde_json::json!({"owner": "team-a", "rotation_days": "30"})),
+            ..Default::default()
+        };
+        assert!(matches!(
+            engine
+                .validate_put("app/owned", &test_data(), &mistyped)
+                .await,
+            Err(SecretsError::InvalidMetadata(_))
+        ));
+
+        let valid = PutOptions {
+            metadata: Some(serde_json::json!({"owner": "team-a", "rotation_days": 30})),
+            ..Default::default()
+        };
+        engine.put("app/owned", test_data(), valid).await.unwrap();
+        assert!(matches!(
+            engine
+                .update_metadata("app/owned", serde_json::json!({"owner": "team-b"}))
+                .await,
+            Err(SecretsError::InvalidMetadata(_))
+        ));
+
+        // Patch and rollback carry the metadata over, so they keep passing.
+        let changes = HashMap::from([("password".to_string(), Some("next".to_string()))]);
+        engine
+            .patch("app/owned", changes, PutOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(engine.rollback("app/owned", 1).await.unwrap(), 3);
+        assert_eq!(
+            engine.get("app/owned").await.unwrap().metadata,
+            Some(serde_json::json!({"owner": "team-a", "rotation_days": 30}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cas_success() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .put("app/secret", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+
+        let opts = PutOptions {
+            cas: Some(1),
+            ..Default::default()
+        };
+
+        let v2 = engine.put("app/secret", test_data(), opts).await.unwrap();
+        assert_eq!(v2, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cas_failure() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .put("app/secret", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+
+        let opts = PutOptions {
+            cas: Some(99), // Wrong version
+            ..Default::default()
+        };
+
+        let result = engine.put("app/secret", test_data(), opts).await;
+        assert!(matches!(
+            result,
+            Err(SecretsError::VersionMismatch {
+                expected: 99,
+                found: 1
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_and_undelete() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .put("app/temp", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+
+        // Delete
+        engine
+            .delete("app/temp", DeleteOptions::default())
+            .await
+            .unwrap();
+
+        // Should not be accessible
+        let result = engine.get("app/temp").await;
+        assert!(matches!(result, Err(SecretsError::Deleted(_))));
+
+        // Undelete
+        engine
+            .undelete("app/temp", UndeleteOptions::default())
+            .await
+            .unwrap();
+
+        // Should be accessible again
+        let secret = engine.get("app/temp").await.unwrap();
+        assert_eq!(secret.data.get("username").unwrap(), "admin");
+    }
+
+    #[tokio::test]
+    async fn test_get_version_respects_soft_delete() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .put("app/deleted", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+
+        // Soft delete
+        engine
+            .delete("app/deleted", DeleteOptions::default())
+            .await
+            .unwrap();
+
+        // get_version should also return Deleted error (regression test)
+        let result = engine.get_version("app/deleted", 1).await;
+        assert!(matches!(result, Err(SecretsError::Deleted(_))));
+
+        // After undelete, get_version should work
+        engine
+            .undelete("app/deleted", UndeleteOptions::default())
+            .await
+            .unwrap();
+        let secret = engine.get_version("app/deleted", 1).await.unwrap();
+        assert_eq!(secret.version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_purge_deleted_does_not_purge_when_older_than_exceeds_now() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .put("app/retained", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        engine
+            .delete("app/retained", DeleteOptions::default())
+            .await
+            .unwrap();
+
+        // A retention duration longer than the current Unix time must not
+        // underflow the cutoff computation, and must not purge anything.
+        let purged = engine
+            .purge_deleted(Duration::from_secs(u64::MAX))
+            .await
+            .unwrap();
+
+        assert_eq!(purged, 0);
+
+        // The secret must still be recoverable.
+        engine
+            .undelete("app/retained", UndeleteOptions::default())
+            .await
+            .unwrap();
         let secret = engine.get("app/retained").await.unwrap();
         assert_eq!(secret.data.get("username").unwrap(), "admin");
     }
@@ -1397,7 +3706,10 @@ mod tests {
             .put("app/stale", test_data(), PutOptions::default())
             .await
             .unwrap();
-        engine.delete("app/stale").await.unwrap();
+        engine
+            .delete("app/stale", DeleteOptions::default())
+            .await
+            .unwrap();
 
         // Ensure deleted_at lands strictly before the purge cutoff.
         tokio::time::sleep(Duration::from_secs(2)).await;
@@ -1437,6 +3749,49 @@ mod tests {
         assert_eq!(db_list.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_list_page_visits_each_secret_once_across_inserts() {
+        let (_tmp, engine) = setup().await;
+        for name in ["b", "d", "f", "h", "j"] {
+            engine
+                .put(&format!("app/{name}"), test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
+
+        let mut visited = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let (page, next) = engine
+                .list_page("app/", cursor.as_deref(), 2)
+                .await
+                .unwrap();
+            assert!(page.len() <= 2);
+            visited.extend(page.into_iter().map(|meta| meta.path));
+
+            if visited.len() == 2 {
+                // Mid-iteration: one insert inside the visited range, one
+                // ahead of the cursor.
+                for path in ["app/a", "app/i"] {
+                    engine
+                        .put(path, test_data(), PutOptions::default())
+                        .await
+                        .unwrap();
+                }
+            }
+
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(
+            visited,
+            vec!["app/b", "app/d", "app/f", "app/h", "app/i", "app/j"]
+        );
+    }
+
     #[tokio::test]
     async fn test_list_does_not_treat_underscore_as_a_wildcard() {
         let (_tmp, engine) = setup().await;
@@ -1460,6 +3815,30 @@ mod tests {
         assert_eq!(list[0].path, "prod_db/password");
     }
 
+    #[tokio::test]
+    async fn test_count_tracks_live_secrets_under_prefix() {
+        let (_tmp, engine) = setup().await;
+
+        for path in ["myapp/db", "myapp/cache", "myapp/queue", "other/secret"] {
+            engine
+                .put(path, test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(engine.count("myapp/", false).await.unwrap(), 3);
+        assert_eq!(engine.count("", false).await.unwrap(), 4);
+
+        engine
+            .delete("myapp/cache", DeleteOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(engine.count("myapp/", false).await.unwrap(), 2);
+        assert_eq!(engine.count("myapp/", true).await.unwrap(), 3);
+        assert_eq!(engine.count("missing/", true).await.unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn test_list_does_not_treat_percent_as_a_wildcard() {
         let (_tmp, engine) = setup().await;
@@ -1532,6 +3911,51 @@ mod tests {
         assert_eq!(secret.data.get("username").unwrap(), "admin");
     }
 
+    #[tokio::test]
+    async fn test_max_ttl_rejects_longer_ttl_and_keeps_shorter_one() {
+        let (_tmp, engine) = setup().await;
+        let engine = engine.with_max_ttl(Duration::from_hours(1), MaxTtlPolicy::Reject);
+
+        let over = PutOptions {
+            ttl: Some(Duration::from_hours(2)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            engine.put("app/long", test_data(), over).await,
+            Err(SecretsError::TtlExceedsMax {
+                requested_secs: 7200,
+                max_secs: 3600
+            })
+        ));
+        assert!(matches!(
+            engine.get("app/long").await,
+            Err(SecretsError::NotFound(_))
+        ));
+
+        let under = PutOptions {
+            ttl: Some(Duration::from_mins(30)),
+            ..Default::default()
+        };
+        engine.put("app/short", test_data(), under).await.unwrap();
+        let secret = engine.get("app/short").await.unwrap();
+        assert_eq!(secret.expires_at, Some(secret.created_at + 1800));
+    }
+
+    #[tokio::test]
+    async fn test_max_ttl_clamps_longer_ttl() {
+        let (_tmp, engine) = setup().await;
+        let engine = engine.with_max_ttl(Duration::from_hours(1), MaxTtlPolicy::Clamp);
+
+        let over = PutOptions {
+            ttl: Some(Duration::from_hours(2)),
+            ..Default::default()
+        };
+        engine.put("app/long", test_data(), over).await.unwrap();
+
+        let secret = engine.get("app/long").await.unwrap();
+        assert_eq!(secret.expires_at, Some(secret.created_at + 3600));
+    }
+
     #[tokio::test]
     async fn test_ttl_expiration() {
         let (_tmp, engine) = setup().await;
@@ -1554,6 +3978,68 @@ mod tests {
         assert!(matches!(result, Err(SecretsError::Expired(_))));
     }
 
+    #[tokio::test]
+    async fn test_renew_moves_expiry_without_new_version() {
+        let (_tmp, engine) = setup().await;
+        let opts = PutOptions {
+            ttl: Some(Duration::from_mins(1)),
+            metadata: Some(serde_json::json!({"owner": "team-a"})),
+            ..Default::default()
+        };
+        engine.put("app/lease", test_data(), opts).await.unwrap();
+
+        let extended = engine
+            .renew("app/lease", Duration::from_hours(1))
+            .await
+            .unwrap();
+        let secret = engine.get("app/lease").await.unwrap();
+        assert_eq!(secret.version, 1);
+        assert_eq!(secret.expires_at, Some(extended));
+        assert_eq!(secret.data, test_data());
+        assert_eq!(
+            secret.metadata,
+            Some(serde_json::json!({"owner": "team-a"}))
+        );
+
+        // Shortening is allowed.
+        let shortened = engine
+            .renew("app/lease", Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert!(shortened < extended);
+        assert_eq!(
+            engine.get("app/lease").await.unwrap().expires_at,
+            Some(shortened)
+        );
+
+        // An expired secret cannot be brought back.
+        engine
+            .storage
+            .execute(
+                "UPDATE secret_versions SET expires_at = 1 WHERE path = ?",
+                &["app/lease"],
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.renew("app/lease", Duration::from_mins(1)).await,
+            Err(SecretsError::Expired(_))
+        ));
+
+        engine
+            .put("app/other", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        engine
+            .delete("app/other", DeleteOptions::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.renew("app/other", Duration::from_mins(1)).await,
+            Err(SecretsError::Deleted(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_metadata() {
         let (_tmp, engine) = setup().await;
@@ -1728,6 +4214,134 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_put_records_explicit_created_by() {
+        let (_tmp, engine) = setup().await;
+
+        let mut data = HashMap::new();
+        data.insert("k".to_string(), "v".to_string());
+        engine
+            .put("app/actor", data.clone(), PutOptions::default())
+            .await
+            .unwrap();
+        engine
+            .put(
+                "app/actor",
+                data,
+                PutOptions {
+                    created_by: Some("user:alice".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let versions = engine.versions("app/actor").await.unwrap();
+        assert_eq!(versions[0].created_by.as_deref(), Some("user:alice"));
+        assert_eq!(versions[1].created_by, None);
+
+        let current = engine.get("app/actor").await.unwrap();
+        assert_eq!(current.created_by.as_deref(), Some("user:alice"));
+        let first = engine.get_version("app/actor", 1).await.unwrap();
+        assert_eq!(first.created_by, None);
+    }
+
+    #[tokio::test]
+    async fn test_validate_put_reports_version_without_writing() {
+        let (_tmp, engine) = setup().await;
+
+        let version = engine
+            .validate_put("app/dry", &test_data(), &PutOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(version, 1);
+        assert!(matches!(
+            engine.get("app/dry").await,
+            Err(SecretsError::NotFound(_))
+        ));
+
+        engine
+            .put("app/dry", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        let version = engine
+            .validate_put("app/dry", &test_data(), &PutOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(engine.get("app/dry").await.unwrap().version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_validate_put_runs_put_checks() {
+        let (_tmp, engine) = setup().await;
+        assert!(matches!(
+            engine
+                .validate_put("/bad", &test_data(), &PutOptions::default())
+                .await,
+            Err(SecretsError::InvalidPath(_))
+        ));
+
+        engine
+            .put("app/cas", test_data(), PutOptions::default())
+            .await
+            .unwrap();
+        let stale = PutOptions {
+            cas: Some(7),
+            ..Default::default()
+        };
+        assert!(matches!(
+            engine.validate_put("app/cas", &test_data(), &stale).await,
+            Err(SecretsError::VersionMismatch {
+                expected: 7,
+                found: 1
+            })
+        ));
+
+        engine
+            .delete("app/cas", DeleteOptions::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine
+                .validate_put("app/cas", &test_data(), &PutOptions::default())
+                .await,
+            Err(SecretsError::Deleted(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_with_cas() {
+        let (_tmp, engine) = setup().await;
+        for _ in 0..2 {
+            engine
+                .put("app/cas-delete", test_data(), PutOptions::default())
+                .await
+                .unwrap();
+        }
+
+        // A client that read version 1 must not delete version 2.
+        assert!(matches!(
+            engine
+                .delete("app/cas-delete", DeleteOptions { cas: Some(1) })
+                .await,
+            Err(SecretsError::VersionMismatch {
+                expected: 1,
+                found: 2
+            })
+        ));
+        assert!(engine.get("app/cas-delete").await.is_ok());
+
+        engine
+            .delete("app/cas-delete", DeleteOptions { cas: Some(2) })
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.get("app/cas-delete").await,
+            Err(SecretsError::Deleted(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_versions_rejects_unparsable_expires_at() {
         let (_tmp, engine) = setup().await;
@@ -1795,9 +4409,11 @@ mod tests {
     async fn test_derived_keys_differ_across_versions_and_paths() {
         let (_tmp, engine) = setup().await;
 
-        let key_v1 = engine.derive_secret_key("app/kdf", 1, None).unwrap();
-        let key_v2 = engine.derive_secret_key("app/kdf", 2, None).unwrap();
-        let key_other_path = engine.derive_secret_key("app/kdf-other", 1, None).unwrap();
+        let key_v1 = engine.derive_secret_key("app/kdf", 1, 2, None).unwrap();
+        let key_v2 = engine.derive_secret_key("app/kdf", 2, 2, None).unwrap();
+        let key_other_path = engine
+            .derive_secret_key("app/kdf-other", 1, 2, None)
+            .unwrap();
 
         assert_ne!(key_v1.as_bytes(), key_v2.as_bytes());
         assert_ne!(key_v1.as_bytes(), key_other_path.as_bytes());
@@ -1808,12 +4424,12 @@ mod tests {
     async fn test_derivation_differs_across_generation_salts() {
         let (_tmp, engine) = setup().await;
 
-        let without = engine.derive_secret_key("app/gen", 1, None).unwrap();
+        let without = engine.derive_secret_key("app/gen", 1, 3, None).unwrap();
         let with_first = engine
-            .derive_secret_key("app/gen", 1, Some("00112233445566778899aabbccddeeff"))
+            .derive_secret_key("app/gen", 1, 3, Some("00112233445566778899aabbccddeeff"))
             .unwrap();
         let with_second = engine
-            .derive_secret_key("app/gen", 1, Some("ffeeddccbbaa99887766554433221100"))
+            .derive_secret_key("app/gen", 1, 3, Some("ffeeddccbbaa99887766554433221100"))
             .unwrap();
 
         assert_ne!(without.as_bytes(), with_first.as_bytes());
@@ -1842,7 +4458,10 @@ mod tests {
             .unwrap()
             .0;
 
-        engine.delete("app/cycle").await.unwrap();
+        engine
+            .delete("app/cycle", DeleteOptions::default())
+            .await
+            .unwrap();
 
         // Ensure deleted_at lands strictly before the purge cutoff.
         tokio::time::sleep(Duration::from_secs(2)).await;
@@ -1889,14 +4508,14 @@ mod tests {
 
         // Simulate a row written before the salt existed: clear the column and
         // re-encrypt under the v2 context.
-        let key = engine.derive_secret_key("app/legacy", 1, None).unwrap();
+        let key = engine.derive_secret_key("app/legacy", 1, 2, None).unwrap();
         let mut legacy = HashMap::new();
         legacy.insert("k".to_string(), "legacy".to_string());
         let plaintext = serde_json::to_vec(&legacy).unwrap();
-        let aad = SecretsEngine::secret_aad("app/legacy", 1, "", "").unwrap();
+        let aad = SecretsEngine::secret_aad("app/legacy", 1, &VersionContext::default()).unwrap();
         let sealed = aead::encrypt(key.as_bytes(), &plaintext, Some(&aad)).unwrap();
-        let nonce_hex = hex_encode(&sealed[..12]);
-        let data_hex = hex_encode(&sealed[12..]);
+        let nonce_hex = encoding::encode_hex(&sealed[..12]);
+        let data_hex = encoding::encode_hex(&sealed[12..]);
 
         engine
             .storage
@@ -1911,6 +4530,146 @@ mod tests {
         assert_eq!(secret.data.get("k"), Some(&"legacy".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_versions_decrypt_under_their_recorded_kdf_version() {
+        let tmp = TempDir::new().unwrap();
+        let master_key = MasterKey::generate().unwrap();
+        let master_key_bytes = master_key.as_bytes().to_vec();
+
+        let engine = SecretsEngine::new(tmp.path(), "test", master_key)
+            .await
+            .unwrap();
+        let mut data = HashMap::new();
+        data.insert("k".to_string(), "old".to_string());
+        engine
+            .put("app/kdf", data, PutOptions::default())
+            .await
+            .unwrap();
+        drop(engine);
+
+        // A newer derivation version becomes the default for new writes.
+        let engine = SecretsEngine::new(
+            tmp.path(),
+            "test",
+            MasterKey::from_bytes(&master_key_bytes).unwrap(),
+        )
+        .await
+        .unwrap()
+        .with_kdf_version(SECRET_KDF_VERSION + 1);
+        let mut data = HashMap::new();
+        data.insert("k".to_string(), "new".to_string());
+        engine
+            .put("app/kdf", data, PutOptions::default())
+            .await
+            .unwrap();
+
+        let recorded = engine
+            .storage
+            .query_all::<(String,)>(
+                "SELECT CAST(kdf_version AS TEXT) FROM secret_versions WHERE path = ? ORDER BY version",
+                &["app/kdf"],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            recorded,
+            vec![
+                (SECRET_KDF_VERSION.to_string(),),
+                ((SECRET_KDF_VERSION + 1).to_string(),)
+            ]
+        );
+
+        let v1 = engine.get_version("app/kdf", 1).await.unwrap();
+        assert_eq!(v1.data.get("k"), Some(&"old".to_string()));
+        let v2 = engine.get("app/kdf").await.unwrap();
+        assert_eq!(v2.data.get("k"), Some(&"new".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_rows_without_a_kdf_version_read_as_v3() {
+        let (_tmp, engine) = setup().await;
+
+        let mut data = HashMap::new();
+        data.insert("k".to_string(), "v".to_string());
+        engine
+            .put("app/nokdf", data, PutOptions::default())
+            .await
+            .unwrap();
+        engine
+            .storage
+            .execute(
+                "UPDATE secret_versions SET kdf_version = NULL WHERE path = ?",
+                &["app/nokdf"],
+            )
+            .await
+            .unwrap();
+
+        let secret = engine.get("app/nokdf").await.unwrap();
+        assert_eq!(secret.data.get("k"), Some(&"v".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_get_falls_back_to_last_good_version_when_enabled() {
+        let tmp = TempDir::new().unwrap();
+        let master_key = MasterKey::generate().unwrap();
+        let master_key_bytes = master_key.as_bytes().to_vec();
+        let engine = SecretsEngine::new(tmp.path(), "test", master_key)
+            .await
+            .unwrap();
+
+        for value in ["v1", "v2", "v3"] {
+            let mut data = HashMap::new();
+            data.insert("k".to_string(), value.to_string());
+            engine
+                .put("app/flaky", data, PutOptions::default())
+                .await
+                .unwrap();
+        }
+
+        // Corrupt the current version's blob.
+        let (data_hex,) = engine
+            .storage
+            .query_one::<(String,)>(
+                "SELECT data FROM secret_versions WHERE path = ? AND version = 3",
+                &["app/flaky"],
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let flipped = if data_hex.starts_with('0') { "1" } else { "0" };
+        engine
+            .storage
+            .execute(
+                "UPDATE secret_versions SET data = ? WHERE path = ? AND version = 3",
+                &[&format!("{flipped}{}", &data_hex[1..]), "app/flaky"],
+            )
+            .await
+            .unwrap();
+
+        // Off by default: the corruption surfaces as an error.
+        assert!(matches!(
+            engine.get("app/flaky").await,
+            Err(SecretsError::Crypto(_))
+        ));
+
+        let engine = SecretsEngine::new(
+            tmp.path(),
+            "test",
+            MasterKey::from_bytes(&master_key_bytes).unwrap(),
+        )
+        .await
+        .unwrap()
+        .with_fallback_to_last_good(true);
+        let secret = engine.get("app/flaky").await.unwrap();
+        assert!(secret.degraded);
+        assert_eq!(secret.version, 2);
+        assert_eq!(secret.data.get("k"), Some(&"v2".to_string()));
+
+        // An explicit version read is never redirected.
+        assert!(engine.get_version("app/flaky", 3).await.is_err());
+        assert!(!engine.get_version("app/flaky", 1).await.unwrap().degraded);
+    }
+
     #[tokio::test]
     async fn test_many_rotations_all_versions_decrypt() {
         let (_tmp, engine) = setup().await;
@@ -2006,6 +4765,15 @@ mod tests {
         let engine = SecretsEngine {
             storage,
             master_key,
+            kdf_version: SECRET_KDF_VERSION,
+            fallback_to_last_good: false,
+            max_ttl: None,
+            max_ttl_policy: MaxTtlPolicy::default(),
+            plaintext_fields: Vec::new(),
+            max_value_bytes: DEFAULT_MAX_SECRET_BYTES,
+            max_total_bytes: DEFAULT_MAX_SECRET_BYTES,
+            max_path_depth: None,
+            required_metadata: Vec::new(),
         };
 
         engine.init_schema().await.unwrap();
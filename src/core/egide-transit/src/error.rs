@@ -28,6 +28,18 @@ pub enum TransitError {
         version: u32,
     },
 
+    /// The ciphertext's key version was trimmed: its material is gone and
+    /// the data can no longer be decrypted by anyone.
+    #[error("key version {name} v{version} was trimmed (oldest available: v{min_available})")]
+    VersionTrimmed {
+        /// Key name.
+        name: String,
+        /// Ciphertext version.
+        version: u32,
+        /// Oldest version still stored.
+        min_available: u32,
+    },
+
     /// Version too old for encryption.
     #[error("key version {version} is below min_encryption_version {min}")]
     VersionBelowMinEncryption {
@@ -66,7 +78,11 @@ pub enum TransitError {
     #[error("invalid key type: {0}")]
     InvalidKeyType(String),
 
-    /// Key type accepted by the API but not implemented by this build.
+    /// Unknown hash algorithm name.
+    #[error("invalid hash algorithm: {0}")]
+    InvalidHashAlgorithm(String),
+
+    /// Key type accepted by the API but not usable for the requested operation.
     #[error("unsupported key type: {0}")]
     UnsupportedKeyType(crate::KeyType),
 
@@ -74,11 +90,10 @@ pub enum TransitError {
     ///
     /// Unlike [`TransitError::UnsupportedKeyType`], which rejects a type the
     /// caller is requesting right now, this variant means the request itself
-    /// is well-formed: the problem is server-side state, a key row written
-    /// under a type this build never actually implemented (for example
-    /// `chacha20-poly1305` on a key created by an earlier release). Retrying
-    /// the same request cannot succeed; the key must be rotated onto an
-    /// implemented algorithm or replaced.
+    /// is well-formed: the problem is server-side state, a key row whose type
+    /// has no cipher behind it (for example an `ed25519` verify-only key asked
+    /// to encrypt). Retrying the same request cannot succeed; the key must be
+    /// replaced.
     #[error("key algorithm not implemented: the persisted key declares {0}, which this build does not implement")]
     KeyAlgorithmNotImplemented(crate::KeyType),
 
@@ -91,6 +106,46 @@ pub enum TransitError {
         found: crate::KeyType,
     },
 
+    /// Imported key material is malformed (wrong size, missing or duplicate versions).
+    #[error("invalid key material: {0}")]
+    InvalidKeyMaterial(String),
+
+    /// A key configuration combines options that cannot be used together.
+    #[error("invalid key configuration: {0}")]
+    InvalidKeyConfig(String),
+
+    /// A batch request asked for zero items or more than the engine allows.
+    #[error("batch size {requested} is out of range (1..={max})")]
+    InvalidBatchSize {
+        /// Number of items requested.
+        requested: usize,
+        /// Largest batch the engine accepts.
+        max: usize,
+    },
+
+    /// A random-bytes request asked for zero bytes or more than the engine
+    /// allows.
+    #[error("random length {requested} is out of range (1..={max})")]
+    InvalidRandomLength {
+        /// Number of bytes requested.
+        requested: usize,
+        /// Largest length the engine generates.
+        max: usize,
+    },
+
+    /// A plaintext exceeds the engine's size limit.
+    #[error("input of {size} bytes exceeds the {max}-byte limit")]
+    InputTooLarge {
+        /// Size of the rejected input in bytes.
+        size: usize,
+        /// Largest input the engine accepts.
+        max: usize,
+    },
+
+    /// A configured ciphertext prefix is not a valid label.
+    #[error("invalid ciphertext prefix: {0}")]
+    InvalidCiphertextPrefix(String),
+
     /// Key is not exportable.
     #[error("key is not exportable: {0}")]
     NotExportable(String),
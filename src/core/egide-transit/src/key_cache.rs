@@ -0,0 +1,113 @@
+//! In-memory cache of unwrapped transit key material.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use zeroize::Zeroizing;
+
+/// Cached material of one key version.
+struct Entry {
+    material: Zeroizing<Vec<u8>>,
+    inserted_at: Instant,
+    /// Tick of the last lookup, for least-recently-used eviction.
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<(String, u32), Entry>,
+    tick: u64,
+}
+
+/// Least-recently-used cache of key version material, keyed by stored key
+/// name and version.
+///
+/// Material is held in [`Zeroizing`] buffers, so evicted and invalidated
+/// entries are wiped when dropped. Eviction scans every entry, which is fine
+/// for the few hundred hot key versions a deployment keeps warm.
+pub(crate) struct KeyCache {
+    capacity: usize,
+    ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl KeyCache {
+    /// Creates a cache holding up to `capacity` versions for `ttl` each.
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        // Entries are only ever inserted or removed whole, so a panic while
+        // the lock was held cannot have left one half-written.
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns a copy of the cached material, dropping it if expired.
+    pub(crate) fn get(&self, name: &str, version: u32) -> Option<Zeroizing<Vec<u8>>> {
+        let mut inner = self.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+        let key = (name.to_string(), version);
+        let entry = inner.entries.get_mut(&key)?;
+        if entry.inserted_at.elapsed() >= self.ttl {
+            inner.entries.remove(&key);
+            return None;
+        }
+        entry.last_used = tick;
+        Some(entry.material.clone())
+    }
+
+    /// Caches `material`, evicting the least recently used version when full.
+    pub(crate) fn insert(&self, name: &str, version: u32, material: &Zeroizing<Vec<u8>>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+        let key = (name.to_string(), version);
+        if !inner.entries.contains_key(&key) && inner.entries.len() >= self.capacity {
+            let ttl = self.ttl;
+            inner
+                .entries
+                .retain(|_, entry| entry.inserted_at.elapsed() < ttl);
+            if inner.entries.len() >= self.capacity {
+                let oldest = inner
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    inner.entries.remove(&oldest);
+                }
+            }
+        }
+        inner.entries.insert(
+            key,
+            Entry {
+                material: material.clone(),
+                inserted_at: Instant::now(),
+                last_used: tick,
+            },
+        );
+    }
+
+    /// Drops every cached version of the key stored as `name`.
+    pub(crate) fn invalidate(&self, name: &str) {
+        self.lock()
+            .entries
+            .retain(|(cached, _), _| cached.as_str() != name);
+    }
+
+    /// Returns how many versions are currently cached, expired ones included.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+}
@@ -12,23 +12,26 @@
 //! ## Ciphertext Format
 //!
 //! Ciphertexts are encoded as `egide:v{version}:{base64}` for AES-256-GCM,
-//! and as `egide:v{version}:{algorithm}:{base64}` for any other algorithm.
+//! and as `egide:v{version}:{algorithm}:{base64}` for any other algorithm
+//! (`chacha20-poly1305`). A key encrypts under the cipher of its stored
+//! `key_type`.
+//! The leading `egide` label is the default ciphertext prefix; white-label
+//! deployments can replace it with
+//! [`TransitEngine::with_ciphertext_prefix`], after which only that prefix
+//! is accepted on decryption.
 //! The short form is normatively AES-256-GCM: it predates algorithm
 //! labelling and is kept readable so ciphertexts written by earlier releases
-//! stay decryptable. Both forms are accepted on decryption, checked against
-//! the engine's effective algorithm rather than a key's declared type, so a
-//! key created under a type accepted but never implemented by an earlier
-//! release stays readable. `encrypt` only ever emits the short form: it
-//! refuses to run on a key whose declared type is not the effective
-//! algorithm, instead of producing a ciphertext with a misleading label.
-//! `rewrap` does not carry that guarantee on its already-latest-version fast
-//! path: it returns the input ciphertext unchanged, so a caller who submits
-//! a long form already at the latest version gets that same long form back.
+//! stay decryptable. Both forms are accepted on decryption as long as the
+//! label agrees with the key's type. A `chacha20-poly1305` key also accepts
+//! the short form: 0.1.0 accepted that type without implementing it and
+//! encrypted under AES-256-GCM regardless. `rewrap` returns a ciphertext
+//! already at the latest version unchanged, so a caller who submits a long
+//! form (or such a legacy short form) gets that same form back.
 //!
 //! ## Message Limit per Key Version
 //!
-//! Each key version encrypts under AES-256-GCM with a fresh 96-bit nonce drawn
-//! from the system CSPRNG, independently per message. With random nonces the
+//! Each key version encrypts under AES-256-GCM (or ChaCha20-Poly1305) with a
+//! fresh 96-bit nonce drawn from the system CSPRNG, independently per message. With random nonces the
 //! safety bound is set by nonce collision, not by key strength: after `q`
 //! encryptions under one key the probability that two messages share a nonce is
 //! about `q^2 / 2^97`. Following NIST SP 800-38D, keep that probability at or
@@ -42,18 +45,30 @@
 #![forbid(unsafe_code)]
 
 pub mod error;
+mod key_cache;
+mod usage;
 
 pub use error::TransitError;
 
+use key_cache::KeyCache;
+use usage::UsageTracker;
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::str::FromStr;
+use std::time::Duration;
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha384, Sha512};
 use tracing::{debug, info, warn};
 use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-use egide_crypto::{aead, kdf, mac, random, MasterKey};
+use egide_crypto::envelope::{AlgorithmId, EnvelopeHeader};
+use egide_crypto::wrapping::KeyWrapper;
+use egide_crypto::{aead, encoding, kdf, kdf::KdfContext, mac, random, signing, MasterKey};
+use egide_storage::prefix_pattern;
 use egide_storage_sqlite::SqliteBackend;
 
 // ============================================================================
@@ -63,6 +78,71 @@ use egide_storage_sqlite::SqliteBackend;
 /// Domain separation for the policy-row MAC subkey.
 const TRANSIT_POLICY_MAC_INFO: &[u8] = b"egide-transit-policy-mac-v1";
 
+/// Domain separation for the HMAC key derived from a key version's material.
+const TRANSIT_HMAC_INFO: &[u8] = b"egide-transit-hmac-v1";
+
+/// KDF domain for key-version wrapping keys (`egide-transit-v{n}:{name}:{version}`).
+const TRANSIT_KDF_DOMAIN: &str = "egide-transit";
+
+/// Storage tenant holding the transit keyring.
+const TRANSIT_TENANT: &str = "transit";
+
+/// Domain separation for each step of a derived-subkey chain.
+const TRANSIT_SUBKEY_INFO_PREFIX: &str = "egide-transit-subkey-v1:";
+
+/// Domain separation for a subkey derived from an opaque context.
+const TRANSIT_CONTEXT_INFO_PREFIX: &[u8] = b"egide-transit-context-v1:";
+
+/// Maximum number of segments in a derivation path.
+const MAX_DERIVATION_DEPTH: usize = 16;
+
+/// Largest number of data keys [`TransitEngine::generate_datakeys`] produces
+/// in one call.
+pub const MAX_DATAKEY_BATCH: usize = 1000;
+
+/// Largest number of items [`TransitEngine::encrypt_batch`] and
+/// [`TransitEngine::decrypt_batch`] process in one call.
+pub const MAX_CRYPT_BATCH: usize = 1000;
+
+/// Largest number of bytes [`TransitEngine::generate_random`] produces in one
+/// call.
+pub const MAX_RANDOM_BYTES: usize = 4096;
+
+/// Default largest plaintext an engine encrypts (32 MiB); see
+/// [`TransitEngine::with_max_plaintext_bytes`].
+pub const DEFAULT_MAX_PLAINTEXT_BYTES: usize = 32 * 1024 * 1024;
+
+/// Default interval between writes of accumulated key usage; see
+/// [`TransitEngine::with_usage_flush_interval`].
+pub const DEFAULT_USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Size of the stored material of a generated Ed25519 version: the private
+/// seed followed by the public key. Imported verify-only versions hold the
+/// public key alone.
+const ED25519_KEYPAIR_SIZE: usize = signing::ED25519_SEED_SIZE + signing::ED25519_PUBLIC_KEY_SIZE;
+
+/// Largest number of tags a transit key carries.
+pub const MAX_KEY_TAGS: usize = 64;
+
+/// Names fetched per query when [`TransitEngine::list_keys`] pages through
+/// every key.
+const LIST_KEYS_PAGE_SIZE: usize = 1000;
+
+/// Derivation version used to wrap newly created key versions.
+///
+/// Each key version records the derivation version it was wrapped under
+/// (`kdf_version`, `NULL` meaning 1 for rows written before the column
+/// existed) and is always unwrapped under that recorded version, so bumping
+/// this constant only affects new writes.
+const TRANSIT_KDF_VERSION: u32 = 1;
+
+/// Default leading label of every ciphertext (`egide:v{n}:...`).
+pub const DEFAULT_CIPHERTEXT_PREFIX: &str = "egide";
+
+/// Segment marking a ciphertext sealed by a convergent key
+/// (`egide:c1:v{n}:...`). It cannot be mistaken for a `v{n}` version.
+const CONVERGENT_MARKER: &str = "c1";
+
 const SCHEMA: &str = r"
 CREATE TABLE IF NOT EXISTS transit_keys (
     name            TEXT PRIMARY KEY,
@@ -70,9 +150,16 @@ CREATE TABLE IF NOT EXISTS transit_keys (
     latest_version  INTEGER NOT NULL DEFAULT 1,
     min_encryption_version INTEGER NOT NULL DEFAULT 1,
     min_decryption_version INTEGER NOT NULL DEFAULT 1,
+    min_available_version INTEGER NOT NULL DEFAULT 1,
     supports_encryption INTEGER NOT NULL DEFAULT 1,
     supports_decryption INTEGER NOT NULL DEFAULT 1,
     supports_derivation INTEGER NOT NULL DEFAULT 0,
+    supports_signing INTEGER NOT NULL DEFAULT 0,
+    convergent      INTEGER NOT NULL DEFAULT 0,
+    auto_rotate_secs INTEGER,
+    tags            TEXT NOT NULL DEFAULT '{}',
+    last_used_at    INTEGER,
+    usage_count     INTEGER NOT NULL DEFAULT 0,
     exportable      INTEGER NOT NULL DEFAULT 0,
     deletion_allowed INTEGER NOT NULL DEFAULT 0,
     created_at      INTEGER NOT NULL,
@@ -86,6 +173,7 @@ CREATE TABLE IF NOT EXISTS transit_key_versions (
     key_material    TEXT NOT NULL,
     nonce           TEXT NOT NULL,
     created_at      INTEGER NOT NULL,
+    kdf_version     INTEGER,
     PRIMARY KEY (name, version),
     FOREIGN KEY (name) REFERENCES transit_keys(name) ON DELETE CASCADE
 );
@@ -97,15 +185,21 @@ CREATE INDEX IF NOT EXISTS idx_transit_key_versions_name ON transit_key_versions
 // Types
 // ============================================================================
 
-/// Supported key types for transit encryption.
+/// Supported key types for transit encryption and signature verification.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum KeyType {
     /// AES-256-GCM (default, widely compatible).
     #[default]
     Aes256Gcm,
-    /// ChaCha20-Poly1305 (fast on systems without AES-NI).
+    /// ChaCha20-Poly1305 (fast on systems without AES-NI; see
+    /// [`TransitEngine::cpu_features`]).
     ChaCha20Poly1305,
+    /// Ed25519 signatures. [`TransitEngine::create_key`] generates a key pair
+    /// that signs and verifies but never encrypts;
+    /// [`TransitEngine::import_public_key`] holds only a public key, to
+    /// verify signatures made elsewhere.
+    Ed25519,
 }
 
 impl std::fmt::Display for KeyType {
@@ -113,6 +207,7 @@ impl std::fmt::Display for KeyType {
         match self {
             Self::Aes256Gcm => write!(f, "aes256-gcm"),
             Self::ChaCha20Poly1305 => write!(f, "chacha20-poly1305"),
+            Self::Ed25519 => write!(f, "ed25519"),
         }
     }
 }
@@ -124,22 +219,148 @@ impl FromStr for KeyType {
         match s {
             "aes256-gcm" => Ok(Self::Aes256Gcm),
             "chacha20-poly1305" => Ok(Self::ChaCha20Poly1305),
+            "ed25519" => Ok(Self::Ed25519),
             _ => Err(TransitError::InvalidKeyType(s.to_string())),
         }
     }
 }
 
-/// The algorithm this build of the engine actually encrypts and decrypts under.
+impl KeyType {
+    /// Whether data is encrypted under this type, as opposed to a signature
+    /// type.
+    const fn is_data_cipher(self) -> bool {
+        matches!(self, Self::Aes256Gcm | Self::ChaCha20Poly1305)
+    }
+}
+
+/// Every key type this build can actually use: the two data ciphers and the
+/// signature type. Reported to clients so they can tell what a
+/// server supports without probing it.
+pub const IMPLEMENTED_KEY_TYPES: [KeyType; 3] = [
+    KeyType::Aes256Gcm,
+    KeyType::ChaCha20Poly1305,
+    KeyType::Ed25519,
+];
+
+/// Hardware acceleration the running CPU offers the AEAD ciphers.
 ///
-/// `KeyType` enumerates every type the API can *declare*, but only one cipher
-/// is wired into [`TransitEngine::encrypt_with_version`] and
-/// [`TransitEngine::decrypt`] today: AES-256-GCM. Every algorithm decision the
-/// engine makes (what a ciphertext is checked against, what a new ciphertext
-/// is labelled with) must compare against this constant, never against a
-/// key's declared `key_type`, so a key whose declared type predates this
-/// distinction stays readable. Update this constant, and only this constant,
-/// the day a second cipher is actually implemented.
-const ENGINE_ALGORITHM: KeyType = KeyType::Aes256Gcm;
+/// AES-GCM is constant-time and fast only with AES and carry-less multiply
+/// instructions; without them the cipher crates fall back to a bitsliced
+/// software implementation, and ChaCha20-Poly1305 (which vectorizes with
+/// AVX2) is the better choice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CpuCryptoFeatures {
+    /// Target architecture of the build, such as `x86_64` or `aarch64`.
+    pub arch: &'static str,
+    /// Hardware AES rounds: AES-NI on x86, the crypto extension on aarch64.
+    pub aes: bool,
+    /// Carry-less multiply for GHASH: PCLMULQDQ on x86, PMULL on aarch64.
+    pub clmul: bool,
+    /// AVX2 vector instructions (x86 only).
+    pub avx2: bool,
+}
+
+/// Text encoding of the bytes returned by [`TransitEngine::generate_random`]
+/// and [`TransitEngine::hash`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Lowercase hexadecimal.
+    Hex,
+    /// Standard base64 with padding.
+    #[default]
+    Base64,
+}
+
+impl OutputFormat {
+    /// Encodes `bytes` in this format.
+    fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Hex => encoding::encode_hex(bytes).as_str().to_owned(),
+            Self::Base64 => BASE64.encode(bytes),
+        }
+    }
+}
+
+/// Encoding of the bytes returned by [`TransitEngine::generate_random`].
+pub type RandomFormat = OutputFormat;
+
+/// Digest algorithms offered by [`TransitEngine::hash`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HashAlgorithm {
+    /// SHA-256 (default).
+    #[default]
+    Sha256,
+    /// SHA-384.
+    Sha384,
+    /// SHA-512.
+    Sha512,
+}
+
+impl std::fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sha256 => write!(f, "sha2-256"),
+            Self::Sha384 => write!(f, "sha2-384"),
+            Self::Sha512 => write!(f, "sha2-512"),
+        }
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = TransitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha2-256" => Ok(Self::Sha256),
+            "sha2-384" => Ok(Self::Sha384),
+            "sha2-512" => Ok(Self::Sha512),
+            _ => Err(TransitError::InvalidHashAlgorithm(s.to_string())),
+        }
+    }
+}
+
+/// How a subkey is derived from a key version's material.
+#[derive(Clone, Copy)]
+enum Derivation<'a> {
+    /// A `/`-separated path, one HKDF step per segment
+    /// ([`TransitEngine::encrypt_derived`]).
+    Path(&'a str),
+    /// Opaque bytes used as HKDF info in a single step
+    /// ([`TransitEngine::encrypt_with_context`]).
+    Context(&'a [u8]),
+}
+
+impl Derivation<'_> {
+    /// Derives the subkey and the engine associated data for `version` of
+    /// the key stored as `stored_name`.
+    fn subkey_and_aad(
+        self,
+        root: &[u8],
+        stored_name: &str,
+        version: u32,
+    ) -> Result<(Zeroizing<[u8; 32]>, String), TransitError> {
+        match self {
+            Self::Path(path) => Ok((
+                TransitEngine::derive_subkey(root, path)?,
+                TransitEngine::derived_aad(stored_name, version, path),
+            )),
+            Self::Context(context) => {
+                let root = root.get(..32).ok_or(TransitError::DecryptionFailed)?;
+                let info = [TRANSIT_CONTEXT_INFO_PREFIX, context].concat();
+                let context_hex = encoding::encode_hex(context);
+                Ok((
+                    kdf::derive_encryption_key(root, &info)?,
+                    format!(
+                        "egide-transit-context:{stored_name}:{version}:{}",
+                        context_hex.as_str()
+                    ),
+                ))
+            },
+        }
+    }
+}
 
 /// Configuration for creating a new transit key.
 // Each bool maps to a distinct, independently togglable capability flag; a state machine would
@@ -155,10 +376,28 @@ pub struct KeyConfig {
     pub supports_decryption: bool,
     /// Allow key derivation (default: false).
     pub supports_derivation: bool,
+    /// Allow signing (default: false). Only [`KeyType::Ed25519`] keys may
+    /// set it; verification needs no flag, so a key pair created without it
+    /// still verifies but never signs.
+    pub supports_signing: bool,
+    /// Encrypt deterministically (default: false), so equal plaintexts
+    /// yield equal ciphertexts and can be deduplicated. Incompatible with
+    /// `supports_derivation`; see [`TransitEngine::encrypt`] for the
+    /// tradeoff.
+    pub convergent: bool,
     /// Allow key export (default: false).
     pub exportable: bool,
     /// Allow key deletion (default: false).
     pub deletion_allowed: bool,
+    /// Rotate the key once this long has passed since its last change
+    /// (default: never). Whole seconds, at least one; see
+    /// [`TransitEngine::rotate_if_due`].
+    pub auto_rotate_interval: Option<Duration>,
+    /// Free-form labels stored with the key, such as its environment or
+    /// owning team (default: none). At most [`MAX_KEY_TAGS`], with names of
+    /// 1 to 128 characters and values of up to 512; see
+    /// [`TransitEngine::update_key_tags`].
+    pub tags: BTreeMap<String, String>,
 }
 
 impl KeyConfig {
@@ -170,8 +409,12 @@ impl KeyConfig {
             supports_encryption: true,
             supports_decryption: true,
             supports_derivation: false,
+            supports_signing: false,
+            convergent: false,
             exportable: false,
             deletion_allowed: false,
+            auto_rotate_interval: None,
+            tags: BTreeMap::new(),
         }
     }
 }
@@ -192,22 +435,69 @@ pub struct TransitKey {
     pub min_encryption_version: u32,
     /// Minimum version allowed for decryption.
     pub min_decryption_version: u32,
+    /// Oldest version whose material is still stored; older ones were
+    /// removed by [`TransitEngine::trim_key`].
+    #[serde(default = "first_version")]
+    pub min_available_version: u32,
     /// Whether encryption is supported.
     pub supports_encryption: bool,
     /// Whether decryption is supported.
     pub supports_decryption: bool,
     /// Whether key derivation is supported.
     pub supports_derivation: bool,
+    /// Whether signing is supported. False for verify-only keys, which hold
+    /// nothing but a public key.
+    #[serde(default)]
+    pub supports_signing: bool,
+    /// Whether encryption is convergent (deterministic).
+    #[serde(default)]
+    pub convergent: bool,
     /// Whether the key can be exported.
     pub exportable: bool,
     /// Whether the key can be deleted.
     pub deletion_allowed: bool,
+    /// Interval after which [`TransitEngine::rotate_if_due`] rotates the key.
+    #[serde(default)]
+    pub auto_rotate_interval: Option<Duration>,
+    /// Free-form labels; informational only, so not covered by the policy MAC.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Last successful encryption or decryption (Unix seconds), if any.
+    /// Informational, like `usage_count`; see
+    /// [`TransitEngine::with_usage_flush_interval`].
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    /// Number of successful encryptions and decryptions.
+    #[serde(default)]
+    pub usage_count: u64,
     /// Creation timestamp (Unix seconds).
     pub created_at: u64,
     /// Last update timestamp (Unix seconds).
     pub updated_at: u64,
 }
 
+/// Serde default for [`TransitKey::min_available_version`]: nothing trimmed.
+fn first_version() -> u32 {
+    1
+}
+
+impl TransitKey {
+    /// Returns the inclusive range of versions `encrypt_with_version` accepts,
+    /// as `(min_encryption_version, latest_version)`.
+    #[must_use]
+    pub fn encryption_version_window(&self) -> (u32, u32) {
+        (self.min_encryption_version, self.latest_version)
+    }
+
+    /// Returns when the key is next due for automatic rotation (Unix
+    /// seconds): `updated_at` plus the interval, or `None` without one.
+    #[must_use]
+    pub fn next_rotation_at(&self) -> Option<u64> {
+        self.auto_rotate_interval
+            .map(|interval| self.updated_at.saturating_add(interval.as_secs()))
+    }
+}
+
 /// Information about a specific key version.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyVersionInfo {
@@ -217,13 +507,100 @@ pub struct KeyVersionInfo {
     pub created_at: u64,
 }
 
+/// Result of [`TransitEngine::decrypt_with_meta`].
+///
+/// Its `Debug` output never prints the plaintext.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DecryptResult {
+    /// Decrypted data.
+    pub plaintext: Vec<u8>,
+    /// Key version the ciphertext was encrypted under.
+    pub version: u32,
+}
+
+impl std::fmt::Debug for DecryptResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DecryptResult")
+            .field("plaintext", &"[REDACTED]")
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+/// Length of a data encryption key produced by
+/// [`TransitEngine::generate_datakey_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DataKeyBits {
+    /// 128-bit key, for AES-128.
+    #[serde(rename = "128")]
+    Bits128,
+    /// 256-bit key (default), for AES-256 or `ChaCha20`.
+    #[default]
+    #[serde(rename = "256")]
+    Bits256,
+}
+
+impl DataKeyBits {
+    /// Key length in bytes.
+    #[must_use]
+    pub fn byte_len(self) -> usize {
+        match self {
+            Self::Bits128 => 16,
+            Self::Bits256 => 32,
+        }
+    }
+}
+
+/// Options for [`TransitEngine::restore_key`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RestoreOptions {
+    /// Replace an existing key of the same name, with all its versions
+    /// (default: false). The existing key must allow deletion.
+    pub force: bool,
+    /// With `force`, accept a backup whose `latest_version` is below the
+    /// existing key's (default: false). Restoring it drops the newer
+    /// versions and rolls back the version floors, so ciphertexts under the
+    /// dropped versions no longer decrypt.
+    pub allow_rollback: bool,
+}
+
+/// Format version written into every [`TransitEngine::backup_key`] blob.
+///
+/// Format 1 carried no policy MAC and is refused on restore.
+const KEY_BACKUP_FORMAT: u32 = 2;
+
+/// Portable form of a key produced by [`TransitEngine::backup_key`]: its
+/// policy and every stored version, material still wrapped.
+#[derive(Serialize, Deserialize)]
+struct KeyBackup {
+    format: u32,
+    /// Policy row, `name` holding the stored (prefixed) name the material is
+    /// wrapped under.
+    key: TransitKey,
+    /// Hex policy MAC of `key`, checked on restore so an edited policy is
+    /// refused rather than re-signed.
+    row_mac: String,
+    versions: Vec<KeyVersionBackup>,
+}
+
+/// One stored version of a [`KeyBackup`].
+#[derive(Serialize, Deserialize)]
+struct KeyVersionBackup {
+    version: u32,
+    key_material: String,
+    nonce: String,
+    kdf_version: u32,
+    created_at: u64,
+}
+
 /// Result of a datakey generation.
 ///
 /// `plaintext` holds the raw data encryption key and is zeroized on drop.
 /// Its `Debug` output never prints the key bytes.
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct DataKey {
-    /// Plaintext key (32 bytes for use by the client).
+    /// Plaintext key for use by the client (32 bytes unless generated with
+    /// [`DataKeyBits::Bits128`]; empty when generated without plaintext).
     pub plaintext: Vec<u8>,
     /// Wrapped (encrypted) key for storage. Already ciphertext, not sensitive.
     #[zeroize(skip)]
@@ -257,28 +634,8 @@ impl DataKey {
 // Hex Encoding Helpers
 // ============================================================================
 
-fn hex_encode(data: &[u8]) -> String {
-    use std::fmt::Write as _;
-    data.iter()
-        .fold(String::with_capacity(data.len() * 2), |mut s, b| {
-            write!(s, "{b:02x}").expect("writing to String is infallible");
-            s
-        })
-}
-
-fn hex_decode(s: &str) -> Result<Vec<u8>, TransitError> {
-    let bytes = s.as_bytes();
-    if !bytes.len().is_multiple_of(2) {
-        return Err(TransitError::Storage("invalid hex length".into()));
-    }
-    bytes
-        .chunks_exact(2)
-        .map(|pair| {
-            let text = std::str::from_utf8(pair)
-                .map_err(|_| TransitError::Storage("invalid hex".into()))?;
-            u8::from_str_radix(text, 16).map_err(|_| TransitError::Storage("invalid hex".into()))
-        })
-        .collect()
+fn hex_decode(s: &str) -> Result<Zeroizing<Vec<u8>>, TransitError> {
+    encoding::decode_hex(s).map_err(|_| TransitError::Storage("invalid hex".into()))
 }
 
 /// Converts a wall-clock time to whole seconds since the Unix epoch, failing closed on a clock set before 1970.
@@ -298,6 +655,18 @@ fn unix_seconds(t: std::time::SystemTime) -> Result<u64, TransitError> {
 pub struct TransitEngine {
     storage: SqliteBackend,
     master_key: MasterKey,
+    /// Prepended to every key name in storage; empty by default.
+    key_prefix: String,
+    /// Derivation version new key versions are wrapped under.
+    kdf_version: u32,
+    /// Leading label of every ciphertext this engine emits and accepts.
+    ciphertext_prefix: String,
+    /// Unwrapped key version material, if caching was opted into.
+    key_cache: Option<KeyCache>,
+    /// Key usage not yet written to `transit_keys`.
+    usage: UsageTracker,
+    /// Largest plaintext accepted for encryption.
+    max_plaintext_bytes: usize,
 }
 
 impl TransitEngine {
@@ -306,43 +675,265 @@ impl TransitEngine {
         data_path: impl AsRef<Path>,
         master_key: MasterKey,
     ) -> Result<Self, TransitError> {
-        let storage = SqliteBackend::open(data_path.as_ref(), "transit")
+        let storage = SqliteBackend::open(data_path.as_ref(), TRANSIT_TENANT)
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?;
+        Self::with_storage(storage, master_key).await
+    }
 
+    /// Creates a `TransitEngine` whose key database is encrypted at rest.
+    ///
+    /// The database key is derived from `master_key`, see
+    /// [`kdf::derive_storage_key`]. Requires `egide-storage-sqlite` built with
+    /// its `sqlcipher` feature; otherwise this fails rather than writing a
+    /// plain file.
+    pub async fn new_encrypted(
+        data_path: impl AsRef<Path>,
+        master_key: MasterKey,
+    ) -> Result<Self, TransitError> {
+        let storage_key = kdf::derive_storage_key(master_key.as_bytes(), TRANSIT_TENANT)?;
+        let storage =
+            SqliteBackend::open_encrypted(data_path.as_ref(), TRANSIT_TENANT, &storage_key)
+                .await
+                .map_err(|e| TransitError::Storage(e.to_string()))?;
+        Self::with_storage(storage, master_key).await
+    }
+
+    async fn with_storage(
+        storage: SqliteBackend,
+        master_key: MasterKey,
+    ) -> Result<Self, TransitError> {
         // Initialize schema
         storage
             .execute_raw(SCHEMA)
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?;
 
+        // The schema is applied on every boot and SQLite rejects ADD COLUMN
+        // IF NOT EXISTS, so the duplicate-column error is the idempotency
+        // signal for databases created before these columns existed.
+        for statement in [
+            "ALTER TABLE transit_key_versions ADD COLUMN kdf_version INTEGER",
+            "ALTER TABLE transit_keys ADD COLUMN supports_signing INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE transit_keys ADD COLUMN convergent INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE transit_keys ADD COLUMN min_available_version INTEGER NOT NULL DEFAULT 1",
+            "ALTER TABLE transit_keys ADD COLUMN auto_rotate_secs INTEGER",
+            "ALTER TABLE transit_keys ADD COLUMN tags TEXT NOT NULL DEFAULT '{}'",
+            "ALTER TABLE transit_keys ADD COLUMN last_used_at INTEGER",
+            "ALTER TABLE transit_keys ADD COLUMN usage_count INTEGER NOT NULL DEFAULT 0",
+        ] {
+            if let Err(error) = storage.execute(statement, &[]).await {
+                let message = error.to_string();
+                if !message.contains("duplicate column") && !message.contains("already exists") {
+                    return Err(TransitError::Storage(message));
+                }
+            }
+        }
+
         info!("Transit engine initialized");
 
         Ok(Self {
             storage,
             master_key,
+            key_prefix: String::new(),
+            kdf_version: TRANSIT_KDF_VERSION,
+            ciphertext_prefix: DEFAULT_CIPHERTEXT_PREFIX.to_string(),
+            key_cache: None,
+            usage: UsageTracker::new(DEFAULT_USAGE_FLUSH_INTERVAL),
+            max_plaintext_bytes: DEFAULT_MAX_PLAINTEXT_BYTES,
         })
     }
 
+    /// Wraps new key versions under `kdf_version`, standing in for a future
+    /// bump of [`TRANSIT_KDF_VERSION`].
+    #[cfg(test)]
+    fn with_kdf_version(mut self, kdf_version: u32) -> Self {
+        self.kdf_version = kdf_version;
+        self
+    }
+
+    /// Namespaces every key this engine stores under `prefix` (e.g. `transit/`).
+    ///
+    /// The API keeps using bare names; the prefix is applied transparently to
+    /// the stored name, and therefore to the key derivation context, the
+    /// ciphertext associated data and the policy MAC. Two engines sharing a
+    /// tenant database with different prefixes can then both hold a key named
+    /// `app` without colliding. An empty prefix is the default and stores
+    /// bare names, exactly as before prefixes existed.
+    ///
+    /// The prefix must end with `/`: bare key names cannot contain `/`, so
+    /// that separator is what keeps prefixed names from ever colliding with
+    /// each other or with unprefixed ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidKeyName`] if the prefix does not end
+    /// with `/`, is longer than 64 characters, or contains an empty segment or
+    /// a character other than alphanumerics, `-`, `_` and `/`.
+    pub fn with_key_prefix(mut self, prefix: impl Into<String>) -> Result<Self, TransitError> {
+        let prefix = prefix.into();
+        Self::validate_key_prefix(&prefix)?;
+        self.key_prefix = prefix;
+        Ok(self)
+    }
+
+    /// Returns the prefix applied to stored key names (empty if none).
+    #[must_use]
+    pub fn key_prefix(&self) -> &str {
+        &self.key_prefix
+    }
+
+    /// Replaces the `egide` label leading every ciphertext (`{prefix}:v{n}:...`).
+    ///
+    /// Lets white-label deployments avoid naming the product in stored data.
+    /// Only the configured prefix is accepted on decryption, so ciphertexts
+    /// written under another prefix, including the default one, must be
+    /// migrated before switching. The prefix is framing only: it is not
+    /// bound into the associated data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidCiphertextPrefix`] if the prefix is
+    /// empty, longer than 32 characters, or contains a character other than
+    /// ASCII alphanumerics, `-` and `_`.
+    pub fn with_ciphertext_prefix(
+        mut self,
+        prefix: impl Into<String>,
+    ) -> Result<Self, TransitError> {
+        let prefix = prefix.into();
+        if prefix.is_empty() || prefix.len() > 32 {
+            return Err(TransitError::InvalidCiphertextPrefix(
+                "ciphertext prefix must be 1 to 32 characters".into(),
+            ));
+        }
+        if !prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(TransitError::InvalidCiphertextPrefix(
+                "ciphertext prefix can only contain alphanumeric, dash, underscore".into(),
+            ));
+        }
+        self.ciphertext_prefix = prefix;
+        Ok(self)
+    }
+
+    /// Returns the label leading every ciphertext (`egide` by default).
+    #[must_use]
+    pub fn ciphertext_prefix(&self) -> &str {
+        &self.ciphertext_prefix
+    }
+
+    /// Keeps up to `capacity` unwrapped key versions in memory for `ttl`.
+    ///
+    /// Without a cache every operation reads and unwraps the version row
+    /// from storage. Cached material sits in zeroized buffers, is evicted
+    /// least recently used first, and is dropped whenever its key is
+    /// rotated, reconfigured, trimmed, restored or deleted through this
+    /// engine. Another engine writing the same database is not seen until
+    /// the entry expires, so keep `ttl` short when several share one. Off
+    /// by default.
+    #[must_use]
+    pub fn with_key_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.key_cache = Some(KeyCache::new(capacity, ttl));
+        self
+    }
+
+    /// Writes key usage counters at most once per `interval`,
+    /// [`DEFAULT_USAGE_FLUSH_INTERVAL`] by default.
+    ///
+    /// Each successful encryption or decryption bumps its key's
+    /// `usage_count` and `last_used_at`. Usage accumulates in memory and the
+    /// operation that finds the interval elapsed writes it all; a zero
+    /// interval writes after every operation. [`Self::get_key`] includes the
+    /// unwritten part, but it is lost if the process exits first, so call
+    /// [`Self::flush_usage`] on shutdown.
+    #[must_use]
+    pub fn with_usage_flush_interval(mut self, interval: Duration) -> Self {
+        self.usage = UsageTracker::new(interval);
+        self
+    }
+
+    /// Caps the plaintext size encryption accepts, [`DEFAULT_MAX_PLAINTEXT_BYTES`]
+    /// by default.
+    ///
+    /// The ciphertext is built and base64-encoded in memory, so an unbounded
+    /// input can exhaust it. Larger inputs fail with
+    /// [`TransitError::InputTooLarge`] before the key is even loaded.
+    #[must_use]
+    pub fn with_max_plaintext_bytes(mut self, max: usize) -> Self {
+        self.max_plaintext_bytes = max;
+        self
+    }
+
+    /// Returns the largest plaintext encryption accepts.
+    #[must_use]
+    pub fn max_plaintext_bytes(&self) -> usize {
+        self.max_plaintext_bytes
+    }
+
+    /// Rejects a plaintext above the configured limit.
+    fn check_plaintext_size(&self, plaintext: &[u8]) -> Result<(), TransitError> {
+        if plaintext.len() > self.max_plaintext_bytes {
+            return Err(TransitError::InputTooLarge {
+                size: plaintext.len(),
+                max: self.max_plaintext_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Drops every cached version of the key stored as `stored_name`.
+    fn invalidate_cached_key(&self, stored_name: &str) {
+        if let Some(cache) = &self.key_cache {
+            cache.invalidate(stored_name);
+        }
+    }
+
+    /// Returns the name a key is stored under: the key prefix, then the bare name.
+    fn stored_name(&self, name: &str) -> String {
+        format!("{}{name}", self.key_prefix)
+    }
+
     // ========================================================================
     // Key Derivation & Encryption Helpers
     // ========================================================================
 
     /// Derives a unique encryption key for a transit key version.
-    fn derive_version_key(&self, name: &str, version: u32) -> Result<[u8; 32], TransitError> {
-        let info = format!("egide-transit-v1:{name}:{version}");
-        let key = kdf::derive_encryption_key(self.master_key.as_bytes(), info.as_bytes())?;
+    fn derive_version_key(
+        &self,
+        name: &str,
+        version: u32,
+        kdf_version: u32,
+    ) -> Result<[u8; 32], TransitError> {
+        Self::derive_version_key_from(&self.master_key, name, version, kdf_version)
+    }
+
+    /// Derives the wrapping key of a transit key version from an explicit
+    /// master key, so a rewrap can unwrap under the key it replaces.
+    fn derive_version_key_from(
+        master_key: &MasterKey,
+        name: &str,
+        version: u32,
+        kdf_version: u32,
+    ) -> Result<[u8; 32], TransitError> {
+        let key = KdfContext::new(TRANSIT_KDF_DOMAIN)
+            .version(kdf_version)
+            .field(name)
+            .field(version.to_string())
+            .derive_encryption_key(master_key.as_bytes())?;
         Ok(*key)
     }
 
-    /// Encrypts raw key material for storage.
+    /// Encrypts raw key material for storage under the engine's current
+    /// derivation version, which the caller must persist with the row.
     fn encrypt_key_material(
         &self,
         name: &str,
         version: u32,
         key: &[u8],
     ) -> Result<(Vec<u8>, Vec<u8>), TransitError> {
-        let wrapping_key = self.derive_version_key(name, version)?;
+        let wrapping_key = self.derive_version_key(name, version, self.kdf_version)?;
         let aad = format!("transit-key:{name}:{version}");
         let ciphertext = aead::encrypt(&wrapping_key, key, Some(aad.as_bytes()))?;
 
@@ -358,10 +949,30 @@ impl TransitEngine {
         &self,
         name: &str,
         version: u32,
+        kdf_version: u32,
+        encrypted: &[u8],
+        nonce: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, TransitError> {
+        Self::decrypt_key_material_from(
+            &self.master_key,
+            name,
+            version,
+            kdf_version,
+            encrypted,
+            nonce,
+        )
+    }
+
+    /// Decrypts stored key material wrapped under `master_key`.
+    fn decrypt_key_material_from(
+        master_key: &MasterKey,
+        name: &str,
+        version: u32,
+        kdf_version: u32,
         encrypted: &[u8],
         nonce: &[u8],
     ) -> Result<Zeroizing<Vec<u8>>, TransitError> {
-        let wrapping_key = self.derive_version_key(name, version)?;
+        let wrapping_key = Self::derive_version_key_from(master_key, name, version, kdf_version)?;
         let aad = format!("transit-key:{name}:{version}");
 
         // Reconstruct ciphertext with nonce prefix
@@ -373,16 +984,34 @@ impl TransitEngine {
         Ok(decrypted)
     }
 
-    /// Gets the raw key material for a specific version.
+    /// Gets the raw key material for a specific version, from the key
+    /// cache when one is configured.
     async fn get_key_material(
         &self,
         name: &str,
         version: u32,
+    ) -> Result<Zeroizing<Vec<u8>>, TransitError> {
+        let Some(cache) = &self.key_cache else {
+            return self.load_key_material(name, version).await;
+        };
+        if let Some(material) = cache.get(name, version) {
+            return Ok(material);
+        }
+        let material = self.load_key_material(name, version).await?;
+        cache.insert(name, version, &material);
+        Ok(material)
+    }
+
+    /// Reads and unwraps the raw key material for a specific version.
+    async fn load_key_material(
+        &self,
+        name: &str,
+        version: u32,
     ) -> Result<Zeroizing<Vec<u8>>, TransitError> {
         let row = self
             .storage
-            .query_one::<(String, String)>(
-                "SELECT key_material, nonce FROM transit_key_versions WHERE name = ? AND version = ?",
+            .query_one::<(String, String, String)>(
+                "SELECT key_material, nonce, COALESCE(CAST(kdf_version AS TEXT), '1') FROM transit_key_versions WHERE name = ? AND version = ?",
                 &[name, &version.to_string()],
             )
             .await
@@ -392,11 +1021,91 @@ impl TransitEngine {
                 version,
             })?;
 
-        let (key_material_hex, nonce_hex) = row;
+        let (key_material_hex, nonce_hex, kdf_version) = row;
         let key_material = hex_decode(&key_material_hex)?;
         let nonce = hex_decode(&nonce_hex)?;
+        let kdf_version = kdf_version.parse().map_err(|_| {
+            TransitError::Integrity(format!("unparsable kdf_version for key {name} v{version}"))
+        })?;
+
+        self.decrypt_key_material(name, version, kdf_version, &key_material, &nonce)
+    }
+
+    // ========================================================================
+    // Usage Tracking
+    // ========================================================================
+
+    /// Counts `count` successful operations against the key stored as
+    /// `stored_name`, flushing if the interval has elapsed.
+    ///
+    /// The operation already succeeded, so a failed flush is only logged;
+    /// its counts stay pending for the next one.
+    async fn record_usage(&self, stored_name: &str, count: u64) {
+        let Ok(now) = Self::now() else {
+            return;
+        };
+        if self.usage.record(stored_name, count, now) {
+            if let Err(error) = self.flush_usage().await {
+                warn!(error = %error, "Failed to flush transit key usage");
+            }
+        }
+    }
+
+    /// Writes all pending key usage counters to storage.
+    ///
+    /// Call it before shutting down or dropping the engine, to persist the
+    /// last interval's usage; see [`Self::with_usage_flush_interval`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::Storage`] if the write fails; the counts are
+    /// then kept for the next flush.
+    pub async fn flush_usage(&self) -> Result<(), TransitError> {
+        let pending = self.usage.take();
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let rows: Vec<[String; 3]> = pending
+            .iter()
+            .map(|(name, usage)| {
+                [
+                    usage.count.to_string(),
+                    usage.last_used_at.to_string(),
+                    name.clone(),
+                ]
+            })
+            .collect();
+        let params: Vec<Vec<&str>> = rows
+            .iter()
+            .map(|row| row.iter().map(String::as_str).collect())
+            .collect();
+        let statements: Vec<(&str, &[&str])> = params
+            .iter()
+            .map(|params| {
+                (
+                    "UPDATE transit_keys SET usage_count = usage_count + CAST(? AS INTEGER), last_used_at = MAX(COALESCE(last_used_at, 0), CAST(? AS INTEGER)) WHERE name = ?",
+                    params.as_slice(),
+                )
+            })
+            .collect();
+
+        if let Err(error) = self.storage.execute_transaction(&statements).await {
+            self.usage.restore(pending);
+            return Err(TransitError::Storage(error.to_string()));
+        }
+        Ok(())
+    }
 
-        self.decrypt_key_material(name, version, &key_material, &nonce)
+    /// Generates the material of a new key version: a 32-byte key for the
+    /// data ciphers, an Ed25519 seed followed by its public key for a
+    /// signature key.
+    fn generate_material(key_type: KeyType) -> Result<Zeroizing<Vec<u8>>, TransitError> {
+        let seed = random::generate_key()?;
+        let mut material = Zeroizing::new(seed.to_vec());
+        if key_type == KeyType::Ed25519 {
+            material.extend_from_slice(&signing::ed25519_public_key(&seed[..])?);
+        }
+        Ok(material)
     }
 
     // ========================================================================
@@ -431,29 +1140,92 @@ impl TransitEngine {
         Ok(())
     }
 
+    fn validate_key_prefix(prefix: &str) -> Result<(), TransitError> {
+        if prefix.is_empty() {
+            return Ok(());
+        }
+        if prefix.len() > 64 {
+            return Err(TransitError::InvalidKeyName(
+                "key prefix too long (max 64 chars)".into(),
+            ));
+        }
+        let Some(segments) = prefix.strip_suffix('/') else {
+            return Err(TransitError::InvalidKeyName(
+                "key prefix must end with /".into(),
+            ));
+        };
+        if segments.split('/').any(|segment| {
+            segment.is_empty()
+                || !segment
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        }) {
+            return Err(TransitError::InvalidKeyName(
+                "key prefix segments can only contain alphanumeric, dash, underscore".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Detects the crypto acceleration available on this CPU.
+    ///
+    /// Uses the same runtime CPUID (or `HWCAP`) detection the cipher crates
+    /// use to pick their backend, so it reflects what encryption actually
+    /// runs on.
+    #[must_use]
+    pub fn cpu_features() -> CpuCryptoFeatures {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        let (aes, clmul, avx2) = (
+            std::arch::is_x86_feature_detected!("aes"),
+            std::arch::is_x86_feature_detected!("pclmulqdq"),
+            std::arch::is_x86_feature_detected!("avx2"),
+        );
+        #[cfg(target_arch = "aarch64")]
+        let (aes, clmul, avx2) = (
+            std::arch::is_aarch64_feature_detected!("aes"),
+            std::arch::is_aarch64_feature_detected!("pmull"),
+            false,
+        );
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        let (aes, clmul, avx2) = (false, false, false);
+
+        CpuCryptoFeatures {
+            arch: std::env::consts::ARCH,
+            aes,
+            clmul,
+            avx2,
+        }
+    }
+
     // ========================================================================
     // Key Management Operations
     // ========================================================================
 
     /// Creates a new transit key.
+    ///
+    /// A [`KeyType::Ed25519`] key is generated as a key pair: its encryption
+    /// and decryption flags are cleared, and it signs with [`Self::sign`]
+    /// only if `supports_signing` is set.
     pub async fn create_key(
         &self,
         name: &str,
-        config: KeyConfig,
+        mut config: KeyConfig,
     ) -> Result<TransitKey, TransitError> {
         Self::validate_name(name)?;
+        Self::check_key_config(&config)?;
 
-        // Only the engine's effective algorithm is implemented. Accepting
-        // another key type would silently encrypt under it anyway, so fail
-        // closed here rather than persist a promise the engine cannot keep.
-        if config.key_type != ENGINE_ALGORITHM {
-            return Err(TransitError::UnsupportedKeyType(config.key_type));
+        // A signature key pair never encrypts, whatever the config says.
+        if config.key_type == KeyType::Ed25519 {
+            config.supports_encryption = false;
+            config.supports_decryption = false;
         }
 
+        let stored = self.stored_name(name);
+
         // Check if key already exists
         let existing = self
             .storage
-            .query_one::<(String,)>("SELECT name FROM transit_keys WHERE name = ?", &[name])
+            .query_one::<(String,)>("SELECT name FROM transit_keys WHERE name = ?", &[&stored])
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?;
 
@@ -463,21 +1235,27 @@ impl TransitEngine {
 
         let now = Self::now()?;
 
-        // Generate initial key material (32 bytes for AES-256 or ChaCha20)
-        let raw_key = random::generate_key()?;
-        let (encrypted_key, nonce) = self.encrypt_key_material(name, 1, raw_key.as_ref())?;
+        let raw_key = Self::generate_material(config.key_type)?;
+        let (encrypted_key, nonce) = self.encrypt_key_material(&stored, 1, &raw_key)?;
 
-        let key = TransitKey {
-            name: name.to_string(),
+        let mut key = TransitKey {
+            name: stored.clone(),
             key_type: config.key_type,
             latest_version: 1,
             min_encryption_version: 1,
             min_decryption_version: 1,
+            min_available_version: 1,
             supports_encryption: config.supports_encryption,
             supports_decryption: config.supports_decryption,
             supports_derivation: config.supports_derivation,
+            supports_signing: config.supports_signing,
+            convergent: config.convergent,
             exportable: config.exportable,
             deletion_allowed: config.deletion_allowed,
+            auto_rotate_interval: config.auto_rotate_interval,
+            tags: config.tags.clone(),
+            last_used_at: None,
+            usage_count: 0,
             created_at: now,
             updated_at: now,
         };
@@ -487,34 +1265,52 @@ impl TransitEngine {
         let supports_encryption = i32::from(config.supports_encryption).to_string();
         let supports_decryption = i32::from(config.supports_decryption).to_string();
         let supports_derivation = i32::from(config.supports_derivation).to_string();
+        let supports_signing = i32::from(config.supports_signing).to_string();
+        let convergent = i32::from(config.convergent).to_string();
+        let auto_rotate_secs = config
+            .auto_rotate_interval
+            .map(|interval| interval.as_secs().to_string())
+            .unwrap_or_default();
         let exportable = i32::from(config.exportable).to_string();
         let deletion_allowed = i32::from(config.deletion_allowed).to_string();
+        let tags = Self::tags_json(&config.tags)?;
         let now_str = now.to_string();
-        let encrypted_key_hex = hex_encode(&encrypted_key);
-        let nonce_hex = hex_encode(&nonce);
+        let encrypted_key_hex = encoding::encode_hex(&encrypted_key);
+        let nonce_hex = encoding::encode_hex(&nonce);
 
-        let key_params: [&str; 10] = [
-            name,
+        let key_params: [&str; 14] = [
+            &stored,
             &key_type_str,
             &supports_encryption,
             &supports_decryption,
             &supports_derivation,
+            &supports_signing,
+            &convergent,
+            &auto_rotate_secs,
+            &tags,
             &exportable,
             &deletion_allowed,
             &now_str,
             &now_str,
             &row_mac,
         ];
-        let version_params: [&str; 4] = [name, &encrypted_key_hex, &nonce_hex, &now_str];
+        let kdf_version = self.kdf_version.to_string();
+        let version_params: [&str; 5] = [
+            &stored,
+            &encrypted_key_hex,
+            &nonce_hex,
+            &now_str,
+            &kdf_version,
+        ];
 
         self.storage
             .execute_transaction(&[
                 (
-                    "INSERT INTO transit_keys (name, key_type, latest_version, min_encryption_version, min_decryption_version, supports_encryption, supports_decryption, supports_derivation, exportable, deletion_allowed, created_at, updated_at, row_mac) VALUES (?, ?, 1, 1, 1, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    "INSERT INTO transit_keys (name, key_type, latest_version, min_encryption_version, min_decryption_version, supports_encryption, supports_decryption, supports_derivation, supports_signing, convergent, auto_rotate_secs, tags, exportable, deletion_allowed, created_at, updated_at, row_mac) VALUES (?, ?, 1, 1, 1, ?, ?, ?, ?, ?, NULLIF(?, ''), ?, ?, ?, ?, ?, ?)",
                     &key_params,
                 ),
                 (
-                    "INSERT INTO transit_key_versions (name, version, key_material, nonce, created_at) VALUES (?, 1, ?, ?, ?)",
+                    "INSERT INTO transit_key_versions (name, version, key_material, nonce, created_at, kdf_version) VALUES (?, 1, ?, ?, ?, ?)",
                     &version_params,
                 ),
             ])
@@ -523,22 +1319,425 @@ impl TransitEngine {
 
         info!(name = name, key_type = %config.key_type, "Transit key created");
 
+        key.name = name.to_string();
         Ok(key)
     }
 
-    /// Computes the hex-encoded keyed MAC authenticating a key's policy row.
+    /// Rejects option combinations a key cannot be created with.
     ///
-    /// Binds every field that governs a security decision (`name`, `key_type`,
-    /// `latest_version`, `min_encryption_version`, `min_decryption_version`, the
-    /// four capability/exportability/deletion flags) under a subkey derived from
-    /// the master key, so a storage-level tamper of any of them is detected on
-    /// read. Timestamps are excluded: they govern no decision.
-    fn policy_mac(&self, key: &TransitKey) -> Result<String, TransitError> {
-        let subkey =
-            kdf::derive_encryption_key(self.master_key.as_bytes(), TRANSIT_POLICY_MAC_INFO)?;
+    /// Convergent keys cannot allow derivation: a derived subkey would need
+    /// its own deterministic nonce scheme, and convergence across a
+    /// derivation tree would leak plaintext equality between the tenants the
+    /// tree is meant to separate. Signature keys can be neither, and only
+    /// they can support signing. A rotation
+    /// interval must be at least one whole second, its storage unit. Tags
+    /// must pass [`Self::check_tags`].
+    fn check_key_config(config: &KeyConfig) -> Result<(), TransitError> {
+        if config.convergent && config.supports_derivation {
+            return Err(TransitError::InvalidKeyConfig(
+                "convergent keys cannot support derivation".into(),
+            ));
+        }
+        if !config.key_type.is_data_cipher() && (config.convergent || config.supports_derivation) {
+            return Err(TransitError::InvalidKeyConfig(format!(
+                "{} keys cannot be convergent or support derivation",
+                config.key_type
+            )));
+        }
+        if config.key_type.is_data_cipher() && config.supports_signing {
+            return Err(TransitError::InvalidKeyConfig(format!(
+                "{} keys cannot support signing",
+                config.key_type
+            )));
+        }
+        if config
+            .auto_rotate_interval
+            .is_some_and(|interval| interval.as_secs() == 0)
+        {
+            return Err(TransitError::InvalidKeyConfig(
+                "auto_rotate_interval must be at least one second".into(),
+            ));
+        }
+        Self::check_tags(&config.tags)
+    }
+
+    /// Rejects more than [`MAX_KEY_TAGS`] tags, empty or over-128-character
+    /// tag names, and values over 512 characters.
+    fn check_tags(tags: &BTreeMap<String, String>) -> Result<(), TransitError> {
+        if tags.len() > MAX_KEY_TAGS {
+            return Err(TransitError::InvalidKeyConfig(format!(
+                "at most {MAX_KEY_TAGS} tags are allowed, got {}",
+                tags.len()
+            )));
+        }
+        for (tag, value) in tags {
+            if tag.is_empty() || tag.chars().count() > 128 {
+                return Err(TransitError::InvalidKeyConfig(
+                    "tag names must be 1 to 128 characters".into(),
+                ));
+            }
+            if value.chars().count() > 512 {
+                return Err(TransitError::InvalidKeyConfig(format!(
+                    "tag {tag} exceeds 512 characters"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes tags for the `tags` column.
+    fn tags_json(tags: &BTreeMap<String, String>) -> Result<String, TransitError> {
+        serde_json::to_string(tags).map_err(|e| TransitError::Storage(e.to_string()))
+    }
+
+    /// Imports externally generated key material as version 1 of a new key.
+    ///
+    /// For keys that must be generated in the caller's own HSM rather than by
+    /// the engine (bring-your-own-key). The material is wrapped under the
+    /// derived version key exactly like [`Self::create_key`] wraps generated
+    /// material; later versions can be supplied with
+    /// [`Self::import_key_version`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidKeyMaterial`] if `key_material` is not
+    /// the length `config.key_type` requires, plus every error of
+    /// [`Self::import_key_versions`].
+    pub async fn import_key(
+        &self,
+        name: &str,
+        key_material: &[u8],
+        config: KeyConfig,
+    ) -> Result<TransitKey, TransitError> {
+        Self::validate_name(name)?;
+        if !config.key_type.is_data_cipher() {
+            return Err(TransitError::UnsupportedKeyType(config.key_type));
+        }
+        Self::check_symmetric_material(key_material)?;
+        self.import_key_versions(
+            name,
+            config.key_type,
+            vec![(1, key_material.to_vec())],
+            config,
+        )
+        .await
+    }
+
+    /// Rejects symmetric key material that is not exactly one key long.
+    fn check_symmetric_material(material: &[u8]) -> Result<(), TransitError> {
+        if material.len() != aead::KEY_SIZE {
+            return Err(TransitError::InvalidKeyMaterial(format!(
+                "key material must be {} bytes, got {}",
+                aead::KEY_SIZE,
+                material.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Imports a key together with its full version history.
+    ///
+    /// Migrating a key from another system must keep every version: old
+    /// ciphertexts name the version they were encrypted under (`egide:v2:...`)
+    /// and only decrypt with that version's material. Each `(version,
+    /// material)` pair is wrapped under the master key and stored at its
+    /// original version number, and `latest_version` is set to the highest
+    /// one, all in a single transaction. The versions must be exactly
+    /// `1..=n` (in any order) and each material must be a 32-byte key.
+    ///
+    /// `key_type` takes precedence over `config.key_type`; the remaining
+    /// capability flags come from `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidKeyName`] for an invalid name,
+    /// [`TransitError::UnsupportedKeyType`] for a type the engine does not
+    /// implement, [`TransitError::InvalidKeyConfig`] for options that cannot
+    /// be combined, [`TransitError::InvalidKeyMaterial`] if the versions are
+    /// empty, non-contiguous, duplicated or not 32 bytes, and
+    /// [`TransitError::KeyExists`] if the name is taken.
+    pub async fn import_key_versions(
+        &self,
+        name: &str,
+        key_type: KeyType,
+        versions: Vec<(u32, Vec<u8>)>,
+        config: KeyConfig,
+    ) -> Result<TransitKey, TransitError> {
+        Self::validate_name(name)?;
+        if !key_type.is_data_cipher() {
+            return Err(TransitError::UnsupportedKeyType(key_type));
+        }
+        Self::check_key_config(&config)?;
+
+        // Zeroize the caller's material once wrapped, whatever the outcome.
+        let mut versions: Vec<(u32, Zeroizing<Vec<u8>>)> = versions
+            .into_iter()
+            .map(|(version, material)| (version, Zeroizing::new(material)))
+            .collect();
+        versions.sort_by_key(|(version, _)| *version);
+        if versions.is_empty() {
+            return Err(TransitError::InvalidKeyMaterial(
+                "at least one version is required".into(),
+            ));
+        }
+        for (expected, (version, material)) in (1u32..).zip(&versions) {
+            if *version != expected {
+                return Err(TransitError::InvalidKeyMaterial(format!(
+                    "versions must be exactly 1..=n, found v{version} where v{expected} was expected"
+                )));
+            }
+            if material.len() != aead::KEY_SIZE {
+                return Err(TransitError::InvalidKeyMaterial(format!(
+                    "v{version} must be {} bytes, got {}",
+                    aead::KEY_SIZE,
+                    material.len()
+                )));
+            }
+        }
+        let latest_version = u32::try_from(versions.len())
+            .map_err(|_| TransitError::InvalidKeyMaterial("too many versions".into()))?;
+
+        let stored = self.stored_name(name);
+        let existing = self
+            .storage
+            .query_one::<(String,)>("SELECT name FROM transit_keys WHERE name = ?", &[&stored])
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        if existing.is_some() {
+            return Err(TransitError::KeyExists(name.to_string()));
+        }
+
+        let now = Self::now()?;
+        let mut key = TransitKey {
+            name: stored.clone(),
+            key_type,
+            latest_version,
+            min_encryption_version: 1,
+            min_decryption_version: 1,
+            min_available_version: 1,
+            supports_encryption: config.supports_encryption,
+            supports_decryption: config.supports_decryption,
+            supports_derivation: config.supports_derivation,
+            supports_signing: false,
+            convergent: config.convergent,
+            exportable: config.exportable,
+            deletion_allowed: config.deletion_allowed,
+            auto_rotate_interval: config.auto_rotate_interval,
+            tags: config.tags.clone(),
+            last_used_at: None,
+            usage_count: 0,
+            created_at: now,
+            updated_at: now,
+        };
+        let row_mac = self.policy_mac(&key)?;
+
+        let key_type_str = key_type.to_string();
+        let latest_version_str = latest_version.to_string();
+        let supports_encryption = i32::from(config.supports_encryption).to_string();
+        let supports_decryption = i32::from(config.supports_decryption).to_string();
+        let supports_derivation = i32::from(config.supports_derivation).to_string();
+        let convergent = i32::from(config.convergent).to_string();
+        let auto_rotate_secs = config
+            .auto_rotate_interval
+            .map(|interval| interval.as_secs().to_string())
+            .unwrap_or_default();
+        let exportable = i32::from(config.exportable).to_string();
+        let deletion_allowed = i32::from(config.deletion_allowed).to_string();
+        let tags = Self::tags_json(&config.tags)?;
+        let now_str = now.to_string();
+        let kdf_version = self.kdf_version.to_string();
+
+        let key_params: [&str; 14] = [
+            &stored,
+            &key_type_str,
+            &latest_version_str,
+            &supports_encryption,
+            &supports_decryption,
+            &supports_derivation,
+            &convergent,
+            &auto_rotate_secs,
+            &tags,
+            &exportable,
+            &deletion_allowed,
+            &now_str,
+            &now_str,
+            &row_mac,
+        ];
+
+        let mut version_rows = Vec::with_capacity(versions.len());
+        for (version, material) in &versions {
+            let (encrypted_key, nonce) = self.encrypt_key_material(&stored, *version, material)?;
+            version_rows.push([
+                version.to_string(),
+                encoding::encode_hex(&encrypted_key).as_str().to_owned(),
+                encoding::encode_hex(&nonce).as_str().to_owned(),
+            ]);
+        }
+        let version_params: Vec<[&str; 6]> = version_rows
+            .iter()
+            .map(|[version, material, nonce]| {
+                [
+                    stored.as_str(),
+                    version.as_str(),
+                    material.as_str(),
+                    nonce.as_str(),
+                    now_str.as_str(),
+                    kdf_version.as_str(),
+                ]
+            })
+            .collect();
+
+        let mut statements: Vec<(&str, &[&str])> = Vec::with_capacity(version_params.len() + 1);
+        statements.push((
+            "INSERT INTO transit_keys (name, key_type, latest_version, min_encryption_version, min_decryption_version, supports_encryption, supports_decryption, supports_derivation, convergent, auto_rotate_secs, tags, exportable, deletion_allowed, created_at, updated_at, row_mac) VALUES (?, ?, ?, 1, 1, ?, ?, ?, ?, NULLIF(?, ''), ?, ?, ?, ?, ?, ?)",
+            &key_params,
+        ));
+        for params in &version_params {
+            statements.push((
+                "INSERT INTO transit_key_versions (name, version, key_material, nonce, created_at, kdf_version) VALUES (?, ?, ?, ?, ?, ?)",
+                params,
+            ));
+        }
+
+        self.storage
+            .execute_transaction(&statements)
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+
+        info!(
+            name = name,
+            versions = latest_version,
+            "Transit key imported"
+        );
+
+        key.name = name.to_string();
+        Ok(key)
+    }
+
+    /// Imports a verify-only key holding nothing but a public key.
+    ///
+    /// For services that check signatures made elsewhere and must hold no
+    /// signing capability: the key verifies with [`Self::verify`], while
+    /// [`Self::sign`], encryption, decryption and rotation are refused. The
+    /// public key is stored as version 1, wrapped like any other material so
+    /// a storage-level swap of the key is detected rather than trusted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidKeyName`] for an invalid name,
+    /// [`TransitError::UnsupportedKeyType`] unless `key_type` is
+    /// [`KeyType::Ed25519`], [`TransitError::InvalidKeyMaterial`] if
+    /// `public_key` is not 32 bytes, and [`TransitError::KeyExists`] if the
+    /// name is taken.
+    pub async fn import_public_key(
+        &self,
+        name: &str,
+        key_type: KeyType,
+        public_key: &[u8],
+        deletion_allowed: bool,
+    ) -> Result<TransitKey, TransitError> {
+        Self::validate_name(name)?;
+        if key_type != KeyType::Ed25519 {
+            return Err(TransitError::UnsupportedKeyType(key_type));
+        }
+        if public_key.len() != signing::ED25519_PUBLIC_KEY_SIZE {
+            return Err(TransitError::InvalidKeyMaterial(format!(
+                "ed25519 public key must be {} bytes, got {}",
+                signing::ED25519_PUBLIC_KEY_SIZE,
+                public_key.len()
+            )));
+        }
+
+        let stored = self.stored_name(name);
+        let existing = self
+            .storage
+            .query_one::<(String,)>("SELECT name FROM transit_keys WHERE name = ?", &[&stored])
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        if existing.is_some() {
+            return Err(TransitError::KeyExists(name.to_string()));
+        }
+
+        let now = Self::now()?;
+        let (encrypted_key, nonce) = self.encrypt_key_material(&stored, 1, public_key)?;
+        let mut key = TransitKey {
+            name: stored.clone(),
+            key_type,
+            latest_version: 1,
+            min_encryption_version: 1,
+            min_decryption_version: 1,
+            min_available_version: 1,
+            supports_encryption: false,
+            supports_decryption: false,
+            supports_derivation: false,
+            supports_signing: false,
+            convergent: false,
+            exportable: false,
+            deletion_allowed,
+            auto_rotate_interval: None,
+            tags: BTreeMap::new(),
+            last_used_at: None,
+            usage_count: 0,
+            created_at: now,
+            updated_at: now,
+        };
+        let row_mac = self.policy_mac(&key)?;
+
+        let key_type_str = key_type.to_string();
+        let deletion_allowed = i32::from(deletion_allowed).to_string();
+        let now_str = now.to_string();
+        let encrypted_key_hex = encoding::encode_hex(&encrypted_key);
+        let nonce_hex = encoding::encode_hex(&nonce);
+        let kdf_version = self.kdf_version.to_string();
+        let key_params: [&str; 6] = [
+            &stored,
+            &key_type_str,
+            &deletion_allowed,
+            &now_str,
+            &now_str,
+            &row_mac,
+        ];
+        let version_params: [&str; 5] = [
+            &stored,
+            &encrypted_key_hex,
+            &nonce_hex,
+            &now_str,
+            &kdf_version,
+        ];
+
+        self.storage
+            .execute_transaction(&[
+                (
+                    "INSERT INTO transit_keys (name, key_type, latest_version, min_encryption_version, min_decryption_version, supports_encryption, supports_decryption, supports_derivation, supports_signing, exportable, deletion_allowed, created_at, updated_at, row_mac) VALUES (?, ?, 1, 1, 1, 0, 0, 0, 0, 0, ?, ?, ?, ?)",
+                    &key_params,
+                ),
+                (
+                    "INSERT INTO transit_key_versions (name, version, key_material, nonce, created_at, kdf_version) VALUES (?, 1, ?, ?, ?, ?)",
+                    &version_params,
+                ),
+            ])
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+
+        info!(name = name, key_type = %key_type, "Transit public key imported");
+
+        key.name = name.to_string();
+        Ok(key)
+    }
+
+    /// Computes the hex-encoded keyed MAC authenticating a key's policy row.
+    ///
+    /// Binds every field that governs a security decision (`name`, `key_type`,
+    /// `latest_version`, `min_encryption_version`, `min_decryption_version`, the
+    /// capability/exportability/deletion flags) under a subkey derived from
+    /// the master key, so a storage-level tamper of any of them is detected on
+    /// read. Timestamps are excluded: they govern no decision.
+    fn policy_mac(&self, key: &TransitKey) -> Result<String, TransitError> {
+        let subkey =
+            kdf::derive_encryption_key(self.master_key.as_bytes(), TRANSIT_POLICY_MAC_INFO)?;
         let data = Self::policy_mac_input(key)?;
         let tag = mac::compute_mac(&subkey[..], &data).map_err(TransitError::from)?;
-        Ok(hex_encode(&tag))
+        Ok(encoding::encode_hex(&tag).as_str().to_owned())
     }
 
     /// Canonical MAC input over the policy row's decision fields.
@@ -548,7 +1747,7 @@ impl TransitEngine {
     /// the check can never drift apart.
     fn policy_mac_input(key: &TransitKey) -> Result<Vec<u8>, TransitError> {
         let key_type_repr = key.key_type.to_string();
-        mac::encode_fields(&[
+        let mut data = mac::encode_fields(&[
             key.name.as_bytes(),
             key_type_repr.as_bytes(),
             &key.latest_version.to_be_bytes(),
@@ -560,7 +1759,34 @@ impl TransitEngine {
             &[u8::from(key.exportable)],
             &[u8::from(key.deletion_allowed)],
         ])
-        .map_err(TransitError::from)
+        .map_err(TransitError::from)?;
+        // Appended only when set, so rows written before the flag existed
+        // (all false) keep their MAC. The length-prefixed encoding still
+        // tells the two apart, so flipping it either way is detected.
+        if key.supports_signing {
+            data.extend(mac::encode_fields(&[&[1]]).map_err(TransitError::from)?);
+        }
+        // Same scheme; the distinct label keeps it apart from the signing
+        // field.
+        if key.convergent {
+            data.extend(mac::encode_fields(&[b"convergent"]).map_err(TransitError::from)?);
+        }
+        if let Some(interval) = key.auto_rotate_interval {
+            data.extend(
+                mac::encode_fields(&[b"auto_rotate_secs", &interval.as_secs().to_be_bytes()])
+                    .map_err(TransitError::from)?,
+            );
+        }
+        if key.min_available_version > 1 {
+            data.extend(
+                mac::encode_fields(&[
+                    b"min_available_version",
+                    &key.min_available_version.to_be_bytes(),
+                ])
+                .map_err(TransitError::from)?,
+            );
+        }
+        Ok(data)
     }
 
     /// Verifies the stored policy-row MAC, failing closed on any anomaly.
@@ -576,8 +1802,17 @@ impl TransitEngine {
     /// [`TransitError::Crypto`] if subkey derivation or the MAC computation
     /// fails. Any error means the policy row must not be trusted.
     fn verify_policy_mac(&self, key: &TransitKey, stored_hex: &str) -> Result<(), TransitError> {
-        let subkey =
-            kdf::derive_encryption_key(self.master_key.as_bytes(), TRANSIT_POLICY_MAC_INFO)?;
+        Self::verify_policy_mac_from(&self.master_key, key, stored_hex)
+    }
+
+    /// Verifies a policy-row MAC like [`Self::verify_policy_mac`], under an
+    /// explicit master key.
+    fn verify_policy_mac_from(
+        master_key: &MasterKey,
+        key: &TransitKey,
+        stored_hex: &str,
+    ) -> Result<(), TransitError> {
+        let subkey = kdf::derive_encryption_key(master_key.as_bytes(), TRANSIT_POLICY_MAC_INFO)?;
         let data = Self::policy_mac_input(key)?;
         let stored = hex_decode(stored_hex).map_err(|_| {
             TransitError::Integrity(format!("invalid policy mac encoding for {}", key.name))
@@ -588,13 +1823,51 @@ impl TransitEngine {
 
     /// Gets metadata for a transit key.
     pub async fn get_key(&self, name: &str) -> Result<TransitKey, TransitError> {
+        let mut key = self.load_key(name).await?;
+        if let Some(pending) = self.usage.pending(&key.name) {
+            key.usage_count += pending.count;
+            key.last_used_at = key.last_used_at.max(Some(pending.last_used_at));
+        }
+        key.name = name.to_string();
+        Ok(key)
+    }
+
+    /// Returns the inclusive range of versions a key can encrypt under, as
+    /// `(min_encryption_version, latest_version)`.
+    ///
+    /// [`Self::encrypt`] always uses `latest_version`; a client pinning a
+    /// version with [`Self::encrypt_with_version`] should pick one in this
+    /// window, since anything below the minimum is rejected.
+    pub async fn encryption_version_window(&self, name: &str) -> Result<(u32, u32), TransitError> {
+        Ok(self.load_key(name).await?.encryption_version_window())
+    }
+
+    /// Loads and authenticates a key's policy row.
+    ///
+    /// Takes the bare name but returns the key under its stored (prefixed)
+    /// name, which is what the policy MAC, key derivation and ciphertext
+    /// associated data are bound to.
+    async fn load_key(&self, name: &str) -> Result<TransitKey, TransitError> {
+        let (key, row_mac) = self.load_key_row(name).await?;
+        self.verify_policy_mac(&key, &row_mac)?;
+        Ok(key)
+    }
+
+    /// Reads a key's policy row and its stored MAC, without checking the MAC.
+    ///
+    /// Only [`Self::load_key`] and [`Self::rewrap_key_from`], which verify
+    /// the MAC themselves, may call this.
+    async fn load_key_row(&self, name: &str) -> Result<(TransitKey, String), TransitError> {
         Self::validate_name(name)?;
+        let stored = self.stored_name(name);
 
+        // The capability flags are read as one string, one `0`/`1` digit per
+        // flag, to stay within the column count a row tuple can hold.
         let row = self
             .storage
-            .query_one::<(String, String, String, String, String, String, String, String, String, String, String, String, String)>(
-                "SELECT name, key_type, CAST(latest_version AS TEXT), CAST(min_encryption_version AS TEXT), CAST(min_decryption_version AS TEXT), CAST(supports_encryption AS TEXT), CAST(supports_decryption AS TEXT), CAST(supports_derivation AS TEXT), CAST(exportable AS TEXT), CAST(deletion_allowed AS TEXT), CAST(created_at AS TEXT), CAST(updated_at AS TEXT), COALESCE(row_mac, '') FROM transit_keys WHERE name = ?",
-                &[name],
+            .query_one::<(String, String, String, String, String, String, String, String, String, String, String, String, String, String)>(
+                "SELECT name, key_type, CAST(latest_version AS TEXT), CAST(min_encryption_version AS TEXT), CAST(min_decryption_version AS TEXT), CAST(min_available_version AS TEXT), supports_encryption || supports_decryption || supports_derivation || supports_signing || convergent || exportable || deletion_allowed, CAST(created_at AS TEXT), CAST(updated_at AS TEXT), COALESCE(CAST(auto_rotate_secs AS TEXT), ''), COALESCE(tags, '{}'), COALESCE(CAST(last_used_at AS TEXT), ''), CAST(usage_count AS TEXT), COALESCE(row_mac, '') FROM transit_keys WHERE name = ?",
+                &[&stored],
             )
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?
@@ -606,13 +1879,14 @@ impl TransitEngine {
             latest_version,
             min_enc,
             min_dec,
-            enc,
-            dec,
-            deriv,
-            export,
-            del,
+            min_avail,
+            flags,
             created,
             updated,
+            auto_rotate,
+            tags,
+            last_used,
+            usage_count,
             row_mac,
         ) = row;
 
@@ -624,10 +1898,35 @@ impl TransitEngine {
             s.parse()
                 .map_err(|_| TransitError::Integrity(format!("unparsable {field} for key {name}")))
         };
-        let parse_flag = |s: &str, field: &str| -> Result<bool, TransitError> {
-            s.parse::<i32>()
-                .map(|v| v != 0)
-                .map_err(|_| TransitError::Integrity(format!("unparsable {field} for key {name}")))
+        let flags: Vec<bool> = flags
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(TransitError::Integrity(format!(
+                    "unparsable capability flags for key {name}"
+                ))),
+            })
+            .collect::<Result<_, _>>()?;
+        let [enc, dec, deriv, sign, conv, export, del] = flags[..] else {
+            return Err(TransitError::Integrity(format!(
+                "unparsable capability flags for key {name}"
+            )));
+        };
+        let auto_rotate_interval = if auto_rotate.is_empty() {
+            None
+        } else {
+            Some(Duration::from_secs(parse_u64(
+                &auto_rotate,
+                "auto_rotate_secs",
+            )?))
+        };
+        let tags = serde_json::from_str(&tags)
+            .map_err(|_| TransitError::Integrity(format!("unparsable tags for key {name}")))?;
+        let last_used_at = if last_used.is_empty() {
+            None
+        } else {
+            Some(parse_u64(&last_used, "last_used_at")?)
         };
 
         let key = TransitKey {
@@ -638,28 +1937,157 @@ impl TransitEngine {
             latest_version: parse_u32(&latest_version, "latest_version")?,
             min_encryption_version: parse_u32(&min_enc, "min_encryption_version")?,
             min_decryption_version: parse_u32(&min_dec, "min_decryption_version")?,
-            supports_encryption: parse_flag(&enc, "supports_encryption")?,
-            supports_decryption: parse_flag(&dec, "supports_decryption")?,
-            supports_derivation: parse_flag(&deriv, "supports_derivation")?,
-            exportable: parse_flag(&export, "exportable")?,
-            deletion_allowed: parse_flag(&del, "deletion_allowed")?,
+            min_available_version: parse_u32(&min_avail, "min_available_version")?,
+            supports_encryption: enc,
+            supports_decryption: dec,
+            supports_derivation: deriv,
+            supports_signing: sign,
+            convergent: conv,
+            exportable: export,
+            deletion_allowed: del,
+            auto_rotate_interval,
+            tags,
+            last_used_at,
+            usage_count: parse_u64(&usage_count, "usage_count")?,
             created_at: parse_u64(&created, "created_at")?,
             updated_at: parse_u64(&updated, "updated_at")?,
         };
 
-        self.verify_policy_mac(&key, &row_mac)?;
-        Ok(key)
+        Ok((key, row_mac))
     }
 
     /// Lists all transit key names.
+    ///
+    /// Only keys under this engine's prefix are returned, as bare names; keys
+    /// stored under another prefix are never listed. Reads through
+    /// [`Self::list_keys_page`] one page at a time, so prefer paging directly
+    /// when there are many keys.
     pub async fn list_keys(&self) -> Result<Vec<String>, TransitError> {
+        let mut names = Vec::new();
+        loop {
+            let page = self
+                .list_keys_page(names.last().map(String::as_str), LIST_KEYS_PAGE_SIZE)
+                .await?;
+            let done = page.len() < LIST_KEYS_PAGE_SIZE;
+            names.extend(page);
+            if done {
+                return Ok(names);
+            }
+        }
+    }
+
+    /// Lists up to `limit` key names, in lexicographic (byte) order, starting
+    /// strictly after `after`.
+    ///
+    /// Pass `None` for the first page and the last name of a page to fetch
+    /// the next; a page shorter than `limit` is the last one. The cursor is
+    /// exclusive and need not name an existing key, so deleting the last key
+    /// of a page does not break paging. Like [`Self::list_keys`], only bare
+    /// names under this engine's prefix are returned.
+    pub async fn list_keys_page(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>, TransitError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let pattern = prefix_pattern(&self.key_prefix);
+        // Every stored name under the prefix sorts after the bare prefix.
+        let cursor = self.stored_name(after.unwrap_or_default());
+        // SQLite's substr is 1-based: this is where the bare name starts.
+        let bare_start = (self.key_prefix.chars().count() + 1).to_string();
+        let limit = limit.to_string();
+
+        // A bare name never contains `/`, so anything with one after the
+        // prefix belongs to a longer, nested prefix. Filtered in the query
+        // rather than afterwards so that pages stay full.
         let rows = self
             .storage
-            .query_all::<(String,)>("SELECT name FROM transit_keys ORDER BY name", &[])
+            .query_all::<(String,)>(
+                "SELECT name FROM transit_keys WHERE name LIKE ? ESCAPE '\\' AND name > ? AND instr(substr(name, CAST(? AS INTEGER)), '/') = 0 ORDER BY name LIMIT CAST(? AS INTEGER)",
+                &[&pattern, &cursor, &bare_start, &limit],
+            )
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(name,)| {
+                name.strip_prefix(self.key_prefix.as_str())
+                    .map(str::to_string)
+            })
+            .collect())
+    }
+
+    /// Checks that every version of a key still unwraps under the master key.
+    ///
+    /// Attempts to decrypt the stored material of each version from
+    /// `min_available_version` to `latest_version`, discarding it immediately,
+    /// and returns `false` if any version is missing, undecodable or fails to
+    /// unwrap. Versions below the floor were trimmed on purpose and are not
+    /// expected. Useful after a
+    /// restore to detect storage corruption or a master key that does not
+    /// match the data, before the first caller hits it on decrypt.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::KeyNotFound`] for an unknown key,
+    /// [`TransitError::Integrity`] if the policy row itself fails
+    /// authentication (which is also how a wrong master key first surfaces),
+    /// and [`TransitError::Storage`] if the database cannot be read.
+    pub async fn verify_key(&self, name: &str) -> Result<bool, TransitError> {
+        let key = self.load_key(name).await?;
+
+        let rows = self
+            .storage
+            .query_all::<(String, String, String, String)>(
+                "SELECT CAST(version AS TEXT), key_material, nonce, COALESCE(CAST(kdf_version AS TEXT), '1') FROM transit_key_versions WHERE name = ? ORDER BY version",
+                &[&key.name],
+            )
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?;
 
-        Ok(rows.into_iter().map(|(name,)| name).collect())
+        let mut expected = key.min_available_version;
+        for (version, key_material_hex, nonce_hex, kdf_version) in &rows {
+            let unwrapped = self.try_unwrap_version(
+                &key.name,
+                version,
+                key_material_hex,
+                nonce_hex,
+                kdf_version,
+            );
+            if unwrapped != Some(expected) {
+                warn!(name = name, version = %version, "Transit key version failed verification");
+                return Ok(false);
+            }
+            expected += 1;
+        }
+
+        let complete = expected == key.latest_version + 1;
+        if !complete {
+            warn!(name = name, "Transit key is missing versions");
+        }
+        Ok(complete)
+    }
+
+    /// Decodes and unwraps one stored version row, returning its version
+    /// number on success and discarding the material.
+    fn try_unwrap_version(
+        &self,
+        stored_name: &str,
+        version: &str,
+        key_material_hex: &str,
+        nonce_hex: &str,
+        kdf_version: &str,
+    ) -> Option<u32> {
+        let version: u32 = version.parse().ok()?;
+        let kdf_version: u32 = kdf_version.parse().ok()?;
+        let key_material = hex_decode(key_material_hex).ok()?;
+        let nonce = hex_decode(nonce_hex).ok()?;
+        self.decrypt_key_material(stored_name, version, kdf_version, &key_material, &nonce)
+            .ok()
+            .map(|_material| version)
     }
 
     /// Lists all versions of a key.
@@ -667,13 +2095,13 @@ impl TransitEngine {
         Self::validate_name(name)?;
 
         // Verify key exists
-        let _ = self.get_key(name).await?;
+        let key = self.load_key(name).await?;
 
         let rows = self
             .storage
             .query_all::<(String, String)>(
                 "SELECT CAST(version AS TEXT), CAST(created_at AS TEXT) FROM transit_key_versions WHERE name = ? ORDER BY version DESC",
-                &[name],
+                &[&key.name],
             )
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?;
@@ -687,28 +2115,336 @@ impl TransitEngine {
             .collect())
     }
 
-    /// Rotates a key to a new version.
-    pub async fn rotate_key(&self, name: &str) -> Result<u32, TransitError> {
-        Self::validate_name(name)?;
+    /// Exports every version of a key wrapped to `wrapping_public_key`.
+    ///
+    /// The material never leaves the engine in plaintext: each version is
+    /// wrapped with ECIES over X25519 (see [`egide_crypto::wrapping`]) under
+    /// the label `egide-transit-export:{name}:{version}`, the usual import
+    /// format for an HSM or external KMS holding the matching private key.
+    /// Each blob opens with an [`egide_crypto::envelope`] header naming that
+    /// algorithm, followed by the wrapped material. Returns
+    /// `(version, blob)` pairs, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::NotExportable`] unless the key was created
+    /// `exportable`, and [`TransitError::Crypto`] if the public key is not a
+    /// valid X25519 key.
+    pub async fn export_key_wrapped(
+        &self,
+        name: &str,
+        wrapping_public_key: &[u8],
+    ) -> Result<Vec<(u32, Vec<u8>)>, TransitError> {
+        let key = self.load_key(name).await?;
+        if !key.exportable {
+            return Err(TransitError::NotExportable(name.to_string()));
+        }
 
-        let key = self.get_key(name).await?;
+        let wrapper = KeyWrapper::new(wrapping_public_key)?;
+        let header = EnvelopeHeader::new(AlgorithmId::X25519Aes256Gcm).encode();
+        let mut versions: Vec<u32> = self
+            .list_versions(name)
+            .await?
+            .into_iter()
+            .map(|info| info.version)
+            .collect();
+        versions.sort_unstable();
+
+        let mut exported = Vec::with_capacity(versions.len());
+        for version in versions {
+            let material = self.get_key_material(&key.name, version).await?;
+            let label = Self::export_label(name, version);
+            let wrapped = wrapper.wrap(label.as_bytes(), &material)?;
+            let mut blob = Vec::with_capacity(header.len() + wrapped.len());
+            blob.extend_from_slice(&header);
+            blob.extend_from_slice(&wrapped);
+            exported.push((version, blob));
+        }
+        Ok(exported)
+    }
 
-        // A key declared under an algorithm the engine does not implement
-        // can never encrypt: a new version of its material would be created
-        // only to sit unusable, since `encrypt_with_version` refuses it too.
-        // An operation that can produce no usable effect must fail loudly
-        // rather than silently succeed.
-        if key.key_type != ENGINE_ALGORITHM {
-            return Err(TransitError::KeyAlgorithmNotImplemented(key.key_type));
+    /// Wrapping label of an exported key version.
+    fn export_label(name: &str, version: u32) -> String {
+        format!("egide-transit-export:{name}:{version}")
+    }
+
+    /// Serializes a key's policy and every stored version into a single
+    /// base64 blob for [`Self::restore_key`].
+    ///
+    /// The material stays wrapped under the master-key-derived version keys
+    /// and the policy carries its MAC, so a backup only restores on an
+    /// instance with the same master key and key prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::KeyNotFound`] if the key does not exist and
+    /// [`TransitError::Integrity`] if its stored rows are corrupt.
+    pub async fn backup_key(&self, name: &str) -> Result<String, TransitError> {
+        Self::validate_name(name)?;
+        let mut key = self.load_key(name).await?;
+        key.name = self.stored_name(name);
+
+        let rows = self
+            .storage
+            .query_all::<(String, String, String, String, String)>(
+                "SELECT CAST(version AS TEXT), key_material, nonce, COALESCE(CAST(kdf_version AS TEXT), '1'), CAST(created_at AS TEXT) FROM transit_key_versions WHERE name = ? ORDER BY version",
+                &[&key.name],
+            )
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        let corrupt = || TransitError::Integrity(format!("unparsable version row for key {name}"));
+        let mut versions = Vec::with_capacity(rows.len());
+        for (version, key_material, nonce, kdf_version, created_at) in rows {
+            versions.push(KeyVersionBackup {
+                version: version.parse().map_err(|_| corrupt())?,
+                key_material,
+                nonce,
+                kdf_version: kdf_version.parse().map_err(|_| corrupt())?,
+                created_at: created_at.parse().map_err(|_| corrupt())?,
+            });
+        }
+
+        let row_mac = self.policy_mac(&key)?;
+        let backup = KeyBackup {
+            format: KEY_BACKUP_FORMAT,
+            key,
+            row_mac,
+            versions,
+        };
+        let json = serde_json::to_vec(&backup).map_err(|e| TransitError::Storage(e.to_string()))?;
+        info!(name = name, "Transit key backed up");
+        Ok(BASE64.encode(json))
+    }
+
+    /// Restores a key from a [`Self::backup_key`] blob under `name`.
+    ///
+    /// `name` must be the name the backup was taken from: the material is
+    /// wrapped under keys derived from it. The policy must match the MAC
+    /// recorded at backup time under this instance's master key, so an
+    /// edited policy (say, flipping `exportable`) or a backup from an
+    /// instance with a different master key is refused.
+    ///
+    /// An existing key of that name is only replaced, with all its versions,
+    /// when [`RestoreOptions::force`] is set and the existing key allows
+    /// deletion, as [`Self::delete_key`] requires. A backup older than the
+    /// existing key would drop its newer versions, so it is refused unless
+    /// [`RestoreOptions::allow_rollback`] is set as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidKeyMaterial`] if the blob is malformed,
+    /// of an unknown format, for another key name, or holds versions other
+    /// than `min_available_version..=latest_version`,
+    /// [`TransitError::Integrity`] if its policy or the existing key's fails
+    /// authentication, [`TransitError::KeyExists`] if the name is taken and
+    /// `force` is unset, [`TransitError::DeletionNotAllowed`] if the existing
+    /// key does not allow deletion, and [`TransitError::OperationNotAllowed`]
+    /// for an older backup without `allow_rollback`.
+    pub async fn restore_key(
+        &self,
+        name: &str,
+        backup: &str,
+        options: RestoreOptions,
+    ) -> Result<(), TransitError> {
+        Self::validate_name(name)?;
+        let stored = self.stored_name(name);
+        let backup = Self::parse_backup(&stored, backup)?;
+        self.verify_policy_mac(&backup.key, &backup.row_mac)?;
+
+        let existing = match self.load_key(name).await {
+            Ok(key) => Some(key),
+            Err(TransitError::KeyNotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        if let Some(existing) = &existing {
+            if !options.force {
+                return Err(TransitError::KeyExists(name.to_string()));
+            }
+            if !existing.deletion_allowed {
+                return Err(TransitError::DeletionNotAllowed(name.to_string()));
+            }
+            if backup.key.latest_version < existing.latest_version && !options.allow_rollback {
+                return Err(TransitError::OperationNotAllowed(format!(
+                    "backup of {name} stops at version {} but the stored key is at version {}",
+                    backup.key.latest_version, existing.latest_version
+                )));
+            }
+        }
+
+        let key = &backup.key;
+        let row_mac = backup.row_mac.clone();
+        let key_row: [String; 20] = [
+            stored.clone(),
+            key.key_type.to_string(),
+            key.latest_version.to_string(),
+            key.min_encryption_version.to_string(),
+            key.min_decryption_version.to_string(),
+            key.min_available_version.to_string(),
+            i32::from(key.supports_encryption).to_string(),
+            i32::from(key.supports_decryption).to_string(),
+            i32::from(key.supports_derivation).to_string(),
+            i32::from(key.supports_signing).to_string(),
+            i32::from(key.convergent).to_string(),
+            key.auto_rotate_interval
+                .map(|interval| interval.as_secs().to_string())
+                .unwrap_or_default(),
+            Self::tags_json(&key.tags)?,
+            key.last_used_at
+                .map(|at| at.to_string())
+                .unwrap_or_default(),
+            key.usage_count.to_string(),
+            i32::from(key.exportable).to_string(),
+            i32::from(key.deletion_allowed).to_string(),
+            key.created_at.to_string(),
+            key.updated_at.to_string(),
+            row_mac,
+        ];
+        let key_params: Vec<&str> = key_row.iter().map(String::as_str).collect();
+        let version_rows: Vec<[String; 6]> = backup
+            .versions
+            .iter()
+            .map(|v| {
+                [
+                    stored.clone(),
+                    v.version.to_string(),
+                    v.key_material.clone(),
+                    v.nonce.clone(),
+                    v.created_at.to_string(),
+                    v.kdf_version.to_string(),
+                ]
+            })
+            .collect();
+        let version_params: Vec<Vec<&str>> = version_rows
+            .iter()
+            .map(|row| row.iter().map(String::as_str).collect())
+            .collect();
+
+        let delete_params: [&str; 1] = [&stored];
+        let mut statements: Vec<(&str, &[&str])> = vec![
+            (
+                "DELETE FROM transit_key_versions WHERE name = ?",
+                &delete_params[..],
+            ),
+            ("DELETE FROM transit_keys WHERE name = ?", &delete_params[..]),
+            (
+                "INSERT INTO transit_keys (name, key_type, latest_version, min_encryption_version, min_decryption_version, min_available_version, supports_encryption, supports_decryption, supports_derivation, supports_signing, convergent, auto_rotate_secs, tags, last_used_at, usage_count, exportable, deletion_allowed, created_at, updated_at, row_mac) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULLIF(?, ''), ?, NULLIF(?, ''), ?, ?, ?, ?, ?, ?)",
+                key_params.as_slice(),
+            ),
+        ];
+        for params in &version_params {
+            statements.push((
+                "INSERT INTO transit_key_versions (name, version, key_material, nonce, created_at, kdf_version) VALUES (?, ?, ?, ?, ?, ?)",
+                params.as_slice(),
+            ));
+        }
+
+        self.storage
+            .execute_transaction(&statements)
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        self.invalidate_cached_key(&stored);
+        self.usage.forget(&stored);
+
+        warn!(
+            name = name,
+            replaced = existing.is_some(),
+            "Transit key restored"
+        );
+        Ok(())
+    }
+
+    /// Decodes a backup blob and checks it describes the key stored as
+    /// `stored` with a contiguous set of versions.
+    fn parse_backup(stored: &str, backup: &str) -> Result<KeyBackup, TransitError> {
+        let invalid = |reason: &str| TransitError::InvalidKeyMaterial(format!("backup {reason}"));
+        let json = BASE64
+            .decode(backup.trim())
+            .map_err(|_| invalid("is not valid base64"))?;
+        let backup: KeyBackup =
+            serde_json::from_slice(&json).map_err(|_| invalid("is not a transit key backup"))?;
+        if backup.format != KEY_BACKUP_FORMAT {
+            return Err(invalid(&format!(
+                "format {} is not supported",
+                backup.format
+            )));
+        }
+        if backup.key.name != stored {
+            return Err(invalid(&format!(
+                "is for key {}, not {stored}",
+                backup.key.name
+            )));
+        }
+        let expected = backup.key.min_available_version..=backup.key.latest_version;
+        if expected.is_empty()
+            || !backup
+                .versions
+                .iter()
+                .map(|v| v.version)
+                .eq(expected.clone())
+        {
+            return Err(invalid(&format!(
+                "must hold versions {} to {}",
+                expected.start(),
+                expected.end()
+            )));
+        }
+        Ok(backup)
+    }
+
+    /// Rotates a key to a new version.
+    pub async fn rotate_key(&self, name: &str) -> Result<u32, TransitError> {
+        self.add_version(name, None).await
+    }
+
+    /// Rotates externally supplied material in as a key's new version.
+    ///
+    /// The bring-your-own-key counterpart of [`Self::rotate_key`]: the
+    /// material is generated in the caller's HSM rather than by the engine,
+    /// then wrapped and stored exactly like a rotated version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidKeyMaterial`] if `key_material` is not
+    /// 32 bytes, plus every error of [`Self::rotate_key`].
+    pub async fn import_key_version(
+        &self,
+        name: &str,
+        key_material: &[u8],
+    ) -> Result<u32, TransitError> {
+        Self::check_symmetric_material(key_material)?;
+        self.add_version(name, Some(key_material)).await
+    }
+
+    /// Stores a new latest version of a key, under `material` if given or
+    /// under freshly generated material otherwise.
+    async fn add_version(&self, name: &str, material: Option<&[u8]>) -> Result<u32, TransitError> {
+        Self::validate_name(name)?;
+
+        let key = self.load_key(name).await?;
+        let stored = key.name.clone();
+
+        // A generated Ed25519 key rotates to a fresh key pair. A verify-only
+        // one holds a caller-supplied public key, so the engine has nothing
+        // to generate a new version from, and no version is ever imported
+        // into a signature key.
+        if key.key_type == KeyType::Ed25519 {
+            let latest = self.get_key_material(&stored, key.latest_version).await?;
+            if material.is_some() || latest.len() != ED25519_KEYPAIR_SIZE {
+                return Err(TransitError::OperationNotAllowed(
+                    "verify-only ed25519 keys cannot be rotated".into(),
+                ));
+            }
         }
 
         let new_version = key.latest_version + 1;
         let now = Self::now()?;
 
-        // Generate new key material
-        let raw_key = random::generate_key()?;
-        let (encrypted_key, nonce) =
-            self.encrypt_key_material(name, new_version, raw_key.as_ref())?;
+        let (encrypted_key, nonce) = if let Some(material) = material {
+            self.encrypt_key_material(&stored, new_version, material)?
+        } else {
+            let raw_key = Self::generate_material(key.key_type)?;
+            self.encrypt_key_material(&stored, new_version, &raw_key)?
+        };
 
         let updated = TransitKey {
             latest_version: new_version,
@@ -719,22 +2455,24 @@ impl TransitEngine {
 
         let new_version_str = new_version.to_string();
         let now_str = now.to_string();
-        let encrypted_key_hex = hex_encode(&encrypted_key);
-        let nonce_hex = hex_encode(&nonce);
+        let encrypted_key_hex = encoding::encode_hex(&encrypted_key);
+        let nonce_hex = encoding::encode_hex(&nonce);
 
-        let version_params: [&str; 5] = [
-            name,
+        let kdf_version = self.kdf_version.to_string();
+        let version_params: [&str; 6] = [
+            &stored,
             &new_version_str,
             &encrypted_key_hex,
             &nonce_hex,
             &now_str,
+            &kdf_version,
         ];
-        let update_params: [&str; 4] = [&new_version_str, &now_str, &row_mac, name];
+        let update_params: [&str; 4] = [&new_version_str, &now_str, &row_mac, &stored];
 
         self.storage
             .execute_transaction(&[
                 (
-                    "INSERT INTO transit_key_versions (name, version, key_material, nonce, created_at) VALUES (?, ?, ?, ?, ?)",
+                    "INSERT INTO transit_key_versions (name, version, key_material, nonce, created_at, kdf_version) VALUES (?, ?, ?, ?, ?, ?)",
                     &version_params,
                 ),
                 (
@@ -744,17 +2482,42 @@ impl TransitEngine {
             ])
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?;
+        self.invalidate_cached_key(&stored);
 
-        info!(name = name, version = new_version, "Transit key rotated");
+        if material.is_some() {
+            info!(
+                name = name,
+                version = new_version,
+                "Transit key version imported"
+            );
+        } else {
+            info!(name = name, version = new_version, "Transit key rotated");
+        }
 
         Ok(new_version)
     }
 
+    /// Rotates a key if its automatic rotation is due, returning the new
+    /// version, or `None` if the key has no interval or is not due yet.
+    ///
+    /// A key is due once [`TransitKey::next_rotation_at`] has passed. The
+    /// interval runs from `updated_at`, so any change to the key (a rotation,
+    /// a config update, a trim) restarts it. Meant to be called periodically
+    /// for every name of [`Self::list_keys`]; two concurrent callers may both
+    /// rotate a key that just became due.
+    pub async fn rotate_if_due(&self, name: &str) -> Result<Option<u32>, TransitError> {
+        let key = self.load_key(name).await?;
+        match key.next_rotation_at() {
+            Some(due_at) if Self::now()? >= due_at => Ok(Some(self.rotate_key(name).await?)),
+            _ => Ok(None),
+        }
+    }
+
     /// Deletes a transit key (if deletion is allowed).
     pub async fn delete_key(&self, name: &str) -> Result<(), TransitError> {
         Self::validate_name(name)?;
 
-        let key = self.get_key(name).await?;
+        let key = self.load_key(name).await?;
 
         if !key.deletion_allowed {
             return Err(TransitError::DeletionNotAllowed(name.to_string()));
@@ -762,32 +2525,135 @@ impl TransitEngine {
 
         self.storage
             .execute_transaction(&[
-                ("DELETE FROM transit_key_versions WHERE name = ?", &[name]),
-                ("DELETE FROM transit_keys WHERE name = ?", &[name]),
+                (
+                    "DELETE FROM transit_key_versions WHERE name = ?",
+                    &[&key.name],
+                ),
+                ("DELETE FROM transit_keys WHERE name = ?", &[&key.name]),
             ])
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?;
+        self.invalidate_cached_key(&key.name);
+        self.usage.forget(&key.name);
 
         warn!(name = name, "Transit key deleted");
 
         Ok(())
     }
 
+    /// Deletes every version of a key strictly below `min_available_version`
+    /// and returns how many were removed.
+    ///
+    /// The floor may not exceed `min_decryption_version` nor
+    /// `min_encryption_version`: raise those first with
+    /// [`Self::update_key_config`], once existing ciphertexts have been
+    /// rewrapped. Trimming is irreversible; ciphertexts under a removed
+    /// version then fail with [`TransitError::VersionTrimmed`]. A floor at
+    /// or below the current one removes nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::OperationNotAllowed`] if the floor is above
+    /// either minimum version.
+    pub async fn trim_key(
+        &self,
+        name: &str,
+        min_available_version: u32,
+    ) -> Result<u32, TransitError> {
+        let key = self.load_key(name).await?;
+        let limit = key.min_decryption_version.min(key.min_encryption_version);
+        if min_available_version > limit {
+            return Err(TransitError::OperationNotAllowed(format!(
+                "cannot trim below v{min_available_version}: versions from v{limit} are still in use"
+            )));
+        }
+        if min_available_version <= key.min_available_version {
+            return Ok(0);
+        }
+
+        let floor = min_available_version.to_string();
+        let (removed,) = self
+            .storage
+            .query_one::<(String,)>(
+                "SELECT CAST(COUNT(*) AS TEXT) FROM transit_key_versions WHERE name = ? AND version < ?",
+                &[&key.name, &floor],
+            )
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?
+            .unwrap_or_else(|| ("0".into(),));
+        let removed: u32 = removed.parse().map_err(|_| {
+            TransitError::Storage(format!("unparsable version count for key {name}"))
+        })?;
+
+        let now = Self::now()?;
+        let stored = key.name.clone();
+        let updated = TransitKey {
+            min_available_version,
+            updated_at: now,
+            ..key
+        };
+        let row_mac = self.policy_mac(&updated)?;
+        let now_str = now.to_string();
+
+        self.storage
+            .execute_transaction(&[
+                (
+                    "DELETE FROM transit_key_versions WHERE name = ? AND version < ?",
+                    &[&stored, &floor],
+                ),
+                (
+                    "UPDATE transit_keys SET min_available_version = ?, updated_at = ?, row_mac = ? WHERE name = ?",
+                    &[&floor, &now_str, &row_mac, &stored],
+                ),
+            ])
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        self.invalidate_cached_key(&stored);
+
+        warn!(
+            name = name,
+            min_available_version, removed, "Transit key versions trimmed"
+        );
+
+        Ok(removed)
+    }
+
     /// Updates key configuration (min versions, etc.).
+    ///
+    /// Versions must keep `min_available_version <= min_decryption_version
+    /// <= latest_version`, and `min_encryption_version <= latest_version`.
+    /// `min_decryption_version` is a policy floor: versions below it are
+    /// refused but their material is kept, so lowering it again reopens
+    /// them. Raising `min_available_version` deletes the material below it,
+    /// exactly like [`Self::trim_key`] (whose extra rule, a floor no higher
+    /// than `min_encryption_version`, applies here too); a value at or below
+    /// the current floor leaves it unchanged, since trimmed material cannot
+    /// come back.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::VersionNotFound`] for a minimum above the
+    /// latest version, and [`TransitError::OperationNotAllowed`] if the
+    /// available floor would end above either other minimum.
     pub async fn update_key_config(
         &self,
         name: &str,
         min_encryption_version: Option<u32>,
         min_decryption_version: Option<u32>,
+        min_available_version: Option<u32>,
         deletion_allowed: Option<bool>,
     ) -> Result<(), TransitError> {
         Self::validate_name(name)?;
 
-        let key = self.get_key(name).await?;
+        let key = self.load_key(name).await?;
+        let stored = key.name.clone();
         let now = Self::now()?;
 
         let min_enc = min_encryption_version.unwrap_or(key.min_encryption_version);
         let min_dec = min_decryption_version.unwrap_or(key.min_decryption_version);
+        let min_avail = min_available_version
+            .unwrap_or(key.min_available_version)
+            .max(key.min_available_version);
         let del = deletion_allowed.unwrap_or(key.deletion_allowed);
 
         // Validate: min versions cannot exceed latest version
@@ -803,44 +2669,323 @@ impl TransitEngine {
                 version: min_dec,
             });
         }
+        let limit = min_dec.min(min_enc);
+        if min_avail > limit {
+            return Err(TransitError::OperationNotAllowed(format!(
+                "min_available_version v{min_avail} cannot exceed min_decryption_version or min_encryption_version (v{limit})"
+            )));
+        }
 
         let updated = TransitKey {
             min_encryption_version: min_enc,
             min_decryption_version: min_dec,
+            min_available_version: min_avail,
             deletion_allowed: del,
             updated_at: now,
             ..key
         };
         let row_mac = self.policy_mac(&updated)?;
 
+        let min_enc = min_enc.to_string();
+        let min_dec = min_dec.to_string();
+        let floor = min_avail.to_string();
+        let del = i32::from(del).to_string();
+        let now = now.to_string();
+        let update_params: [&str; 7] = [&min_enc, &min_dec, &floor, &del, &now, &row_mac, &stored];
+        let trim_params: [&str; 2] = [&stored, &floor];
+
+        // Deleting below an unchanged floor finds nothing, so the trim can
+        // run unconditionally in the same transaction.
+        self.storage
+            .execute_transaction(&[
+                (
+                    "DELETE FROM transit_key_versions WHERE name = ? AND version < ?",
+                    &trim_params,
+                ),
+                (
+                    "UPDATE transit_keys SET min_encryption_version = ?, min_decryption_version = ?, min_available_version = ?, deletion_allowed = ?, updated_at = ?, row_mac = ? WHERE name = ?",
+                    &update_params,
+                ),
+            ])
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        self.invalidate_cached_key(&stored);
+
+        debug!(name = name, "Transit key config updated");
+
+        Ok(())
+    }
+
+    /// Replaces a key's tags with `tags`; an empty map clears them.
+    ///
+    /// Tags are informational: they are outside the policy MAC and leave
+    /// `updated_at` alone, so retagging never restarts the rotation
+    /// interval.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidKeyConfig`] if the tags fail the limits
+    /// documented on [`KeyConfig::tags`], and [`TransitError::KeyNotFound`]
+    /// if the key does not exist.
+    pub async fn update_key_tags(
+        &self,
+        name: &str,
+        tags: BTreeMap<String, String>,
+    ) -> Result<(), TransitError> {
+        Self::check_tags(&tags)?;
+        let key = self.load_key(name).await?;
+        let tags = Self::tags_json(&tags)?;
+
         self.storage
             .execute(
-                "UPDATE transit_keys SET min_encryption_version = ?, min_decryption_version = ?, deletion_allowed = ?, updated_at = ?, row_mac = ? WHERE name = ?",
-                &[
-                    &min_enc.to_string(),
-                    &min_dec.to_string(),
-                    &i32::from(del).to_string(),
-                    &now.to_string(),
-                    &row_mac,
-                    name,
-                ],
+                "UPDATE transit_keys SET tags = ? WHERE name = ?",
+                &[&tags, &key.name],
             )
             .await
             .map_err(|e| TransitError::Storage(e.to_string()))?;
 
-        debug!(name = name, "Transit key config updated");
+        debug!(name = name, "Transit key tags updated");
+
+        Ok(())
+    }
+
+    /// Renames a key, rewrapping every stored version under the new name.
+    ///
+    /// Version material is wrapped under a key derived from the stored name,
+    /// so each version is unwrapped and wrapped again under `new`, and both
+    /// tables are rewritten in one transaction. The material itself, the
+    /// versions, the policy and the tags carry over.
+    ///
+    /// Existing ciphertexts do **not** carry over: the key name is bound into
+    /// their associated data (and into derived subkeys and HMAC output
+    /// labels), so anything encrypted under `old` no longer decrypts under
+    /// `new`. Rename before a key has been used, or decrypt and re-encrypt
+    /// its data afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidKeyName`] for an invalid name,
+    /// [`TransitError::KeyNotFound`] if `old` does not exist, and
+    /// [`TransitError::KeyExists`] if `new` does.
+    pub async fn rename_key(&self, old: &str, new: &str) -> Result<(), TransitError> {
+        Self::validate_name(new)?;
+        // Persist pending usage first, so the copied row carries it.
+        self.flush_usage().await?;
+        let key = self.load_key(old).await?;
+        let old_stored = key.name.clone();
+        let new_stored = self.stored_name(new);
+
+        let existing = self
+            .storage
+            .query_one::<(String,)>(
+                "SELECT name FROM transit_keys WHERE name = ?",
+                &[&new_stored],
+            )
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        if existing.is_some() {
+            return Err(TransitError::KeyExists(new.to_string()));
+        }
+
+        let rows = self
+            .storage
+            .query_all::<(String, String, String, String, String)>(
+                "SELECT CAST(version AS TEXT), key_material, nonce, COALESCE(CAST(kdf_version AS TEXT), '1'), CAST(created_at AS TEXT) FROM transit_key_versions WHERE name = ? ORDER BY version",
+                &[&old_stored],
+            )
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+
+        let unparsable =
+            |field: &str| TransitError::Integrity(format!("unparsable {field} for key {old}"));
+        let mut version_rows = Vec::with_capacity(rows.len());
+        for (version, key_material, nonce, kdf_version, created_at) in rows {
+            let version: u32 = version.parse().map_err(|_| unparsable("version"))?;
+            let kdf_version: u32 = kdf_version.parse().map_err(|_| unparsable("kdf_version"))?;
+            let material = self.decrypt_key_material(
+                &old_stored,
+                version,
+                kdf_version,
+                &hex_decode(&key_material)?,
+                &hex_decode(&nonce)?,
+            )?;
+            let (encrypted, nonce) = self.encrypt_key_material(&new_stored, version, &material)?;
+            version_rows.push([
+                new_stored.clone(),
+                version.to_string(),
+                encoding::encode_hex(&encrypted).as_str().to_owned(),
+                encoding::encode_hex(&nonce).as_str().to_owned(),
+                created_at,
+                self.kdf_version.to_string(),
+            ]);
+        }
+
+        let now = Self::now()?;
+        let renamed = TransitKey {
+            name: new_stored.clone(),
+            updated_at: now,
+            ..key
+        };
+        let row_mac = self.policy_mac(&renamed)?;
+        let now = now.to_string();
+
+        let key_params: [&str; 4] = [&new_stored, &now, &row_mac, &old_stored];
+        let delete_params: [&str; 1] = [&old_stored];
+        let version_params: Vec<Vec<&str>> = version_rows
+            .iter()
+            .map(|row| row.iter().map(String::as_str).collect())
+            .collect();
+        let mut statements: Vec<(&str, &[&str])> = vec![(
+            "INSERT INTO transit_keys (name, key_type, latest_version, min_encryption_version, min_decryption_version, min_available_version, supports_encryption, supports_decryption, supports_derivation, supports_signing, convergent, auto_rotate_secs, tags, last_used_at, usage_count, exportable, deletion_allowed, created_at, updated_at, row_mac) SELECT ?, key_type, latest_version, min_encryption_version, min_decryption_version, min_available_version, supports_encryption, supports_decryption, supports_derivation, supports_signing, convergent, auto_rotate_secs, tags, last_used_at, usage_count, exportable, deletion_allowed, created_at, ?, ? FROM transit_keys WHERE name = ?",
+            &key_params[..],
+        )];
+        for params in &version_params {
+            statements.push((
+                "INSERT INTO transit_key_versions (name, version, key_material, nonce, created_at, kdf_version) VALUES (?, ?, ?, ?, ?, ?)",
+                params.as_slice(),
+            ));
+        }
+        statements.push((
+            "DELETE FROM transit_key_versions WHERE name = ?",
+            &delete_params[..],
+        ));
+        statements.push((
+            "DELETE FROM transit_keys WHERE name = ?",
+            &delete_params[..],
+        ));
+
+        self.storage
+            .execute_transaction(&statements)
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        self.invalidate_cached_key(&old_stored);
+        // Usage recorded after the flush above belongs to a row that no
+        // longer exists.
+        self.usage.forget(&old_stored);
+
+        warn!(old = old, new = new, "Transit key renamed");
 
         Ok(())
     }
 
+    /// Rewraps every version of key `name` from `old_master` to the engine's
+    /// master key, after a master key rotation, and returns how many versions
+    /// were rewrapped.
+    ///
+    /// Each version is unwrapped under its recorded derivation with
+    /// `old_master` and wrapped again under the engine's master key and
+    /// current derivation version; versions below `min_available_version`
+    /// were trimmed and are not stored. The policy-row MAC is recomputed
+    /// under the new key, and all rows are written in one transaction. The
+    /// material itself does not change, so existing ciphertexts keep
+    /// decrypting. A key whose policy row already authenticates under the
+    /// engine's key is left alone and reports 0, so the call can be retried
+    /// over every key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::Integrity`] if the policy row authenticates
+    /// under neither key, and [`TransitError::Crypto`] if a version does not
+    /// unwrap under `old_master`; nothing is written in either case.
+    pub async fn rewrap_key_from(
+        &self,
+        name: &str,
+        old_master: &MasterKey,
+    ) -> Result<u32, TransitError> {
+        let (key, row_mac) = self.load_key_row(name).await?;
+        if self.verify_policy_mac(&key, &row_mac).is_ok() {
+            return Ok(0);
+        }
+        Self::verify_policy_mac_from(old_master, &key, &row_mac)?;
+        let stored = key.name.clone();
+
+        let rows = self
+            .storage
+            .query_all::<(String, String, String, String)>(
+                "SELECT CAST(version AS TEXT), key_material, nonce, COALESCE(CAST(kdf_version AS TEXT), '1') FROM transit_key_versions WHERE name = ? ORDER BY version",
+                &[&stored],
+            )
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+
+        let unparsable =
+            |field: &str| TransitError::Integrity(format!("unparsable {field} for key {name}"));
+        let kdf_version = self.kdf_version.to_string();
+        let mut version_rows = Vec::with_capacity(rows.len());
+        for (version_repr, key_material, nonce, old_kdf_version) in rows {
+            let version: u32 = version_repr.parse().map_err(|_| unparsable("version"))?;
+            let old_kdf_version: u32 = old_kdf_version
+                .parse()
+                .map_err(|_| unparsable("kdf_version"))?;
+            let material = Self::decrypt_key_material_from(
+                old_master,
+                &stored,
+                version,
+                old_kdf_version,
+                &hex_decode(&key_material)?,
+                &hex_decode(&nonce)?,
+            )?;
+            let (encrypted, nonce) = self.encrypt_key_material(&stored, version, &material)?;
+            version_rows.push([
+                encoding::encode_hex(&encrypted).as_str().to_owned(),
+                encoding::encode_hex(&nonce).as_str().to_owned(),
+                kdf_version.clone(),
+                stored.clone(),
+                version_repr,
+            ]);
+        }
+
+        let row_mac = self.policy_mac(&key)?;
+        let key_params: [&str; 2] = [&row_mac, &stored];
+        let mut statements: Vec<(&str, &[&str])> = version_rows
+            .iter()
+            .map(|params| {
+                (
+                    "UPDATE transit_key_versions SET key_material = ?, nonce = ?, kdf_version = ? WHERE name = ? AND version = ?",
+                    &params[..],
+                )
+            })
+            .collect();
+        statements.push((
+            "UPDATE transit_keys SET row_mac = ? WHERE name = ?",
+            &key_params[..],
+        ));
+        self.storage
+            .execute_transaction(&statements)
+            .await
+            .map_err(|e| TransitError::Storage(e.to_string()))?;
+        self.invalidate_cached_key(&stored);
+
+        let count = u32::try_from(version_rows.len()).unwrap_or(u32::MAX);
+        info!(
+            name = name,
+            versions = count,
+            "Transit key rewrapped under new master key"
+        );
+        Ok(count)
+    }
+
     // ========================================================================
     // Encryption/Decryption Operations
     // ========================================================================
 
     /// Encrypts plaintext using the latest version of a key.
     ///
-    /// Returns ciphertext in format: `egide:v{version}:{base64}`
+    /// Returns ciphertext in format: `egide:v{version}:{base64}`, or
+    /// `egide:c1:v{version}:{base64}` for a convergent key.
+    ///
+    /// A convergent key derives the nonce from an HMAC of the plaintext under
+    /// the key version's material instead of drawing it at random, so
+    /// encrypting the same plaintext (with the same associated data) twice
+    /// yields the same ciphertext, and stored ciphertexts can be
+    /// deduplicated. The price is confidentiality of equality: anyone who
+    /// sees two ciphertexts learns whether the plaintexts match, and can
+    /// confirm a guessed plaintext given encryption access. Only use it for
+    /// high-entropy data, or where that equality is already public.
+    /// Distinct plaintexts still get distinct nonces, so the nonce-reuse
+    /// guarantees of AES-GCM hold.
     pub async fn encrypt(&self, name: &str, plaintext: &[u8]) -> Result<String, TransitError> {
+        self.check_plaintext_size(plaintext)?;
         let key = self.get_key(name).await?;
 
         if !key.supports_encryption {
@@ -860,22 +3005,79 @@ impl TransitEngine {
         plaintext: &[u8],
         version: u32,
     ) -> Result<String, TransitError> {
-        let key = self.get_key(name).await?;
+        self.check_plaintext_size(plaintext)?;
+        let key = self.load_key(name).await?;
+        self.encrypt_with_key(&key, name, None, None, plaintext, version)
+            .await
+    }
+
+    /// Encrypts plaintext using the latest version of a key, binding
+    /// caller-supplied associated data into the ciphertext.
+    ///
+    /// The associated data is authenticated but not encrypted, and not stored
+    /// in the ciphertext: [`Self::decrypt_with_aad`] and
+    /// [`Self::rewrap_with_aad`] must be given the same bytes, and
+    /// [`Self::decrypt`] (or [`Self::rewrap`]) fails on such a ciphertext.
+    pub async fn encrypt_with_aad(
+        &self,
+        name: &str,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<String, TransitError> {
+        self.check_plaintext_size(plaintext)?;
+        let key = self.load_key(name).await?;
+        let version = key.latest_version;
+        self.encrypt_with_key(&key, name, None, Some(associated_data), plaintext, version)
+            .await
+    }
+
+    /// Encrypts under an already-loaded key, enforcing its policy, optionally
+    /// under the subkey derived for `derivation_path` and binding caller
+    /// `associated_data`.
+    async fn encrypt_with_key(
+        &self,
+        key: &TransitKey,
+        name: &str,
+        derivation: Option<Derivation<'_>>,
+        associated_data: Option<&[u8]>,
+        plaintext: &[u8],
+        version: u32,
+    ) -> Result<String, TransitError> {
+        Self::check_encryption_policy(key, name, version)?;
+
+        // Get the raw key material
+        let raw_key = self.get_key_material(&key.name, version).await?;
+
+        let ciphertext = self.seal_with_material(
+            key,
+            &raw_key,
+            derivation,
+            associated_data,
+            plaintext,
+            version,
+        )?;
+        self.record_usage(&key.name, 1).await;
+        Ok(ciphertext)
+    }
 
+    /// Checks that `key` may encrypt under `version`.
+    fn check_encryption_policy(
+        key: &TransitKey,
+        name: &str,
+        version: u32,
+    ) -> Result<(), TransitError> {
         if !key.supports_encryption {
             return Err(TransitError::OperationNotAllowed(
                 "encryption not allowed for this key".into(),
             ));
         }
 
-        // A key declared under an algorithm the engine does not implement
-        // (a type accepted by an earlier release) must not be used for new
-        // encryption: there is no cipher to honor the promise, and labelling
-        // the ciphertext with that declared type would be a lie. Existing
-        // ciphertexts under such a key remain readable via decrypt/rewrap.
-        // This is server-side state, not a malformed request, hence the
-        // distinct variant from the one `create_key` returns.
-        if key.key_type != ENGINE_ALGORITHM {
+        // A key declared under a type with no cipher behind it must not be
+        // used for new encryption: labelling the ciphertext with that type
+        // would be a lie. This is server-side state, not a malformed
+        // request, hence the distinct variant from the one `create_key`
+        // returns.
+        if !key.key_type.is_data_cipher() {
             return Err(TransitError::KeyAlgorithmNotImplemented(key.key_type));
         }
 
@@ -893,51 +3095,366 @@ impl TransitEngine {
             });
         }
 
-        // Get the raw key material
-        let raw_key = self.get_key_material(name, version).await?;
-
-        // Encrypt with AAD containing key name for domain separation
-        let aad = format!("egide-transit:{name}:{version}");
-        let ciphertext = aead::encrypt(&raw_key, plaintext, Some(aad.as_bytes()))?;
+        Ok(())
+    }
 
-        // Label with the effective algorithm, not the declared key_type: the
-        // check above already guarantees the two agree for any key reaching
-        // this point, and this keeps the short form the only form ever
-        // emitted in practice.
-        Ok(Self::format_ciphertext(
+    /// Encrypts under already-loaded material of `key` at `version`, which
+    /// the caller has checked with [`Self::check_encryption_policy`].
+    fn seal_with_material(
+        &self,
+        key: &TransitKey,
+        raw_key: &[u8],
+        derivation: Option<Derivation<'_>>,
+        associated_data: Option<&[u8]>,
+        plaintext: &[u8],
+        version: u32,
+    ) -> Result<String, TransitError> {
+        let convergent = key.convergent && derivation.is_none();
+        let sealed = Self::seal_raw(
+            key,
+            raw_key,
+            derivation,
+            associated_data,
+            plaintext,
             version,
-            ENGINE_ALGORITHM,
-            &ciphertext,
+        )?;
+
+        // AES-256-GCM keeps the short form; ChaCha20-Poly1305 is labelled.
+        let formatted = self.format_ciphertext(version, key.key_type, &sealed);
+        if !convergent {
+            return Ok(formatted);
+        }
+        // The marker goes right after the prefix: egide:c1:v{n}:...
+        let prefix = &self.ciphertext_prefix;
+        Ok(format!(
+            "{prefix}:{CONVERGENT_MARKER}{}",
+            &formatted[prefix.len()..]
         ))
     }
 
+    /// Seals `plaintext` and returns the bare AEAD output (nonce, ciphertext
+    /// and tag), without the string envelope.
+    fn seal_raw(
+        key: &TransitKey,
+        raw_key: &[u8],
+        derivation: Option<Derivation<'_>>,
+        associated_data: Option<&[u8]>,
+        plaintext: &[u8],
+        version: u32,
+    ) -> Result<Vec<u8>, TransitError> {
+        // Encrypt with AAD containing the stored key name for domain separation
+        match derivation {
+            None if key.convergent => {
+                let aad = format!("egide-transit:{}:{version}", key.name);
+                let aad = Self::bind_caller_aad(aad, associated_data)?;
+                // The AAD is the nonce context, so equal plaintexts under
+                // another key, version or caller AAD still differ.
+                let nonce = kdf::derive_nonce(raw_key, plaintext, &aad)?;
+                Ok(match key.key_type {
+                    KeyType::ChaCha20Poly1305 => {
+                        aead::encrypt_chacha_with_nonce(raw_key, &nonce, plaintext, Some(&aad[..]))?
+                    },
+                    _ => aead::encrypt_with_nonce(raw_key, &nonce, plaintext, Some(&aad[..]))?,
+                })
+            },
+            // A derivation key only encrypts under a subkey, so a ciphertext
+            // always says which path or context it belongs to.
+            None if key.supports_derivation => Err(TransitError::OperationNotAllowed(
+                "derivation keys require a derivation path or context".into(),
+            )),
+            None => {
+                let aad = format!("egide-transit:{}:{version}", key.name);
+                let aad = Self::bind_caller_aad(aad, associated_data)?;
+                Self::aead_seal(key.key_type, raw_key, plaintext, &aad)
+            },
+            Some(derivation) => {
+                let (subkey, aad) = derivation.subkey_and_aad(raw_key, &key.name, version)?;
+                let aad = Self::bind_caller_aad(aad, associated_data)?;
+                Self::aead_seal(key.key_type, &subkey[..], plaintext, &aad)
+            },
+        }
+    }
+
+    /// Encrypts under the data cipher `algorithm` names.
+    fn aead_seal(
+        algorithm: KeyType,
+        raw_key: &[u8],
+        plaintext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TransitError> {
+        Ok(match algorithm {
+            KeyType::ChaCha20Poly1305 => aead::encrypt_chacha(raw_key, plaintext, Some(aad))?,
+            KeyType::Aes256Gcm => aead::encrypt(raw_key, plaintext, Some(aad))?,
+            KeyType::Ed25519 => return Err(TransitError::KeyAlgorithmNotImplemented(algorithm)),
+        })
+    }
+
+    /// Decrypts under the data cipher `algorithm` names.
+    fn aead_open(
+        algorithm: KeyType,
+        raw_key: &[u8],
+        data: &[u8],
+        aad: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, TransitError> {
+        match algorithm {
+            KeyType::ChaCha20Poly1305 => aead::decrypt_chacha(raw_key, data, Some(aad)),
+            KeyType::Aes256Gcm => aead::decrypt(raw_key, data, Some(aad)),
+            KeyType::Ed25519 => return Err(TransitError::KeyAlgorithmNotImplemented(algorithm)),
+        }
+        .map_err(|_| TransitError::DecryptionFailed)
+    }
+
     /// Decrypts ciphertext.
     ///
     /// Automatically determines the key version from the ciphertext format.
     pub async fn decrypt(&self, name: &str, ciphertext: &str) -> Result<Vec<u8>, TransitError> {
-        let key = self.get_key(name).await?;
+        Ok(self.decrypt_with_meta(name, ciphertext).await?.plaintext)
+    }
+
+    /// Decrypts ciphertext and reports the key version that opened it, for
+    /// audit logging without parsing the ciphertext.
+    pub async fn decrypt_with_meta(
+        &self,
+        name: &str,
+        ciphertext: &str,
+    ) -> Result<DecryptResult, TransitError> {
+        let key = self.load_key(name).await?;
+        self.decrypt_with_key(&key, None, None, ciphertext).await
+    }
+
+    /// Decrypts a ciphertext produced by [`Self::encrypt_with_aad`].
+    ///
+    /// Fails with [`TransitError::DecryptionFailed`] unless `associated_data`
+    /// is exactly the value given at encryption.
+    pub async fn decrypt_with_aad(
+        &self,
+        name: &str,
+        ciphertext: &str,
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>, TransitError> {
+        let key = self.load_key(name).await?;
+        self.decrypt_with_key(&key, None, Some(associated_data), ciphertext)
+            .await
+            .map(|result| result.plaintext)
+    }
+
+    /// Encrypts plaintext with the latest version of a key and returns that
+    /// version with the bare AEAD output, without the string envelope.
+    ///
+    /// The bytes are the nonce, ciphertext and tag that
+    /// [`Self::encrypt`] would base64-encode, a third smaller than the
+    /// envelope, for callers storing the version in an integer column and
+    /// the ciphertext as a blob. Nothing in them names the algorithm or the
+    /// version, so keep both alongside and decrypt with
+    /// [`Self::decrypt_raw`] under the same key.
+    pub async fn encrypt_raw(
+        &self,
+        name: &str,
+        plaintext: &[u8],
+    ) -> Result<(u32, Vec<u8>), TransitError> {
+        self.check_plaintext_size(plaintext)?;
+        let key = self.load_key(name).await?;
+        let version = key.latest_version;
+        Self::check_encryption_policy(&key, name, version)?;
+        let raw_key = self.get_key_material(&key.name, version).await?;
+
+        let data = Self::seal_raw(&key, &raw_key, None, None, plaintext, version)?;
+        self.record_usage(&key.name, 1).await;
+        Ok((version, data))
+    }
+
+    /// Decrypts the output of [`Self::encrypt_raw`] sealed under `version`.
+    ///
+    /// Applies the same version policy as [`Self::decrypt`]; the algorithm is
+    /// the key's own. A wrong version, like tampered data, fails with
+    /// [`TransitError::DecryptionFailed`].
+    pub async fn decrypt_raw(
+        &self,
+        name: &str,
+        version: u32,
+        data: &[u8],
+    ) -> Result<Vec<u8>, TransitError> {
+        let key = self.load_key(name).await?;
+        Self::check_decryption_allowed(&key)?;
+        Self::check_decryption_version(&key, version)?;
+        let raw_key = self.get_key_material(&key.name, version).await?;
+
+        let plaintext =
+            Self::open_with_material(&key, &raw_key, None, None, key.key_type, version, data)?;
+        self.record_usage(&key.name, 1).await;
+        Ok(plaintext)
+    }
+
+    /// Encrypts every plaintext of `items` under the latest version of a key.
+    ///
+    /// The key policy and material are loaded once for the whole batch.
+    /// Results are returned in input order, one per item, so a failing item
+    /// does not abort the rest of the batch.
+    ///
+    /// # Errors
+    ///
+    /// Fails as a whole with [`TransitError::InvalidBatchSize`] unless
+    /// `items` holds between 1 and [`MAX_CRYPT_BATCH`] entries, or if the key
+    /// cannot be loaded or may not encrypt.
+    pub async fn encrypt_batch(
+        &self,
+        name: &str,
+        items: &[Vec<u8>],
+    ) -> Result<Vec<Result<String, TransitError>>, TransitError> {
+        Self::check_crypt_batch_size(items.len())?;
+
+        let key = self.load_key(name).await?;
+        let version = key.latest_version;
+        Self::check_encryption_policy(&key, name, version)?;
+        let raw_key = self.get_key_material(&key.name, version).await?;
+
+        let results: Vec<_> = items
+            .iter()
+            .map(|plaintext| {
+                self.check_plaintext_size(plaintext)?;
+                self.seal_with_material(&key, &raw_key, None, None, plaintext, version)
+            })
+            .collect();
+        self.record_batch_usage(&key.name, &results).await;
+        Ok(results)
+    }
+
+    /// Decrypts every ciphertext of `items` under a key.
+    ///
+    /// The key is loaded once, and the material of each key version the
+    /// batch references is unwrapped once. Results are returned in input
+    /// order, one per item, so a malformed or tampered ciphertext only fails
+    /// its own entry.
+    ///
+    /// # Errors
+    ///
+    /// Fails as a whole with [`TransitError::InvalidBatchSize`] unless
+    /// `items` holds between 1 and [`MAX_CRYPT_BATCH`] entries, or if the key
+    /// cannot be loaded or may not decrypt.
+    pub async fn decrypt_batch(
+        &self,
+        name: &str,
+        items: &[String],
+    ) -> Result<Vec<Result<Vec<u8>, TransitError>>, TransitError> {
+        Self::check_crypt_batch_size(items.len())?;
+
+        let key = self.load_key(name).await?;
+        Self::check_decryption_allowed(&key)?;
+
+        let mut materials: HashMap<u32, Zeroizing<Vec<u8>>> = HashMap::new();
+        let mut results = Vec::with_capacity(items.len());
+        for ciphertext in items {
+            let (version, algorithm, data) = match self.parse_for_decryption(&key, ciphertext) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    results.push(Err(e));
+                    continue;
+                },
+            };
+            if let Entry::Vacant(slot) = materials.entry(version) {
+                match self.get_key_material(&key.name, version).await {
+                    Ok(raw_key) => {
+                        slot.insert(raw_key);
+                    },
+                    Err(e) => {
+                        results.push(Err(e));
+                        continue;
+                    },
+                }
+            }
+            let raw_key = &materials[&version];
+            results.push(Self::open_with_material(
+                &key, raw_key, None, None, algorithm, version, &data,
+            ));
+        }
+        self.record_batch_usage(&key.name, &results).await;
+        Ok(results)
+    }
+
+    /// Counts the successful items of a batch as usage of its key.
+    async fn record_batch_usage<T>(&self, stored_name: &str, results: &[Result<T, TransitError>]) {
+        let succeeded = results.iter().filter(|result| result.is_ok()).count();
+        if succeeded > 0 {
+            self.record_usage(stored_name, u64::try_from(succeeded).unwrap_or(u64::MAX))
+                .await;
+        }
+    }
+
+    fn check_crypt_batch_size(len: usize) -> Result<(), TransitError> {
+        if len == 0 || len > MAX_CRYPT_BATCH {
+            return Err(TransitError::InvalidBatchSize {
+                requested: len,
+                max: MAX_CRYPT_BATCH,
+            });
+        }
+        Ok(())
+    }
+
+    /// Decrypts under an already-loaded key, enforcing its policy, optionally
+    /// under the subkey derived for `derivation_path` and checking caller
+    /// `associated_data`.
+    async fn decrypt_with_key(
+        &self,
+        key: &TransitKey,
+        derivation: Option<Derivation<'_>>,
+        associated_data: Option<&[u8]>,
+        ciphertext: &str,
+    ) -> Result<DecryptResult, TransitError> {
+        Self::check_decryption_allowed(key)?;
+        let (version, algorithm, data) = self.parse_for_decryption(key, ciphertext)?;
+
+        // Get the raw key material for this version
+        let raw_key = self.get_key_material(&key.name, version).await?;
+
+        let plaintext = Self::open_with_material(
+            key,
+            &raw_key,
+            derivation,
+            associated_data,
+            algorithm,
+            version,
+            &data,
+        )?;
+        self.record_usage(&key.name, 1).await;
+        Ok(DecryptResult { plaintext, version })
+    }
 
+    /// Checks that `key` may decrypt at all.
+    fn check_decryption_allowed(key: &TransitKey) -> Result<(), TransitError> {
         if !key.supports_decryption {
             return Err(TransitError::OperationNotAllowed(
                 "decryption not allowed for this key".into(),
             ));
         }
 
+        Ok(())
+    }
+
+    /// Parses a ciphertext and checks it against `key`'s decryption policy,
+    /// returning the key version it was sealed under and the sealed bytes.
+    fn parse_for_decryption(
+        &self,
+        key: &TransitKey,
+        ciphertext: &str,
+    ) -> Result<(u32, KeyType, Vec<u8>), TransitError> {
         // Parse ciphertext format: egide:v{version}:{base64} (or the explicit
         // egide:v{version}:{algorithm}:{base64} form).
-        let (version, ciphertext_key_type, data) = Self::parse_ciphertext(ciphertext)?;
-
-        // Compared against the engine's effective algorithm, not the key's
-        // declared type: a key created under a type accepted but never
-        // implemented by an earlier release (chacha20-poly1305 in 0.1.0) was
-        // always encrypted under this algorithm regardless, so its
-        // ciphertexts must stay decryptable. A ciphertext claiming any other
-        // algorithm would be decrypted under the wrong cipher; refuse rather
-        // than guess.
-        if ciphertext_key_type != ENGINE_ALGORITHM {
-            return Err(TransitError::CiphertextAlgorithmMismatch {
-                expected: ENGINE_ALGORITHM,
-                found: ciphertext_key_type,
+        let (version, ciphertext_key_type, data) = self.parse_sealed(ciphertext)?;
+        Self::check_ciphertext_algorithm(key, ciphertext_key_type)?;
+        Self::check_decryption_version(key, version)?;
+
+        Ok((version, ciphertext_key_type, data))
+    }
+
+    /// Checks that `key` may still decrypt under `version`.
+    fn check_decryption_version(key: &TransitKey, version: u32) -> Result<(), TransitError> {
+        // Checked first: a trimmed version is also below the decryption
+        // minimum, but its data is gone for good rather than merely refused.
+        if version < key.min_available_version {
+            return Err(TransitError::VersionTrimmed {
+                name: key.name.clone(),
+                version,
+                min_available: key.min_available_version,
             });
         }
 
@@ -948,38 +3465,268 @@ impl TransitEngine {
             });
         }
 
-        // Get the raw key material for this version
-        let raw_key = self.get_key_material(name, version).await?;
+        Ok(())
+    }
+
+    /// Checks that a ciphertext labelled `found` may be opened under `key`,
+    /// returning the cipher to open it with.
+    ///
+    /// The cipher is chosen by the key's stored type; the label only has to
+    /// agree with it. One exception: a `chacha20-poly1305` key also opens the
+    /// short AES-256-GCM form. 0.1.0 accepted that type without implementing
+    /// it and encrypted under AES-256-GCM regardless, so those keys' existing
+    /// ciphertexts must stay decryptable. A ciphertext claiming any other
+    /// algorithm would be opened under the wrong cipher; refuse rather than
+    /// guess.
+    fn check_ciphertext_algorithm(key: &TransitKey, found: KeyType) -> Result<(), TransitError> {
+        let legacy = key.key_type == KeyType::ChaCha20Poly1305 && found == KeyType::Aes256Gcm;
+        if found == key.key_type || legacy {
+            Ok(())
+        } else {
+            Err(TransitError::CiphertextAlgorithmMismatch {
+                expected: key.key_type,
+                found,
+            })
+        }
+    }
 
+    /// Opens sealed bytes with the already-unwrapped material of `version`
+    /// under the `algorithm` the ciphertext was checked to carry.
+    fn open_with_material(
+        key: &TransitKey,
+        raw_key: &[u8],
+        derivation: Option<Derivation<'_>>,
+        associated_data: Option<&[u8]>,
+        algorithm: KeyType,
+        version: u32,
+        data: &[u8],
+    ) -> Result<Vec<u8>, TransitError> {
         // Decrypt with AAD
-        let aad = format!("egide-transit:{name}:{version}");
-        let decrypted = aead::decrypt(&raw_key, &data, Some(aad.as_bytes()))
-            .map_err(|_| TransitError::DecryptionFailed)?;
+        let decrypted = match derivation {
+            None => {
+                let aad = format!("egide-transit:{}:{version}", key.name);
+                let aad = Self::bind_caller_aad(aad, associated_data)?;
+                Self::aead_open(algorithm, raw_key, data, &aad)?
+            },
+            Some(derivation) => {
+                let (subkey, aad) = derivation.subkey_and_aad(raw_key, &key.name, version)?;
+                let aad = Self::bind_caller_aad(aad, associated_data)?;
+                Self::aead_open(algorithm, &subkey[..], data, &aad)?
+            },
+        };
         Ok(decrypted.to_vec())
     }
 
+    // ========================================================================
+    // Derived Subkeys
+    // ========================================================================
+
+    /// Encrypts under the subkey derived from a key for a hierarchical path.
+    ///
+    /// `path` is a `/`-separated list of segments such as `customer/123`. The
+    /// subkey is derived by chaining HKDF over the segments, starting from
+    /// the key version's material: each level is derived from its parent
+    /// alone. The derivation is deterministic, so the same path always yields
+    /// the same subkey, and one-way, so a compromised subkey exposes neither
+    /// its parent nor its siblings. The path is bound into the associated
+    /// data, so the ciphertext only decrypts through [`Self::decrypt_derived`]
+    /// with the same path.
+    ///
+    /// The key must have been created with `supports_derivation`. The
+    /// ciphertext uses the regular `egide:v{n}:...` envelope and the latest
+    /// key version, so rotation and rewrap-free decryption of older versions
+    /// behave as for [`Self::encrypt`].
+    pub async fn encrypt_derived(
+        &self,
+        name: &str,
+        path: &str,
+        plaintext: &[u8],
+    ) -> Result<String, TransitError> {
+        Self::validate_derivation_path(path)?;
+        self.check_plaintext_size(plaintext)?;
+        let key = self.load_key(name).await?;
+        Self::require_derivation(&key)?;
+        let version = key.latest_version;
+        self.encrypt_with_key(
+            &key,
+            name,
+            Some(Derivation::Path(path)),
+            None,
+            plaintext,
+            version,
+        )
+        .await
+    }
+
+    /// Decrypts a ciphertext produced by [`Self::encrypt_derived`] for `path`.
+    pub async fn decrypt_derived(
+        &self,
+        name: &str,
+        path: &str,
+        ciphertext: &str,
+    ) -> Result<Vec<u8>, TransitError> {
+        Self::validate_derivation_path(path)?;
+        let key = self.load_key(name).await?;
+        Self::require_derivation(&key)?;
+        self.decrypt_with_key(&key, Some(Derivation::Path(path)), None, ciphertext)
+            .await
+            .map(|result| result.plaintext)
+    }
+
+    /// Encrypts under the subkey derived from a key for an opaque `context`.
+    ///
+    /// The flat counterpart of [`Self::encrypt_derived`] for callers whose
+    /// tenant or record identifier is arbitrary bytes rather than a path:
+    /// the subkey is derived from the key version's material by HKDF with
+    /// `context` as info. The context is not stored in the ciphertext; it is
+    /// bound into the associated data, so [`Self::decrypt_with_context`] must
+    /// be given the same bytes. The key must have been created with
+    /// `supports_derivation`.
+    pub async fn encrypt_with_context(
+        &self,
+        name: &str,
+        plaintext: &[u8],
+        context: &[u8],
+    ) -> Result<String, TransitError> {
+        Self::validate_derivation_context(context)?;
+        self.check_plaintext_size(plaintext)?;
+        let key = self.load_key(name).await?;
+        Self::require_derivation(&key)?;
+        let version = key.latest_version;
+        self.encrypt_with_key(
+            &key,
+            name,
+            Some(Derivation::Context(context)),
+            None,
+            plaintext,
+            version,
+        )
+        .await
+    }
+
+    /// Decrypts a ciphertext produced by [`Self::encrypt_with_context`] for
+    /// `context`.
+    pub async fn decrypt_with_context(
+        &self,
+        name: &str,
+        ciphertext: &str,
+        context: &[u8],
+    ) -> Result<Vec<u8>, TransitError> {
+        Self::validate_derivation_context(context)?;
+        let key = self.load_key(name).await?;
+        Self::require_derivation(&key)?;
+        self.decrypt_with_key(&key, Some(Derivation::Context(context)), None, ciphertext)
+            .await
+            .map(|result| result.plaintext)
+    }
+
+    fn require_derivation(key: &TransitKey) -> Result<(), TransitError> {
+        if key.supports_derivation {
+            Ok(())
+        } else {
+            Err(TransitError::OperationNotAllowed(
+                "derivation not enabled for this key".into(),
+            ))
+        }
+    }
+
+    /// Validates a derivation path: 1 to 16 non-empty segments of
+    /// alphanumerics, `-` and `_`, at most 256 characters in total.
+    fn validate_derivation_path(path: &str) -> Result<(), TransitError> {
+        let invalid = |reason: &str| {
+            Err(TransitError::OperationNotAllowed(format!(
+                "invalid derivation path: {reason}"
+            )))
+        };
+        if path.is_empty() || path.len() > 256 {
+            return invalid("must be 1 to 256 characters");
+        }
+        let mut depth = 0usize;
+        for segment in path.split('/') {
+            depth += 1;
+            if segment.is_empty()
+                || !segment
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+            {
+                return invalid("segments must be non-empty alphanumeric, dash, underscore");
+            }
+        }
+        if depth > MAX_DERIVATION_DEPTH {
+            return invalid("too many segments");
+        }
+        Ok(())
+    }
+
+    /// Validates a derivation context: 1 to 256 bytes.
+    fn validate_derivation_context(context: &[u8]) -> Result<(), TransitError> {
+        if context.is_empty() || context.len() > 256 {
+            return Err(TransitError::OperationNotAllowed(
+                "invalid derivation context: must be 1 to 256 bytes".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Derives the subkey for `path` by chaining HKDF over its segments.
+    fn derive_subkey(root: &[u8], path: &str) -> Result<Zeroizing<[u8; 32]>, TransitError> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        key.copy_from_slice(root.get(..32).ok_or(TransitError::DecryptionFailed)?);
+        for segment in path.split('/') {
+            let info = format!("{TRANSIT_SUBKEY_INFO_PREFIX}{segment}");
+            key = kdf::derive_encryption_key(&key[..], info.as_bytes())?;
+        }
+        Ok(key)
+    }
+
+    /// Associated data for a derived ciphertext: binds the key, version and path.
+    fn derived_aad(stored_name: &str, version: u32, path: &str) -> String {
+        format!("egide-transit-derived:{stored_name}:{version}:{path}")
+    }
+
+    /// Appends caller associated data to the engine's own AAD.
+    ///
+    /// Without caller data the engine AAD is used verbatim, so existing
+    /// ciphertexts keep decrypting. With it, both parts are length-prefixed:
+    /// the encoding starts with a zero length byte where the verbatim form
+    /// starts with `e`, so the two can never collide, and a ciphertext bound
+    /// to caller data never decrypts without it.
+    fn bind_caller_aad(
+        engine_aad: String,
+        associated_data: Option<&[u8]>,
+    ) -> Result<Vec<u8>, TransitError> {
+        match associated_data {
+            None => Ok(engine_aad.into_bytes()),
+            Some(data) => Ok(mac::encode_fields(&[engine_aad.as_bytes(), data])?),
+        }
+    }
+
     /// Formats a ciphertext envelope.
     ///
     /// AES-256-GCM keeps the historical short form `egide:v{n}:{b64}`, which
     /// is normatively defined as AES-256-GCM. Any other algorithm uses the
     /// explicit form `egide:v{n}:{alg}:{b64}` so the ciphertext is never
-    /// ambiguous.
-    fn format_ciphertext(version: u32, key_type: KeyType, data: &[u8]) -> String {
+    /// ambiguous. `egide` stands for the configured ciphertext prefix.
+    fn format_ciphertext(&self, version: u32, key_type: KeyType, data: &[u8]) -> String {
+        let prefix = &self.ciphertext_prefix;
         let encoded = BASE64.encode(data);
         match key_type {
-            KeyType::Aes256Gcm => format!("egide:v{version}:{encoded}"),
-            other @ KeyType::ChaCha20Poly1305 => format!("egide:v{version}:{other}:{encoded}"),
+            KeyType::Aes256Gcm => format!("{prefix}:v{version}:{encoded}"),
+            other @ (KeyType::ChaCha20Poly1305 | KeyType::Ed25519) => {
+                format!("{prefix}:v{version}:{other}:{encoded}")
+            },
         }
     }
 
     /// Parses a ciphertext envelope in either the short or the explicit form.
     ///
     /// The short form omits the algorithm and means AES-256-GCM. The base64
-    /// alphabet excludes `:`, so counting the segments is unambiguous.
-    fn parse_ciphertext(ciphertext: &str) -> Result<(u32, KeyType, Vec<u8>), TransitError> {
+    /// alphabet excludes `:`, so counting the segments is unambiguous. Only
+    /// the configured ciphertext prefix is accepted.
+    fn parse_ciphertext(&self, ciphertext: &str) -> Result<(u32, KeyType, Vec<u8>), TransitError> {
         let parts: Vec<&str> = ciphertext.splitn(4, ':').collect();
 
-        if parts.len() < 3 || parts[0] != "egide" {
+        if parts.len() < 3 || parts[0] != self.ciphertext_prefix {
             return Err(TransitError::InvalidCiphertext);
         }
 
@@ -1014,36 +3761,241 @@ impl TransitEngine {
         Ok((version, key_type, data))
     }
 
+    /// Parses a ciphertext produced by encryption, in the regular or the
+    /// convergent form.
+    ///
+    /// A convergent ciphertext (`egide:c1:v{n}:...`) stores its derived
+    /// nonce where a random one would be, so once the marker is dropped it
+    /// parses and opens like any other. Signatures never carry the marker
+    /// and go through [`Self::parse_ciphertext`] directly.
+    fn parse_sealed(&self, ciphertext: &str) -> Result<(u32, KeyType, Vec<u8>), TransitError> {
+        let marker = format!("{}:{CONVERGENT_MARKER}:", self.ciphertext_prefix);
+        match ciphertext.strip_prefix(&marker) {
+            Some(rest) => self.parse_ciphertext(&format!("{}:{rest}", self.ciphertext_prefix)),
+            None => self.parse_ciphertext(ciphertext),
+        }
+    }
+
     /// Rewraps ciphertext with the latest key version.
     ///
     /// This decrypts and re-encrypts without exposing plaintext to the caller.
     pub async fn rewrap(&self, name: &str, ciphertext: &str) -> Result<String, TransitError> {
         let key = self.get_key(name).await?;
+        self.rewrap_to_version(name, ciphertext, key.latest_version)
+            .await
+    }
 
-        // Parse to get current version
-        let (current_version, ciphertext_key_type, _data) = Self::parse_ciphertext(ciphertext)?;
-
-        // Compared against the engine's effective algorithm, not the key's
-        // declared type, for the same reason as in decrypt: a legacy key
-        // declared under a never-implemented type must still rewrap. A
-        // ciphertext claiming any other algorithm is refused rather than
-        // guessed, even on the already-latest-version fast path below that
-        // never calls decrypt.
-        if ciphertext_key_type != ENGINE_ALGORITHM {
-            return Err(TransitError::CiphertextAlgorithmMismatch {
-                expected: ENGINE_ALGORITHM,
-                found: ciphertext_key_type,
+    /// Rewraps ciphertext with a specific key version.
+    ///
+    /// Useful during a staged rotation, when ciphertexts should move to an
+    /// intermediate version rather than straight to the latest one. The
+    /// target must lie within the key's encryption version window.
+    pub async fn rewrap_to_version(
+        &self,
+        name: &str,
+        ciphertext: &str,
+        target_version: u32,
+    ) -> Result<String, TransitError> {
+        self.rewrap_sealed(name, ciphertext, target_version, None)
+            .await
+    }
+
+    /// Rewraps a ciphertext produced by [`Self::encrypt_with_aad`] with the
+    /// latest key version.
+    ///
+    /// The ciphertext is opened with `associated_data` and sealed again
+    /// bound to the same bytes, so it keeps needing them to decrypt. As with
+    /// [`Self::rewrap`], a ciphertext already at the latest version is
+    /// returned unchanged.
+    pub async fn rewrap_with_aad(
+        &self,
+        name: &str,
+        ciphertext: &str,
+        associated_data: &[u8],
+    ) -> Result<String, TransitError> {
+        let key = self.get_key(name).await?;
+        self.rewrap_sealed(name, ciphertext, key.latest_version, Some(associated_data))
+            .await
+    }
+
+    /// Moves a ciphertext to `target_version`, opening and sealing it with
+    /// the caller's `associated_data` when there is one.
+    async fn rewrap_sealed(
+        &self,
+        name: &str,
+        ciphertext: &str,
+        target_version: u32,
+        associated_data: Option<&[u8]>,
+    ) -> Result<String, TransitError> {
+        let key = self.get_key(name).await?;
+
+        let (min_version, max_version) = key.encryption_version_window();
+        if target_version < min_version {
+            return Err(TransitError::VersionBelowMinEncryption {
+                version: target_version,
+                min: min_version,
+            });
+        }
+        if target_version > max_version {
+            return Err(TransitError::VersionNotFound {
+                name: name.to_string(),
+                version: target_version,
             });
         }
 
-        // If already at latest version, return as-is
-        if current_version == key.latest_version {
+        // Parse to get current version
+        let (current_version, ciphertext_key_type, _data) = self.parse_sealed(ciphertext)?;
+
+        // Checked as in decrypt, so a ciphertext claiming an algorithm the
+        // key does not use is refused even on the already-latest-version
+        // fast path below that never calls decrypt.
+        Self::check_ciphertext_algorithm(&key, ciphertext_key_type)?;
+
+        // If already at the target version, return as-is
+        if current_version == target_version {
+            return Ok(ciphertext.to_string());
+        }
+
+        // Decrypt with the embedded version, encrypt with the target
+        match associated_data {
+            Some(aad) => {
+                let plaintext = self.decrypt_with_aad(name, ciphertext, aad).await?;
+                let key = self.load_key(name).await?;
+                self.encrypt_with_key(&key, name, None, Some(aad), &plaintext, target_version)
+                    .await
+            },
+            None => {
+                let plaintext = self.decrypt(name, ciphertext).await?;
+                self.encrypt_with_version(name, &plaintext, target_version)
+                    .await
+            },
+        }
+    }
+
+    /// Rewraps every ciphertext of `items` to a key's latest version.
+    ///
+    /// The key is loaded once, the latest version's material is unwrapped
+    /// once, and so is the material of each older version the batch
+    /// references. Items already at the latest version are returned
+    /// unchanged without being decrypted, as [`Self::rewrap`] does. Results
+    /// are returned in input order, one per item, so a malformed or tampered
+    /// ciphertext only fails its own entry. Each successful item counts once
+    /// towards the key's usage.
+    ///
+    /// # Errors
+    ///
+    /// Fails as a whole with [`TransitError::InvalidBatchSize`] unless
+    /// `items` holds between 1 and [`MAX_CRYPT_BATCH`] entries, or if the key
+    /// cannot be loaded or may not both decrypt and encrypt.
+    pub async fn rewrap_batch(
+        &self,
+        name: &str,
+        items: &[String],
+    ) -> Result<Vec<Result<String, TransitError>>, TransitError> {
+        Self::check_crypt_batch_size(items.len())?;
+
+        let key = self.load_key(name).await?;
+        let target_version = key.latest_version;
+        Self::check_decryption_allowed(&key)?;
+        Self::check_encryption_policy(&key, name, target_version)?;
+
+        let mut materials: HashMap<u32, Zeroizing<Vec<u8>>> = HashMap::new();
+        materials.insert(
+            target_version,
+            self.get_key_material(&key.name, target_version).await?,
+        );
+        let mut results = Vec::with_capacity(items.len());
+        for ciphertext in items {
+            results.push(
+                self.rewrap_with_materials(&key, &mut materials, ciphertext)
+                    .await,
+            );
+        }
+        self.record_batch_usage(&key.name, &results).await;
+        Ok(results)
+    }
+
+    /// Rewraps one ciphertext of a batch to `key.latest_version`, unwrapping
+    /// and caching the material of its version on first use.
+    async fn rewrap_with_materials(
+        &self,
+        key: &TransitKey,
+        materials: &mut HashMap<u32, Zeroizing<Vec<u8>>>,
+        ciphertext: &str,
+    ) -> Result<String, TransitError> {
+        let (version, algorithm, data) = self.parse_for_decryption(key, ciphertext)?;
+        if version == key.latest_version {
             return Ok(ciphertext.to_string());
         }
+        if let Entry::Vacant(slot) = materials.entry(version) {
+            slot.insert(self.get_key_material(&key.name, version).await?);
+        }
+        let plaintext = Zeroizing::new(Self::open_with_material(
+            key,
+            &materials[&version],
+            None,
+            None,
+            algorithm,
+            version,
+            &data,
+        )?);
+        self.seal_with_material(
+            key,
+            &materials[&key.latest_version],
+            None,
+            None,
+            &plaintext,
+            key.latest_version,
+        )
+    }
+
+    // ========================================================================
+    // Random Bytes and Hashing
+    // ========================================================================
+
+    /// Generates `bytes` random bytes from the system CSPRNG, encoded as
+    /// `format`.
+    ///
+    /// Uses no key, so it works whatever the capability flags of the keys in
+    /// the keyring.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidRandomLength`] if `bytes` is zero or
+    /// above [`MAX_RANDOM_BYTES`], and [`TransitError::Crypto`] if the system
+    /// RNG fails.
+    // Takes `&self` like every other engine operation, so callers reach it
+    // through the engine they already hold.
+    #[allow(clippy::unused_self)]
+    pub fn generate_random(
+        &self,
+        bytes: usize,
+        format: RandomFormat,
+    ) -> Result<String, TransitError> {
+        if bytes == 0 || bytes > MAX_RANDOM_BYTES {
+            return Err(TransitError::InvalidRandomLength {
+                requested: bytes,
+                max: MAX_RANDOM_BYTES,
+            });
+        }
+        let random = random::generate_bytes(bytes)?;
+        Ok(format.encode(&random))
+    }
 
-        // Decrypt with old version, encrypt with new
-        let plaintext = self.decrypt(name, ciphertext).await?;
-        self.encrypt(name, &plaintext).await
+    /// Hashes `input` under `algorithm`, encoded as `format`.
+    ///
+    /// Meant for content digests stored alongside transit ciphertext. Uses
+    /// no key and reads no storage.
+    // Takes `&self` like every other engine operation, so callers reach it
+    // through the engine they already hold.
+    #[allow(clippy::unused_self)]
+    #[must_use]
+    pub fn hash(&self, algorithm: HashAlgorithm, input: &[u8], format: OutputFormat) -> String {
+        match algorithm {
+            HashAlgorithm::Sha256 => format.encode(&Sha256::digest(input)),
+            HashAlgorithm::Sha384 => format.encode(&Sha384::digest(input)),
+            HashAlgorithm::Sha512 => format.encode(&Sha512::digest(input)),
+        }
     }
 
     // ========================================================================
@@ -1055,6 +4007,22 @@ impl TransitEngine {
     /// Returns both the plaintext key (for immediate use) and the wrapped key
     /// (for storage). The plaintext key should be used and then discarded.
     pub async fn generate_datakey(&self, name: &str) -> Result<DataKey, TransitError> {
+        self.generate_datakey_with(name, DataKeyBits::Bits256, true)
+            .await
+    }
+
+    /// Generates a data encryption key of `bits` length.
+    ///
+    /// With `return_plaintext` false the DEK is only returned wrapped:
+    /// [`DataKey::plaintext`] is left empty and the generated plaintext is
+    /// zeroized before returning, for callers that store the wrapped key now
+    /// and unwrap it with [`Self::decrypt_datakey`] later.
+    pub async fn generate_datakey_with(
+        &self,
+        name: &str,
+        bits: DataKeyBits,
+        return_plaintext: bool,
+    ) -> Result<DataKey, TransitError> {
         let key = self.get_key(name).await?;
 
         if !key.supports_encryption {
@@ -1063,28 +4031,299 @@ impl TransitEngine {
             ));
         }
 
-        // Generate a random 32-byte key
-        let plaintext_key = random::generate_key()?;
+        let plaintext_key = random::generate_bytes(bits.byte_len())?;
 
         // Wrap it with the transit key
-        let wrapped = self.encrypt(name, plaintext_key.as_ref()).await?;
+        let wrapped = self.encrypt(name, &plaintext_key).await?;
 
         Ok(DataKey {
-            plaintext: plaintext_key.to_vec(),
+            plaintext: if return_plaintext {
+                plaintext_key.to_vec()
+            } else {
+                Vec::new()
+            },
             ciphertext: wrapped,
         })
     }
 
-    /// Decrypts a wrapped data key.
-    pub async fn decrypt_datakey(
+    /// Generates `count` independent data encryption keys in one call.
+    ///
+    /// Equivalent to calling [`Self::generate_datakey`] `count` times, but
+    /// the key policy and material are loaded once for the whole batch. Every
+    /// DEK is drawn separately from the system CSPRNG and wrapped under the
+    /// latest key version, and each counts as one use of the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::InvalidBatchSize`] unless `count` is between 1
+    /// and [`MAX_DATAKEY_BATCH`].
+    pub async fn generate_datakeys(
         &self,
         name: &str,
-        wrapped: &str,
-    ) -> Result<Vec<u8>, TransitError> {
-        self.decrypt(name, wrapped).await
-    }
-}
-
+        count: usize,
+    ) -> Result<Vec<DataKey>, TransitError> {
+        if count == 0 || count > MAX_DATAKEY_BATCH {
+            return Err(TransitError::InvalidBatchSize {
+                requested: count,
+                max: MAX_DATAKEY_BATCH,
+            });
+        }
+
+        let key = self.load_key(name).await?;
+        if !key.supports_encryption {
+            return Err(TransitError::OperationNotAllowed(
+                "datakey generation requires encryption capability".into(),
+            ));
+        }
+        let version = key.latest_version;
+        Self::check_encryption_policy(&key, name, version)?;
+        let raw_key = self.get_key_material(&key.name, version).await?;
+
+        let datakeys = (0..count)
+            .map(|_| {
+                let plaintext_key = random::generate_key()?;
+                let ciphertext = self.seal_with_material(
+                    &key,
+                    &raw_key,
+                    None,
+                    None,
+                    plaintext_key.as_ref(),
+                    version,
+                )?;
+                Ok(DataKey {
+                    plaintext: plaintext_key.to_vec(),
+                    ciphertext,
+                })
+            })
+            .collect::<Result<Vec<_>, TransitError>>()?;
+        self.record_usage(&key.name, u64::try_from(count).unwrap_or(u64::MAX))
+            .await;
+        Ok(datakeys)
+    }
+
+    /// Decrypts a wrapped data key.
+    pub async fn decrypt_datakey(
+        &self,
+        name: &str,
+        wrapped: &str,
+    ) -> Result<Vec<u8>, TransitError> {
+        self.decrypt(name, wrapped).await
+    }
+
+    // ========================================================================
+    // Signature Operations
+    // ========================================================================
+
+    /// Signs a message with the latest version of a key.
+    ///
+    /// Returns `egide:v{n}:ed25519:{base64}`, under the configured
+    /// ciphertext prefix, which [`Self::verify`] checks. Anyone holding the
+    /// version's public key (see [`Self::get_public_key`]) can verify it
+    /// without the engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::OperationNotAllowed`] if the key does not
+    /// support signing, which includes every verify-only key imported with
+    /// [`Self::import_public_key`].
+    pub async fn sign(&self, name: &str, message: &[u8]) -> Result<String, TransitError> {
+        let key = self.load_key(name).await?;
+        if !key.supports_signing || key.key_type != KeyType::Ed25519 {
+            return Err(TransitError::OperationNotAllowed(
+                "signing not allowed for this key".into(),
+            ));
+        }
+
+        let version = key.latest_version;
+        let material = self.get_key_material(&key.name, version).await?;
+        if material.len() != ED25519_KEYPAIR_SIZE {
+            return Err(TransitError::OperationNotAllowed(
+                "signing not allowed for this key".into(),
+            ));
+        }
+        let signature = signing::sign_ed25519(&material[..signing::ED25519_SEED_SIZE], message)?;
+        self.record_usage(&key.name, 1).await;
+        Ok(self.format_ciphertext(version, KeyType::Ed25519, &signature))
+    }
+
+    /// Returns the Ed25519 public key of a signature key version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::OperationNotAllowed`] if the key is not a
+    /// signature key, and [`TransitError::VersionNotFound`] if the version
+    /// does not exist (or was trimmed).
+    pub async fn get_public_key(&self, name: &str, version: u32) -> Result<Vec<u8>, TransitError> {
+        let key = self.load_key(name).await?;
+        if key.key_type != KeyType::Ed25519 {
+            return Err(TransitError::OperationNotAllowed(
+                "only ed25519 keys have a public key".into(),
+            ));
+        }
+        let material = self.get_key_material(&key.name, version).await?;
+        Ok(Self::ed25519_public_part(&material).to_vec())
+    }
+
+    /// Returns the public key held in an Ed25519 version's material.
+    fn ed25519_public_part(material: &[u8]) -> &[u8] {
+        &material[material
+            .len()
+            .saturating_sub(signing::ED25519_PUBLIC_KEY_SIZE)..]
+    }
+
+    /// Verifies a signature (`egide:v{n}:ed25519:{base64}`) over a message.
+    ///
+    /// Returns `Ok(false)` when the signature is well-formed but does not
+    /// match. Verification is a read-only check gated on no capability flag:
+    /// a key with signing, encryption and decryption all off still verifies.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::OperationNotAllowed`] if the key is not a
+    /// signature key, [`TransitError::InvalidCiphertext`] if the signature is
+    /// malformed or not an Ed25519 signature, and
+    /// [`TransitError::VersionBelowMinDecryption`] or
+    /// [`TransitError::VersionNotFound`] for a version that cannot be used.
+    pub async fn verify(
+        &self,
+        name: &str,
+        message: &[u8],
+        signature: &str,
+    ) -> Result<bool, TransitError> {
+        let key = self.load_key(name).await?;
+        if key.key_type != KeyType::Ed25519 {
+            return Err(TransitError::OperationNotAllowed(
+                "signature verification requires an ed25519 key".into(),
+            ));
+        }
+
+        let (version, signature_type, signature) = self.parse_ciphertext(signature)?;
+        if signature_type != KeyType::Ed25519 {
+            return Err(TransitError::InvalidCiphertext);
+        }
+        if version < key.min_decryption_version {
+            return Err(TransitError::VersionBelowMinDecryption {
+                version,
+                min: key.min_decryption_version,
+            });
+        }
+
+        let material = self.get_key_material(&key.name, version).await?;
+        let public_key = Self::ed25519_public_part(&material);
+        match signing::verify_ed25519(public_key, message, &signature) {
+            Ok(()) => Ok(true),
+            Err(egide_crypto::CryptoError::VerificationFailed) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // ========================================================================
+    // HMAC Operations
+    // ========================================================================
+
+    /// Computes an HMAC-SHA256 of `input` with the latest version of a key.
+    ///
+    /// The HMAC key is derived from the version's material by HKDF under its
+    /// own context, so it never coincides with the key that encrypts. The
+    /// result reads `egide:hmac:v{n}:{base64}`, under the configured
+    /// ciphertext prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::OperationNotAllowed`] if the key is not a
+    /// symmetric key.
+    pub async fn hmac(&self, name: &str, input: &[u8]) -> Result<String, TransitError> {
+        let key = self.load_key(name).await?;
+        Self::require_symmetric(&key)?;
+        let version = key.latest_version;
+        let hmac_key = self.hmac_key(&key, version).await?;
+        let tag = mac::compute_mac(&hmac_key[..], input)?;
+        Ok(format!(
+            "{}:hmac:v{version}:{}",
+            self.ciphertext_prefix,
+            BASE64.encode(tag)
+        ))
+    }
+
+    /// Verifies an HMAC produced by [`Self::hmac`] over `input`.
+    ///
+    /// Returns `Ok(false)` when the HMAC is well-formed but does not match.
+    /// The comparison is constant-time, and versions below the key's
+    /// `min_decryption_version` are refused as [`Self::decrypt`] refuses
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransitError::OperationNotAllowed`] if the key is not a
+    /// symmetric key, [`TransitError::InvalidCiphertext`] if the HMAC is
+    /// malformed, and [`TransitError::VersionBelowMinDecryption`] or
+    /// [`TransitError::VersionNotFound`] for a version that cannot be used.
+    pub async fn verify_hmac(
+        &self,
+        name: &str,
+        input: &[u8],
+        hmac: &str,
+    ) -> Result<bool, TransitError> {
+        let key = self.load_key(name).await?;
+        Self::require_symmetric(&key)?;
+
+        let (version, tag) = self.parse_hmac(hmac)?;
+        if version < key.min_decryption_version {
+            return Err(TransitError::VersionBelowMinDecryption {
+                version,
+                min: key.min_decryption_version,
+            });
+        }
+
+        let hmac_key = self.hmac_key(&key, version).await?;
+        match mac::verify_mac(&hmac_key[..], input, &tag) {
+            Ok(()) => Ok(true),
+            Err(egide_crypto::CryptoError::VerificationFailed) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn require_symmetric(key: &TransitKey) -> Result<(), TransitError> {
+        if key.key_type == KeyType::Ed25519 {
+            return Err(TransitError::OperationNotAllowed(
+                "hmac requires a symmetric key".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Derives the HMAC key of a key version.
+    async fn hmac_key(
+        &self,
+        key: &TransitKey,
+        version: u32,
+    ) -> Result<Zeroizing<[u8; 32]>, TransitError> {
+        let raw_key = self.get_key_material(&key.name, version).await?;
+        kdf::derive_encryption_key(&raw_key, TRANSIT_HMAC_INFO).map_err(TransitError::from)
+    }
+
+    /// Parses `{prefix}:hmac:v{version}:{base64}`.
+    fn parse_hmac(&self, hmac: &str) -> Result<(u32, Vec<u8>), TransitError> {
+        let mut parts = hmac.splitn(4, ':');
+        let (Some(prefix), Some("hmac"), Some(version), Some(encoded)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(TransitError::InvalidCiphertext);
+        };
+        if prefix != self.ciphertext_prefix {
+            return Err(TransitError::InvalidCiphertext);
+        }
+        let version = version
+            .strip_prefix('v')
+            .and_then(|v| v.parse().ok())
+            .ok_or(TransitError::InvalidCiphertext)?;
+        let tag = BASE64
+            .decode(encoded)
+            .map_err(|_| TransitError::InvalidCiphertext)?;
+        Ok((version, tag))
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1154,26 +4393,118 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_create_key_rejects_unimplemented_key_type() {
+    async fn test_ed25519_key_signs_verifies_and_rotates() {
         let (_tmp, engine) = setup().await;
+        let config = KeyConfig {
+            key_type: KeyType::Ed25519,
+            supports_signing: true,
+            ..KeyConfig::new()
+        };
+        let key = engine.create_key("signer", config).await.unwrap();
+        assert!(key.supports_signing);
+        assert!(!key.supports_encryption && !key.supports_decryption);
+
+        let signature = engine.sign("signer", b"release-1.2.3").await.unwrap();
+        assert!(signature.starts_with("egide:v1:ed25519:"));
+        assert!(engine
+            .verify("signer", b"release-1.2.3", &signature)
+            .await
+            .unwrap());
+        assert!(!engine
+            .verify("signer", b"release-1.2.4", &signature)
+            .await
+            .unwrap());
+
+        // The public key alone verifies, outside the engine.
+        let public_key = engine.get_public_key("signer", 1).await.unwrap();
+        let encoded = signature.rsplit(':').next().unwrap();
+        signing::verify_ed25519(
+            &public_key,
+            b"release-1.2.3",
+            &BASE64.decode(encoded).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(engine.rotate_key("signer").await.unwrap(), 2);
+        let rotated = engine.sign("signer", b"release-1.2.3").await.unwrap();
+        assert!(rotated.starts_with("egide:v2:ed25519:"));
+        assert_ne!(
+            engine.get_public_key("signer", 2).await.unwrap(),
+            public_key
+        );
+        assert!(engine
+            .verify("signer", b"release-1.2.3", &signature)
+            .await
+            .unwrap());
+
+        assert!(matches!(
+            engine.encrypt("signer", b"data").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+        assert!(matches!(
+            engine.hmac("signer", b"data").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+        assert!(matches!(
+            engine.import_key_version("signer", &[7u8; 32]).await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+
+        engine.create_key("aes", KeyConfig::new()).await.unwrap();
+        assert!(matches!(
+            engine.sign("aes", b"m").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+        let config = KeyConfig {
+            supports_signing: true,
+            ..KeyConfig::new()
+        };
+        assert!(matches!(
+            engine.create_key("aes-signer", config).await,
+            Err(TransitError::InvalidKeyConfig(_))
+        ));
+        assert!(matches!(
+            engine.get_public_key("aes", 1).await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+    }
 
+    #[tokio::test]
+    async fn test_chacha20_key_encrypts_under_chacha20() {
+        let (_tmp, engine) = setup().await;
         let config = KeyConfig {
             key_type: KeyType::ChaCha20Poly1305,
             ..KeyConfig::default()
         };
-        let result = engine.create_key("unsupported", config).await;
+        engine.create_key("chacha", config).await.unwrap();
 
-        assert!(
-            matches!(
-                result,
-                Err(TransitError::UnsupportedKeyType(KeyType::ChaCha20Poly1305))
-            ),
-            "an unimplemented key type must be rejected, got {result:?}"
+        let ciphertext = engine.encrypt("chacha", b"payload").await.unwrap();
+        let body = ciphertext
+            .strip_prefix("egide:v1:chacha20-poly1305:")
+            .expect("chacha20-poly1305 ciphertexts carry their label");
+        assert_eq!(
+            engine.decrypt("chacha", &ciphertext).await.unwrap(),
+            b"payload"
         );
 
-        // The rejection must happen before any row is written.
-        let lookup = engine.get_key("unsupported").await;
-        assert!(matches!(lookup, Err(TransitError::KeyNotFound(_))));
+        // The bytes really are ChaCha20-Poly1305, not relabelled AES-GCM.
+        let raw = engine.get_key_material("chacha", 1).await.unwrap();
+        let sealed = BASE64.decode(body).unwrap();
+        let aad = b"egide-transit:chacha:1";
+        assert_eq!(
+            &*aead::decrypt_chacha(&raw, &sealed, Some(aad)).unwrap(),
+            b"payload"
+        );
+        assert!(aead::decrypt(&raw, &sealed, Some(aad)).is_err());
+
+        // Rotation and rewrap stay on the key's cipher.
+        engine.rotate_key("chacha").await.unwrap();
+        let rewrapped = engine.rewrap("chacha", &ciphertext).await.unwrap();
+        assert!(rewrapped.starts_with("egide:v2:chacha20-poly1305:"));
+        assert_eq!(
+            engine.decrypt("chacha", &rewrapped).await.unwrap(),
+            b"payload"
+        );
     }
 
     #[tokio::test]
@@ -1240,6 +4571,23 @@ mod tests {
         assert_eq!(keys, vec!["alpha", "beta", "gamma"]);
     }
 
+    #[tokio::test]
+    async fn test_list_keys_page() {
+        let (_tmp, engine) = setup().await;
+        for name in ["delta", "alpha", "gamma", "beta"] {
+            engine.create_key(name, KeyConfig::new()).await.unwrap();
+        }
+
+        let first = engine.list_keys_page(None, 3).await.unwrap();
+        assert_eq!(first, vec!["alpha", "beta", "delta"]);
+        let rest = engine.list_keys_page(Some("delta"), 3).await.unwrap();
+        assert_eq!(rest, vec!["gamma"]);
+        // The cursor is exclusive and need not exist.
+        let after = engine.list_keys_page(Some("c"), 10).await.unwrap();
+        assert_eq!(after, vec!["delta", "gamma"]);
+        assert!(engine.list_keys_page(None, 0).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_encrypt_decrypt() {
         let (_tmp, engine) = setup().await;
@@ -1258,6 +4606,75 @@ mod tests {
         assert_eq!(decrypted, plaintext);
     }
 
+    #[test]
+    fn test_cpu_features_detects_without_panicking() {
+        let features = TransitEngine::cpu_features();
+        assert_eq!(features.arch, std::env::consts::ARCH);
+        assert!(!features.arch.is_empty());
+        // Detection is cached and stable for the life of the process.
+        assert_eq!(features, TransitEngine::cpu_features());
+        if cfg!(not(any(target_arch = "x86", target_arch = "x86_64"))) {
+            assert!(!features.avx2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_convergent_encryption_is_repeatable() {
+        let (_tmp, engine) = setup().await;
+        let config = KeyConfig {
+            convergent: true,
+            ..KeyConfig::new()
+        };
+        let key = engine.create_key("dedup", config).await.unwrap();
+        assert!(key.convergent);
+        assert!(engine.get_key("dedup").await.unwrap().convergent);
+
+        let first = engine.encrypt("dedup", b"same").await.unwrap();
+        let second = engine.encrypt("dedup", b"same").await.unwrap();
+        let other = engine.encrypt("dedup", b"different").await.unwrap();
+        assert!(first.starts_with("egide:c1:v1:"));
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert_eq!(engine.decrypt("dedup", &first).await.unwrap(), b"same");
+
+        // Caller AAD is part of the nonce context.
+        let bound = engine
+            .encrypt_with_aad("dedup", b"same", b"ctx")
+            .await
+            .unwrap();
+        assert_ne!(first, bound);
+        assert_eq!(
+            engine
+                .decrypt_with_aad("dedup", &bound, b"ctx")
+                .await
+                .unwrap(),
+            b"same"
+        );
+
+        // After rotation, the same plaintext converges under the new version.
+        engine.rotate_key("dedup").await.unwrap();
+        let rotated = engine.encrypt("dedup", b"same").await.unwrap();
+        assert!(rotated.starts_with("egide:c1:v2:"));
+        assert_eq!(engine.rewrap("dedup", &first).await.unwrap(), rotated);
+    }
+
+    #[tokio::test]
+    async fn test_convergent_rejects_derivation() {
+        let (_tmp, engine) = setup().await;
+        let config = KeyConfig {
+            convergent: true,
+            supports_derivation: true,
+            ..KeyConfig::new()
+        };
+
+        let result = engine.create_key("dedup-derived", config).await;
+        assert!(matches!(result, Err(TransitError::InvalidKeyConfig(_))));
+        assert!(matches!(
+            engine.get_key("dedup-derived").await,
+            Err(TransitError::KeyNotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_encrypt_decrypt_large_data() {
         let (_tmp, engine) = setup().await;
@@ -1277,269 +4694,1997 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_key_rotation() {
+    async fn test_decrypt_with_meta_reports_version() {
         let (_tmp, engine) = setup().await;
+        engine.create_key("meta", KeyConfig::new()).await.unwrap();
+        let v1 = engine.encrypt("meta", b"one").await.unwrap();
+        engine.rotate_key("meta").await.unwrap();
+        let v2 = engine.encrypt("meta", b"two").await.unwrap();
+
+        let result = engine.decrypt_with_meta("meta", &v1).await.unwrap();
+        assert_eq!(
+            (result.plaintext.as_slice(), result.version),
+            (&b"one"[..], 1)
+        );
+        let result = engine.decrypt_with_meta("meta", &v2).await.unwrap();
+        assert_eq!(
+            (result.plaintext.as_slice(), result.version),
+            (&b"two"[..], 2)
+        );
+        assert!(!format!("{result:?}").contains("two"));
+    }
+
+    #[tokio::test]
+    async fn test_key_cache_skips_storage_reads() {
+        let tmp = TempDir::new().unwrap();
+        let engine = TransitEngine::new(tmp.path(), MasterKey::generate().unwrap())
+            .await
+            .unwrap()
+            .with_key_cache(16, Duration::from_mins(1));
+        engine.create_key("hot", KeyConfig::new()).await.unwrap();
+        let ciphertext = engine.encrypt("hot", b"cached").await.unwrap();
 
+        // Once cached, the version row is no longer read at all.
         engine
-            .create_key("rotate-key", KeyConfig::new())
+            .storage
+            .execute("DELETE FROM transit_key_versions WHERE name = ?", &["hot"])
             .await
             .unwrap();
+        assert_eq!(engine.decrypt("hot", &ciphertext).await.unwrap(), b"cached");
 
-        // Encrypt with v1
-        let ciphertext_v1 = engine.encrypt("rotate-key", b"secret").await.unwrap();
-        assert!(ciphertext_v1.starts_with("egide:v1:"));
-
-        // Rotate
-        let new_version = engine.rotate_key("rotate-key").await.unwrap();
-        assert_eq!(new_version, 2);
+        // Rotation drops the key's entries, so the next read hits storage.
+        engine.rotate_key("hot").await.unwrap();
+        assert_eq!(engine.key_cache.as_ref().unwrap().len(), 0);
+        assert!(matches!(
+            engine.decrypt("hot", &ciphertext).await,
+            Err(TransitError::VersionNotFound { version: 1, .. })
+        ));
+    }
 
-        // Encrypt with v2
-        let ciphertext_v2 = engine.encrypt("rotate-key", b"secret").await.unwrap();
-        assert!(ciphertext_v2.starts_with("egide:v2:"));
+    #[test]
+    fn test_key_cache_evicts_and_expires() {
+        let cache = KeyCache::new(2, Duration::from_mins(1));
+        let material = Zeroizing::new(vec![7u8; 32]);
+        cache.insert("a", 1, &material);
+        cache.insert("b", 1, &material);
+        assert!(cache.get("a", 1).is_some());
+        cache.insert("c", 1, &material);
+        assert!(
+            cache.get("b", 1).is_none(),
+            "least recently used is evicted"
+        );
+        assert!(cache.get("a", 1).is_some());
+        cache.invalidate("a");
+        assert!(cache.get("a", 1).is_none());
 
-        // Both should still decrypt
-        let decrypted_v1 = engine.decrypt("rotate-key", &ciphertext_v1).await.unwrap();
-        let decrypted_v2 = engine.decrypt("rotate-key", &ciphertext_v2).await.unwrap();
-        assert_eq!(decrypted_v1, b"secret");
-        assert_eq!(decrypted_v2, b"secret");
+        let expired = KeyCache::new(2, Duration::ZERO);
+        expired.insert("a", 1, &material);
+        assert!(expired.get("a", 1).is_none());
     }
 
     #[tokio::test]
-    async fn test_rewrap() {
+    async fn test_key_tags_round_trip() {
         let (_tmp, engine) = setup().await;
+        let mut config = KeyConfig::new();
+        config.tags.insert("env".into(), "prod".into());
+        config.tags.insert("team".into(), "payments".into());
+        let created = engine.create_key("tagged", config).await.unwrap();
+        assert_eq!(created.tags["team"], "payments");
+
+        let key = engine.get_key("tagged").await.unwrap();
+        assert_eq!(key.tags, created.tags);
 
+        let tags = BTreeMap::from([("env".to_string(), "staging".to_string())]);
         engine
-            .create_key("rewrap-key", KeyConfig::new())
+            .update_key_tags("tagged", tags.clone())
             .await
             .unwrap();
+        let key = engine.get_key("tagged").await.unwrap();
+        assert_eq!(key.tags, tags);
+        assert_eq!(key.updated_at, created.updated_at);
 
-        let ciphertext_v1 = engine.encrypt("rewrap-key", b"data").await.unwrap();
-        assert!(ciphertext_v1.starts_with("egide:v1:"));
-
-        // Rotate key
-        engine.rotate_key("rewrap-key").await.unwrap();
-
-        // Rewrap
-        let ciphertext_v2 = engine.rewrap("rewrap-key", &ciphertext_v1).await.unwrap();
-        assert!(ciphertext_v2.starts_with("egide:v2:"));
-
-        // Verify data unchanged
-        let decrypted = engine.decrypt("rewrap-key", &ciphertext_v2).await.unwrap();
-        assert_eq!(decrypted, b"data");
+        let untagged = engine.create_key("plain", KeyConfig::new()).await.unwrap();
+        assert!(untagged.tags.is_empty());
+        let bad = BTreeMap::from([(String::new(), "x".to_string())]);
+        assert!(matches!(
+            engine.update_key_tags("plain", bad).await,
+            Err(TransitError::InvalidKeyConfig(_))
+        ));
     }
 
     #[tokio::test]
-    async fn rotate_keeps_latest_in_sync_with_versions() {
+    async fn test_usage_is_counted_and_flushed() {
         let (_tmp, engine) = setup().await;
-        engine
-            .create_key("sync", KeyConfig::default())
+        let created = engine.create_key("used", KeyConfig::new()).await.unwrap();
+        assert_eq!((created.usage_count, created.last_used_at), (0, None));
+
+        let ciphertext = engine.encrypt("used", b"data").await.unwrap();
+        engine.decrypt("used", &ciphertext).await.unwrap();
+        assert!(engine.decrypt("used", "egide:v1:AAAA").await.is_err());
+        let results = engine
+            .encrypt_batch("used", &[b"a".to_vec(), b"b".to_vec()])
             .await
             .unwrap();
+        assert_eq!(results.len(), 2);
 
-        let v2 = engine.rotate_key("sync").await.unwrap();
-        let v3 = engine.rotate_key("sync").await.unwrap();
-        assert_eq!(v2, 2);
-        assert_eq!(v3, 3);
-
-        let key = engine.get_key("sync").await.unwrap();
-        assert_eq!(key.latest_version, 3);
+        // Held in memory until the interval elapses or a flush.
+        assert_eq!(engine.load_key("used").await.unwrap().usage_count, 0);
+        assert_eq!(engine.get_key("used").await.unwrap().usage_count, 4);
 
-        let versions = engine.list_versions("sync").await.unwrap();
-        let mut numbers: Vec<u32> = versions.iter().map(|v| v.version).collect();
-        numbers.sort_unstable();
-        assert_eq!(numbers, vec![1, 2, 3]);
+        engine.flush_usage().await.unwrap();
+        let stored = engine.load_key("used").await.unwrap();
+        assert_eq!(stored.usage_count, 4);
+        assert!(stored.last_used_at.is_some());
     }
 
     #[tokio::test]
-    async fn test_min_decryption_version() {
+    async fn test_rewrap_batch_counts_usage() {
         let (_tmp, engine) = setup().await;
+        engine.create_key("used", KeyConfig::new()).await.unwrap();
+        let old = engine.encrypt("used", b"data").await.unwrap();
+        engine.rotate_key("used").await.unwrap();
+        let before = engine.get_key("used").await.unwrap().usage_count;
 
-        engine
+        let results = engine
+            .rewrap_batch("used", &[old.clone(), old, "egide:v1:AAAA".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 2);
+
+        engine.flush_usage().await.unwrap();
+        let key = engine.load_key("used").await.unwrap();
+        assert_eq!(key.usage_count, before + 2);
+        assert!(key.last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_usage_flush_interval_defers_writes() {
+        let tmp = TempDir::new().unwrap();
+        let engine = TransitEngine::new(tmp.path(), MasterKey::generate().unwrap())
+            .await
+            .unwrap()
+            .with_usage_flush_interval(Duration::from_hours(1));
+        engine.create_key("lazy", KeyConfig::new()).await.unwrap();
+        engine.encrypt("lazy", b"one").await.unwrap();
+        engine.encrypt("lazy", b"two").await.unwrap();
+
+        assert_eq!(engine.load_key("lazy").await.unwrap().usage_count, 0);
+        assert_eq!(engine.get_key("lazy").await.unwrap().usage_count, 2);
+
+        engine.flush_usage().await.unwrap();
+        let stored = engine.load_key("lazy").await.unwrap();
+        assert_eq!(stored.usage_count, 2);
+        assert_eq!(engine.get_key("lazy").await.unwrap().usage_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_zero_usage_flush_interval_writes_every_operation() {
+        let tmp = TempDir::new().unwrap();
+        let engine = TransitEngine::new(tmp.path(), MasterKey::generate().unwrap())
+            .await
+            .unwrap()
+            .with_usage_flush_interval(Duration::ZERO);
+        engine.create_key("eager", KeyConfig::new()).await.unwrap();
+        engine.encrypt("eager", b"one").await.unwrap();
+
+        assert_eq!(engine.load_key("eager").await.unwrap().usage_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rename_key_keeps_material_and_policy() {
+        let (_tmp, engine) = setup().await;
+        let mut config = KeyConfig::new();
+        config.tags.insert("team".into(), "billing".into());
+        engine.create_key("typo", config).await.unwrap();
+        engine.rotate_key("typo").await.unwrap();
+        let v1 = engine.get_key_material("typo", 1).await.unwrap();
+        let old_ciphertext = engine.encrypt("typo", b"data").await.unwrap();
+
+        engine.rename_key("typo", "fixed").await.unwrap();
+
+        assert!(matches!(
+            engine.get_key("typo").await,
+            Err(TransitError::KeyNotFound(_))
+        ));
+        let key = engine.get_key("fixed").await.unwrap();
+        assert_eq!(key.latest_version, 2);
+        assert_eq!(key.tags["team"], "billing");
+        assert_eq!(key.usage_count, 1);
+        assert_eq!(
+            engine
+                .get_key_material("fixed", 1)
+                .await
+                .unwrap()
+                .as_slice(),
+            v1.as_slice()
+        );
+        assert!(engine.verify_key("fixed").await.unwrap());
+
+        let ciphertext = engine.encrypt("fixed", b"new").await.unwrap();
+        assert_eq!(engine.decrypt("fixed", &ciphertext).await.unwrap(), b"new");
+        // The old name is bound into existing ciphertexts.
+        assert!(matches!(
+            engine.decrypt("fixed", &old_ciphertext).await,
+            Err(TransitError::DecryptionFailed)
+        ));
+
+        engine.create_key("other", KeyConfig::new()).await.unwrap();
+        assert!(matches!(
+            engine.rename_key("other", "fixed").await,
+            Err(TransitError::KeyExists(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_key_rotation() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .create_key("rotate-key", KeyConfig::new())
+            .await
+            .unwrap();
+
+        // Encrypt with v1
+        let ciphertext_v1 = engine.encrypt("rotate-key", b"secret").await.unwrap();
+        assert!(ciphertext_v1.starts_with("egide:v1:"));
+
+        // Rotate
+        let new_version = engine.rotate_key("rotate-key").await.unwrap();
+        assert_eq!(new_version, 2);
+
+        // Encrypt with v2
+        let ciphertext_v2 = engine.encrypt("rotate-key", b"secret").await.unwrap();
+        assert!(ciphertext_v2.starts_with("egide:v2:"));
+
+        // Both should still decrypt
+        let decrypted_v1 = engine.decrypt("rotate-key", &ciphertext_v1).await.unwrap();
+        let decrypted_v2 = engine.decrypt("rotate-key", &ciphertext_v2).await.unwrap();
+        assert_eq!(decrypted_v1, b"secret");
+        assert_eq!(decrypted_v2, b"secret");
+    }
+
+    #[tokio::test]
+    async fn test_rewrap() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .create_key("rewrap-key", KeyConfig::new())
+            .await
+            .unwrap();
+
+        let ciphertext_v1 = engine.encrypt("rewrap-key", b"data").await.unwrap();
+        assert!(ciphertext_v1.starts_with("egide:v1:"));
+
+        // Rotate key
+        engine.rotate_key("rewrap-key").await.unwrap();
+
+        // Rewrap
+        let ciphertext_v2 = engine.rewrap("rewrap-key", &ciphertext_v1).await.unwrap();
+        assert!(ciphertext_v2.starts_with("egide:v2:"));
+
+        // Verify data unchanged
+        let decrypted = engine.decrypt("rewrap-key", &ciphertext_v2).await.unwrap();
+        assert_eq!(decrypted, b"data");
+    }
+
+    #[tokio::test]
+    async fn test_export_key_wrapped_unwraps_to_original_material() {
+        use egide_crypto::wrapping::{derive_wrapping_key, X25519_PUBLIC_KEY_SIZE};
+        use ring::agreement::{agree_ephemeral, EphemeralPrivateKey, UnparsedPublicKey, X25519};
+
+        let (_tmp, engine) = setup().await;
+        let mut config = KeyConfig::new();
+        config.exportable = true;
+        let key = engine.create_key("hsm", config).await.unwrap();
+        engine.rotate_key("hsm").await.unwrap();
+
+        let rng = ring::rand::SystemRandom::new();
+        let recipient = EphemeralPrivateKey::generate(&X25519, &rng).unwrap();
+        let recipient_public = recipient.compute_public_key().unwrap();
+
+        let exported = engine
+            .export_key_wrapped("hsm", recipient_public.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(
+            exported.iter().map(|(v, _)| *v).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        let (header, payload) = EnvelopeHeader::decode(&exported[0].1).unwrap();
+        assert_eq!(header.algorithm, AlgorithmId::X25519Aes256Gcm);
+        let ephemeral_public = payload[..X25519_PUBLIC_KEY_SIZE].to_vec();
+        let unwrapped = agree_ephemeral(
+            recipient,
+            &UnparsedPublicKey::new(&X25519, &ephemeral_public),
+            |shared| {
+                exported
+                    .iter()
+                    .map(|(version, blob)| {
+                        let (_, wrapped) = EnvelopeHeader::decode(blob).unwrap();
+                        let label = TransitEngine::export_label("hsm", *version);
+                        let key = derive_wrapping_key(
+                            shared,
+                            &ephemeral_public,
+                            recipient_public.as_ref(),
+                            label.as_bytes(),
+                        )
+                        .unwrap();
+                        aead::decrypt(
+                            &key[..],
+                            &wrapped[X25519_PUBLIC_KEY_SIZE..],
+                            Some(label.as_bytes()),
+                        )
+                        .unwrap()
+                        .to_vec()
+                    })
+                    .collect::<Vec<_>>()
+            },
+        )
+        .unwrap();
+
+        for (version, material) in [1, 2].into_iter().zip(unwrapped) {
+            let original = engine.get_key_material(&key.name, version).await.unwrap();
+            assert_eq!(material, original.to_vec());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backup_restore_roundtrip_and_force() {
+        let (_tmp, engine) = setup().await;
+        engine
+            .create_key(
+                "k",
+                KeyConfig {
+                    deletion_allowed: true,
+                    ..KeyConfig::new()
+                },
+            )
+            .await
+            .unwrap();
+        engine.rotate_key("k").await.unwrap();
+        let v1 = engine.encrypt_with_version("k", b"old", 1).await.unwrap();
+        let backup = engine.backup_key("k").await.unwrap();
+        let force = RestoreOptions {
+            force: true,
+            ..RestoreOptions::default()
+        };
+
+        // Same master key: a second engine over a fresh database.
+        let other_dir = TempDir::new().unwrap();
+        let same_master = MasterKey::from_bytes(engine.master_key.as_bytes()).unwrap();
+        let restored = TransitEngine::new(other_dir.path(), same_master)
+            .await
+            .unwrap();
+        restored
+            .restore_key("k", &backup, RestoreOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(restored.get_key("k").await.unwrap().latest_version, 2);
+        assert_eq!(restored.decrypt("k", &v1).await.unwrap(), b"old");
+        assert!(restored.verify_key("k").await.unwrap());
+
+        assert!(matches!(
+            engine
+                .restore_key("k", &backup, RestoreOptions::default())
+                .await,
+            Err(TransitError::KeyExists(_))
+        ));
+        engine.restore_key("k", &backup, force).await.unwrap();
+        assert_eq!(engine.get_key("k").await.unwrap().latest_version, 2);
+
+        assert!(matches!(
+            engine
+                .restore_key("other", &backup, RestoreOptions::default())
+                .await,
+            Err(TransitError::InvalidKeyMaterial(_))
+        ));
+        assert!(matches!(
+            engine.restore_key("k", "not a backup", force).await,
+            Err(TransitError::InvalidKeyMaterial(_))
+        ));
+
+        // Different master key: the policy MAC does not verify.
+        let foreign_dir = TempDir::new().unwrap();
+        let foreign = TransitEngine::new(foreign_dir.path(), MasterKey::generate().unwrap())
+            .await
+            .unwrap();
+        assert!(matches!(
+            foreign
+                .restore_key("k", &backup, RestoreOptions::default())
+                .await,
+            Err(TransitError::Integrity(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_restore_key_force_requires_deletion_allowed() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+        let backup = engine.backup_key("k").await.unwrap();
+        engine.rotate_key("k").await.unwrap();
+
+        assert!(matches!(
+            engine
+                .restore_key(
+                    "k",
+                    &backup,
+                    RestoreOptions {
+                        force: true,
+                        allow_rollback: true,
+                    }
+                )
+                .await,
+            Err(TransitError::DeletionNotAllowed(_))
+        ));
+        assert_eq!(engine.get_key("k").await.unwrap().latest_version, 2);
+    }
+
+    #[tokio::test]
+    async fn test_restore_key_refuses_older_backup_without_rollback() {
+        let (_tmp, engine) = setup().await;
+        engine
+            .create_key(
+                "k",
+                KeyConfig {
+                    deletion_allowed: true,
+                    ..KeyConfig::new()
+                },
+            )
+            .await
+            .unwrap();
+        let backup = engine.backup_key("k").await.unwrap();
+        engine.rotate_key("k").await.unwrap();
+        let v2 = engine.encrypt("k", b"newer").await.unwrap();
+
+        assert!(matches!(
+            engine
+                .restore_key(
+                    "k",
+                    &backup,
+                    RestoreOptions {
+                        force: true,
+                        ..RestoreOptions::default()
+                    }
+                )
+                .await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+        assert_eq!(engine.get_key("k").await.unwrap().latest_version, 2);
+        assert_eq!(engine.decrypt("k", &v2).await.unwrap(), b"newer");
+
+        engine
+            .restore_key(
+                "k",
+                &backup,
+                RestoreOptions {
+                    force: true,
+                    allow_rollback: true,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(engine.get_key("k").await.unwrap().latest_version, 1);
+        assert!(engine.decrypt("k", &v2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restore_key_refuses_tampered_policy() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+        let backup = engine.backup_key("k").await.unwrap();
+
+        let mut json: serde_json::Value =
+            serde_json::from_slice(&BASE64.decode(&backup).unwrap()).unwrap();
+        json["key"]["exportable"] = serde_json::Value::Bool(true);
+        json["key"]["deletion_allowed"] = serde_json::Value::Bool(true);
+        let tampered = BASE64.encode(serde_json::to_vec(&json).unwrap());
+
+        assert!(matches!(
+            engine
+                .restore_key(
+                    "k",
+                    &tampered,
+                    RestoreOptions {
+                        force: true,
+                        ..RestoreOptions::default()
+                    }
+                )
+                .await,
+            Err(TransitError::Integrity(_))
+        ));
+        let key = engine.get_key("k").await.unwrap();
+        assert!(!key.exportable);
+        assert!(!key.deletion_allowed);
+
+        json["format"] = serde_json::Value::from(1);
+        json.as_object_mut().unwrap().remove("row_mac");
+        let legacy = BASE64.encode(serde_json::to_vec(&json).unwrap());
+        assert!(matches!(
+            engine
+                .restore_key(
+                    "k",
+                    &legacy,
+                    RestoreOptions {
+                        force: true,
+                        ..RestoreOptions::default()
+                    }
+                )
+                .await,
+            Err(TransitError::InvalidKeyMaterial(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_export_key_wrapped_requires_exportable() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("sealed", KeyConfig::new()).await.unwrap();
+
+        assert!(matches!(
+            engine.export_key_wrapped("sealed", &[9u8; 32]).await,
+            Err(TransitError::NotExportable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_custom_ciphertext_prefix_round_trips_and_rejects_others() {
+        let tmp = TempDir::new().unwrap();
+        let master_key = MasterKey::generate().unwrap();
+        let engine = TransitEngine::new(tmp.path(), master_key)
+            .await
+            .unwrap()
+            .with_ciphertext_prefix("acme")
+            .unwrap();
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+
+        let ciphertext = engine.encrypt("k", b"white label").await.unwrap();
+        assert!(ciphertext.starts_with("acme:v1:"));
+        assert_eq!(
+            engine.decrypt("k", &ciphertext).await.unwrap(),
+            b"white label"
+        );
+
+        let body = ciphertext.strip_prefix("acme:").unwrap();
+        assert!(matches!(
+            engine.decrypt("k", &format!("egide:{body}")).await,
+            Err(TransitError::InvalidCiphertext)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ciphertext_prefix_is_validated() {
+        let (_tmp, engine) = setup().await;
+        assert_eq!(engine.ciphertext_prefix(), DEFAULT_CIPHERTEXT_PREFIX);
+
+        for prefix in ["", "with:colon", "x".repeat(33).as_str()] {
+            let (_tmp, engine) = setup().await;
+            assert!(matches!(
+                engine.with_ciphertext_prefix(prefix),
+                Err(TransitError::InvalidCiphertextPrefix(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_batch_reports_per_item() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+
+        let old = engine.encrypt("k", b"before rotation").await.unwrap();
+        engine.rotate_key("k").await.unwrap();
+
+        let items = vec![b"one".to_vec(), b"two".to_vec()];
+        let encrypted = engine.encrypt_batch("k", &items).await.unwrap();
+        let mut ciphertexts: Vec<String> = encrypted.into_iter().map(Result::unwrap).collect();
+        assert!(ciphertexts.iter().all(|ct| ct.starts_with("egide:v2:")));
+
+        ciphertexts.insert(1, "egide:v2:not-base64!".to_string());
+        ciphertexts.push(old);
+        let decrypted = engine.decrypt_batch("k", &ciphertexts).await.unwrap();
+        assert_eq!(decrypted.len(), 4);
+        assert_eq!(decrypted[0].as_ref().unwrap(), b"one");
+        assert!(decrypted[1].is_err());
+        assert_eq!(decrypted[2].as_ref().unwrap(), b"two");
+        assert_eq!(decrypted[3].as_ref().unwrap(), b"before rotation");
+    }
+
+    #[tokio::test]
+    async fn test_crypt_batch_rejects_bad_sizes() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+
+        assert!(matches!(
+            engine.encrypt_batch("k", &[]).await,
+            Err(TransitError::InvalidBatchSize { requested: 0, .. })
+        ));
+        let oversized = vec![String::new(); MAX_CRYPT_BATCH + 1];
+        assert!(matches!(
+            engine.decrypt_batch("k", &oversized).await,
+            Err(TransitError::InvalidBatchSize { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_generate_random_encodes_and_bounds_length() {
+        let (_tmp, engine) = setup().await;
+        let hex = engine.generate_random(16, RandomFormat::Hex).unwrap();
+        assert_eq!(hex.len(), 32);
+        assert!(hex.chars().all(|c| c.is_ascii_hexdigit()));
+        let b64 = engine
+            .generate_random(MAX_RANDOM_BYTES, RandomFormat::Base64)
+            .unwrap();
+        assert_eq!(BASE64.decode(b64).unwrap().len(), MAX_RANDOM_BYTES);
+        assert_ne!(engine.generate_random(16, RandomFormat::Hex).unwrap(), hex);
+        for bytes in [0, MAX_RANDOM_BYTES + 1] {
+            assert!(matches!(
+                engine.generate_random(bytes, RandomFormat::Hex),
+                Err(TransitError::InvalidRandomLength { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hash_matches_known_digests() {
+        let (_tmp, engine) = setup().await;
+        assert_eq!(
+            engine.hash(HashAlgorithm::Sha256, b"abc", OutputFormat::Hex),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            engine.hash(HashAlgorithm::Sha256, b"abc", OutputFormat::Base64),
+            "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0="
+        );
+        assert_eq!(
+            engine
+                .hash(HashAlgorithm::Sha384, b"abc", OutputFormat::Hex)
+                .len(),
+            96
+        );
+        assert!(engine
+            .hash(HashAlgorithm::Sha512, b"abc", OutputFormat::Hex)
+            .starts_with("ddaf35a193617aba"));
+
+        for algorithm in [
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Sha384,
+            HashAlgorithm::Sha512,
+        ] {
+            assert_eq!(
+                algorithm.to_string().parse::<HashAlgorithm>().unwrap(),
+                algorithm
+            );
+        }
+        assert!(matches!(
+            "md5".parse::<HashAlgorithm>(),
+            Err(TransitError::InvalidHashAlgorithm(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_hmac_verifies_across_rotation_and_rejects_tampering() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("mac", KeyConfig::new()).await.unwrap();
+
+        let v1 = engine.hmac("mac", b"message").await.unwrap();
+        assert!(v1.starts_with("egide:hmac:v1:"));
+        engine.rotate_key("mac").await.unwrap();
+        let v2 = engine.hmac("mac", b"message").await.unwrap();
+        assert!(v2.starts_with("egide:hmac:v2:"));
+        assert_ne!(v1, v2);
+
+        assert!(engine.verify_hmac("mac", b"message", &v1).await.unwrap());
+        assert!(engine.verify_hmac("mac", b"message", &v2).await.unwrap());
+        assert!(!engine.verify_hmac("mac", b"massage", &v2).await.unwrap());
+
+        // The HMAC key is not the encryption key: a MAC computed directly
+        // under the raw version material does not verify.
+        let stored = engine.load_key("mac").await.unwrap().name;
+        let raw_key = engine.get_key_material(&stored, 2).await.unwrap();
+        let raw_tag = mac::compute_mac(&raw_key, b"message").unwrap();
+        let forged = format!("egide:hmac:v2:{}", BASE64.encode(raw_tag));
+        assert!(!engine
+            .verify_hmac("mac", b"message", &forged)
+            .await
+            .unwrap());
+
+        assert!(matches!(
+            engine.verify_hmac("mac", b"message", "egide:v2:AAAA").await,
+            Err(TransitError::InvalidCiphertext)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_hmac_respects_min_decryption_version() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("mac", KeyConfig::new()).await.unwrap();
+        let v1 = engine.hmac("mac", b"message").await.unwrap();
+        engine.rotate_key("mac").await.unwrap();
+        engine
+            .update_key_config("mac", None, Some(2), None, None)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            engine.verify_hmac("mac", b"message", &v1).await,
+            Err(TransitError::VersionBelowMinDecryption { version: 1, min: 2 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_generate_datakeys_batch() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("dek", KeyConfig::new()).await.unwrap();
+
+        let datakeys = engine.generate_datakeys("dek", 100).await.unwrap();
+        assert_eq!(datakeys.len(), 100);
+
+        let distinct: std::collections::HashSet<&[u8]> =
+            datakeys.iter().map(|dk| dk.plaintext.as_slice()).collect();
+        assert_eq!(distinct.len(), 100);
+
+        for datakey in &datakeys {
+            let recovered = engine
+                .decrypt_datakey("dek", &datakey.ciphertext)
+                .await
+                .unwrap();
+            assert_eq!(recovered, datakey.plaintext);
+        }
+        // One use per DEK generated, plus one per unwrap above.
+        assert_eq!(engine.get_key("dek").await.unwrap().usage_count, 200);
+
+        for count in [0, MAX_DATAKEY_BATCH + 1] {
+            assert!(matches!(
+                engine.generate_datakeys("dek", count).await,
+                Err(TransitError::InvalidBatchSize { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_with_aad_requires_matching_aad() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("aad", KeyConfig::new()).await.unwrap();
+
+        let ciphertext = engine
+            .encrypt_with_aad("aad", b"data", b"tenant-1")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            engine
+                .decrypt_with_aad("aad", &ciphertext, b"tenant-1")
+                .await
+                .unwrap(),
+            b"data"
+        );
+        assert!(matches!(
+            engine
+                .decrypt_with_aad("aad", &ciphertext, b"tenant-2")
+                .await,
+            Err(TransitError::DecryptionFailed)
+        ));
+        assert!(matches!(
+            engine.decrypt("aad", &ciphertext).await,
+            Err(TransitError::DecryptionFailed)
+        ));
+
+        // Plain ciphertexts are unaffected and do not accept caller AAD.
+        let plain = engine.encrypt("aad", b"data").await.unwrap();
+        assert!(engine.decrypt_with_aad("aad", &plain, b"").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rewrap_with_aad_keeps_the_binding() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("aad", KeyConfig::new()).await.unwrap();
+        let ciphertext = engine
+            .encrypt_with_aad("aad", b"data", b"tenant-1")
+            .await
+            .unwrap();
+        engine.rotate_key("aad").await.unwrap();
+
+        assert!(matches!(
+            engine.rewrap("aad", &ciphertext).await,
+            Err(TransitError::DecryptionFailed)
+        ));
+        assert!(matches!(
+            engine
+                .rewrap_with_aad("aad", &ciphertext, b"tenant-2")
+                .await,
+            Err(TransitError::DecryptionFailed)
+        ));
+
+        let rewrapped = engine
+            .rewrap_with_aad("aad", &ciphertext, b"tenant-1")
+            .await
+            .unwrap();
+        assert!(rewrapped.starts_with("egide:v2:"));
+        assert_eq!(
+            engine
+                .decrypt_with_aad("aad", &rewrapped, b"tenant-1")
+                .await
+                .unwrap(),
+            b"data"
+        );
+        assert!(engine.decrypt("aad", &rewrapped).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rewrap_batch_reports_per_item() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+        let v1 = engine.encrypt("k", b"one").await.unwrap();
+        engine.rotate_key("k").await.unwrap();
+        let v2 = engine.encrypt("k", b"two").await.unwrap();
+
+        let results = engine
+            .rewrap_batch("k", &[v1, v2.clone(), "garbage".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        let rewrapped = results[0].as_ref().unwrap();
+        assert!(rewrapped.starts_with("egide:v2:"));
+        assert_eq!(engine.decrypt("k", rewrapped).await.unwrap(), b"one");
+        assert_eq!(results[1].as_ref().unwrap(), &v2);
+        assert!(matches!(results[2], Err(TransitError::InvalidCiphertext)));
+
+        assert!(matches!(
+            engine.rewrap_batch("k", &[]).await,
+            Err(TransitError::InvalidBatchSize { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rewrap_to_intermediate_version() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("staged", KeyConfig::new()).await.unwrap();
+
+        let ciphertext_v1 = engine.encrypt("staged", b"data").await.unwrap();
+        engine.rotate_key("staged").await.unwrap();
+        engine.rotate_key("staged").await.unwrap();
+
+        let ciphertext_v2 = engine
+            .rewrap_to_version("staged", &ciphertext_v1, 2)
+            .await
+            .unwrap();
+        assert!(ciphertext_v2.starts_with("egide:v2:"));
+        assert_eq!(
+            engine.decrypt("staged", &ciphertext_v2).await.unwrap(),
+            b"data"
+        );
+
+        assert!(matches!(
+            engine.rewrap_to_version("staged", &ciphertext_v1, 4).await,
+            Err(TransitError::VersionNotFound { version: 4, .. })
+        ));
+
+        engine
+            .update_key_config("staged", Some(3), None, None, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.rewrap_to_version("staged", &ciphertext_v1, 2).await,
+            Err(TransitError::VersionBelowMinEncryption { version: 2, min: 3 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn rotate_keeps_latest_in_sync_with_versions() {
+        let (_tmp, engine) = setup().await;
+        engine
+            .create_key("sync", KeyConfig::default())
+            .await
+            .unwrap();
+
+        let v2 = engine.rotate_key("sync").await.unwrap();
+        let v3 = engine.rotate_key("sync").await.unwrap();
+        assert_eq!(v2, 2);
+        assert_eq!(v3, 3);
+
+        let key = engine.get_key("sync").await.unwrap();
+        assert_eq!(key.latest_version, 3);
+
+        let versions = engine.list_versions("sync").await.unwrap();
+        let mut numbers: Vec<u32> = versions.iter().map(|v| v.version).collect();
+        numbers.sort_unstable();
+        assert_eq!(numbers, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_min_decryption_version() {
+        let (_tmp, engine) = setup().await;
+
+        engine
             .create_key("min-dec", KeyConfig::new())
             .await
             .unwrap();
 
-        let ciphertext_v1 = engine.encrypt("min-dec", b"old").await.unwrap();
+        let ciphertext_v1 = engine.encrypt("min-dec", b"old").await.unwrap();
+
+        // Rotate and update min_decryption_version
+        engine.rotate_key("min-dec").await.unwrap();
+        engine
+            .update_key_config("min-dec", None, Some(2), None, None)
+            .await
+            .unwrap();
+
+        // v1 ciphertext should fail
+        let result = engine.decrypt("min-dec", &ciphertext_v1).await;
+        assert!(matches!(
+            result,
+            Err(TransitError::VersionBelowMinDecryption { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_key() {
+        let (_tmp, engine) = setup().await;
+
+        // Create with deletion_allowed = false (default)
+        engine
+            .create_key("no-delete", KeyConfig::new())
+            .await
+            .unwrap();
+        let result = engine.delete_key("no-delete").await;
+        assert!(matches!(result, Err(TransitError::DeletionNotAllowed(_))));
+
+        // Create with deletion_allowed = true
+        let mut config = KeyConfig::new();
+        config.deletion_allowed = true;
+        engine.create_key("can-delete", config).await.unwrap();
+        engine.delete_key("can-delete").await.unwrap();
+
+        let result = engine.get_key("can-delete").await;
+        assert!(matches!(result, Err(TransitError::KeyNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_generate_datakey() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .create_key("dek-key", KeyConfig::new())
+            .await
+            .unwrap();
+
+        let datakey = engine.generate_datakey("dek-key").await.unwrap();
+        assert_eq!(datakey.plaintext.len(), 32);
+        assert!(datakey.ciphertext.starts_with("egide:v1:"));
+
+        // Verify we can decrypt the wrapped key
+        let decrypted = engine
+            .decrypt_datakey("dek-key", &datakey.ciphertext)
+            .await
+            .unwrap();
+        assert_eq!(decrypted, datakey.plaintext);
+    }
+
+    #[tokio::test]
+    async fn test_generate_datakey_with_bits_and_wrapped_only() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+
+        let short = engine
+            .generate_datakey_with("k", DataKeyBits::Bits128, true)
+            .await
+            .unwrap();
+        assert_eq!(short.plaintext.len(), 16);
+        assert_eq!(
+            engine
+                .decrypt_datakey("k", &short.ciphertext)
+                .await
+                .unwrap(),
+            short.plaintext
+        );
+
+        let wrapped = engine
+            .generate_datakey_with("k", DataKeyBits::Bits256, false)
+            .await
+            .unwrap();
+        assert!(wrapped.plaintext.is_empty());
+        assert_eq!(
+            engine
+                .decrypt_datakey("k", &wrapped.ciphertext)
+                .await
+                .unwrap()
+                .len(),
+            32
+        );
+    }
+
+    #[test]
+    fn test_datakey_debug_redacts_plaintext() {
+        let datakey = DataKey {
+            plaintext: vec![0xAB; 32],
+            ciphertext: "egide:v1:dummy-ciphertext".to_string(),
+        };
+
+        let debug_str = format!("{datakey:?}");
+
+        // The decimal representation of 0xAB (171) must never leak into the
+        // Debug output, whether as a Vec<u8> element list or otherwise.
+        assert!(!debug_str.contains("171"));
+        assert!(debug_str.contains("REDACTED"));
+    }
+
+    #[test]
+    fn test_datakey_zeroize_wipes_plaintext_and_skips_ciphertext() {
+        let mut datakey = DataKey {
+            plaintext: vec![0xAB; 32],
+            ciphertext: "egide:v1:dummy-ciphertext".to_string(),
+        };
+
+        datakey.zeroize();
+
+        // The plaintext key is wiped, while the non-sensitive ciphertext is
+        // skipped and left intact, confirming the field-level zeroize wiring.
+        assert!(datakey.plaintext.is_empty());
+        assert_eq!(datakey.ciphertext, "egide:v1:dummy-ciphertext");
+    }
+
+    #[tokio::test]
+    async fn test_encryption_disabled() {
+        let (_tmp, engine) = setup().await;
+
+        let mut config = KeyConfig::new();
+        config.supports_encryption = false;
+
+        engine.create_key("no-enc", config).await.unwrap();
+
+        let result = engine.encrypt("no-enc", b"data").await;
+        assert!(matches!(result, Err(TransitError::OperationNotAllowed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_decryption_disabled() {
+        let (_tmp, engine) = setup().await;
+
+        // Create key, encrypt something, then test with a decrypt-disabled key
+        engine
+            .create_key("enc-only", KeyConfig::new())
+            .await
+            .unwrap();
+        let ciphertext = engine.encrypt("enc-only", b"data").await.unwrap();
+
+        // Create a new engine to test with a decrypt-disabled key
+        let (_tmp2, engine2) = setup().await;
+        let mut config = KeyConfig::new();
+        config.supports_decryption = false;
+        engine2.create_key("no-dec", config).await.unwrap();
+
+        let result = engine2.decrypt("no-dec", &ciphertext).await;
+        assert!(matches!(result, Err(TransitError::OperationNotAllowed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_ciphertext() {
+        let (_tmp, engine) = setup().await;
+
+        engine
+            .create_key("test-key", KeyConfig::new())
+            .await
+            .unwrap();
+
+        let result = engine.decrypt("test-key", "invalid").await;
+        assert!(matches!(result, Err(TransitError::InvalidCiphertext)));
+
+        let result = engine.decrypt("test-key", "not:a:valid:format").await;
+        assert!(matches!(result, Err(TransitError::InvalidCiphertext)));
+
+        let result = engine
+            .decrypt("test-key", "egide:v1:!!!invalid-base64!!!")
+            .await;
+        assert!(matches!(result, Err(TransitError::InvalidCiphertext)));
+    }
+
+    #[tokio::test]
+    async fn test_key_isolation() {
+        let (_tmp, engine) = setup().await;
+
+        engine.create_key("key-a", KeyConfig::new()).await.unwrap();
+        engine.create_key("key-b", KeyConfig::new()).await.unwrap();
+
+        let ciphertext = engine.encrypt("key-a", b"secret").await.unwrap();
+
+        // Should fail when trying to decrypt with different key
+        let result = engine.decrypt("key-b", &ciphertext).await;
+        assert!(matches!(result, Err(TransitError::DecryptionFailed)));
+    }
+
+    #[tokio::test]
+    async fn test_key_prefixes_namespace_shared_tenant_db() {
+        let tmp = TempDir::new().unwrap();
+        let master_key = MasterKey::generate().unwrap();
+        let master_key_bytes = master_key.as_bytes().to_vec();
+        let transit = TransitEngine::new(tmp.path(), master_key)
+            .await
+            .unwrap()
+            .with_key_prefix("transit/")
+            .unwrap();
+        let kms = TransitEngine::new(
+            tmp.path(),
+            MasterKey::from_bytes(&master_key_bytes).unwrap(),
+        )
+        .await
+        .unwrap()
+        .with_key_prefix("kms/")
+        .unwrap();
+
+        let created = transit.create_key("app", KeyConfig::new()).await.unwrap();
+        assert_eq!(created.name, "app");
+        kms.create_key("app", KeyConfig::new()).await.unwrap();
+        kms.rotate_key("app").await.unwrap();
+
+        assert_eq!(transit.get_key("app").await.unwrap().latest_version, 1);
+        assert_eq!(kms.get_key("app").await.unwrap().latest_version, 2);
+        assert_eq!(transit.list_keys().await.unwrap(), vec!["app"]);
+        assert_eq!(kms.list_keys().await.unwrap(), vec!["app"]);
+
+        // Stored names carry the prefix; the API never sees it.
+        let stored = transit
+            .storage
+            .query_all::<(String,)>("SELECT name FROM transit_keys ORDER BY name", &[])
+            .await
+            .unwrap();
+        assert_eq!(
+            stored,
+            vec![("kms/app".to_string(),), ("transit/app".to_string(),)]
+        );
+
+        // Same bare name, distinct keys: ciphertexts do not cross namespaces.
+        let ciphertext = transit.encrypt("app", b"secret").await.unwrap();
+        assert_eq!(
+            transit.decrypt("app", &ciphertext).await.unwrap(),
+            b"secret"
+        );
+        assert!(matches!(
+            kms.decrypt("app", &ciphertext).await,
+            Err(TransitError::DecryptionFailed)
+        ));
+
+        let unprefixed = TransitEngine::new(
+            tmp.path(),
+            MasterKey::from_bytes(&master_key_bytes).unwrap(),
+        )
+        .await
+        .unwrap();
+        assert!(unprefixed.list_keys().await.unwrap().is_empty());
+        assert!(matches!(
+            unprefixed.get_key("app").await,
+            Err(TransitError::KeyNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_v1_derived_versions_decrypt_after_v2_becomes_default() {
+        let tmp = TempDir::new().unwrap();
+        let master_key = MasterKey::generate().unwrap();
+        let master_key_bytes = master_key.as_bytes().to_vec();
+
+        let v1_engine = TransitEngine::new(tmp.path(), master_key).await.unwrap();
+        v1_engine.create_key("k", KeyConfig::new()).await.unwrap();
+        let old = v1_engine.encrypt("k", b"under v1").await.unwrap();
+        drop(v1_engine);
+
+        let engine = TransitEngine::new(
+            tmp.path(),
+            MasterKey::from_bytes(&master_key_bytes).unwrap(),
+        )
+        .await
+        .unwrap()
+        .with_kdf_version(2);
+        assert_eq!(engine.rotate_key("k").await.unwrap(), 2);
+        let new = engine.encrypt("k", b"under v2").await.unwrap();
+
+        let recorded = engine
+            .storage
+            .query_all::<(String, String)>(
+                "SELECT CAST(version AS TEXT), CAST(kdf_version AS TEXT) FROM transit_key_versions WHERE name = ? ORDER BY version",
+                &["k"],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            recorded,
+            vec![
+                ("1".to_string(), "1".to_string()),
+                ("2".to_string(), "2".to_string())
+            ]
+        );
+
+        assert_eq!(engine.decrypt("k", &old).await.unwrap(), b"under v1");
+        assert_eq!(engine.decrypt("k", &new).await.unwrap(), b"under v2");
+        assert_eq!(
+            engine
+                .decrypt("k", &engine.rewrap("k", &old).await.unwrap())
+                .await
+                .unwrap(),
+            b"under v1"
+        );
+    }
 
-        // Rotate and update min_decryption_version
-        engine.rotate_key("min-dec").await.unwrap();
+    #[tokio::test]
+    async fn test_missing_kdf_version_reads_as_v1() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+        let ciphertext = engine.encrypt("k", b"legacy").await.unwrap();
+
+        // Rows written before the column existed carry NULL.
         engine
-            .update_key_config("min-dec", None, Some(2), None)
+            .storage
+            .execute(
+                "UPDATE transit_key_versions SET kdf_version = NULL WHERE name = ?",
+                &["k"],
+            )
             .await
             .unwrap();
 
-        // v1 ciphertext should fail
-        let result = engine.decrypt("min-dec", &ciphertext_v1).await;
+        assert_eq!(engine.decrypt("k", &ciphertext).await.unwrap(), b"legacy");
+    }
+
+    #[tokio::test]
+    async fn test_verify_key_under_correct_and_wrong_master_key() {
+        let tmp = TempDir::new().unwrap();
+        let master_key = MasterKey::generate().unwrap();
+        let engine = TransitEngine::new(tmp.path(), master_key).await.unwrap();
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+        engine.rotate_key("k").await.unwrap();
+        assert!(engine.verify_key("k").await.unwrap());
+        drop(engine);
+
+        let wrong = TransitEngine::new(tmp.path(), MasterKey::generate().unwrap())
+            .await
+            .unwrap();
+        assert!(!matches!(wrong.verify_key("k").await, Ok(true)));
+    }
+
+    #[tokio::test]
+    async fn test_rewrap_key_from_moves_versions_to_new_master() {
+        let tmp = TempDir::new().unwrap();
+        let old_master = MasterKey::generate().unwrap();
+        let old_engine = TransitEngine::new(tmp.path(), old_master.clone())
+            .await
+            .unwrap();
+        old_engine.create_key("k", KeyConfig::new()).await.unwrap();
+        let v1 = old_engine.encrypt("k", b"first").await.unwrap();
+        old_engine.rotate_key("k").await.unwrap();
+        let v2 = old_engine.encrypt("k", b"second").await.unwrap();
+        drop(old_engine);
+
+        let engine = TransitEngine::new(tmp.path(), MasterKey::generate().unwrap())
+            .await
+            .unwrap();
         assert!(matches!(
-            result,
-            Err(TransitError::VersionBelowMinDecryption { .. })
+            engine.decrypt("k", &v1).await,
+            Err(TransitError::Integrity(_))
         ));
+        let wrong = MasterKey::generate().unwrap();
+        assert!(matches!(
+            engine.rewrap_key_from("k", &wrong).await,
+            Err(TransitError::Integrity(_))
+        ));
+
+        assert_eq!(engine.rewrap_key_from("k", &old_master).await.unwrap(), 2);
+        assert_eq!(engine.decrypt("k", &v1).await.unwrap(), b"first");
+        assert_eq!(engine.decrypt("k", &v2).await.unwrap(), b"second");
+        assert!(engine.verify_key("k").await.unwrap());
+        assert_eq!(engine.rewrap_key_from("k", &old_master).await.unwrap(), 0);
     }
 
     #[tokio::test]
-    async fn test_delete_key() {
+    async fn test_verify_key_detects_corrupted_or_missing_versions() {
         let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+        engine.rotate_key("k").await.unwrap();
+        engine.rotate_key("k").await.unwrap();
 
-        // Create with deletion_allowed = false (default)
+        let (material,) = engine
+            .storage
+            .query_one::<(String,)>(
+                "SELECT key_material FROM transit_key_versions WHERE name = ? AND version = 2",
+                &["k"],
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let flipped = if material.starts_with('0') { "1" } else { "0" };
+        let corrupted = format!("{flipped}{}", &material[1..]);
         engine
-            .create_key("no-delete", KeyConfig::new())
+            .storage
+            .execute(
+                "UPDATE transit_key_versions SET key_material = ? WHERE name = ? AND version = 2",
+                &[&corrupted, "k"],
+            )
             .await
             .unwrap();
-        let result = engine.delete_key("no-delete").await;
-        assert!(matches!(result, Err(TransitError::DeletionNotAllowed(_))));
+        assert!(!engine.verify_key("k").await.unwrap());
 
-        // Create with deletion_allowed = true
+        engine
+            .storage
+            .execute(
+                "DELETE FROM transit_key_versions WHERE name = ? AND version = 2",
+                &["k"],
+            )
+            .await
+            .unwrap();
+        assert!(!engine.verify_key("k").await.unwrap());
+
+        assert!(matches!(
+            engine.verify_key("missing").await,
+            Err(TransitError::KeyNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_key_accepts_trimmed_key() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("k", KeyConfig::new()).await.unwrap();
+        engine.rotate_key("k").await.unwrap();
+        engine.rotate_key("k").await.unwrap();
+        engine
+            .update_key_config("k", Some(2), Some(2), None, None)
+            .await
+            .unwrap();
+        assert_eq!(engine.trim_key("k", 2).await.unwrap(), 1);
+        assert!(engine.verify_key("k").await.unwrap());
+
+        engine
+            .storage
+            .execute(
+                "DELETE FROM transit_key_versions WHERE name = ? AND version = 3",
+                &["k"],
+            )
+            .await
+            .unwrap();
+        assert!(!engine.verify_key("k").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_import_key_versions_decrypts_existing_ciphertext() {
+        // The "original system": a key with three versions and a ciphertext
+        // produced under v2.
+        let (_src_tmp, source) = setup().await;
+        source
+            .create_key("migrated", KeyConfig::new())
+            .await
+            .unwrap();
+        source.rotate_key("migrated").await.unwrap();
+        source.rotate_key("migrated").await.unwrap();
+        let ciphertext = source
+            .encrypt_with_version("migrated", b"legacy data", 2)
+            .await
+            .unwrap();
+        assert!(ciphertext.starts_with("egide:v2:"));
+        let mut versions = Vec::new();
+        for version in [3, 1, 2] {
+            let material = source.get_key_material("migrated", version).await.unwrap();
+            versions.push((version, material.to_vec()));
+        }
+
+        // A separate engine under its own master key.
+        let (_tmp, engine) = setup().await;
+        let key = engine
+            .import_key_versions("migrated", KeyType::Aes256Gcm, versions, KeyConfig::new())
+            .await
+            .unwrap();
+        assert_eq!(key.name, "migrated");
+        assert_eq!(key.latest_version, 3);
+
+        assert_eq!(
+            engine.decrypt("migrated", &ciphertext).await.unwrap(),
+            b"legacy data"
+        );
+        assert!(engine.verify_key("migrated").await.unwrap());
+        assert_eq!(engine.list_versions("migrated").await.unwrap().len(), 3);
+
+        // New encryptions use the imported latest version; rotation continues from it.
+        let fresh = engine.encrypt("migrated", b"new").await.unwrap();
+        assert!(fresh.starts_with("egide:v3:"));
+        assert_eq!(engine.rotate_key("migrated").await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_import_key_and_version_use_supplied_material() {
+        let (_tmp, engine) = setup().await;
+        let key = engine
+            .import_key("byok", &[1u8; 32], KeyConfig::new())
+            .await
+            .unwrap();
+        assert_eq!(key.latest_version, 1);
+        assert_eq!(
+            engine.get_key_material("byok", 1).await.unwrap().as_slice(),
+            &[1u8; 32]
+        );
+        assert!(matches!(
+            engine
+                .import_key("byok", &[1u8; 32], KeyConfig::new())
+                .await,
+            Err(TransitError::KeyExists(_))
+        ));
+        assert!(matches!(
+            engine
+                .import_key("short", &[1u8; 16], KeyConfig::new())
+                .await,
+            Err(TransitError::InvalidKeyMaterial(_))
+        ));
+
+        let v1 = engine.encrypt("byok", b"before").await.unwrap();
+        assert_eq!(
+            engine.import_key_version("byok", &[2u8; 32]).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            engine.get_key_material("byok", 2).await.unwrap().as_slice(),
+            &[2u8; 32]
+        );
+        assert!(engine
+            .encrypt("byok", b"after")
+            .await
+            .unwrap()
+            .starts_with("egide:v2:"));
+        assert_eq!(engine.decrypt("byok", &v1).await.unwrap(), b"before");
+        assert!(matches!(
+            engine.import_key_version("byok", &[2u8; 31]).await,
+            Err(TransitError::InvalidKeyMaterial(_))
+        ));
+        assert!(engine.verify_key("byok").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_import_key_versions_rejects_bad_input() {
+        let (_tmp, engine) = setup().await;
+        let material = || vec![7u8; 32];
+
+        let cases: Vec<Vec<(u32, Vec<u8>)>> = vec![
+            vec![],
+            vec![(2, material())],
+            vec![(1, material()), (3, material())],
+            vec![(1, material()), (1, material())],
+            vec![(1, vec![7u8; 16])],
+        ];
+        for versions in cases {
+            assert!(matches!(
+                engine
+                    .import_key_versions("bad", KeyType::Aes256Gcm, versions, KeyConfig::new())
+                    .await,
+                Err(TransitError::InvalidKeyMaterial(_))
+            ));
+        }
+        assert!(matches!(
+            engine
+                .import_key_versions(
+                    "bad",
+                    KeyType::Ed25519,
+                    vec![(1, material())],
+                    KeyConfig::new()
+                )
+                .await,
+            Err(TransitError::UnsupportedKeyType(_))
+        ));
+
+        engine.create_key("taken", KeyConfig::new()).await.unwrap();
+        assert!(matches!(
+            engine
+                .import_key_versions(
+                    "taken",
+                    KeyType::Aes256Gcm,
+                    vec![(1, material())],
+                    KeyConfig::new()
+                )
+                .await,
+            Err(TransitError::KeyExists(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_only_key_verifies_but_refuses_to_sign() {
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+
+        let (_tmp, engine) = setup().await;
+        let pair = Ed25519KeyPair::from_seed_unchecked(&[5u8; 32]).unwrap();
+        let key = engine
+            .import_public_key("peer", KeyType::Ed25519, pair.public_key().as_ref(), false)
+            .await
+            .unwrap();
+        assert!(!key.supports_signing);
+        assert!(!key.supports_encryption && !key.supports_decryption);
+
+        let signature = format!(
+            "egide:v1:ed25519:{}",
+            BASE64.encode(pair.sign(b"release-1.2.3").as_ref())
+        );
+        assert!(engine
+            .verify("peer", b"release-1.2.3", &signature)
+            .await
+            .unwrap());
+        assert!(!engine
+            .verify("peer", b"release-1.2.4", &signature)
+            .await
+            .unwrap());
+
+        assert!(matches!(
+            engine.sign("peer", b"release-1.2.3").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+        assert!(matches!(
+            engine.encrypt("peer", b"data").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+        assert!(matches!(
+            engine.rotate_key("peer").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_key_pair_without_signing_still_verifies() {
+        let (_tmp, engine) = setup().await;
+        let config = KeyConfig {
+            key_type: KeyType::Ed25519,
+            ..KeyConfig::new()
+        };
+        let key = engine.create_key("holder", config).await.unwrap();
+        assert!(!key.supports_signing);
+        assert!(!key.supports_encryption && !key.supports_decryption);
+        assert!(matches!(
+            engine.sign("holder", b"payload").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+
+        // Verification is still allowed: a forged signature is rejected as
+        // a mismatch, not refused.
+        assert!(!engine
+            .verify(
+                "holder",
+                b"payload",
+                &format!("egide:v1:ed25519:{}", BASE64.encode([0u8; 64]))
+            )
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_import_public_key_rejects_bad_input() {
+        let (_tmp, engine) = setup().await;
+        assert!(matches!(
+            engine
+                .import_public_key("pk", KeyType::Aes256Gcm, &[1u8; 32], false)
+                .await,
+            Err(TransitError::UnsupportedKeyType(_))
+        ));
+        assert!(matches!(
+            engine
+                .import_public_key("pk", KeyType::Ed25519, &[1u8; 31], false)
+                .await,
+            Err(TransitError::InvalidKeyMaterial(_))
+        ));
+
+        engine.create_key("aes", KeyConfig::new()).await.unwrap();
+        assert!(matches!(
+            engine.verify("aes", b"m", "egide:v1:ed25519:AAAA").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tampered_signing_flag_fails_policy_mac() {
+        let (_tmp, engine) = setup().await;
+        engine
+            .import_public_key("peer", KeyType::Ed25519, &[1u8; 32], false)
+            .await
+            .unwrap();
+        engine
+            .storage
+            .execute(
+                "UPDATE transit_keys SET supports_signing = 1 WHERE name = ?",
+                &["peer"],
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine.get_key("peer").await,
+            Err(TransitError::Integrity(_))
+        ));
+    }
+
+    async fn derivation_key(engine: &TransitEngine) {
         let mut config = KeyConfig::new();
-        config.deletion_allowed = true;
-        engine.create_key("can-delete", config).await.unwrap();
-        engine.delete_key("can-delete").await.unwrap();
+        config.supports_derivation = true;
+        engine.create_key("tree", config).await.unwrap();
+    }
 
-        let result = engine.get_key("can-delete").await;
-        assert!(matches!(result, Err(TransitError::KeyNotFound(_))));
+    #[tokio::test]
+    async fn test_derived_encrypt_decrypt_roundtrip_is_stable() {
+        let (_tmp, engine) = setup().await;
+        derivation_key(&engine).await;
+
+        let first = engine
+            .encrypt_derived("tree", "customer/123", b"hello")
+            .await
+            .unwrap();
+        let second = engine
+            .encrypt_derived("tree", "customer/123", b"hello")
+            .await
+            .unwrap();
+        assert!(first.starts_with("egide:v1:"));
+        assert_eq!(
+            engine
+                .decrypt_derived("tree", "customer/123", &first)
+                .await
+                .unwrap(),
+            b"hello"
+        );
+        assert_eq!(
+            engine
+                .decrypt_derived("tree", "customer/123", &second)
+                .await
+                .unwrap(),
+            b"hello"
+        );
+
+        // Same path, same subkey across calls.
+        let raw = engine.get_key_material("tree", 1).await.unwrap();
+        assert_eq!(
+            *TransitEngine::derive_subkey(&raw, "customer/123").unwrap(),
+            *TransitEngine::derive_subkey(&raw, "customer/123").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_context_derivation_roundtrip_and_isolation() {
+        let (_tmp, engine) = setup().await;
+        derivation_key(&engine).await;
+
+        let ciphertext = engine
+            .encrypt_with_context("tree", b"secret", b"tenant-a")
+            .await
+            .unwrap();
+        assert_eq!(
+            engine
+                .decrypt_with_context("tree", &ciphertext, b"tenant-a")
+                .await
+                .unwrap(),
+            b"secret"
+        );
+        assert!(matches!(
+            engine
+                .decrypt_with_context("tree", &ciphertext, b"tenant-b")
+                .await,
+            Err(TransitError::DecryptionFailed)
+        ));
+        // A context and a path spelling the same bytes stay distinct.
+        assert!(matches!(
+            engine
+                .decrypt_derived("tree", "tenant-a", &ciphertext)
+                .await,
+            Err(TransitError::DecryptionFailed)
+        ));
+
+        // Plain encryption is refused on a derivation key.
+        assert!(matches!(
+            engine.encrypt("tree", b"secret").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
+
+        engine.create_key("flat", KeyConfig::new()).await.unwrap();
+        assert!(matches!(
+            engine
+                .encrypt_with_context("flat", b"secret", b"tenant-a")
+                .await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_generate_datakey() {
+    async fn test_derived_paths_yield_independent_keys() {
         let (_tmp, engine) = setup().await;
+        derivation_key(&engine).await;
 
-        engine
-            .create_key("dek-key", KeyConfig::new())
-            .await
-            .unwrap();
-
-        let datakey = engine.generate_datakey("dek-key").await.unwrap();
-        assert_eq!(datakey.plaintext.len(), 32);
-        assert!(datakey.ciphertext.starts_with("egide:v1:"));
+        let raw = engine.get_key_material("tree", 1).await.unwrap();
+        let a = TransitEngine::derive_subkey(&raw, "customer/123").unwrap();
+        let b = TransitEngine::derive_subkey(&raw, "customer/124").unwrap();
+        let parent = TransitEngine::derive_subkey(&raw, "customer").unwrap();
+        assert_ne!(*a, *b);
+        assert_ne!(*a, *parent);
+        assert_ne!(&a[..], &raw[..]);
 
-        // Verify we can decrypt the wrapped key
-        let decrypted = engine
-            .decrypt_datakey("dek-key", &datakey.ciphertext)
+        let ciphertext = engine
+            .encrypt_derived("tree", "customer/123", b"secret")
             .await
             .unwrap();
-        assert_eq!(decrypted, datakey.plaintext);
-    }
-
-    #[test]
-    fn test_datakey_debug_redacts_plaintext() {
-        let datakey = DataKey {
-            plaintext: vec![0xAB; 32],
-            ciphertext: "egide:v1:dummy-ciphertext".to_string(),
-        };
-
-        let debug_str = format!("{datakey:?}");
-
-        // The decimal representation of 0xAB (171) must never leak into the
-        // Debug output, whether as a Vec<u8> element list or otherwise.
-        assert!(!debug_str.contains("171"));
-        assert!(debug_str.contains("REDACTED"));
+        for other in ["customer/124", "customer", "customer/123/x"] {
+            assert!(matches!(
+                engine.decrypt_derived("tree", other, &ciphertext).await,
+                Err(TransitError::DecryptionFailed)
+            ));
+        }
+        assert!(matches!(
+            engine.decrypt("tree", &ciphertext).await,
+            Err(TransitError::DecryptionFailed)
+        ));
     }
 
-    #[test]
-    fn test_datakey_zeroize_wipes_plaintext_and_skips_ciphertext() {
-        let mut datakey = DataKey {
-            plaintext: vec![0xAB; 32],
-            ciphertext: "egide:v1:dummy-ciphertext".to_string(),
-        };
-
-        datakey.zeroize();
+    #[tokio::test]
+    async fn test_derived_requires_derivation_enabled_and_valid_path() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("flat", KeyConfig::new()).await.unwrap();
+        assert!(matches!(
+            engine.encrypt_derived("flat", "customer/1", b"x").await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
 
-        // The plaintext key is wiped, while the non-sensitive ciphertext is
-        // skipped and left intact, confirming the field-level zeroize wiring.
-        assert!(datakey.plaintext.is_empty());
-        assert_eq!(datakey.ciphertext, "egide:v1:dummy-ciphertext");
+        derivation_key(&engine).await;
+        for bad in ["", "customer//1", "/customer", "customer/1:2", "a b"] {
+            assert!(
+                matches!(
+                    engine.encrypt_derived("tree", bad, b"x").await,
+                    Err(TransitError::OperationNotAllowed(_))
+                ),
+                "{bad:?}"
+            );
+        }
     }
 
     #[tokio::test]
-    async fn test_encryption_disabled() {
+    async fn test_key_prefix_validation() {
         let (_tmp, engine) = setup().await;
+        assert_eq!(engine.key_prefix(), "");
 
-        let mut config = KeyConfig::new();
-        config.supports_encryption = false;
-
-        engine.create_key("no-enc", config).await.unwrap();
+        for bad in ["transit", "/transit/", "a//", "tran sit/", "a:b/"] {
+            let (_tmp, engine) = setup().await;
+            assert!(
+                matches!(
+                    engine.with_key_prefix(bad),
+                    Err(TransitError::InvalidKeyName(_))
+                ),
+                "{bad}"
+            );
+        }
 
-        let result = engine.encrypt("no-enc", b"data").await;
-        assert!(matches!(result, Err(TransitError::OperationNotAllowed(_))));
+        let engine = engine.with_key_prefix("tenant-1/transit/").unwrap();
+        assert_eq!(engine.key_prefix(), "tenant-1/transit/");
     }
 
     #[tokio::test]
-    async fn test_decryption_disabled() {
+    async fn test_list_versions() {
         let (_tmp, engine) = setup().await;
 
-        // Create key, encrypt something, then test with a decrypt-disabled key
         engine
-            .create_key("enc-only", KeyConfig::new())
+            .create_key("ver-key", KeyConfig::new())
             .await
             .unwrap();
-        let ciphertext = engine.encrypt("enc-only", b"data").await.unwrap();
-
-        // Create a new engine to test with a decrypt-disabled key
-        let (_tmp2, engine2) = setup().await;
-        let mut config = KeyConfig::new();
-        config.supports_decryption = false;
-        engine2.create_key("no-dec", config).await.unwrap();
+        engine.rotate_key("ver-key").await.unwrap();
+        engine.rotate_key("ver-key").await.unwrap();
 
-        let result = engine2.decrypt("no-dec", &ciphertext).await;
-        assert!(matches!(result, Err(TransitError::OperationNotAllowed(_))));
+        let versions = engine.list_versions("ver-key").await.unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].version, 3); // Most recent first
+        assert_eq!(versions[1].version, 2);
+        assert_eq!(versions[2].version, 1);
     }
 
     #[tokio::test]
-    async fn test_invalid_ciphertext() {
+    async fn test_rotate_if_due_follows_interval() {
         let (_tmp, engine) = setup().await;
-
+        let config = KeyConfig {
+            auto_rotate_interval: Some(Duration::from_hours(1)),
+            ..KeyConfig::new()
+        };
+        let key = engine.create_key("scheduled", config).await.unwrap();
+        assert_eq!(key.next_rotation_at(), Some(key.updated_at + 3600));
+        assert_eq!(engine.rotate_if_due("scheduled").await.unwrap(), None);
+
+        // Backdate the last change past the interval, re-signing the row.
+        let stored = engine.load_key("scheduled").await.unwrap();
+        let backdated = TransitKey {
+            updated_at: stored.updated_at - 7200,
+            ..stored
+        };
+        let mac = engine.policy_mac(&backdated).unwrap();
         engine
-            .create_key("test-key", KeyConfig::new())
+            .storage
+            .execute(
+                "UPDATE transit_keys SET updated_at = ?, row_mac = ? WHERE name = ?",
+                &[&backdated.updated_at.to_string(), &mac, &backdated.name],
+            )
             .await
             .unwrap();
 
-        let result = engine.decrypt("test-key", "invalid").await;
-        assert!(matches!(result, Err(TransitError::InvalidCiphertext)));
+        assert_eq!(engine.rotate_if_due("scheduled").await.unwrap(), Some(2));
+        // The rotation restarts the interval.
+        assert_eq!(engine.rotate_if_due("scheduled").await.unwrap(), None);
+        let key = engine.get_key("scheduled").await.unwrap();
+        assert_eq!(key.latest_version, 2);
+        assert_eq!(key.auto_rotate_interval, Some(Duration::from_hours(1)));
 
-        let result = engine.decrypt("test-key", "not:a:valid:format").await;
-        assert!(matches!(result, Err(TransitError::InvalidCiphertext)));
+        // Keys without an interval never rotate on their own.
+        engine.create_key("manual", KeyConfig::new()).await.unwrap();
+        assert_eq!(engine.rotate_if_due("manual").await.unwrap(), None);
+        assert_eq!(
+            engine.get_key("manual").await.unwrap().next_rotation_at(),
+            None
+        );
+    }
 
-        let result = engine
-            .decrypt("test-key", "egide:v1:!!!invalid-base64!!!")
-            .await;
-        assert!(matches!(result, Err(TransitError::InvalidCiphertext)));
+    #[tokio::test]
+    async fn test_auto_rotate_interval_rejects_sub_second() {
+        let (_tmp, engine) = setup().await;
+        let config = KeyConfig {
+            auto_rotate_interval: Some(Duration::from_millis(500)),
+            ..KeyConfig::new()
+        };
+        assert!(matches!(
+            engine.create_key("too-fast", config).await,
+            Err(TransitError::InvalidKeyConfig(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_key_isolation() {
+    async fn test_trim_key_removes_old_versions() {
         let (_tmp, engine) = setup().await;
+        engine.create_key("trim", KeyConfig::new()).await.unwrap();
+        let old = engine.encrypt("trim", b"old").await.unwrap();
+        engine.rotate_key("trim").await.unwrap();
+        engine.rotate_key("trim").await.unwrap();
+        let current = engine.encrypt("trim", b"current").await.unwrap();
 
-        engine.create_key("key-a", KeyConfig::new()).await.unwrap();
-        engine.create_key("key-b", KeyConfig::new()).await.unwrap();
+        // Versions still allowed to decrypt cannot be trimmed.
+        assert!(matches!(
+            engine.trim_key("trim", 3).await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
 
-        let ciphertext = engine.encrypt("key-a", b"secret").await.unwrap();
+        engine
+            .update_key_config("trim", Some(3), Some(3), None, None)
+            .await
+            .unwrap();
+        assert_eq!(engine.trim_key("trim", 3).await.unwrap(), 2);
+        assert_eq!(engine.trim_key("trim", 2).await.unwrap(), 0);
 
-        // Should fail when trying to decrypt with different key
-        let result = engine.decrypt("key-b", &ciphertext).await;
-        assert!(matches!(result, Err(TransitError::DecryptionFailed)));
+        let key = engine.get_key("trim").await.unwrap();
+        assert_eq!(key.min_available_version, 3);
+        let versions = engine.list_versions("trim").await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, 3);
+
+        assert!(matches!(
+            engine.decrypt("trim", &old).await,
+            Err(TransitError::VersionTrimmed {
+                version: 1,
+                min_available: 3,
+                ..
+            })
+        ));
+        assert_eq!(engine.decrypt("trim", &current).await.unwrap(), b"current");
     }
 
     #[tokio::test]
-    async fn test_list_versions() {
+    async fn test_update_key_config_sets_min_available_version() {
         let (_tmp, engine) = setup().await;
+        engine
+            .create_key("archive", KeyConfig::new())
+            .await
+            .unwrap();
+        let v1 = engine.encrypt("archive", b"v1").await.unwrap();
+        engine.rotate_key("archive").await.unwrap();
+        let v2 = engine.encrypt("archive", b"v2").await.unwrap();
+        engine.rotate_key("archive").await.unwrap();
+
+        // The available floor may not pass the decryption floor.
+        assert!(matches!(
+            engine
+                .update_key_config("archive", Some(3), Some(2), Some(3), None)
+                .await,
+            Err(TransitError::OperationNotAllowed(_))
+        ));
 
+        // Stop v1 and v2 from decrypting but keep their material...
         engine
-            .create_key("ver-key", KeyConfig::new())
+            .update_key_config("archive", Some(3), Some(3), None, None)
             .await
             .unwrap();
-        engine.rotate_key("ver-key").await.unwrap();
-        engine.rotate_key("ver-key").await.unwrap();
+        assert!(matches!(
+            engine.decrypt("archive", &v2).await,
+            Err(TransitError::VersionBelowMinDecryption { .. })
+        ));
+        engine
+            .update_key_config("archive", None, Some(2), None, None)
+            .await
+            .unwrap();
+        assert_eq!(engine.decrypt("archive", &v2).await.unwrap(), b"v2");
 
-        let versions = engine.list_versions("ver-key").await.unwrap();
-        assert_eq!(versions.len(), 3);
-        assert_eq!(versions[0].version, 3); // Most recent first
-        assert_eq!(versions[1].version, 2);
-        assert_eq!(versions[2].version, 1);
+        // ...then physically remove v1 only.
+        engine
+            .update_key_config("archive", None, None, Some(2), None)
+            .await
+            .unwrap();
+        let key = engine.get_key("archive").await.unwrap();
+        assert_eq!(key.min_available_version, 2);
+        assert_eq!(engine.list_versions("archive").await.unwrap().len(), 2);
+        assert!(matches!(
+            engine.decrypt("archive", &v1).await,
+            Err(TransitError::VersionTrimmed { version: 1, .. })
+        ));
+
+        // Lowering the available floor cannot bring material back.
+        engine
+            .update_key_config("archive", None, None, Some(1), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            engine
+                .get_key("archive")
+                .await
+                .unwrap()
+                .min_available_version,
+            2
+        );
     }
 
     // ========================================================================
     // Edge Case Tests
     // ========================================================================
 
+    #[tokio::test]
+    async fn test_encrypt_rejects_oversized_plaintext_before_key_lookup() {
+        let (_tmp, engine) = setup().await;
+        let engine = engine.with_max_plaintext_bytes(8);
+        engine.create_key("small", KeyConfig::new()).await.unwrap();
+
+        assert!(engine.encrypt("small", b"12345678").await.is_ok());
+        assert!(matches!(
+            engine.encrypt("small", b"123456789").await,
+            Err(TransitError::InputTooLarge { size: 9, max: 8 })
+        ));
+        // Checked first, so even a missing key reports the size.
+        assert!(matches!(
+            engine.encrypt_with_version("missing", &[0; 9], 1).await,
+            Err(TransitError::InputTooLarge { .. })
+        ));
+        let results = engine
+            .encrypt_batch("small", &[b"ok".to_vec(), vec![0; 9]])
+            .await
+            .unwrap();
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            results[1],
+            Err(TransitError::InputTooLarge { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_raw_ciphertext_round_trips_without_envelope() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("blob", KeyConfig::new()).await.unwrap();
+        engine.rotate_key("blob").await.unwrap();
+
+        let (version, data) = engine.encrypt_raw("blob", b"compact").await.unwrap();
+        assert_eq!(version, 2);
+        // Nonce, ciphertext and tag only.
+        assert_eq!(data.len(), 12 + b"compact".len() + 16);
+        assert_eq!(
+            engine.decrypt_raw("blob", version, &data).await.unwrap(),
+            b"compact"
+        );
+
+        // The version is bound into the associated data.
+        assert!(matches!(
+            engine.decrypt_raw("blob", 1, &data).await,
+            Err(TransitError::DecryptionFailed)
+        ));
+        assert!(matches!(
+            engine.decrypt_raw("blob", 3, &data).await,
+            Err(TransitError::VersionNotFound { .. })
+        ));
+
+        // The envelope form wraps exactly the same bytes.
+        let enveloped = format!("egide:v{version}:{}", BASE64.encode(&data));
+        assert_eq!(
+            engine.decrypt("blob", &enveloped).await.unwrap(),
+            b"compact"
+        );
+    }
+
     #[tokio::test]
     async fn test_encrypt_empty_data() {
         let (_tmp, engine) = setup().await;
@@ -1666,7 +6811,7 @@ mod tests {
 
         // Set min_encryption_version to 2
         engine
-            .update_key_config("min-enc", Some(2), None, None)
+            .update_key_config("min-enc", Some(2), None, None, None)
             .await
             .unwrap();
 
@@ -1685,6 +6830,40 @@ mod tests {
         assert!(ciphertext.starts_with("egide:v2:"));
     }
 
+    #[tokio::test]
+    async fn test_encryption_version_window_tracks_min_encryption_version() {
+        let (_tmp, engine) = setup().await;
+        engine.create_key("window", KeyConfig::new()).await.unwrap();
+        assert_eq!(
+            engine.encryption_version_window("window").await.unwrap(),
+            (1, 1)
+        );
+
+        engine.rotate_key("window").await.unwrap();
+        engine.rotate_key("window").await.unwrap();
+        engine
+            .update_key_config("window", Some(2), None, None, None)
+            .await
+            .unwrap();
+
+        let (min, latest) = engine.encryption_version_window("window").await.unwrap();
+        assert_eq!((min, latest), (2, 3));
+        for version in min..=latest {
+            engine
+                .encrypt_with_version("window", b"data", version)
+                .await
+                .unwrap();
+        }
+        assert!(engine
+            .encrypt_with_version("window", b"data", min - 1)
+            .await
+            .is_err());
+        assert!(matches!(
+            engine.encryption_version_window("missing").await,
+            Err(TransitError::KeyNotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_encrypt_with_nonexistent_version() {
         let (_tmp, engine) = setup().await;
@@ -1848,13 +7027,13 @@ mod tests {
 
         // Try to set min_encryption_version higher than latest
         let result = engine
-            .update_key_config("cfg-ver", Some(99), None, None)
+            .update_key_config("cfg-ver", Some(99), None, None, None)
             .await;
         assert!(matches!(result, Err(TransitError::VersionNotFound { .. })));
 
         // Try to set min_decryption_version higher than latest
         let result = engine
-            .update_key_config("cfg-ver", None, Some(99), None)
+            .update_key_config("cfg-ver", None, Some(99), None, None)
             .await;
         assert!(matches!(result, Err(TransitError::VersionNotFound { .. })));
     }
@@ -1915,7 +7094,7 @@ mod tests {
 
         // 7. Update min_decryption_version to deprecate v1
         engine
-            .update_key_config("lifecycle", None, Some(2), None)
+            .update_key_config("lifecycle", None, Some(2), None, None)
             .await
             .unwrap();
 
@@ -2136,7 +7315,7 @@ mod tests {
             .unwrap();
         engine.rotate_key("kdec").await.unwrap(); // latest = 2
         engine
-            .update_key_config("kdec", None, Some(2), None)
+            .update_key_config("kdec", None, Some(2), None, None)
             .await
             .unwrap();
 
@@ -2194,7 +7373,7 @@ mod tests {
         let current = engine.get_key("ktype").await.unwrap();
         let other = match current.key_type {
             KeyType::Aes256Gcm => "chacha20-poly1305",
-            KeyType::ChaCha20Poly1305 => "aes256-gcm",
+            KeyType::ChaCha20Poly1305 | KeyType::Ed25519 => "aes256-gcm",
         };
         engine
             .storage
@@ -2247,7 +7426,7 @@ mod tests {
         engine.rotate_key("klife").await.unwrap();
         assert_eq!(engine.get_key("klife").await.unwrap().latest_version, 2);
         engine
-            .update_key_config("klife", None, Some(2), None)
+            .update_key_config("klife", None, Some(2), None, None)
             .await
             .unwrap();
         assert_eq!(
@@ -2367,8 +7546,8 @@ mod tests {
     //
     // 0.1.0 accepted chacha20-poly1305 at key creation, persisted that
     // declared type, and encrypted under AES-256-GCM regardless, emitting the
-    // short ciphertext form. create_key now refuses that type outright, so a
-    // legacy row is simulated directly: the key_type column is rewritten and
+    // short ciphertext form. create_key now really encrypts under that type,
+    // so a legacy row is simulated directly: the key_type column is rewritten and
     // the policy-row MAC recomputed over the rewritten row, exactly as the
     // historical 0.1.0 write path would have produced it. A plain UPDATE of
     // key_type alone would leave a stale MAC and fail every read with
@@ -2403,8 +7582,8 @@ mod tests {
             .await
             .unwrap();
 
-        // The declared type is now the never-implemented ChaCha20-Poly1305,
-        // but the ciphertext bytes are (and always were) AES-256-GCM.
+        // The declared type is now ChaCha20-Poly1305, but the ciphertext
+        // bytes are (and always were) AES-256-GCM.
         // Refusing to read it back would make production data permanently
         // inaccessible.
         let decrypted = engine.decrypt("legacy-chacha", &ciphertext).await.unwrap();
@@ -2412,13 +7591,17 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn legacy_chacha20_declared_key_refuses_new_encryption() {
+    async fn legacy_chacha20_declared_key_encrypts_under_chacha20_and_rotates() {
         let (_tmp, engine) = setup().await;
 
         let key = engine
             .create_key("legacy-chacha-enc", KeyConfig::new())
             .await
             .unwrap();
+        let old = engine
+            .encrypt("legacy-chacha-enc", b"old-data")
+            .await
+            .unwrap();
 
         let legacy = TransitKey {
             key_type: KeyType::ChaCha20Poly1305,
@@ -2434,62 +7617,53 @@ mod tests {
             .await
             .unwrap();
 
-        let result = engine.encrypt("legacy-chacha-enc", b"new-data").await;
-        assert!(
-            matches!(
-                result,
-                Err(TransitError::KeyAlgorithmNotImplemented(
-                    KeyType::ChaCha20Poly1305
-                ))
-            ),
-            "a legacy key declared chacha20-poly1305 must refuse new encryption \
-             rather than emit a mislabeled ciphertext, got {result:?}"
+        // New data goes under the declared cipher, labelled as such.
+        let new = engine
+            .encrypt("legacy-chacha-enc", b"new-data")
+            .await
+            .unwrap();
+        assert!(new.starts_with("egide:v1:chacha20-poly1305:"));
+        assert_eq!(
+            engine.decrypt("legacy-chacha-enc", &new).await.unwrap(),
+            b"new-data"
+        );
+
+        // Rotation works, and rewrap moves the legacy AES-256-GCM ciphertext
+        // onto the declared cipher.
+        assert_eq!(engine.rotate_key("legacy-chacha-enc").await.unwrap(), 2);
+        let rewrapped = engine.rewrap("legacy-chacha-enc", &old).await.unwrap();
+        assert!(rewrapped.starts_with("egide:v2:chacha20-poly1305:"));
+        assert_eq!(
+            engine
+                .decrypt("legacy-chacha-enc", &rewrapped)
+                .await
+                .unwrap(),
+            b"old-data"
         );
     }
 
     #[tokio::test]
-    async fn legacy_chacha20_declared_key_refuses_rotation() {
+    async fn chacha20_labelled_ciphertext_is_refused_by_aes_key() {
         let (_tmp, engine) = setup().await;
-
-        let key = engine
-            .create_key("legacy-chacha-rotate", KeyConfig::new())
-            .await
-            .unwrap();
-
-        let legacy = TransitKey {
+        let config = KeyConfig {
             key_type: KeyType::ChaCha20Poly1305,
-            ..key
+            ..KeyConfig::default()
         };
-        let legacy_mac = engine.policy_mac(&legacy).unwrap();
+        engine.create_key("chacha-src", config).await.unwrap();
         engine
-            .storage
-            .execute(
-                "UPDATE transit_keys SET key_type = ?, row_mac = ? WHERE name = ?",
-                &["chacha20-poly1305", &legacy_mac, "legacy-chacha-rotate"],
-            )
+            .create_key("aes-dst", KeyConfig::new())
             .await
             .unwrap();
 
-        let result = engine.rotate_key("legacy-chacha-rotate").await;
-        assert!(
-            matches!(
-                result,
-                Err(TransitError::KeyAlgorithmNotImplemented(
-                    KeyType::ChaCha20Poly1305
-                ))
-            ),
-            "rotating a legacy key declared under a never-implemented \
-             algorithm must fail, since the new version could never encrypt \
-             anything, got {result:?}"
-        );
-
-        // The rejection must happen before any new version row is written.
-        let versions = engine.list_versions("legacy-chacha-rotate").await.unwrap();
-        assert_eq!(
-            versions.len(),
-            1,
-            "rotation must not persist a new version on rejection"
-        );
+        let ciphertext = engine.encrypt("chacha-src", b"data").await.unwrap();
+        let result = engine.decrypt("aes-dst", &ciphertext).await;
+        assert!(matches!(
+            result,
+            Err(TransitError::CiphertextAlgorithmMismatch {
+                expected: KeyType::Aes256Gcm,
+                found: KeyType::ChaCha20Poly1305,
+            })
+        ));
     }
 
     #[tokio::test]
@@ -0,0 +1,93 @@
+//! In-memory accumulation of transit key usage between storage flushes.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Usage of one key not yet written to storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct PendingUsage {
+    /// Successful operations since the last flush.
+    pub(crate) count: u64,
+    /// Unix seconds of the most recent of those operations.
+    pub(crate) last_used_at: u64,
+}
+
+impl PendingUsage {
+    fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.last_used_at = self.last_used_at.max(other.last_used_at);
+    }
+}
+
+struct Inner {
+    pending: HashMap<String, PendingUsage>,
+    last_flush: Instant,
+}
+
+/// Per-key usage counters, keyed by stored key name, flushed to storage at
+/// most once per `flush_interval`.
+pub(crate) struct UsageTracker {
+    flush_interval: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl UsageTracker {
+    /// Creates a tracker asking for a flush once `flush_interval` has passed
+    /// since the previous one; zero flushes on every operation.
+    pub(crate) fn new(flush_interval: Duration) -> Self {
+        Self {
+            flush_interval,
+            inner: Mutex::new(Inner {
+                pending: HashMap::new(),
+                last_flush: Instant::now(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        // Every update is a single map operation, so a poisoned lock still
+        // guards consistent counters.
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Adds `count` operations at `now` and returns whether a flush is due.
+    pub(crate) fn record(&self, name: &str, count: u64, now: u64) -> bool {
+        let mut inner = self.lock();
+        inner
+            .pending
+            .entry(name.to_string())
+            .or_default()
+            .merge(PendingUsage {
+                count,
+                last_used_at: now,
+            });
+        inner.last_flush.elapsed() >= self.flush_interval
+    }
+
+    /// Returns the usage of `name` not yet flushed.
+    pub(crate) fn pending(&self, name: &str) -> Option<PendingUsage> {
+        self.lock().pending.get(name).copied()
+    }
+
+    /// Removes and returns every pending entry, restarting the interval.
+    pub(crate) fn take(&self) -> HashMap<String, PendingUsage> {
+        let mut inner = self.lock();
+        inner.last_flush = Instant::now();
+        std::mem::take(&mut inner.pending)
+    }
+
+    /// Puts back entries whose flush failed, merging with anything recorded
+    /// since.
+    pub(crate) fn restore(&self, entries: HashMap<String, PendingUsage>) {
+        let mut inner = self.lock();
+        for (name, usage) in entries {
+            inner.pending.entry(name).or_default().merge(usage);
+        }
+    }
+
+    /// Drops the pending usage of `name`, whose row was deleted or replaced.
+    pub(crate) fn forget(&self, name: &str) {
+        self.lock().pending.remove(name);
+    }
+}
@@ -1,15 +1,19 @@
 //! Transport-agnostic application context shared by REST and gRPC.
 
 use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
 use std::time::Instant;
 
 use tokio::sync::RwLock;
 
 use egide_auth::{AuthService, ServiceTokenStore};
+use egide_crypto::MasterKey;
 use egide_seal::{SealManager, SealStatus};
 use egide_secrets::SecretsEngine;
 use egide_transit::TransitEngine;
 
+use crate::{PrefixMetrics, SecretGenerators};
+
 /// Shared application state, owned as `Arc<ServiceContext>` by every transport.
 pub struct ServiceContext {
     /// Authentication service (composed backends).
@@ -28,6 +32,17 @@ pub struct ServiceContext {
     pub version: &'static str,
     /// Native service token store (shared with the auth backend).
     pub service_tokens: ServiceTokenStore,
+    /// Named generators available to secret rotation.
+    pub generators: SecretGenerators,
+    /// Whether engine databases are encrypted at rest under a key derived
+    /// from the master key (`--encrypt-storage`).
+    pub encrypt_storage: bool,
+    /// Secret operation counters per tracked path prefix, exposed on
+    /// `/metrics`.
+    pub secret_metrics: PrefixMetrics,
+    /// Secret reads that failed to decrypt or authenticate (a wrong master
+    /// key or corrupted storage), exposed on `/metrics`.
+    pub secret_decrypt_failures: AtomicU64,
 }
 
 impl ServiceContext {
@@ -44,16 +59,27 @@ impl ServiceContext {
 
         let mut secrets = self.secrets.write().await;
         if secrets.is_none() {
-            // Use "default" tenant for v0.1
-            let engine = SecretsEngine::new(&self.data_dir, "default", master_key.clone())
-                .await
-                .map_err(|e| e.to_string())?;
-            *secrets = Some(engine);
+            *secrets = Some(self.open_secrets_engine(master_key.clone()).await?);
             tracing::info!("Secrets engine initialized");
         }
         Ok(())
     }
 
+    /// Opens the secrets engine under `master_key`, without touching the
+    /// shared slot.
+    pub(crate) async fn open_secrets_engine(
+        &self,
+        master_key: MasterKey,
+    ) -> Result<SecretsEngine, String> {
+        // Use "default" tenant for v0.1
+        if self.encrypt_storage {
+            SecretsEngine::new_encrypted(&self.data_dir, "default", master_key).await
+        } else {
+            SecretsEngine::new(&self.data_dir, "default", master_key).await
+        }
+        .map_err(|e| e.to_string())
+    }
+
     /// Clears the secrets engine (called on seal).
     pub async fn clear_secrets_engine(&self) {
         let mut secrets = self.secrets.write().await;
@@ -74,19 +100,49 @@ impl ServiceContext {
 
         let mut transit = self.transit.write().await;
         if transit.is_none() {
-            let engine = TransitEngine::new(&self.data_dir, master_key.clone())
-                .await
-                .map_err(|e| e.to_string())?;
-            *transit = Some(engine);
+            *transit = Some(self.open_transit_engine(master_key.clone()).await?);
             tracing::info!("Transit engine initialized");
         }
         Ok(())
     }
 
-    /// Clears the transit engine (called on seal).
+    /// Opens the transit engine under `master_key`, without touching the
+    /// shared slot.
+    pub(crate) async fn open_transit_engine(
+        &self,
+        master_key: MasterKey,
+    ) -> Result<TransitEngine, String> {
+        if self.encrypt_storage {
+            TransitEngine::new_encrypted(&self.data_dir, master_key).await
+        } else {
+            TransitEngine::new(&self.data_dir, master_key).await
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    /// Clears the transit engine (called on seal), writing its pending key
+    /// usage first.
     pub async fn clear_transit_engine(&self) {
         let mut transit = self.transit.write().await;
+        flush_pending_usage(transit.as_ref()).await;
         *transit = None;
         tracing::info!("Transit engine cleared");
     }
+
+    /// Writes the pending key usage of the transit engine, if one is open.
+    ///
+    /// Usage is batched in memory between flushes, so call this on shutdown.
+    pub async fn flush_transit_usage(&self) {
+        flush_pending_usage(self.transit.read().await.as_ref()).await;
+    }
+}
+
+/// Writes the pending key usage of `engine`. A failure is only logged: the
+/// counters are informational and nothing else depends on the write.
+pub(crate) async fn flush_pending_usage(engine: Option<&TransitEngine>) {
+    if let Some(engine) = engine {
+        if let Err(e) = engine.flush_usage().await {
+            tracing::warn!(error = %e, "Failed to flush transit key usage");
+        }
+    }
 }
@@ -27,6 +27,14 @@ pub enum ServiceError {
     /// Decryption failed (kept distinct for intent; mapped like `BadRequest`, anti-oracle).
     #[error("decryption failed")]
     DecryptionFailed,
+    /// The operation was refused because it ran too recently; retry later.
+    #[error("rate limited: {0}")]
+    RateLimited(String),
+    /// Stored data failed to decrypt or authenticate: a wrong master key or
+    /// corrupted storage. Unlike [`ServiceError::DecryptionFailed`], which
+    /// rejects caller-supplied ciphertext, this is a server-side fault.
+    #[error("decryption failed: {0}")]
+    Undecryptable(String),
     /// Unexpected engine or storage failure.
     #[error("internal: {0}")]
     Internal(String),
@@ -0,0 +1,191 @@
+//! Pluggable value generators used to rotate secrets.
+//!
+//! A rotation replaces one field of a secret with a freshly generated value
+//! and writes it as a new version. Generators are looked up by name in a
+//! [`SecretGenerators`] registry held by the [`crate::ServiceContext`]; the
+//! default registry provides `random-password`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use egide_crypto::random;
+
+use crate::ServiceError;
+
+/// Name under which [`RandomPassword`] is registered by default.
+pub const RANDOM_PASSWORD: &str = "random-password";
+
+/// Characters drawn by the default `random-password` generator.
+pub const DEFAULT_PASSWORD_CHARSET: &str =
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_.~!@#%^&*";
+
+/// Length of passwords produced by the default `random-password` generator.
+pub const DEFAULT_PASSWORD_LENGTH: usize = 32;
+
+/// Longest value a [`RandomPassword`] generator may be configured to produce.
+const MAX_PASSWORD_LENGTH: usize = 1024;
+
+/// Produces new secret values.
+pub trait SecretGenerator: Send + Sync {
+    /// Generates a fresh value.
+    fn generate(&self) -> Result<String, ServiceError>;
+}
+
+/// Generates uniformly random passwords over a fixed character set.
+#[derive(Debug, Clone)]
+pub struct RandomPassword {
+    length: usize,
+    charset: Vec<char>,
+}
+
+impl RandomPassword {
+    /// Creates a generator producing `length` characters drawn from `charset`.
+    ///
+    /// Returns [`ServiceError::BadRequest`] if `length` is zero or above 1024,
+    /// or if `charset` is empty, has more than 256 characters, or repeats a
+    /// character (which would bias the draw towards it).
+    pub fn new(length: usize, charset: &str) -> Result<Self, ServiceError> {
+        if length == 0 || length > MAX_PASSWORD_LENGTH {
+            return Err(ServiceError::BadRequest(format!(
+                "password length must be between 1 and {MAX_PASSWORD_LENGTH}"
+            )));
+        }
+        let chars: Vec<char> = charset.chars().collect();
+        if chars.is_empty() || chars.len() > 256 {
+            return Err(ServiceError::BadRequest(
+                "password charset must have between 1 and 256 characters".into(),
+            ));
+        }
+        let mut unique = chars.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        if unique.len() != chars.len() {
+            return Err(ServiceError::BadRequest(
+                "password charset must not repeat characters".into(),
+            ));
+        }
+        Ok(Self {
+            length,
+            charset: chars,
+        })
+    }
+}
+
+impl Default for RandomPassword {
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_PASSWORD_LENGTH,
+            charset: DEFAULT_PASSWORD_CHARSET.chars().collect(),
+        }
+    }
+}
+
+impl SecretGenerator for RandomPassword {
+    fn generate(&self) -> Result<String, ServiceError> {
+        // Rejection sampling: bytes at or above the largest multiple of the
+        // charset size are discarded so every character is equally likely.
+        let n = self.charset.len();
+        let limit = 256 - (256 % n);
+        let mut out = String::with_capacity(self.length);
+        let mut produced = 0;
+        while produced < self.length {
+            let bytes = random::generate_bytes(self.length - produced + 16)
+                .map_err(|e| ServiceError::Internal(e.to_string()))?;
+            for &b in bytes.iter() {
+                if produced == self.length {
+                    break;
+                }
+                let b = usize::from(b);
+                if b < limit {
+                    out.push(self.charset[b % n]);
+                    produced += 1;
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Registry of named [`SecretGenerator`]s.
+///
+/// [`SecretGenerators::default`] registers [`RandomPassword`] under
+/// [`RANDOM_PASSWORD`]; [`SecretGenerators::new`] starts empty.
+#[derive(Clone)]
+pub struct SecretGenerators {
+    generators: HashMap<String, Arc<dyn SecretGenerator>>,
+}
+
+impl SecretGenerators {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            generators: HashMap::new(),
+        }
+    }
+
+    /// Registers `generator` under `name`, replacing any previous entry.
+    #[must_use]
+    pub fn with(
+        mut self,
+        name: impl Into<String>,
+        generator: impl SecretGenerator + 'static,
+    ) -> Self {
+        self.generators.insert(name.into(), Arc::new(generator));
+        self
+    }
+
+    /// Looks up a generator by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&dyn SecretGenerator> {
+        self.generators.get(name).map(AsRef::as_ref)
+    }
+}
+
+impl Default for SecretGenerators {
+    fn default() -> Self {
+        Self::new().with(RANDOM_PASSWORD, RandomPassword::default())
+    }
+}
+
+impl std::fmt::Debug for SecretGenerators {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<&str> = self.generators.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        f.debug_struct("SecretGenerators")
+            .field("generators", &names)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_password_respects_length_and_charset() {
+        let generator = RandomPassword::new(40, "ab1").unwrap();
+        let value = generator.generate().unwrap();
+        assert_eq!(value.chars().count(), 40);
+        assert!(value.chars().all(|c| "ab1".contains(c)));
+        assert_ne!(value, generator.generate().unwrap());
+    }
+
+    #[test]
+    fn random_password_rejects_bad_config() {
+        assert!(RandomPassword::new(0, "abc").is_err());
+        assert!(RandomPassword::new(MAX_PASSWORD_LENGTH + 1, "abc").is_err());
+        assert!(RandomPassword::new(8, "").is_err());
+        assert!(RandomPassword::new(8, "aab").is_err());
+    }
+
+    #[test]
+    fn default_registry_has_random_password() {
+        let registry = SecretGenerators::default();
+        let value = registry.get(RANDOM_PASSWORD).unwrap().generate().unwrap();
+        assert_eq!(value.len(), DEFAULT_PASSWORD_LENGTH);
+        assert!(registry.get("nope").is_none());
+        assert!(SecretGenerators::new().get(RANDOM_PASSWORD).is_none());
+    }
+}
@@ -16,6 +16,12 @@ pub mod transit;
 
 pub mod service_tokens;
 
+pub mod generators;
+pub use generators::SecretGenerators;
+
+pub mod prefix_metrics;
+pub use prefix_metrics::PrefixMetrics;
+
 /// Generated protobuf/gRPC types for the `egide.v1` package.
 #[allow(missing_docs, clippy::all, clippy::pedantic)]
 pub mod proto {
@@ -0,0 +1,114 @@
+//! Per-prefix secret operation counters, for attributing usage to teams.
+//!
+//! An operation is attributed to the top-level segment of its path (`teamA`
+//! for `teamA/db/password`) when that segment is one of the tracked
+//! prefixes, and to [`OTHER_PREFIX`] otherwise. With no tracked prefixes,
+//! nothing is counted.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Label under which operations outside every tracked prefix are counted.
+pub const OTHER_PREFIX: &str = "other";
+
+/// Operation counters keyed by top-level path prefix.
+#[derive(Debug, Default)]
+pub struct PrefixMetrics {
+    /// Tracked prefixes, in configuration order.
+    prefixes: Vec<String>,
+    /// One counter per tracked prefix, then the [`OTHER_PREFIX`] counter.
+    counts: Vec<AtomicU64>,
+}
+
+impl PrefixMetrics {
+    /// Creates counters for the given top-level prefixes.
+    ///
+    /// Surrounding `/` are ignored, and empty or duplicate prefixes are
+    /// dropped. A prefix spelled `other` is dropped too: its operations land
+    /// in the [`OTHER_PREFIX`] counter either way.
+    #[must_use]
+    pub fn new<I, S>(prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut tracked: Vec<String> = Vec::new();
+        for prefix in prefixes {
+            let prefix = prefix.as_ref().trim_matches('/');
+            if !prefix.is_empty() && prefix != OTHER_PREFIX && !tracked.iter().any(|p| p == prefix)
+            {
+                tracked.push(prefix.to_string());
+            }
+        }
+        let counts = if tracked.is_empty() {
+            Vec::new()
+        } else {
+            (0..=tracked.len()).map(|_| AtomicU64::new(0)).collect()
+        };
+        Self {
+            prefixes: tracked,
+            counts,
+        }
+    }
+
+    /// Whether any prefix is tracked.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        !self.prefixes.is_empty()
+    }
+
+    /// Counts one operation on `path`.
+    pub fn record(&self, path: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let top = path.trim_start_matches('/').split('/').next().unwrap_or("");
+        let index = self
+            .prefixes
+            .iter()
+            .position(|p| p == top)
+            .unwrap_or(self.prefixes.len());
+        self.counts[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current count per label: the tracked prefixes in configuration order,
+    /// then [`OTHER_PREFIX`]. Empty when no prefix is tracked.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<(&str, u64)> {
+        self.prefixes
+            .iter()
+            .map(String::as_str)
+            .chain(self.is_enabled().then_some(OTHER_PREFIX))
+            .zip(&self.counts)
+            .map(|(label, count)| (label, count.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_operations_to_top_level_prefix() {
+        let metrics = PrefixMetrics::new(["teamA", "/teamB/", "teamA", "", "other"]);
+        metrics.record("teamA/db/password");
+        metrics.record("teamA/api");
+        metrics.record("teamB/token");
+        metrics.record("teamAB/token");
+        metrics.record("shared");
+
+        assert_eq!(
+            metrics.snapshot(),
+            vec![("teamA", 2), ("teamB", 1), (OTHER_PREFIX, 2)]
+        );
+    }
+
+    #[test]
+    fn disabled_without_prefixes() {
+        let metrics = PrefixMetrics::default();
+        metrics.record("teamA/db");
+        assert!(!metrics.is_enabled());
+        assert!(metrics.snapshot().is_empty());
+    }
+}
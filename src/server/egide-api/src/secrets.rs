@@ -1,11 +1,16 @@
 //! Secrets domain service methods.
 //!
 //! All operations require the vault to be unsealed. They are open to any authenticated bearer
-//! (no root privilege required).
+//! (no root privilege required). Each operation is counted against its path in
+//! [`ServiceContext::secret_metrics`].
 
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
-use egide_secrets::{PutOptions, SecretMetadata, SecretsError};
+use egide_secrets::{
+    DeleteOptions, PutOptions, Secret, SecretMetadata, SecretVersionInfo, SecretsError,
+};
 
 use crate::{ServiceContext, ServiceError};
 
@@ -28,16 +33,28 @@ impl ServiceContext {
     ///
     /// Returns [`ServiceError::Sealed`] if the vault is sealed.
     /// Returns [`ServiceError::NotFound`] if the path does not exist or has been deleted.
+    /// Returns [`ServiceError::Undecryptable`] if the stored secret fails to
+    /// decrypt or authenticate, counting it in `secret_decrypt_failures`.
     pub async fn secret_get(&self, path: &str) -> Result<SecretView, ServiceError> {
+        self.secret_metrics.record(path);
+        let secret = self.read_secret(path).await?;
+        Ok(SecretView {
+            data: secret.data,
+            version: secret.version,
+            created_at: secret.created_at,
+        })
+    }
+
+    async fn read_secret(&self, path: &str) -> Result<Secret, ServiceError> {
         let guard = self.secrets.read().await;
         let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
         match engine.get(path).await {
-            Ok(s) => Ok(SecretView {
-                data: s.data,
-                version: s.version,
-                created_at: s.created_at,
-            }),
+            Ok(secret) => Ok(secret),
             Err(e) if is_not_found(&e) => Err(ServiceError::NotFound),
+            Err(e @ (SecretsError::Crypto(_) | SecretsError::Integrity(_))) => {
+                self.secret_decrypt_failures.fetch_add(1, Ordering::Relaxed);
+                Err(ServiceError::Undecryptable(e.to_string()))
+            },
             Err(e) => Err(ServiceError::Internal(e.to_string())),
         }
     }
@@ -57,25 +74,152 @@ impl ServiceContext {
         data: HashMap<String, String>,
         cas: Option<u32>,
     ) -> Result<u32, ServiceError> {
+        self.secret_metrics.record(path);
+        self.write_secret(path, data, cas, None, None).await
+    }
+
+    /// Stores or updates a secret, recording `actor` as the version's author.
+    ///
+    /// Identical to [`Self::secret_put`] otherwise; transports call this with
+    /// the authenticated account so the version history shows who wrote what.
+    pub async fn secret_put_as(
+        &self,
+        actor: &str,
+        path: &str,
+        data: HashMap<String, String>,
+        cas: Option<u32>,
+    ) -> Result<u32, ServiceError> {
+        self.secret_metrics.record(path);
+        self.write_secret(path, data, cas, Some(actor.to_string()), None)
+            .await
+    }
+
+    /// Validates a write without performing it (dry run).
+    ///
+    /// Runs the same checks as [`Self::secret_put`] and returns the version
+    /// the write would create; nothing is persisted.
+    ///
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    /// Returns [`ServiceError::BadRequest`] if the path is invalid.
+    /// Returns [`ServiceError::Conflict`] on a `cas` mismatch.
+    pub async fn secret_validate_put(
+        &self,
+        path: &str,
+        data: &HashMap<String, String>,
+        cas: Option<u32>,
+    ) -> Result<u32, ServiceError> {
+        self.secret_metrics.record(path);
         let guard = self.secrets.read().await;
         let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
         let options = PutOptions {
             cas,
             ..Default::default()
         };
+        engine
+            .validate_put(path, data, &options)
+            .await
+            .map_err(map_put_error)
+    }
+
+    async fn write_secret(
+        &self,
+        path: &str,
+        data: HashMap<String, String>,
+        cas: Option<u32>,
+        created_by: Option<String>,
+        ttl: Option<Duration>,
+    ) -> Result<u32, ServiceError> {
+        let guard = self.secrets.read().await;
+        let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
+        let options = PutOptions {
+            cas,
+            created_by,
+            ttl,
+            ..Default::default()
+        };
         engine.put(path, data, options).await.map_err(map_put_error)
     }
 
+    /// Rotates one field of a secret to a freshly generated value.
+    ///
+    /// Looks up `generator` in [`ServiceContext::generators`], replaces (or
+    /// adds) `field` in the current data, and writes the result as a new
+    /// version recorded under `actor`. The other fields and the remaining
+    /// lifetime of the current version are carried over unchanged. The write is check-and-set against the version that was
+    /// read, so a concurrent write makes the rotation fail instead of being
+    /// silently overwritten. Returns the new version number; the generated
+    /// value is only readable through a subsequent `get`.
+    ///
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    /// Returns [`ServiceError::BadRequest`] if the generator is unknown or the field is empty.
+    /// Returns [`ServiceError::NotFound`] if the path does not exist or has been deleted.
+    /// Returns [`ServiceError::Conflict`] if the secret changed during the rotation.
+    pub async fn secret_rotate(
+        &self,
+        actor: &str,
+        path: &str,
+        generator: &str,
+        field: &str,
+    ) -> Result<u32, ServiceError> {
+        if field.is_empty() {
+            return Err(ServiceError::BadRequest("field must not be empty".into()));
+        }
+        let generator = self
+            .generators
+            .get(generator)
+            .ok_or_else(|| ServiceError::BadRequest(format!("unknown generator: {generator}")))?;
+
+        self.secret_metrics.record(path);
+        let current = self.read_secret(path).await?;
+        let ttl = current.remaining_ttl();
+        let mut data = current.data;
+        data.insert(field.to_string(), generator.generate()?);
+        self.write_secret(
+            path,
+            data,
+            Some(current.version),
+            Some(actor.to_string()),
+            ttl,
+        )
+        .await
+    }
+
+    /// Lists every stored version of a secret, newest first.
+    ///
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    /// Returns [`ServiceError::NotFound`] if the path does not exist.
+    /// Returns [`ServiceError::BadRequest`] if the path is invalid.
+    pub async fn secret_versions(
+        &self,
+        path: &str,
+    ) -> Result<Vec<SecretVersionInfo>, ServiceError> {
+        self.secret_metrics.record(path);
+        let guard = self.secrets.read().await;
+        let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
+        engine.versions(path).await.map_err(|e| match e {
+            SecretsError::NotFound(_) => ServiceError::NotFound,
+            SecretsError::InvalidPath(msg) => ServiceError::BadRequest(msg),
+            other => ServiceError::Internal(other.to_string()),
+        })
+    }
+
     /// Soft-deletes the secret at the given path.
     ///
+    /// When `cas` is `Some(n)`, the delete only happens if the current version
+    /// equals `n`; a mismatch yields [`ServiceError::Conflict`] (HTTP 409).
+    ///
     /// Returns [`ServiceError::Sealed`] if the vault is sealed.
     /// Returns [`ServiceError::NotFound`] if the path does not exist or is already deleted.
-    pub async fn secret_delete(&self, path: &str) -> Result<(), ServiceError> {
+    pub async fn secret_delete(&self, path: &str, cas: Option<u32>) -> Result<(), ServiceError> {
+        self.secret_metrics.record(path);
         let guard = self.secrets.read().await;
         let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
-        match engine.delete(path).await {
+        match engine.delete(path, DeleteOptions { cas }).await {
             Ok(()) => Ok(()),
             Err(e) if is_not_found(&e) => Err(ServiceError::NotFound),
+            Err(SecretsError::VersionMismatch { .. }) => Err(ServiceError::Conflict(
+                "version mismatch: the current version differs from the one provided".into(),
+            )),
             Err(e) => Err(ServiceError::Internal(e.to_string())),
         }
     }
@@ -84,6 +228,7 @@ impl ServiceContext {
     ///
     /// Returns [`ServiceError::Sealed`] if the vault is sealed.
     pub async fn secret_list(&self, prefix: &str) -> Result<Vec<SecretMetadata>, ServiceError> {
+        self.secret_metrics.record(prefix);
         let guard = self.secrets.read().await;
         let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
         engine
@@ -91,6 +236,24 @@ impl ServiceContext {
             .await
             .map_err(|e| ServiceError::Internal(e.to_string()))
     }
+
+    /// Counts secrets whose path starts with the given prefix.
+    ///
+    /// Soft-deleted secrets are only counted when `include_deleted` is set.
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    pub async fn secret_count(
+        &self,
+        prefix: &str,
+        include_deleted: bool,
+    ) -> Result<u64, ServiceError> {
+        self.secret_metrics.record(prefix);
+        let guard = self.secrets.read().await;
+        let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
+        engine
+            .count(prefix, include_deleted)
+            .await
+            .map_err(|e| ServiceError::Internal(e.to_string()))
+    }
 }
 
 /// Maps a [`SecretsError`] from a `put` call to the appropriate [`ServiceError`].
@@ -99,13 +262,21 @@ impl ServiceContext {
 /// |------------------------|-----------------------|------|
 /// | `VersionMismatch`      | `Conflict`            | 409  |
 /// | `InvalidPath`          | `BadRequest`          | 400  |
+/// | `InvalidMetadata`      | `BadRequest`          | 400  |
+/// | `TtlExceedsMax`        | `BadRequest`          | 400  |
+/// | `ValueTooLarge`        | `BadRequest`          | 400  |
 /// | everything else        | `Internal`            | 500  |
 fn map_put_error(e: SecretsError) -> ServiceError {
     match e {
         SecretsError::VersionMismatch { .. } => ServiceError::Conflict(
             "version mismatch: the current version differs from the one provided".into(),
         ),
-        SecretsError::InvalidPath(msg) => ServiceError::BadRequest(msg),
+        SecretsError::InvalidPath(msg) | SecretsError::InvalidMetadata(msg) => {
+            ServiceError::BadRequest(msg)
+        },
+        e @ (SecretsError::TtlExceedsMax { .. } | SecretsError::ValueTooLarge { .. }) => {
+            ServiceError::BadRequest(e.to_string())
+        },
         other => ServiceError::Internal(other.to_string()),
     }
 }
@@ -144,10 +315,100 @@ mod tests {
         assert_eq!(view.data.get("password").unwrap(), "s3cr3t");
     }
 
+    #[tokio::test]
+    async fn versions_record_actor_newest_first() {
+        let (_t, c) = crate::test_support::unsealed_context().await;
+
+        let mut data = HashMap::new();
+        data.insert("k".to_string(), "v".to_string());
+        c.secret_put_as("alice", "app/hist", data.clone(), None)
+            .await
+            .unwrap();
+        c.secret_put_as("bob", "app/hist", data, None)
+            .await
+            .unwrap();
+
+        let versions = c.secret_versions("app/hist").await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].version, 2);
+        assert_eq!(versions[0].created_by.as_deref(), Some("bob"));
+        assert_eq!(versions[1].created_by.as_deref(), Some("alice"));
+
+        let err = c.secret_versions("app/none").await.unwrap_err();
+        assert!(matches!(err, crate::ServiceError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn validate_put_returns_prospective_version_without_writing() {
+        let (_t, c) = crate::test_support::unsealed_context().await;
+
+        let mut data = HashMap::new();
+        data.insert("k".to_string(), "v".to_string());
+        let version = c.secret_validate_put("app/dry", &data, None).await.unwrap();
+        assert_eq!(version, 1);
+        assert!(matches!(
+            c.secret_get("app/dry").await.unwrap_err(),
+            crate::ServiceError::NotFound
+        ));
+
+        let err = c
+            .secret_validate_put("/bad", &data, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::ServiceError::BadRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn rotate_regenerates_field_and_keeps_the_rest() {
+        let (_t, c) = crate::test_support::unsealed_context().await;
+
+        let mut data = HashMap::new();
+        data.insert("username".to_string(), "admin".to_string());
+        data.insert("password".to_string(), "old".to_string());
+        c.secret_put("app/db", data, None).await.unwrap();
+
+        let version = c
+            .secret_rotate("alice", "app/db", "random-password", "password")
+            .await
+            .unwrap();
+        assert_eq!(version, 2);
+
+        let view = c.secret_get("app/db").await.unwrap();
+        assert_eq!(view.version, 2);
+        assert_eq!(view.data["username"], "admin");
+        assert_ne!(view.data["password"], "old");
+        assert_eq!(
+            view.data["password"].len(),
+            crate::generators::DEFAULT_PASSWORD_LENGTH
+        );
+        let versions = c.secret_versions("app/db").await.unwrap();
+        assert_eq!(versions[0].created_by.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn rotate_rejects_unknown_generator_and_missing_secret() {
+        let (_t, c) = crate::test_support::unsealed_context().await;
+
+        let err = c
+            .secret_rotate("alice", "app/none", "random-password", "password")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::ServiceError::NotFound));
+
+        let mut data = HashMap::new();
+        data.insert("password".to_string(), "old".to_string());
+        c.secret_put("app/db", data, None).await.unwrap();
+        let err = c
+            .secret_rotate("alice", "app/db", "nope", "password")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::ServiceError::BadRequest(_)));
+    }
+
     #[tokio::test]
     async fn delete_missing_is_not_found() {
         let (_t, c) = crate::test_support::unsealed_context().await;
-        let err = c.secret_delete("ghost/key").await.unwrap_err();
+        let err = c.secret_delete("ghost/key", None).await.unwrap_err();
         assert!(matches!(err, crate::ServiceError::NotFound));
     }
 
@@ -159,7 +420,7 @@ mod tests {
         data.insert("key".to_string(), "value".to_string());
         c.secret_put("app/temp", data, None).await.unwrap();
 
-        c.secret_delete("app/temp").await.unwrap();
+        c.secret_delete("app/temp", None).await.unwrap();
 
         let err = c.secret_get("app/temp").await.unwrap_err();
         assert!(matches!(err, crate::ServiceError::NotFound));
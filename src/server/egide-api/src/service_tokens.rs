@@ -1,6 +1,6 @@
 //! Service-token domain methods on [`ServiceContext`].
 
-use egide_auth::AuthContext;
+use egide_auth::{service_token, AuthContext, AuthMethod};
 
 use crate::{ServiceContext, ServiceError};
 
@@ -70,6 +70,36 @@ impl ServiceContext {
             Err(ServiceError::NotFound)
         }
     }
+
+    /// Revokes the token the caller authenticated with (logout).
+    ///
+    /// `token` is the raw bearer token that produced `ctx`. Service tokens are
+    /// marked revoked in storage, so the next request presenting them fails.
+    /// The root token cannot revoke itself: losing it would lock operators
+    /// out of root-only operations.
+    ///
+    /// Returns [`ServiceError::Forbidden`] for the root token.
+    /// Returns [`ServiceError::BadRequest`] if the caller holds no revocable token.
+    pub async fn revoke_self(&self, ctx: &AuthContext, token: &str) -> Result<(), ServiceError> {
+        match ctx.auth_method {
+            AuthMethod::ServiceToken => {
+                let (token_id, _) = service_token::parse_token(token).ok_or_else(|| {
+                    ServiceError::BadRequest("token is not a service token".into())
+                })?;
+                self.service_tokens
+                    .revoke(&token_id)
+                    .await
+                    .map_err(|e| ServiceError::Internal(e.to_string()))?;
+                Ok(())
+            },
+            AuthMethod::RootToken => Err(ServiceError::Forbidden(
+                "the root token cannot revoke itself".into(),
+            )),
+            AuthMethod::Local | AuthMethod::Anonymous => Err(ServiceError::BadRequest(
+                "no revocable token presented".into(),
+            )),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -194,6 +224,39 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn revoke_self_revokes_the_presented_service_token() {
+        let (_tmp, ctx) = unsealed_context().await;
+        let (token_id, raw_token) = ctx
+            .create_service_token(&AuthContext::root(), "identity")
+            .await
+            .expect("create must succeed");
+
+        ctx.revoke_self(&non_root_ctx(), &raw_token)
+            .await
+            .expect("self-revoke must succeed");
+
+        let records = ctx
+            .list_service_tokens(&AuthContext::root())
+            .await
+            .expect("list must succeed");
+        let record = records
+            .iter()
+            .find(|r| r.token_id == token_id)
+            .expect("record kept");
+        assert!(record.revoked_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn revoke_self_with_root_returns_forbidden() {
+        let (_tmp, ctx) = unsealed_context().await;
+        let result = ctx.revoke_self(&AuthContext::root(), "root-token").await;
+        assert!(
+            matches!(result, Err(ServiceError::Forbidden(_))),
+            "expected Forbidden, got {result:?}"
+        );
+    }
+
     #[tokio::test]
     async fn revoke_without_root_returns_forbidden() {
         let (_tmp, ctx) = unsealed_context().await;
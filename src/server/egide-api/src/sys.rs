@@ -1,10 +1,16 @@
-//! System domain service methods: status, init, unseal, seal.
+//! System domain service methods: status, init, unseal, seal, maintenance.
+
+use std::time::Duration;
 
 use egide_auth::AuthContext;
-use egide_seal::{SealError, SealStatus, ShamirConfig, Share};
+use egide_seal::{SealError, SealManager, SealStatus, ShamirConfig, Share};
 
+use crate::context::flush_pending_usage;
 use crate::{ServiceContext, ServiceError};
 
+/// Secrets listed per page while rewrapping after a master key rotation.
+const REWRAP_PAGE_SIZE: usize = 500;
+
 /// Snapshot of the vault system status.
 #[derive(Debug)]
 pub struct StatusView {
@@ -31,6 +37,25 @@ pub struct InitView {
     pub shares_base64: Vec<String>,
 }
 
+/// Seal state and pending unseal progress, read without submitting a share.
+#[derive(Debug)]
+pub struct SealStatusView {
+    /// Whether the vault is currently sealed.
+    pub sealed: bool,
+    /// Whether the vault has been initialized.
+    pub initialized: bool,
+    /// Total number of unseal shares (0 before initialization).
+    pub total_shares: u8,
+    /// Threshold required to unseal (0 before initialization).
+    pub threshold: u8,
+    /// Number of valid shares submitted towards the pending unseal.
+    pub progress: u8,
+    /// Initialization time in seconds since the UNIX epoch.
+    pub initialized_at: Option<u64>,
+    /// Whether the server runs in dev mode.
+    pub dev_mode: bool,
+}
+
 /// Progress snapshot returned after each unseal share submission.
 #[derive(Debug)]
 pub struct UnsealView {
@@ -40,6 +65,9 @@ pub struct UnsealView {
     pub threshold: u8,
     /// Number of valid shares submitted so far.
     pub progress: u8,
+    /// Nonce of the active unseal operation, to send with the next share.
+    /// `None` once the vault is unsealed.
+    pub nonce: Option<String>,
 }
 
 impl ServiceContext {
@@ -56,6 +84,23 @@ impl ServiceContext {
         }
     }
 
+    /// Returns the seal state and pending unseal progress.
+    ///
+    /// Open to any caller, like [`Self::status`]. Only reads the seal
+    /// manager: polling never advances or resets the progress.
+    pub async fn seal_status(&self) -> SealStatusView {
+        let detail = self.seal.read().await.seal_status();
+        SealStatusView {
+            sealed: detail.status != SealStatus::Unsealed,
+            initialized: detail.status != SealStatus::Uninitialized,
+            total_shares: detail.total_shares,
+            threshold: detail.threshold,
+            progress: detail.progress,
+            initialized_at: detail.initialized_at,
+            dev_mode: detail.dev_mode,
+        }
+    }
+
     /// Initializes the vault by generating Shamir shares and a root token.
     ///
     /// Requires a root [`AuthContext`]; returns [`ServiceError::Forbidden`] otherwise.
@@ -89,12 +134,22 @@ impl ServiceContext {
 
     /// Submits one unseal share.
     ///
+    /// `nonce` names the unseal operation the share belongs to, as returned
+    /// by the previous submission or [`Self::reset_unseal`]. Without one, the
+    /// share joins the active operation, beginning one if none is active.
+    ///
     /// Open to any caller (the share itself is the credential).
-    /// Returns [`ServiceError::BadRequest`] if the vault is not initialized or the share is invalid.
-    pub async fn unseal(&self, share_hex: &str) -> Result<UnsealView, ServiceError> {
+    /// Returns [`ServiceError::Conflict`] if `nonce` is not the active
+    /// operation's nonce, and [`ServiceError::BadRequest`] if the vault is not
+    /// initialized or the share is invalid.
+    pub async fn unseal(
+        &self,
+        share_hex: &str,
+        nonce: Option<&str>,
+    ) -> Result<UnsealView, ServiceError> {
         let share = Share::from_hex(share_hex)
             .map_err(|e| ServiceError::BadRequest(format!("invalid key: {e}")))?;
-        let progress = {
+        let (progress, nonce) = {
             let mut seal = self.seal.write().await;
             match seal.status() {
                 SealStatus::Uninitialized => {
@@ -105,12 +160,23 @@ impl ServiceContext {
                         sealed: false,
                         threshold: 0,
                         progress: 0,
+                        nonce: None,
                     })
                 },
-                SealStatus::Sealed => seal
-                    .unseal(&share)
-                    .await
-                    .map_err(|e| ServiceError::BadRequest(e.to_string()))?,
+                SealStatus::Sealed => {
+                    let nonce = match nonce {
+                        Some(nonce) => nonce.to_owned(),
+                        None => seal.begin_unseal().map_err(unseal_error)?,
+                    };
+                    let progress = seal.unseal(&nonce, &share).await.map_err(unseal_error)?;
+                    // Still sealed: hand back the active operation's nonce.
+                    let nonce = if progress.sealed {
+                        Some(seal.begin_unseal().map_err(unseal_error)?)
+                    } else {
+                        None
+                    };
+                    (progress, nonce)
+                },
             }
         };
         if !progress.sealed {
@@ -120,11 +186,38 @@ impl ServiceContext {
             self.ensure_transit_engine()
                 .await
                 .map_err(ServiceError::Internal)?;
+            // A rotation interrupted before its rewrap finished left the old
+            // key wrapped in storage; finish it now so the old key leaves
+            // memory again.
+            let mut seal = self.seal.write().await;
+            if seal.previous_master_key().is_some() {
+                if let Err(e) = self.rewrap_after_rotation(&mut seal).await {
+                    tracing::error!(error = %e, "Pending master key rotation not finished");
+                }
+            }
         }
         Ok(UnsealView {
             sealed: progress.sealed,
             threshold: progress.threshold,
             progress: progress.progress,
+            nonce,
+        })
+    }
+
+    /// Discards the shares submitted so far and begins a new unseal
+    /// operation; shares sent with the old nonce are refused from then on.
+    ///
+    /// Open to any caller, like [`Self::unseal`].
+    /// Returns [`ServiceError::BadRequest`] unless the vault is sealed.
+    pub async fn reset_unseal(&self) -> Result<UnsealView, ServiceError> {
+        let mut seal = self.seal.write().await;
+        let nonce = seal.reset_unseal().map_err(unseal_error)?;
+        let progress = seal.unseal_progress();
+        Ok(UnsealView {
+            sealed: progress.sealed,
+            threshold: progress.threshold,
+            progress: progress.progress,
+            nonce: Some(nonce),
         })
     }
 
@@ -147,6 +240,242 @@ impl ServiceContext {
         self.clear_transit_engine().await;
         Ok(())
     }
+
+    /// Replaces the master key and rewraps every secret and transit key
+    /// under the new one. Returns the new shares and root token, shown once;
+    /// the old shares and root token stop working.
+    ///
+    /// The steps run in this order, under the seal lock and both engine
+    /// locks, so no request reads or writes until the rotation is done:
+    ///
+    /// 1. [`SealManager::rotate_master_key`] generates the new key and stores
+    ///    its HMAC, share commitments and root token hash together with the
+    ///    old key, wrapped under the new one, in one transaction.
+    /// 2. Both engines are reopened under the new key.
+    /// 3. Every secret, soft-deleted ones included, is rewrapped with
+    ///    `SecretsEngine::rewrap_secret`, then every transit key with
+    ///    `TransitEngine::rewrap_key_from`.
+    /// 4. [`SealManager::finish_master_key_rotation`] deletes the wrapped old
+    ///    key and drops it from memory.
+    ///
+    /// Both keys are in memory from step 1 until step 4 succeeds. If step 3
+    /// fails, the rotation stays pending: the error is returned, the old key
+    /// stays available, and the next unseal (with the new shares) resumes
+    /// the rewrap; both rewraps skip what is already done.
+    ///
+    /// Requires a root [`AuthContext`]; returns [`ServiceError::Forbidden`] otherwise.
+    /// Returns [`ServiceError::BadRequest`] if the vault is not unsealed, runs
+    /// in dev mode, has a rotation pending, or encrypts its storage at rest
+    /// (the `SQLCipher` databases are keyed by the master key and cannot be
+    /// rekeyed yet).
+    ///
+    /// [`SealManager::rotate_master_key`]: egide_seal::SealManager::rotate_master_key
+    /// [`SealManager::finish_master_key_rotation`]: egide_seal::SealManager::finish_master_key_rotation
+    pub async fn rotate_master_key(&self, ctx: &AuthContext) -> Result<InitView, ServiceError> {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+        if !ctx.is_root() {
+            return Err(ServiceError::Forbidden(
+                "master key rotation requires root".into(),
+            ));
+        }
+        if self.encrypt_storage {
+            return Err(ServiceError::BadRequest(
+                "master key rotation is not supported with encrypted storage".into(),
+            ));
+        }
+        let mut seal = self.seal.write().await;
+        let res = seal.rotate_master_key().await.map_err(|e| match e {
+            SealError::NotInitialized | SealError::Sealed => {
+                ServiceError::BadRequest("not unsealed".into())
+            },
+            SealError::InvalidConfig(msg) => ServiceError::BadRequest(msg),
+            other => ServiceError::Internal(other.to_string()),
+        })?;
+        tracing::warn!(actor = %ctx.account_id, "Master key rotated, rewrapping engine data");
+        self.rewrap_after_rotation(&mut seal).await?;
+        Ok(InitView {
+            root_token: res.root_token,
+            shares_hex: res.shares.iter().map(egide_seal::Share::to_hex).collect(),
+            shares_base64: res.shares.iter().map(|s| BASE64.encode(&s.data)).collect(),
+        })
+    }
+
+    /// Steps 2 to 4 of [`Self::rotate_master_key`]: reopens the engines under
+    /// the new key, rewraps their data from the pending rotation's old key,
+    /// and finishes the rotation.
+    ///
+    /// Takes the seal lock from the caller, then both engine locks, in the
+    /// order [`Self::ensure_secrets_engine`] uses.
+    async fn rewrap_after_rotation(&self, seal: &mut SealManager) -> Result<(), ServiceError> {
+        let (Some(old_master), Some(new_master)) = (
+            seal.previous_master_key().cloned(),
+            seal.master_key().cloned(),
+        ) else {
+            return Ok(());
+        };
+        let mut secrets = self.secrets.write().await;
+        let mut transit = self.transit.write().await;
+        flush_pending_usage(transit.as_ref()).await;
+        *secrets = None;
+        *transit = None;
+        let secrets = secrets.insert(
+            self.open_secrets_engine(new_master.clone())
+                .await
+                .map_err(ServiceError::Internal)?,
+        );
+        let transit = transit.insert(
+            self.open_transit_engine(new_master)
+                .await
+                .map_err(ServiceError::Internal)?,
+        );
+
+        let mut secret_versions = 0u64;
+        let mut cursor: Option<String> = None;
+        loop {
+            let (page, next) = secrets
+                .list_page("", cursor.as_deref(), REWRAP_PAGE_SIZE)
+                .await
+                .map_err(|e| ServiceError::Internal(e.to_string()))?;
+            for secret in &page {
+                secret_versions += u64::from(
+                    secrets
+                        .rewrap_secret(&secret.path, &old_master)
+                        .await
+                        .map_err(|e| ServiceError::Internal(e.to_string()))?,
+                );
+            }
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        let mut key_versions = 0u64;
+        for name in transit
+            .list_keys()
+            .await
+            .map_err(|e| ServiceError::Internal(e.to_string()))?
+        {
+            key_versions += u64::from(
+                transit
+                    .rewrap_key_from(&name, &old_master)
+                    .await
+                    .map_err(|e| ServiceError::Internal(e.to_string()))?,
+            );
+        }
+
+        seal.finish_master_key_rotation()
+            .await
+            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        tracing::info!(
+            secret_versions,
+            key_versions,
+            "Engine data rewrapped, master key rotation finished"
+        );
+        Ok(())
+    }
+
+    /// Seals the vault if it has been idle past the seal manager's
+    /// [`SealManager::set_seal_after_idle`] time, dropping the engines like
+    /// [`Self::seal`]. Returns whether it sealed.
+    ///
+    /// Meant to be called periodically by the server; activity is recorded
+    /// with [`SealManager::touch`] on every authenticated request.
+    ///
+    /// [`SealManager::set_seal_after_idle`]: egide_seal::SealManager::set_seal_after_idle
+    /// [`SealManager::touch`]: egide_seal::SealManager::touch
+    pub async fn seal_if_idle(&self) -> bool {
+        if !self.seal.write().await.check_idle() {
+            return false;
+        }
+        self.clear_secrets_engine().await;
+        self.clear_transit_engine().await;
+        true
+    }
+
+    /// Replaces the root token and returns the new one, shown only once.
+    ///
+    /// The old root token stops validating immediately. Every attempt is
+    /// logged with its outcome for audit.
+    ///
+    /// Requires a root [`AuthContext`]; returns [`ServiceError::Forbidden`] otherwise.
+    /// Returns [`ServiceError::RateLimited`] if the root token was rotated
+    /// within the seal manager's rotation interval.
+    pub async fn rotate_root(&self, ctx: &AuthContext) -> Result<String, ServiceError> {
+        if !ctx.is_root() {
+            tracing::warn!(
+                actor = %ctx.account_id,
+                "Root token rotation refused: root required"
+            );
+            return Err(ServiceError::Forbidden("rotate-root requires root".into()));
+        }
+        let result = self.seal.write().await.rotate_root_token().await;
+        match result {
+            Ok(token) => {
+                tracing::info!(actor = %ctx.account_id, "Root token rotated");
+                Ok(token)
+            },
+            Err(e) => {
+                tracing::warn!(actor = %ctx.account_id, error = %e, "Root token rotation failed");
+                Err(match e {
+                    e @ SealError::RootRotationRateLimited { .. } => {
+                        ServiceError::RateLimited(e.to_string())
+                    },
+                    SealError::NotInitialized => ServiceError::BadRequest("not initialized".into()),
+                    other => ServiceError::Internal(other.to_string()),
+                })
+            },
+        }
+    }
+
+    /// Permanently removes secrets soft-deleted more than `older_than` ago.
+    ///
+    /// Returns the number of secrets purged. Rows whose deletion flag fails its
+    /// integrity check are skipped, not purged.
+    ///
+    /// Requires a root [`AuthContext`]; returns [`ServiceError::Forbidden`] otherwise.
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    pub async fn purge_deleted_secrets(
+        &self,
+        ctx: &AuthContext,
+        older_than: Duration,
+    ) -> Result<u32, ServiceError> {
+        if !ctx.is_root() {
+            return Err(ServiceError::Forbidden("purge requires root".into()));
+        }
+        let guard = self.secrets.read().await;
+        let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
+        let purged = engine
+            .purge_deleted(older_than)
+            .await
+            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        tracing::info!(purged, "Purged deleted secrets");
+        Ok(purged)
+    }
+
+    /// Permanently removes expired secret versions, keeping each secret's
+    /// current version, and returns how many were removed.
+    ///
+    /// Meant to be called periodically by the server, so it takes no
+    /// [`AuthContext`].
+    ///
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    pub async fn purge_expired_secrets(&self) -> Result<u32, ServiceError> {
+        let guard = self.secrets.read().await;
+        let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
+        engine
+            .purge_expired()
+            .await
+            .map_err(|e| ServiceError::Internal(e.to_string()))
+    }
+}
+
+/// Maps an unseal failure: a stale nonce conflicts with the active
+/// operation, anything else is the caller's fault.
+fn unseal_error(e: SealError) -> ServiceError {
+    match e {
+        SealError::StaleNonce => ServiceError::Conflict(e.to_string()),
+        other => ServiceError::BadRequest(other.to_string()),
+    }
 }
 
 #[cfg(test)]
@@ -156,6 +485,45 @@ mod tests {
 
     use crate::test_support::{uninitialized_context, unsealed_context};
 
+    #[tokio::test]
+    async fn unseal_returns_nonce_and_refuses_stale_one() {
+        let (_t, c) = uninitialized_context().await;
+        let init = c.init(&AuthContext::root(), 3, 2).await.expect("init");
+
+        let first = c.unseal(&init.shares_hex[0], None).await.expect("unseal");
+        assert!(first.sealed);
+        assert_eq!(first.progress, 1);
+        let nonce = first.nonce.expect("nonce while sealed");
+
+        let reset = c.reset_unseal().await.expect("reset");
+        assert_eq!(reset.progress, 0);
+        let fresh = reset.nonce.expect("nonce after reset");
+        assert_ne!(fresh, nonce);
+
+        let err = c
+            .unseal(&init.shares_hex[1], Some(&nonce))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, ServiceError::Conflict(_)),
+            "expected Conflict, got {err:?}"
+        );
+
+        c.unseal(&init.shares_hex[0], Some(&fresh))
+            .await
+            .expect("unseal");
+        let done = c
+            .unseal(&init.shares_hex[1], Some(&fresh))
+            .await
+            .expect("unseal");
+        assert!(!done.sealed);
+        assert!(done.nonce.is_none());
+        assert!(matches!(
+            c.reset_unseal().await,
+            Err(ServiceError::BadRequest(_))
+        ));
+    }
+
     #[tokio::test]
     async fn status_reports_unsealed() {
         let (_t, c) = unsealed_context().await;
@@ -181,6 +549,57 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn purge_deleted_secrets_requires_root() {
+        let (_t, c) = unsealed_context().await;
+        let non_root = AuthContext {
+            account_id: "svc".into(),
+            email: None,
+            display_name: None,
+            auth_method: AuthMethod::ServiceToken,
+            expires_at: None,
+        };
+        let err = c
+            .purge_deleted_secrets(&non_root, Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, ServiceError::Forbidden(_)),
+            "expected Forbidden, got {err:?}"
+        );
+        assert_eq!(
+            c.purge_deleted_secrets(&AuthContext::root(), Duration::ZERO)
+                .await
+                .expect("purge"),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn rotate_root_requires_root_and_is_rate_limited() {
+        let (_t, c) = unsealed_context().await;
+        let non_root = AuthContext {
+            account_id: "svc".into(),
+            email: None,
+            display_name: None,
+            auth_method: AuthMethod::ServiceToken,
+            expires_at: None,
+        };
+        let err = c.rotate_root(&non_root).await.unwrap_err();
+        assert!(
+            matches!(err, ServiceError::Forbidden(_)),
+            "expected Forbidden, got {err:?}"
+        );
+
+        let token = c.rotate_root(&AuthContext::root()).await.expect("rotate");
+        assert!(!token.is_empty());
+        let err = c.rotate_root(&AuthContext::root()).await.unwrap_err();
+        assert!(
+            matches!(err, ServiceError::RateLimited(_)),
+            "expected RateLimited, got {err:?}"
+        );
+    }
+
     #[tokio::test]
     async fn seal_root_succeeds_and_reports_sealed() {
         let (_t, c) = unsealed_context().await;
@@ -189,6 +608,99 @@ mod tests {
         assert!(s.sealed, "vault should be sealed after seal()");
     }
 
+    #[tokio::test]
+    async fn rotate_master_key_rewraps_engines() {
+        let (_t, c) = unsealed_context().await;
+        let data = std::collections::HashMap::from([("k".to_string(), "v".to_string())]);
+        c.secrets
+            .read()
+            .await
+            .as_ref()
+            .unwrap()
+            .put("app/db", data.clone(), egide_secrets::PutOptions::default())
+            .await
+            .unwrap();
+        let ciphertext = {
+            let guard = c.transit.read().await;
+            let transit = guard.as_ref().unwrap();
+            transit
+                .create_key("k", egide_transit::KeyConfig::new())
+                .await
+                .unwrap();
+            transit.encrypt("k", b"hello").await.unwrap()
+        };
+        let old_master = c.seal.read().await.master_key().unwrap().clone();
+
+        let non_root = AuthContext {
+            account_id: "svc".into(),
+            email: None,
+            display_name: None,
+            auth_method: AuthMethod::ServiceToken,
+            expires_at: None,
+        };
+        assert!(matches!(
+            c.rotate_master_key(&non_root).await,
+            Err(ServiceError::Forbidden(_))
+        ));
+
+        let rotated = c.rotate_master_key(&AuthContext::root()).await.unwrap();
+        assert_eq!(rotated.shares_hex.len(), 5);
+        {
+            let seal = c.seal.read().await;
+            assert!(seal.previous_master_key().is_none());
+            assert_ne!(seal.master_key().unwrap().as_bytes(), old_master.as_bytes());
+        }
+        let secret = c
+            .secrets
+            .read()
+            .await
+            .as_ref()
+            .unwrap()
+            .get("app/db")
+            .await
+            .unwrap();
+        assert_eq!(secret.data, data);
+        let plaintext = c
+            .transit
+            .read()
+            .await
+            .as_ref()
+            .unwrap()
+            .decrypt("k", &ciphertext)
+            .await
+            .unwrap();
+        assert_eq!(plaintext, b"hello");
+    }
+
+    #[tokio::test]
+    async fn seal_if_idle_seals_and_clears_engines() {
+        let (_t, c) = unsealed_context().await;
+        assert!(!c.seal_if_idle().await, "no idle timeout by default");
+        assert!(c.secrets.read().await.is_some());
+
+        c.seal
+            .write()
+            .await
+            .set_seal_after_idle(Some(Duration::ZERO));
+        assert!(c.seal_if_idle().await);
+        assert!(c.status().await.sealed);
+        assert!(c.secrets.read().await.is_none());
+        assert!(c.transit.read().await.is_none());
+        assert!(!c.seal_if_idle().await, "already sealed");
+    }
+
+    #[tokio::test]
+    async fn purge_expired_secrets_needs_an_unsealed_vault() {
+        let (_t, c) = unsealed_context().await;
+        assert_eq!(c.purge_expired_secrets().await.unwrap(), 0);
+
+        c.seal(&AuthContext::root()).await.unwrap();
+        assert!(matches!(
+            c.purge_expired_secrets().await,
+            Err(ServiceError::Sealed)
+        ));
+    }
+
     #[tokio::test]
     async fn init_requires_root() {
         let (_t, c) = unsealed_context().await;
@@ -2,6 +2,7 @@
 //!
 //! This module is only compiled in `#[cfg(test)]` contexts.
 
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -11,7 +12,7 @@ use egide_auth::{AuthService, RootTokenBackend, ServiceTokenBackend, ServiceToke
 use egide_seal::{SealManager, ShamirConfig};
 use egide_storage::StorageBackend;
 
-use crate::ServiceContext;
+use crate::{PrefixMetrics, SecretGenerators, ServiceContext};
 
 /// Builds an initialized, fully unsealed [`ServiceContext`] backed by a temporary directory.
 ///
@@ -31,8 +32,12 @@ pub(crate) async fn unsealed_context() -> (tempfile::TempDir, Arc<ServiceContext
         .await
         .expect("initialize");
 
+    let nonce = seal_manager.begin_unseal().expect("begin unseal");
     for share in init.shares.iter().take(3) {
-        seal_manager.unseal(share).await.expect("unseal share");
+        seal_manager
+            .unseal(&nonce, share)
+            .await
+            .expect("unseal share");
     }
 
     let storage: Arc<dyn StorageBackend> = Arc::new(seal_manager.storage());
@@ -51,6 +56,10 @@ pub(crate) async fn unsealed_context() -> (tempfile::TempDir, Arc<ServiceContext
         start_time: Instant::now(),
         version: "0.1.0-test",
         service_tokens: service_store,
+        generators: SecretGenerators::default(),
+        encrypt_storage: false,
+        secret_metrics: PrefixMetrics::default(),
+        secret_decrypt_failures: AtomicU64::new(0),
     });
 
     ctx.ensure_secrets_engine()
@@ -92,6 +101,10 @@ pub(crate) async fn uninitialized_context() -> (tempfile::TempDir, Arc<ServiceCo
         start_time: Instant::now(),
         version: "0.1.0-test",
         service_tokens: service_store,
+        generators: SecretGenerators::default(),
+        encrypt_storage: false,
+        secret_metrics: PrefixMetrics::default(),
+        secret_decrypt_failures: AtomicU64::new(0),
     });
 
     (tmp, ctx)
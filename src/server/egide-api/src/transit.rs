@@ -28,7 +28,10 @@ use crate::{ServiceContext, ServiceError};
 /// | `KeyAlgorithmNotImplemented`                                | `Conflict("key declares an algorithm this build does not implement")` |
 /// | `InvalidCiphertext` / `InvalidKeyName` / `InvalidKeyType` /  | `BadRequest`              |
 /// | `UnsupportedKeyType` / `VersionBelowMinEncryption` /         |                           |
-/// | `VersionBelowMinDecryption` / `CiphertextAlgorithmMismatch`  |                           |
+/// | `VersionBelowMinDecryption` / `CiphertextAlgorithmMismatch` /|                           |
+/// | `InvalidBatchSize` / `InvalidKeyConfig` / `VersionTrimmed`   |                           |
+/// | `InvalidRandomLength` / `InvalidHashAlgorithm` /            |                           |
+/// | `InputTooLarge`                                             |                           |
 /// | `DecryptionFailed`                                          | `DecryptionFailed`        |
 /// | `OperationNotAllowed` / `NotExportable` / `DeletionNotAllowed` | `Forbidden`            |
 /// | `Storage` / `Crypto` / `Integrity` / `Clock`                | `Internal`                |
@@ -48,6 +51,9 @@ fn map_transit_error(err: TransitError) -> ServiceError {
         TransitError::InvalidKeyName(msg) | TransitError::InvalidKeyType(msg) => {
             ServiceError::BadRequest(msg)
         },
+        TransitError::InvalidKeyMaterial(msg) => {
+            ServiceError::BadRequest(format!("invalid key material: {msg}"))
+        },
         TransitError::UnsupportedKeyType(key_type) => {
             ServiceError::BadRequest(format!("unsupported key type: {key_type}"))
         },
@@ -60,6 +66,12 @@ fn map_transit_error(err: TransitError) -> ServiceError {
         TransitError::CiphertextAlgorithmMismatch { expected, found } => ServiceError::BadRequest(
             format!("ciphertext algorithm {found} does not match engine algorithm {expected}"),
         ),
+        e @ (TransitError::InvalidHashAlgorithm(_)
+        | TransitError::InvalidBatchSize { .. }
+        | TransitError::InvalidKeyConfig(_)
+        | TransitError::VersionTrimmed { .. }
+        | TransitError::InvalidRandomLength { .. }
+        | TransitError::InputTooLarge { .. }) => ServiceError::BadRequest(e.to_string()),
         TransitError::DecryptionFailed => ServiceError::DecryptionFailed,
         TransitError::OperationNotAllowed(msg)
         | TransitError::NotExportable(msg)
@@ -127,6 +139,7 @@ impl ServiceContext {
 
         let mut config = KeyConfig::new();
         config.key_type = parsed_type;
+        config.supports_signing = parsed_type == KeyType::Ed25519;
         config.deletion_allowed = deletion_allowed;
 
         engine
@@ -222,6 +235,50 @@ impl ServiceContext {
             .map_err(map_transit_error)
     }
 
+    /// Encrypts plaintext bytes, binding caller associated data into the
+    /// ciphertext.
+    ///
+    /// The associated data is authenticated but not encrypted; decryption
+    /// must present the same bytes through [`Self::decrypt_with_aad`].
+    ///
+    /// Authorization: open to any authenticated bearer.
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    /// Returns [`ServiceError::NotFound`] if the key does not exist.
+    pub async fn encrypt_with_aad(
+        &self,
+        name: &str,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<String, ServiceError> {
+        let guard = self.transit.read().await;
+        let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
+        engine
+            .encrypt_with_aad(name, plaintext, associated_data)
+            .await
+            .map_err(map_transit_error)
+    }
+
+    /// Decrypts a ciphertext produced by [`Self::encrypt_with_aad`].
+    ///
+    /// A missing or different `associated_data` is reported as
+    /// [`ServiceError::DecryptionFailed`].
+    ///
+    /// Authorization: open to any authenticated bearer.
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    pub async fn decrypt_with_aad(
+        &self,
+        name: &str,
+        ciphertext: &str,
+        associated_data: &[u8],
+    ) -> Result<Vec<u8>, ServiceError> {
+        let guard = self.transit.read().await;
+        let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
+        engine
+            .decrypt_with_aad(name, ciphertext, associated_data)
+            .await
+            .map_err(map_transit_error)
+    }
+
     /// Rewraps a ciphertext with the latest key version without exposing plaintext.
     ///
     /// If the ciphertext is already encrypted under the latest version, it is
@@ -239,6 +296,29 @@ impl ServiceContext {
             .map_err(map_transit_error)
     }
 
+    /// Rewraps a ciphertext produced by [`Self::encrypt_with_aad`] with the
+    /// latest key version, keeping it bound to `associated_data`.
+    ///
+    /// A missing or different `associated_data` is reported as
+    /// [`ServiceError::DecryptionFailed`].
+    ///
+    /// Authorization: open to any authenticated bearer.
+    /// Returns [`ServiceError::Sealed`] if the vault is sealed.
+    /// Returns [`ServiceError::NotFound`] if the key does not exist.
+    pub async fn rewrap_with_aad(
+        &self,
+        name: &str,
+        ciphertext: &str,
+        associated_data: &[u8],
+    ) -> Result<String, ServiceError> {
+        let guard = self.transit.read().await;
+        let engine = guard.as_ref().ok_or(ServiceError::Sealed)?;
+        engine
+            .rewrap_with_aad(name, ciphertext, associated_data)
+            .await
+            .map_err(map_transit_error)
+    }
+
     /// Generates a data encryption key (DEK) wrapped under a transit key.
     ///
     /// Returns both the plaintext key (for immediate use by the caller) and a
@@ -351,6 +431,21 @@ mod tests {
         assert!(matches!(err, crate::ServiceError::NotFound));
     }
 
+    #[tokio::test]
+    async fn clearing_the_engine_writes_pending_usage() {
+        let (_t, c) = crate::test_support::unsealed_context().await;
+        c.create_key(&AuthContext::root(), "used", "aes256-gcm", false)
+            .await
+            .unwrap();
+        c.encrypt("used", b"one").await.unwrap();
+        c.encrypt("used", b"two").await.unwrap();
+
+        c.clear_transit_engine().await;
+        c.ensure_transit_engine().await.unwrap();
+
+        assert_eq!(c.get_key("used").await.unwrap().usage_count, 2);
+    }
+
     // ---- Conflict test ---------------------------------------------------
 
     #[tokio::test]
@@ -477,24 +572,19 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn create_key_chacha20_is_rejected_as_unsupported() {
-        // ChaCha20-Poly1305 is accepted by the wire format but not implemented
-        // by the engine: creation must fail closed instead of silently
-        // encrypting under AES-256-GCM.
+    async fn create_key_chacha20_encrypts_under_chacha20() {
         let (_t, c) = crate::test_support::unsealed_context().await;
-        let err = c
-            .create_key(
-                &AuthContext::root(),
-                "chacha-key",
-                "chacha20-poly1305",
-                false,
-            )
-            .await
-            .unwrap_err();
-        assert!(
-            matches!(err, crate::ServiceError::BadRequest(_)),
-            "expected BadRequest for unsupported key type, got {err:?}"
-        );
+        c.create_key(
+            &AuthContext::root(),
+            "chacha-key",
+            "chacha20-poly1305",
+            false,
+        )
+        .await
+        .unwrap();
+        let ct = c.encrypt("chacha-key", b"payload").await.unwrap();
+        assert!(ct.starts_with("egide:v1:chacha20-poly1305:"));
+        assert_eq!(c.decrypt("chacha-key", &ct).await.unwrap(), b"payload");
     }
 
     #[tokio::test]
@@ -12,6 +12,8 @@ pub enum AuthMethod {
     RootToken,
     /// Native service token issued by Egide (machine-to-machine).
     ServiceToken,
+    /// No credentials: a read of an explicitly allowlisted public path.
+    Anonymous,
 }
 
 /// Authenticated user context.
@@ -49,6 +51,18 @@ impl AuthContext {
         }
     }
 
+    /// Creates the synthetic context for an unauthenticated public read.
+    #[must_use]
+    pub fn anonymous() -> Self {
+        Self {
+            account_id: "anonymous".to_string(),
+            email: None,
+            display_name: None,
+            auth_method: AuthMethod::Anonymous,
+            expires_at: None,
+        }
+    }
+
     /// Checks if this is a root context.
     #[must_use]
     pub fn is_root(&self) -> bool {
@@ -77,4 +91,11 @@ mod tests {
         };
         assert!(!ctx.is_root());
     }
+
+    #[test]
+    fn anonymous_context_is_not_root() {
+        let ctx = AuthContext::anonymous();
+        assert_eq!(ctx.auth_method, AuthMethod::Anonymous);
+        assert!(!ctx.is_root());
+    }
 }
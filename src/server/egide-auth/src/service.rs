@@ -11,13 +11,49 @@ use crate::{AuthBackend, AuthContext, AuthError};
 /// Combined authentication service that tries multiple backends.
 pub struct AuthService {
     backends: Vec<Box<dyn AuthBackend>>,
+    /// Secret path prefixes readable without a token; empty by default.
+    public_read_prefixes: Vec<String>,
 }
 
 impl AuthService {
     /// Creates a new auth service with the given backends.
     #[must_use]
     pub fn new(backends: Vec<Box<dyn AuthBackend>>) -> Self {
-        Self { backends }
+        Self {
+            backends,
+            public_read_prefixes: Vec::new(),
+        }
+    }
+
+    /// Allows unauthenticated reads of secrets under the given path prefixes.
+    ///
+    /// Strictly opt-in: with no prefixes (the default) every read requires a
+    /// token. A prefix matches whole path segments, so `config/flags` covers
+    /// `config/flags` and `config/flags/beta` but not `config/flagship`.
+    /// Leading and trailing `/` are ignored and empty prefixes are dropped,
+    /// so a stray blank entry can never open the whole tree. Writes are never
+    /// anonymous.
+    #[must_use]
+    pub fn with_public_read_prefixes<I, P>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<str>,
+    {
+        self.public_read_prefixes = prefixes
+            .into_iter()
+            .map(|p| p.as_ref().trim_matches('/').to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        self
+    }
+
+    /// Returns whether `path` may be read without a token.
+    #[must_use]
+    pub fn allows_anonymous_read(&self, path: &str) -> bool {
+        self.public_read_prefixes.iter().any(|prefix| {
+            path.strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
     }
 
     /// Validates a token against all configured backends.
@@ -51,3 +87,24 @@ impl AuthBackend for AuthService {
         "auth-service"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anonymous_read_is_disabled_by_default() {
+        let auth = AuthService::new(Vec::new());
+        assert!(!auth.allows_anonymous_read("config/flags"));
+    }
+
+    #[test]
+    fn public_prefixes_match_whole_segments() {
+        let auth = AuthService::new(Vec::new()).with_public_read_prefixes(["/config/flags/", ""]);
+        assert!(auth.allows_anonymous_read("config/flags"));
+        assert!(auth.allows_anonymous_read("config/flags/beta"));
+        assert!(!auth.allows_anonymous_read("config/flagship"));
+        assert!(!auth.allows_anonymous_read("config"));
+        assert!(!auth.allows_anonymous_read("app/db"));
+    }
+}
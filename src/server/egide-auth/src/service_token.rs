@@ -47,13 +47,16 @@ pub fn format_token(token_id: &str, secret: &str) -> String {
 /// Computes the hex-encoded SHA-256 hash of a token secret.
 #[must_use]
 pub fn hash_secret(secret: &str) -> String {
-    hex::encode(Sha256::digest(secret.as_bytes()))
+    encoding::encode_hex(&Sha256::digest(secret.as_bytes()))
+        .as_str()
+        .to_owned()
 }
 
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
+use egide_crypto::encoding;
 use egide_storage::StorageBackend;
 use rand::Rng;
 use subtle::ConstantTimeEq;
@@ -80,11 +83,11 @@ impl ServiceTokenStore {
     pub async fn create(&self, service_name: &str) -> Result<(String, String), AuthError> {
         let mut id_bytes = [0u8; 16];
         rand::rng().fill_bytes(&mut id_bytes);
-        let token_id = hex::encode(id_bytes);
+        let token_id = encoding::encode_hex(&id_bytes).as_str().to_owned();
 
         let mut secret_bytes = [0u8; 32];
         rand::rng().fill_bytes(&mut secret_bytes);
-        let secret = hex::encode(secret_bytes);
+        let secret = encoding::encode_hex(&secret_bytes);
 
         let record = ServiceTokenRecord {
             token_id: token_id.clone(),
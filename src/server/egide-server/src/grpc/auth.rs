@@ -9,7 +9,8 @@ use egide_api::ServiceContext;
 use egide_auth::AuthContext;
 use tonic::{Request, Status};
 
-/// Resolves the bearer token from request metadata into an [`AuthContext`].
+/// Resolves the bearer token from request metadata into an [`AuthContext`],
+/// recording the activity against the idle seal timeout.
 ///
 /// Returns `Status::unauthenticated` when the `authorization` metadata key is
 /// absent, malformed, or carries an invalid token.
@@ -25,19 +26,24 @@ pub async fn authenticate<T>(
         .map(str::trim)
         .filter(|t| !t.is_empty())
         .ok_or_else(|| Status::unauthenticated("missing bearer token"))?;
-    ctx.auth
+    let auth = ctx
+        .auth
         .validate(token)
         .await
-        .map_err(|_| Status::unauthenticated("invalid credentials"))
+        .map_err(|_| Status::unauthenticated("invalid credentials"))?;
+    ctx.seal.read().await.touch();
+    Ok(auth)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::sync::atomic::AtomicU64;
     use std::sync::Arc;
     use std::time::Instant;
 
+    use egide_api::{PrefixMetrics, SecretGenerators};
     use egide_auth::{AuthService, RootTokenBackend, ServiceTokenBackend, ServiceTokenStore};
     use egide_seal::{SealManager, ShamirConfig};
     use egide_storage::StorageBackend;
@@ -57,8 +63,9 @@ mod tests {
             .await
             .expect("initialize");
         let root_token = init.root_token.clone();
+        let nonce = seal.begin_unseal().expect("begin unseal");
         for share in init.shares.iter().take(3) {
-            seal.unseal(share).await.expect("unseal");
+            seal.unseal(&nonce, share).await.expect("unseal");
         }
 
         // `SealManager::storage()` returns a concrete `SqliteBackend`.
@@ -81,6 +88,10 @@ mod tests {
             start_time: Instant::now(),
             version: "0.1.0-test",
             service_tokens: service_store,
+            generators: SecretGenerators::default(),
+            encrypt_storage: false,
+            secret_metrics: PrefixMetrics::default(),
+            secret_decrypt_failures: AtomicU64::new(0),
         });
 
         (tmp, ctx, root_token)
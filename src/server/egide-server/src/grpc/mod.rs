@@ -31,6 +31,27 @@ pub async fn serve(
     state: Arc<ServiceContext>,
     addr: SocketAddr,
     shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    serve_services(state, addr, true, shutdown).await
+}
+
+/// Like [`serve`], without the Sys service.
+///
+/// Used on the data listener when `--admin-bind` moves administration to its
+/// own listener; Sys RPCs then answer `UNIMPLEMENTED` here.
+pub async fn serve_data_plane(
+    state: Arc<ServiceContext>,
+    addr: SocketAddr,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    serve_services(state, addr, false, shutdown).await
+}
+
+async fn serve_services(
+    state: Arc<ServiceContext>,
+    addr: SocketAddr,
+    with_sys: bool,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
 ) -> anyhow::Result<()> {
     let (health_reporter, health_service) = tonic_health::server::health_reporter();
     health_reporter
@@ -41,12 +62,16 @@ pub async fn serve(
         .register_encoded_file_descriptor_set(egide_api::proto::FILE_DESCRIPTOR_SET)
         .build_v1()?;
 
+    let sys = with_sys.then(|| {
+        proto::sys_service_server::SysServiceServer::new(SysGrpc {
+            state: state.clone(),
+        })
+    });
+
     tonic::transport::Server::builder()
         .add_service(health_service)
         .add_service(reflection)
-        .add_service(proto::sys_service_server::SysServiceServer::new(SysGrpc {
-            state: state.clone(),
-        }))
+        .add_optional_service(sys)
         .add_service(proto::secrets_service_server::SecretsServiceServer::new(
             SecretsGrpc {
                 state: state.clone(),
@@ -51,26 +51,29 @@ impl SecretsService for SecretsGrpc {
         &self,
         request: Request<PutSecretRequest>,
     ) -> Result<Response<PutSecretResponse>, Status> {
-        authenticate(&request, &self.state).await?;
+        let ctx = authenticate(&request, &self.state).await?;
         let req = request.into_inner();
         let cas = if req.has_cas { Some(req.cas) } else { None };
         let version = self
             .state
-            .secret_put(&req.path, req.data, cas)
+            .secret_put_as(&ctx.account_id, &req.path, req.data, cas)
             .await
             .map_err(to_status)?;
         Ok(Response::new(PutSecretResponse { version }))
     }
 
     /// Soft-deletes a secret. Bearer token required.
+    ///
+    /// The optional CAS field is encoded as for [`Self::put`].
     async fn delete(
         &self,
         request: Request<DeleteSecretRequest>,
     ) -> Result<Response<DeleteSecretResponse>, Status> {
         authenticate(&request, &self.state).await?;
         let req = request.into_inner();
+        let cas = if req.has_cas { Some(req.cas) } else { None };
         self.state
-            .secret_delete(&req.path)
+            .secret_delete(&req.path, cas)
             .await
             .map_err(to_status)?;
         Ok(Response::new(DeleteSecretResponse {}))
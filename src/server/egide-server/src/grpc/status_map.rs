@@ -16,6 +16,8 @@ pub fn to_status(e: ServiceError) -> Status {
         ServiceError::Forbidden(m) => Status::permission_denied(m),
         ServiceError::Sealed => Status::unavailable("vault is sealed"),
         ServiceError::DecryptionFailed => Status::invalid_argument("decryption failed"),
+        ServiceError::RateLimited(m) => Status::resource_exhausted(m),
+        ServiceError::Undecryptable(m) => Status::data_loss(m),
         ServiceError::Internal(m) => Status::internal(m),
     }
 }
@@ -50,11 +52,23 @@ mod tests {
         assert_eq!(s.code(), Code::PermissionDenied);
     }
 
+    #[test]
+    fn rate_limited_maps_to_resource_exhausted() {
+        let s = to_status(ServiceError::RateLimited("retry in 5s".into()));
+        assert_eq!(s.code(), Code::ResourceExhausted);
+    }
+
     #[test]
     fn sealed_maps_to_unavailable() {
         assert_eq!(to_status(ServiceError::Sealed).code(), Code::Unavailable);
     }
 
+    #[test]
+    fn undecryptable_maps_to_data_loss() {
+        let s = to_status(ServiceError::Undecryptable("wrong key".into()));
+        assert_eq!(s.code(), Code::DataLoss);
+    }
+
     #[test]
     fn decryption_failed_maps_to_invalid_argument() {
         assert_eq!(
@@ -4,6 +4,7 @@
 //! - `Status`  : no bearer required (open endpoint).
 //! - `Init`    : no bearer required; service uses a synthetic root [`AuthContext`].
 //! - `Unseal`  : no bearer required (the shares are the credential).
+//! - `UnsealReset` : no bearer required, like `Unseal`.
 //! - `Seal`    : bearer required; service layer enforces root privilege.
 
 use std::sync::Arc;
@@ -14,7 +15,7 @@ use tonic::{Request, Response, Status};
 
 use egide_api::proto::{
     sys_service_server::SysService, InitRequest, InitResponse, SealRequest, SealResponse,
-    StatusRequest, StatusResponse, UnsealRequest, UnsealResponse,
+    StatusRequest, StatusResponse, UnsealRequest, UnsealResetRequest, UnsealResponse,
 };
 
 use crate::grpc::auth::authenticate;
@@ -70,17 +71,31 @@ impl SysService for SysGrpc {
     }
 
     /// Submits an unseal share. No authentication required.
+    ///
+    /// proto3 strings default to empty; an empty `nonce` joins the active
+    /// operation, like a REST body without one.
     async fn unseal(
         &self,
         request: Request<UnsealRequest>,
     ) -> Result<Response<UnsealResponse>, Status> {
         let req = request.into_inner();
-        let view = self.state.unseal(&req.share_hex).await.map_err(to_status)?;
-        Ok(Response::new(UnsealResponse {
-            sealed: view.sealed,
-            threshold: u32::from(view.threshold),
-            progress: u32::from(view.progress),
-        }))
+        let nonce = Some(req.nonce.as_str()).filter(|n| !n.is_empty());
+        let view = self
+            .state
+            .unseal(&req.share_hex, nonce)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(unseal_response(view)))
+    }
+
+    /// Discards the submitted shares and begins a new unseal operation.
+    /// No authentication required.
+    async fn unseal_reset(
+        &self,
+        _request: Request<UnsealResetRequest>,
+    ) -> Result<Response<UnsealResponse>, Status> {
+        let view = self.state.reset_unseal().await.map_err(to_status)?;
+        Ok(Response::new(unseal_response(view)))
     }
 
     /// Seals the vault. Bearer token required; service layer enforces root privilege.
@@ -91,6 +106,15 @@ impl SysService for SysGrpc {
     }
 }
 
+fn unseal_response(view: egide_api::sys::UnsealView) -> UnsealResponse {
+    UnsealResponse {
+        sealed: view.sealed,
+        threshold: u32::from(view.threshold),
+        progress: u32::from(view.progress),
+        nonce: view.nonce.unwrap_or_default(),
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::disallowed_methods)]
 mod tests {
@@ -162,11 +186,52 @@ mod tests {
         let res = svc
             .unseal(Request::new(UnsealRequest {
                 share_hex: share.clone(),
+                nonce: String::new(),
             }))
             .await
             .unwrap();
         // After one share with threshold 3, still sealed.
-        assert!(res.into_inner().sealed);
+        let body = res.into_inner();
+        assert!(body.sealed);
+        assert!(!body.nonce.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unseal_with_nonce_from_before_reset_is_refused() {
+        let (_tmp, svc) = fresh_svc().await;
+        let init_res = svc
+            .init(Request::new(InitRequest {
+                shares: 5,
+                threshold: 3,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let first = svc
+            .unseal(Request::new(UnsealRequest {
+                share_hex: init_res.shares_hex[0].clone(),
+                nonce: String::new(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let reset = svc
+            .unseal_reset(Request::new(UnsealResetRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(reset.progress, 0);
+        assert_ne!(reset.nonce, first.nonce);
+
+        let err = svc
+            .unseal(Request::new(UnsealRequest {
+                share_hex: init_res.shares_hex[1].clone(),
+                nonce: first.nonce,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::AlreadyExists);
     }
 
     // ------------------------------------------------------------------
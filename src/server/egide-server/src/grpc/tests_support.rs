@@ -2,6 +2,7 @@
 //!
 //! This module is compiled only in `#[cfg(test)]` contexts.
 
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -12,7 +13,7 @@ use egide_seal::{SealManager, ShamirConfig};
 use egide_storage::StorageBackend;
 use tokio::sync::RwLock;
 
-use egide_api::ServiceContext;
+use egide_api::{PrefixMetrics, SecretGenerators, ServiceContext};
 
 /// Builds an uninitialized, sealed [`ServiceContext`].
 ///
@@ -38,6 +39,10 @@ pub(crate) async fn sealed_context() -> (tempfile::TempDir, Arc<ServiceContext>)
         start_time: Instant::now(),
         version: "0.1.0-test",
         service_tokens: service_store,
+        generators: SecretGenerators::default(),
+        encrypt_storage: false,
+        secret_metrics: PrefixMetrics::default(),
+        secret_decrypt_failures: AtomicU64::new(0),
     });
 
     (tmp, ctx)
@@ -59,8 +64,12 @@ pub(crate) async fn unsealed_context_with_token() -> (tempfile::TempDir, Arc<Ser
         .await
         .expect("initialize");
     let root_token = init.root_token.clone();
+    let nonce = seal_manager.begin_unseal().expect("begin unseal");
     for share in init.shares.iter().take(3) {
-        seal_manager.unseal(share).await.expect("unseal share");
+        seal_manager
+            .unseal(&nonce, share)
+            .await
+            .expect("unseal share");
     }
 
     let storage: Arc<dyn StorageBackend> = Arc::new(seal_manager.storage());
@@ -79,6 +88,10 @@ pub(crate) async fn unsealed_context_with_token() -> (tempfile::TempDir, Arc<Ser
         start_time: Instant::now(),
         version: "0.1.0-test",
         service_tokens: service_store,
+        generators: SecretGenerators::default(),
+        encrypt_storage: false,
+        secret_metrics: PrefixMetrics::default(),
+        secret_decrypt_failures: AtomicU64::new(0),
     });
 
     ctx.ensure_secrets_engine()
@@ -2,17 +2,23 @@
 
 pub mod grpc;
 
+pub mod metrics;
+
 pub mod problem;
 pub use problem::Problem;
 
 pub mod transit;
 
+pub mod validation;
+
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Instant;
 
 use axum::{
+    error_handling::HandleErrorLayer,
     extract::{FromRequestParts, State},
     http::{request::Parts, StatusCode},
     routing::{delete, get, post},
@@ -21,20 +27,26 @@ use axum::{
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::load_shed::LoadShedLayer;
+use tower::{BoxError, ServiceBuilder};
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 pub use egide_api::ServiceContext as AppState;
+pub use egide_api::{PrefixMetrics, SecretGenerators};
 pub use egide_auth::AuthService;
 use egide_auth::{
     AuthContext, AuthError, RootTokenBackend, ServiceTokenBackend, ServiceTokenStore,
 };
-use egide_seal::SealManager;
+use egide_seal::{SealManager, UnsealLockout};
+use validation::{check_field, RequestSchema, ValidatedJson};
 
 /// Authenticated request extractor.
 ///
 /// Validates the `Authorization: Bearer <token>` header (RFC 6750) and returns
-/// the authentication context.
+/// the authentication context. A valid token counts as activity against the
+/// idle seal timeout.
 pub struct Authenticated(pub AuthContext);
 
 impl FromRequestParts<Arc<AppState>> for Authenticated {
@@ -44,13 +56,7 @@ impl FromRequestParts<Arc<AppState>> for Authenticated {
         parts: &mut Parts,
         state: &Arc<AppState>,
     ) -> Result<Self, Self::Rejection> {
-        let token = parts
-            .headers
-            .get(axum::http::header::AUTHORIZATION)
-            .and_then(|v| v.to_str().ok())
-            .and_then(|h| h.strip_prefix("Bearer "))
-            .map(str::trim)
-            .filter(|t| !t.is_empty())
+        let token = bearer_token(&parts.headers)
             .ok_or_else(|| Problem::new(StatusCode::UNAUTHORIZED, "missing bearer token"))?;
 
         let ctx = state.auth.validate(token).await.map_err(|e| {
@@ -60,11 +66,55 @@ impl FromRequestParts<Arc<AppState>> for Authenticated {
             };
             Problem::new(StatusCode::UNAUTHORIZED, detail)
         })?;
+        state.seal.read().await.touch();
 
         Ok(Authenticated(ctx))
     }
 }
 
+/// Returns the token of an `Authorization: Bearer <token>` header, if any.
+fn bearer_token(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Authentication extractor for secret reads.
+///
+/// Behaves like [`Authenticated`], except that a request carrying no
+/// `Authorization` header for a path on the configured public-read allowlist
+/// is served under [`AuthContext::anonymous`]. A presented token is always
+/// validated, and the allowlist is empty unless explicitly configured.
+pub struct ReadAuthenticated(pub AuthContext);
+
+impl FromRequestParts<Arc<AppState>> for ReadAuthenticated {
+    type Rejection = Problem;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        if !parts
+            .headers
+            .contains_key(axum::http::header::AUTHORIZATION)
+        {
+            if let Ok(axum::extract::Path(path)) =
+                axum::extract::Path::<String>::from_request_parts(parts, state).await
+            {
+                if state.auth.allows_anonymous_read(&path) {
+                    return Ok(ReadAuthenticated(AuthContext::anonymous()));
+                }
+            }
+        }
+
+        let Authenticated(ctx) = Authenticated::from_request_parts(parts, state).await?;
+        Ok(ReadAuthenticated(ctx))
+    }
+}
+
 // ============================================================================
 // CLI Arguments
 // ============================================================================
@@ -90,6 +140,74 @@ pub struct Cli {
     /// gRPC server bind address.
     #[arg(long, default_value = "0.0.0.0:8201", env = "EGIDE_GRPC_BIND")]
     pub grpc_bind: String,
+
+    /// Separate REST bind address for the `/v1/sys/*` admin endpoints and
+    /// `/metrics`. When set, `--bind` serves only data routes and health,
+    /// and `--grpc-bind` does not serve the Sys service.
+    #[arg(long, env = "EGIDE_ADMIN_BIND_ADDRESS")]
+    pub admin_bind: Option<String>,
+
+    /// Secret path prefix readable without a token (repeatable; none by default).
+    #[arg(
+        long = "public-read-prefix",
+        env = "EGIDE_PUBLIC_READ_PREFIXES",
+        value_delimiter = ','
+    )]
+    pub public_read_prefixes: Vec<String>,
+
+    /// Top-level secret path prefix whose operations are counted separately
+    /// on `/metrics` (repeatable; none by default).
+    #[arg(
+        long = "metrics-prefix",
+        env = "EGIDE_METRICS_PREFIXES",
+        value_delimiter = ','
+    )]
+    pub metrics_prefixes: Vec<String>,
+
+    /// Encrypt engine databases at rest under a key derived from the master
+    /// key (requires a build with the `sqlcipher` feature).
+    #[arg(long, env = "EGIDE_ENCRYPT_STORAGE")]
+    pub encrypt_storage: bool,
+
+    /// Failed unseal reconstructions allowed before unseal is locked out.
+    #[arg(long, default_value_t = 5, env = "EGIDE_UNSEAL_MAX_ATTEMPTS")]
+    pub unseal_max_attempts: u32,
+
+    /// Seconds unseal stays locked out after too many failed reconstructions.
+    #[arg(long, default_value_t = 300, env = "EGIDE_UNSEAL_LOCKOUT_SECS")]
+    pub unseal_lockout_secs: u64,
+
+    /// Seal the vault after this many seconds without an authenticated
+    /// request (never when unset; ignored in dev mode).
+    #[arg(long, env = "EGIDE_SEAL_AFTER_IDLE_SECS")]
+    pub seal_after_idle_secs: Option<u64>,
+
+    /// Purge expired secret versions every this many seconds (never when
+    /// unset). A secret's current version is kept even once expired.
+    #[arg(long, env = "EGIDE_PURGE_EXPIRED_INTERVAL_SECS")]
+    pub purge_expired_interval_secs: Option<u64>,
+
+    /// Minimum seconds between two root token rotations.
+    #[arg(long, default_value_t = 60, env = "EGIDE_ROOT_ROTATION_INTERVAL_SECS")]
+    pub root_rotation_interval_secs: u64,
+
+    /// Maximum number of REST requests handled at once (unlimited when unset).
+    #[arg(long, env = "EGIDE_MAX_CONCURRENT_REQUESTS")]
+    pub max_concurrent_requests: Option<usize>,
+
+    /// Answer requests over `--max-concurrent-requests` with 503 instead of
+    /// queueing them.
+    #[arg(long, env = "EGIDE_SHED_EXCESS_REQUESTS")]
+    pub shed_excess_requests: bool,
+}
+
+/// What happens to a request arriving while the concurrency limit is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverloadPolicy {
+    /// Wait for a slot to free up.
+    Queue,
+    /// Fail fast with `503 Service Unavailable`.
+    Shed,
 }
 
 // ============================================================================
@@ -114,6 +232,31 @@ pub struct StatusResponse {
     sealed: bool,
 }
 
+/// Seal status response body.
+#[derive(Serialize)]
+pub struct SealStatusResponse {
+    sealed: bool,
+    initialized: bool,
+    total_shares: u8,
+    threshold: u8,
+    progress: u8,
+    initialized_at: Option<u64>,
+    dev_mode: bool,
+}
+
+/// Version response body.
+#[derive(Serialize)]
+pub struct VersionResponse {
+    version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_sha: Option<&'static str>,
+    enabled_engines: Vec<&'static str>,
+    crypto_algorithms: Vec<String>,
+    cpu_features: egide_transit::CpuCryptoFeatures,
+    features: Vec<&'static str>,
+    build_profile: &'static str,
+}
+
 /// Init request body.
 #[derive(Deserialize)]
 pub struct InitRequest {
@@ -123,6 +266,13 @@ pub struct InitRequest {
     secret_threshold: u8,
 }
 
+impl RequestSchema for InitRequest {
+    fn validate(body: &serde_json::Map<String, serde_json::Value>) -> Result<(), Problem> {
+        check_field::<u8>(body, "secret_shares", false)?;
+        check_field::<u8>(body, "secret_threshold", false)
+    }
+}
+
 fn default_shares() -> u8 {
     5
 }
@@ -142,6 +292,10 @@ pub struct InitResponse {
 #[derive(Deserialize)]
 pub struct UnsealRequest {
     key: String,
+    /// Nonce of the unseal operation, from a previous response. Without it
+    /// the share joins the active operation.
+    #[serde(default)]
+    nonce: Option<String>,
 }
 
 /// Unseal response body.
@@ -150,6 +304,20 @@ pub struct UnsealResponse {
     sealed: bool,
     threshold: u8,
     progress: u8,
+    /// Nonce to send with the next share; absent once unsealed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<String>,
+}
+
+impl From<egide_api::sys::UnsealView> for UnsealResponse {
+    fn from(view: egide_api::sys::UnsealView) -> Self {
+        Self {
+            sealed: view.sealed,
+            threshold: view.threshold,
+            progress: view.progress,
+            nonce: view.nonce,
+        }
+    }
 }
 
 /// Seal response body.
@@ -158,12 +326,37 @@ pub struct SealResponse {
     sealed: bool,
 }
 
+/// Body for POST `/v1/sys/maintenance/purge-deleted`.
+#[derive(Deserialize)]
+pub struct PurgeDeletedRequest {
+    /// Only secrets soft-deleted more than this many seconds ago are purged.
+    pub older_than_secs: u64,
+}
+
+/// Response for POST `/v1/sys/maintenance/purge-deleted`.
+#[derive(Serialize)]
+pub struct PurgeDeletedResponse {
+    purged: u32,
+}
+
+/// Response for POST `/v1/sys/rotate-root`.
+#[derive(Serialize)]
+pub struct RotateRootResponse {
+    root_token: String,
+}
+
 /// Error response body.
 #[derive(Serialize)]
 pub struct ErrorResponse {
     error: String,
+    /// Machine-readable cause, for failures worth telling apart in alerts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<&'static str>,
 }
 
+/// `error_code` of a secret that failed to decrypt or authenticate.
+pub const DECRYPTION_FAILED: &str = "decryption_failed";
+
 // Secrets types
 
 /// Secret write request body.
@@ -176,6 +369,37 @@ pub struct SecretPutRequest {
     cas: Option<u32>,
 }
 
+impl RequestSchema for SecretPutRequest {
+    fn validate(body: &serde_json::Map<String, serde_json::Value>) -> Result<(), Problem> {
+        check_field::<std::collections::HashMap<String, String>>(body, "data", true)?;
+        check_field::<Option<u32>>(body, "cas", false)
+    }
+}
+
+/// Query parameters of a secret write.
+#[derive(Deserialize)]
+pub struct SecretPutQuery {
+    /// Validate the write and report the version it would create, without persisting it.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Query parameters of a secret delete.
+#[derive(Deserialize)]
+pub struct SecretDeleteQuery {
+    /// Only delete if the current version matches.
+    cas: Option<u32>,
+}
+
+/// Secret rotation request body.
+#[derive(Deserialize)]
+pub struct SecretRotateRequest {
+    /// Name of a registered generator, e.g. `random-password`.
+    generator: String,
+    /// Field of the secret that receives the generated value.
+    field: String,
+}
+
 /// Secret read response body.
 #[derive(Serialize)]
 pub struct SecretResponse {
@@ -195,6 +419,25 @@ pub struct SecretMetadataResponse {
 #[derive(Serialize)]
 pub struct SecretWriteResponse {
     version: u32,
+    /// Set on a dry run: `version` is the one the write would have created.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    dry_run: bool,
+}
+
+/// Secret version history response body.
+#[derive(Serialize)]
+pub struct SecretVersionsResponse {
+    versions: Vec<SecretVersionResponse>,
+}
+
+/// One entry of a secret's version history.
+#[derive(Serialize)]
+pub struct SecretVersionResponse {
+    version: u32,
+    created_at: u64,
+    expires_at: Option<u64>,
+    created_by: Option<String>,
+    expired: bool,
 }
 
 /// Secret list response body.
@@ -203,6 +446,23 @@ pub struct SecretListResponse {
     keys: Vec<String>,
 }
 
+/// Query parameters of a secret count.
+#[derive(Deserialize)]
+pub struct SecretCountQuery {
+    /// Only count secrets whose path starts with this prefix.
+    #[serde(default)]
+    prefix: String,
+    /// Also count soft-deleted secrets.
+    #[serde(default)]
+    include_deleted: bool,
+}
+
+/// Secret count response body.
+#[derive(Serialize)]
+pub struct SecretCountResponse {
+    count: u64,
+}
+
 // Service token types
 
 #[derive(serde::Deserialize)]
@@ -255,13 +515,60 @@ pub async fn status_handler(State(state): State<Arc<AppState>>) -> Json<StatusRe
     })
 }
 
+/// Handles GET `/v1/sys/seal-status`.
+///
+/// Lets operators coordinating a quorum poll the unseal progress without
+/// submitting a share.
+pub async fn seal_status_handler(State(state): State<Arc<AppState>>) -> Json<SealStatusResponse> {
+    let view = state.seal_status().await;
+    Json(SealStatusResponse {
+        sealed: view.sealed,
+        initialized: view.initialized,
+        total_shares: view.total_shares,
+        threshold: view.threshold,
+        progress: view.progress,
+        initialized_at: view.initialized_at,
+        dev_mode: view.dev_mode,
+    })
+}
+
+/// Handles GET `/v1/sys/version`.
+///
+/// Describes the build rather than the vault: the crate version, the commit
+/// it was built from (when `EGIDE_GIT_SHA` is set at compile time), the
+/// engines, algorithms and optional features compiled in, and the CPU's
+/// crypto acceleration. Like health, it needs no token.
+pub async fn version_handler() -> Json<VersionResponse> {
+    let mut features = Vec::new();
+    if cfg!(feature = "sqlcipher") {
+        features.push("sqlcipher");
+    }
+
+    Json(VersionResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: option_env!("EGIDE_GIT_SHA"),
+        enabled_engines: vec!["secrets", "transit"],
+        crypto_algorithms: egide_transit::IMPLEMENTED_KEY_TYPES
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        cpu_features: egide_transit::TransitEngine::cpu_features(),
+        features,
+        build_profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        },
+    })
+}
+
 /// Handles POST `/v1/sys/init`.
 ///
 /// Init is a bootstrap operation: no bearer token is required. The service layer
 /// enforces that init can only succeed when the vault is not yet initialized.
 pub async fn init_handler(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<InitRequest>,
+    ValidatedJson(req): ValidatedJson<InitRequest>,
 ) -> Result<Json<InitResponse>, (StatusCode, Json<ErrorResponse>)> {
     // Bootstrap context: init is open to any caller (the shares are the credential).
     let view = state
@@ -282,6 +589,7 @@ pub async fn init_handler(
                 status,
                 Json(ErrorResponse {
                     error: e.to_string(),
+                    error_code: None,
                 }),
             )
         })?;
@@ -304,19 +612,10 @@ pub async fn unseal_handler(
     State(state): State<Arc<AppState>>,
     Json(req): Json<UnsealRequest>,
 ) -> Result<Json<UnsealResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let view = state.unseal(&req.key).await.map_err(|e| {
-        use egide_api::ServiceError as E;
-        let status = match &e {
-            E::BadRequest(_) => StatusCode::BAD_REQUEST,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        };
-        (
-            status,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
-    })?;
+    let view = state
+        .unseal(&req.key, req.nonce.as_deref())
+        .await
+        .map_err(|e| unseal_error(&e))?;
 
     if view.sealed {
         tracing::info!("Unseal progress: {}/{}", view.progress, view.threshold);
@@ -324,11 +623,35 @@ pub async fn unseal_handler(
         tracing::info!("Egide unsealed successfully");
     }
 
-    Ok(Json(UnsealResponse {
-        sealed: view.sealed,
-        threshold: view.threshold,
-        progress: view.progress,
-    }))
+    Ok(Json(view.into()))
+}
+
+/// Handles POST `/v1/sys/unseal/reset`.
+///
+/// Discards the shares submitted so far and returns the nonce of a new
+/// unseal operation. Open like unseal.
+pub async fn unseal_reset_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<UnsealResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let view = state.reset_unseal().await.map_err(|e| unseal_error(&e))?;
+    tracing::info!("Unseal progress reset");
+    Ok(Json(view.into()))
+}
+
+fn unseal_error(e: &egide_api::ServiceError) -> (StatusCode, Json<ErrorResponse>) {
+    use egide_api::ServiceError as E;
+    let status = match e {
+        E::BadRequest(_) => StatusCode::BAD_REQUEST,
+        E::Conflict(_) => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            error: e.to_string(),
+            error_code: None,
+        }),
+    )
 }
 
 /// Handles POST `/v1/sys/seal`.
@@ -347,6 +670,7 @@ pub async fn seal_handler(
             status,
             Json(ErrorResponse {
                 error: e.to_string(),
+                error_code: None,
             }),
         )
     })?;
@@ -355,13 +679,55 @@ pub async fn seal_handler(
     Ok(Json(SealResponse { sealed: true }))
 }
 
+/// Handles POST `/v1/sys/rotate-root` (root-only).
+///
+/// Routine root-credential hygiene: the presented root token is replaced by
+/// a new one, returned once. Rate-limited by `--root-rotation-interval-secs`.
+pub async fn rotate_root_handler(
+    Authenticated(ctx): Authenticated,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<RotateRootResponse>, Problem> {
+    let root_token = state.rotate_root(&ctx).await.map_err(Problem::from)?;
+    Ok(Json(RotateRootResponse { root_token }))
+}
+
+/// Handles POST `/v1/sys/rotate-master-key` (root-only).
+///
+/// Replaces the master key and rewraps all engine data under it; answers
+/// with the new shares and root token in the body `init` returns. Requests
+/// wait while the rewrap runs.
+pub async fn rotate_master_key_handler(
+    Authenticated(ctx): Authenticated,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<InitResponse>, Problem> {
+    let view = state.rotate_master_key(&ctx).await.map_err(Problem::from)?;
+    Ok(Json(InitResponse {
+        root_token: view.root_token,
+        keys: view.shares_hex,
+        keys_base64: view.shares_base64,
+    }))
+}
+
+/// Handles POST `/v1/sys/maintenance/purge-deleted` (root-only).
+pub async fn purge_deleted_handler(
+    Authenticated(ctx): Authenticated,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PurgeDeletedRequest>,
+) -> Result<Json<PurgeDeletedResponse>, Problem> {
+    let purged = state
+        .purge_deleted_secrets(&ctx, std::time::Duration::from_secs(req.older_than_secs))
+        .await
+        .map_err(Problem::from)?;
+    Ok(Json(PurgeDeletedResponse { purged }))
+}
+
 // ============================================================================
 // Handlers - Secrets
 // ============================================================================
 
 /// Handles GET `/v1/secrets/{*path}`.
 pub async fn secrets_get_handler(
-    Authenticated(ctx): Authenticated,
+    ReadAuthenticated(ctx): ReadAuthenticated,
     State(state): State<Arc<AppState>>,
     axum::extract::Path(path): axum::extract::Path<String>,
 ) -> Result<Json<SecretResponse>, (StatusCode, Json<ErrorResponse>)> {
@@ -374,10 +740,12 @@ pub async fn secrets_get_handler(
             E::Sealed => StatusCode::SERVICE_UNAVAILABLE,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
+        let error_code = matches!(e, E::Undecryptable(_)).then_some(DECRYPTION_FAILED);
         (
             status,
             Json(ErrorResponse {
                 error: e.to_string(),
+                error_code,
             }),
         )
     })?;
@@ -392,21 +760,119 @@ pub async fn secrets_get_handler(
     }))
 }
 
+/// Handles GET `/v1/secrets-versions/{*path}`: the version history of a
+/// secret, newest first.
+///
+/// Served apart from the secrets catch-all so every secret path stays
+/// readable, and never public: unlike a read, it takes a bearer token even
+/// under a public-read prefix.
+pub async fn secrets_versions_handler(
+    Authenticated(ctx): Authenticated,
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> Result<Json<SecretVersionsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    tracing::debug!(account = %ctx.account_id, path = %path, "secrets.versions");
+
+    let versions = state.secret_versions(&path).await.map_err(|e| {
+        use egide_api::ServiceError as E;
+        let status = match &e {
+            E::NotFound => StatusCode::NOT_FOUND,
+            E::BadRequest(_) => StatusCode::BAD_REQUEST,
+            E::Sealed => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                error_code: None,
+            }),
+        )
+    })?;
+
+    Ok(Json(SecretVersionsResponse {
+        versions: versions
+            .into_iter()
+            .map(|v| SecretVersionResponse {
+                version: v.version,
+                created_at: v.created_at,
+                expires_at: v.expires_at,
+                created_by: v.created_by,
+                expired: v.expired,
+            })
+            .collect(),
+    }))
+}
+
 /// Handles PUT `/v1/secrets/{*path}`.
+///
+/// With `?dry_run=true` the write is validated and the prospective version
+/// returned, but nothing is stored.
 pub async fn secrets_put_handler(
     Authenticated(ctx): Authenticated,
     State(state): State<Arc<AppState>>,
     axum::extract::Path(path): axum::extract::Path<String>,
-    Json(req): Json<SecretPutRequest>,
+    axum::extract::Query(query): axum::extract::Query<SecretPutQuery>,
+    ValidatedJson(req): ValidatedJson<SecretPutRequest>,
+) -> Result<Json<SecretWriteResponse>, (StatusCode, Json<ErrorResponse>)> {
+    tracing::debug!(account = %ctx.account_id, path = %path, dry_run = query.dry_run, "secrets.put");
+
+    let result = if query.dry_run {
+        state.secret_validate_put(&path, &req.data, req.cas).await
+    } else {
+        state
+            .secret_put_as(&ctx.account_id, &path, req.data, req.cas)
+            .await
+    };
+    let version = result.map_err(|e| {
+        use egide_api::ServiceError as E;
+        let status = match &e {
+            E::Conflict(_) => StatusCode::CONFLICT,
+            E::BadRequest(_) => StatusCode::BAD_REQUEST,
+            E::Sealed => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                error_code: None,
+            }),
+        )
+    })?;
+
+    Ok(Json(SecretWriteResponse {
+        version,
+        dry_run: query.dry_run,
+    }))
+}
+
+/// Handles POST `/v1/secrets-rotate/{*path}`.
+///
+/// Generates a new value for one field with a registered generator and writes
+/// it as a new version. Only the new version number is returned. Served apart
+/// from the secrets catch-all so no secret path is reserved for it.
+pub async fn secrets_rotate_handler(
+    Authenticated(ctx): Authenticated,
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    Json(req): Json<SecretRotateRequest>,
 ) -> Result<Json<SecretWriteResponse>, (StatusCode, Json<ErrorResponse>)> {
-    tracing::debug!(account = %ctx.account_id, path = %path, "secrets.put");
+    tracing::debug!(
+        account = %ctx.account_id,
+        path = %path,
+        generator = %req.generator,
+        field = %req.field,
+        "secrets.rotate"
+    );
 
     let version = state
-        .secret_put(&path, req.data, req.cas)
+        .secret_rotate(&ctx.account_id, &path, &req.generator, &req.field)
         .await
         .map_err(|e| {
             use egide_api::ServiceError as E;
             let status = match &e {
+                E::NotFound => StatusCode::NOT_FOUND,
                 E::Conflict(_) => StatusCode::CONFLICT,
                 E::BadRequest(_) => StatusCode::BAD_REQUEST,
                 E::Sealed => StatusCode::SERVICE_UNAVAILABLE,
@@ -416,25 +882,34 @@ pub async fn secrets_put_handler(
                 status,
                 Json(ErrorResponse {
                     error: e.to_string(),
+                    error_code: None,
                 }),
             )
         })?;
 
-    Ok(Json(SecretWriteResponse { version }))
+    Ok(Json(SecretWriteResponse {
+        version,
+        dry_run: false,
+    }))
 }
 
 /// Handles DELETE `/v1/secrets/{*path}`.
+///
+/// With `?cas=N` the secret is only deleted if its current version is `N`;
+/// otherwise the request fails with 409.
 pub async fn secrets_delete_handler(
     Authenticated(ctx): Authenticated,
     State(state): State<Arc<AppState>>,
     axum::extract::Path(path): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<SecretDeleteQuery>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    tracing::debug!(account = %ctx.account_id, path = %path, "secrets.delete");
+    tracing::debug!(account = %ctx.account_id, path = %path, cas = ?query.cas, "secrets.delete");
 
-    state.secret_delete(&path).await.map_err(|e| {
+    state.secret_delete(&path, query.cas).await.map_err(|e| {
         use egide_api::ServiceError as E;
         let status = match &e {
             E::NotFound => StatusCode::NOT_FOUND,
+            E::Conflict(_) => StatusCode::CONFLICT,
             E::Sealed => StatusCode::SERVICE_UNAVAILABLE,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
@@ -442,6 +917,7 @@ pub async fn secrets_delete_handler(
             status,
             Json(ErrorResponse {
                 error: e.to_string(),
+                error_code: None,
             }),
         )
     })?;
@@ -466,6 +942,7 @@ pub async fn secrets_list_root_handler(
             status,
             Json(ErrorResponse {
                 error: e.to_string(),
+                error_code: None,
             }),
         )
     })?;
@@ -475,6 +952,35 @@ pub async fn secrets_list_root_handler(
     }))
 }
 
+/// Handles GET `/v1/secrets-count`.
+pub async fn secrets_count_handler(
+    Authenticated(ctx): Authenticated,
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<SecretCountQuery>,
+) -> Result<Json<SecretCountResponse>, (StatusCode, Json<ErrorResponse>)> {
+    tracing::debug!(account = %ctx.account_id, prefix = %query.prefix, "secrets.count");
+
+    let count = state
+        .secret_count(&query.prefix, query.include_deleted)
+        .await
+        .map_err(|e| {
+            use egide_api::ServiceError as E;
+            let status = match &e {
+                E::Sealed => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (
+                status,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                    error_code: None,
+                }),
+            )
+        })?;
+
+    Ok(Json(SecretCountResponse { count }))
+}
+
 // ============================================================================
 // Handlers - Service Tokens
 // ============================================================================
@@ -529,6 +1035,24 @@ async fn service_token_revoke_handler(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Handles POST `/v1/auth/revoke-self`.
+///
+/// Revokes the bearer token of the request itself (logout).
+async fn revoke_self_handler(
+    Authenticated(ctx): Authenticated,
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+) -> Result<StatusCode, Problem> {
+    // `Authenticated` already validated this header.
+    let token = bearer_token(&headers)
+        .ok_or_else(|| Problem::new(StatusCode::UNAUTHORIZED, "missing bearer token"))?;
+    state
+        .revoke_self(&ctx, token)
+        .await
+        .map_err(Problem::from)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 // ============================================================================
 // Utilities
 // ============================================================================
@@ -548,22 +1072,74 @@ fn create_auth_service(
 // Router and server startup
 // ============================================================================
 
-/// Builds the axum router for the given application state.
+/// Builds the axum router for the given application state, serving every
+/// route on one listener.
 pub fn build_router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/", get(root_handler))
+        .merge(sys_routes())
+        .merge(data_routes())
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+/// Builds the router for the admin listener of a split deployment: the
+/// `/v1/sys/*` endpoints and `/metrics`.
+pub fn build_admin_router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/", get(root_handler))
+        .merge(sys_routes())
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+/// Builds the router for the data listener of a split deployment: secrets,
+/// transit and auth routes, plus health so load balancers can probe it.
+pub fn build_data_router(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/", get(root_handler))
         .route("/v1/sys/health", get(health_handler))
+        .merge(data_routes())
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+/// Admin routes: system lifecycle and observability.
+fn sys_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/metrics", get(metrics::metrics_handler))
+        .route("/v1/sys/health", get(health_handler))
         .route("/v1/sys/status", get(status_handler))
+        .route("/v1/sys/seal-status", get(seal_status_handler))
+        .route("/v1/sys/version", get(version_handler))
         .route("/v1/sys/init", post(init_handler))
         .route("/v1/sys/unseal", post(unseal_handler))
+        .route("/v1/sys/unseal/reset", post(unseal_reset_handler))
         .route("/v1/sys/seal", post(seal_handler))
+        .route("/v1/sys/rotate-root", post(rotate_root_handler))
+        .route("/v1/sys/rotate-master-key", post(rotate_master_key_handler))
+        .route(
+            "/v1/sys/maintenance/purge-deleted",
+            post(purge_deleted_handler),
+        )
+}
+
+/// Data-plane routes: secrets, auth and transit.
+fn data_routes() -> Router<Arc<AppState>> {
+    Router::new()
         .route("/v1/secrets", get(secrets_list_root_handler))
+        .route("/v1/secrets-count", get(secrets_count_handler))
         .route(
             "/v1/secrets/{*path}",
             get(secrets_get_handler)
                 .put(secrets_put_handler)
                 .delete(secrets_delete_handler),
         )
+        .route(
+            "/v1/secrets-versions/{*path}",
+            get(secrets_versions_handler),
+        )
+        .route("/v1/secrets-rotate/{*path}", post(secrets_rotate_handler))
         .route(
             "/v1/auth/service-tokens",
             post(service_token_create_handler).get(service_token_list_handler),
@@ -572,6 +1148,7 @@ pub fn build_router(state: Arc<AppState>) -> Router {
             "/v1/auth/service-tokens/{token_id}",
             delete(service_token_revoke_handler),
         )
+        .route("/v1/auth/revoke-self", post(revoke_self_handler))
         .route(
             "/v1/transit/keys",
             post(transit::create_key_handler).get(transit::list_keys_handler),
@@ -588,8 +1165,25 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         .route("/v1/transit/decrypt/{name}", post(transit::decrypt_handler))
         .route("/v1/transit/datakey/{name}", post(transit::datakey_handler))
         .route("/v1/transit/rewrap/{name}", post(transit::rewrap_handler))
-        .layer(TraceLayer::new_for_http())
-        .with_state(state)
+}
+
+/// Caps the number of requests `router` handles at once.
+///
+/// The limit is global across routes. Excess requests wait or are shed with
+/// a `503` depending on `overload`; combined with the storage pool size this
+/// keeps a burst of clients from exhausting connections and memory.
+pub fn limit_concurrency(router: Router, max_requests: usize, overload: OverloadPolicy) -> Router {
+    match overload {
+        OverloadPolicy::Queue => router.layer(GlobalConcurrencyLimitLayer::new(max_requests)),
+        OverloadPolicy::Shed => router.layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(|_: BoxError| async {
+                    Problem::new(StatusCode::SERVICE_UNAVAILABLE, "server overloaded")
+                }))
+                .layer(LoadShedLayer::new())
+                .layer(GlobalConcurrencyLimitLayer::new(max_requests)),
+        ),
+    }
 }
 
 /// Runs the server: builds state from the CLI, binds and serves.
@@ -617,6 +1211,15 @@ pub async fn run(cli: Cli) -> anyhow::Result<()> {
 
     // Initialize seal manager.
     let mut seal_manager = SealManager::new(&cli.data_dir).await?;
+    seal_manager.set_unseal_lockout(UnsealLockout {
+        max_failed_attempts: cli.unseal_max_attempts,
+        cooldown: std::time::Duration::from_secs(cli.unseal_lockout_secs),
+    });
+    seal_manager.set_root_rotation_interval(std::time::Duration::from_secs(
+        cli.root_rotation_interval_secs,
+    ));
+    let seal_after_idle = cli.seal_after_idle_secs.map(std::time::Duration::from_secs);
+    seal_manager.set_seal_after_idle(seal_after_idle);
 
     // In dev mode, enable auto-unseal.
     if cli.dev {
@@ -628,7 +1231,17 @@ pub async fn run(cli: Cli) -> anyhow::Result<()> {
     let service_store = ServiceTokenStore::new(
         Arc::new(seal_manager.storage().clone()) as Arc<dyn egide_storage::StorageBackend>
     );
-    let auth_service = create_auth_service(&seal_manager, service_store.clone());
+    let auth_service = create_auth_service(&seal_manager, service_store.clone())
+        .with_public_read_prefixes(&cli.public_read_prefixes);
+    if !cli.public_read_prefixes.is_empty() {
+        tracing::warn!(
+            prefixes = ?cli.public_read_prefixes,
+            "Anonymous reads enabled for public secret paths"
+        );
+    }
+    if cli.encrypt_storage {
+        tracing::info!("Engine databases encrypted at rest");
+    }
 
     let state = Arc::new(AppState {
         auth: auth_service,
@@ -639,6 +1252,10 @@ pub async fn run(cli: Cli) -> anyhow::Result<()> {
         start_time: Instant::now(),
         version: env!("CARGO_PKG_VERSION"),
         service_tokens: service_store,
+        generators: SecretGenerators::default(),
+        encrypt_storage: cli.encrypt_storage,
+        secret_metrics: PrefixMetrics::new(&cli.metrics_prefixes),
+        secret_decrypt_failures: AtomicU64::new(0),
     });
 
     // If already unsealed (dev mode), initialize the engines.
@@ -656,7 +1273,39 @@ pub async fn run(cli: Cli) -> anyhow::Result<()> {
         }
     }
 
-    let app = build_router(state.clone());
+    if let Some(idle) = seal_after_idle {
+        tracing::info!(
+            idle_secs = idle.as_secs(),
+            "Sealing after idle timeout enabled"
+        );
+        spawn_idle_sealer(state.clone());
+    }
+
+    if let Some(secs) = cli.purge_expired_interval_secs {
+        tracing::info!(
+            interval_secs = secs,
+            "Scheduled purge of expired secret versions enabled"
+        );
+        spawn_expired_purger(state.clone(), std::time::Duration::from_secs(secs.max(1)));
+    }
+
+    // With an admin listener, the main one serves only the data plane. The
+    // concurrency limit applies to the data plane alone, so operators can
+    // still reach the admin endpoints during a burst.
+    let mut app = if cli.admin_bind.is_some() {
+        build_data_router(state.clone())
+    } else {
+        build_router(state.clone())
+    };
+    if let Some(max_requests) = cli.max_concurrent_requests {
+        let overload = if cli.shed_excess_requests {
+            OverloadPolicy::Shed
+        } else {
+            OverloadPolicy::Queue
+        };
+        tracing::info!(max_requests, ?overload, "REST concurrency limit enabled");
+        app = limit_concurrency(app, max_requests, overload);
+    }
 
     let rest_addr: SocketAddr = cli.bind.parse()?;
     let grpc_addr: SocketAddr = cli.grpc_bind.parse()?;
@@ -675,23 +1324,110 @@ pub async fn run(cli: Cli) -> anyhow::Result<()> {
 
     tracing::info!("REST on http://{local_addr}, gRPC on http://{grpc_addr}");
 
+    let admin_handle = match &cli.admin_bind {
+        Some(admin_bind) => Some(spawn_admin_listener(admin_bind, state.clone()).await?),
+        None => None,
+    };
+
     let rest_handle = tokio::spawn(async move {
         axum::serve(listener, app)
             .with_graceful_shutdown(shutdown_signal())
             .await
             .map_err(anyhow::Error::from)
     });
-    let grpc_handle = tokio::spawn(grpc::serve(state, grpc_addr, shutdown_signal()));
+    // The Sys service follows the REST sys routes onto the admin side: with
+    // an admin listener, the data gRPC listener does not serve it.
+    let grpc_handle = if cli.admin_bind.is_some() {
+        tokio::spawn(grpc::serve_data_plane(
+            state.clone(),
+            grpc_addr,
+            shutdown_signal(),
+        ))
+    } else {
+        tokio::spawn(grpc::serve(state.clone(), grpc_addr, shutdown_signal()))
+    };
 
     let (rest_res, grpc_res) = tokio::join!(rest_handle, grpc_handle);
+    let admin_res = match admin_handle {
+        Some(admin_handle) => Some(admin_handle.await),
+        None => None,
+    };
+
+    // Transit key usage is batched in memory; write the last interval's
+    // before exiting, whichever way the listeners stopped.
+    state.flush_transit_usage().await;
+
     rest_res.map_err(|e| anyhow::anyhow!("REST task panicked: {e}"))??;
     grpc_res.map_err(|e| anyhow::anyhow!("gRPC task panicked: {e}"))??;
+    if let Some(admin_res) = admin_res {
+        admin_res.map_err(|e| anyhow::anyhow!("admin REST task panicked: {e}"))??;
+    }
 
     tracing::info!("Egide server stopped");
 
     Ok(())
 }
 
+/// How often the idle sealer checks the time since the last authenticated
+/// request. Bounds how late past `--seal-after-idle-secs` the vault seals.
+const IDLE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Spawns the task that seals the vault once it has been idle past the seal
+/// manager's timeout, through the same path as `POST /v1/sys/seal`.
+fn spawn_idle_sealer(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            state.seal_if_idle().await;
+        }
+    });
+}
+
+/// Spawns the task that purges expired secret versions every `every`.
+///
+/// A sealed vault has nothing to purge, so the tick is skipped; other
+/// failures are logged and retried on the next tick.
+fn spawn_expired_purger(state: Arc<AppState>, every: std::time::Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match state.purge_expired_secrets().await {
+                Ok(_) | Err(egide_api::ServiceError::Sealed) => {},
+                Err(e) => tracing::warn!(error = %e, "Failed to purge expired secret versions"),
+            }
+        }
+    });
+}
+
+/// Binds the admin listener and serves [`build_admin_router`] on it until
+/// shutdown, announcing the bound address on stdout like the main listener.
+async fn spawn_admin_listener(
+    admin_bind: &str,
+    state: Arc<AppState>,
+) -> anyhow::Result<tokio::task::JoinHandle<anyhow::Result<()>>> {
+    let admin_addr: SocketAddr = admin_bind.parse()?;
+    let listener = tokio::net::TcpListener::bind(admin_addr).await?;
+    let local_addr = listener.local_addr()?;
+    {
+        use std::io::Write;
+        println!("EGIDE_ADMIN_LISTEN_ADDR={local_addr}");
+        let _ = std::io::stdout().flush();
+    }
+    tracing::info!("Admin REST on http://{local_addr}");
+
+    let app = build_admin_router(state);
+    Ok(tokio::spawn(async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .map_err(anyhow::Error::from)
+    }))
+}
+
 async fn shutdown_signal() {
     tokio::signal::ctrl_c()
         .await
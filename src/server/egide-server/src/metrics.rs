@@ -0,0 +1,149 @@
+//! Prometheus text exposition for `GET /metrics`.
+//!
+//! A deliberately small subset of the planned metrics surface: the seal and
+//! initialization state plus a build-info gauge, enough to alert on an
+//! unexpected seal. Values are read from the live seal state on every scrape.
+//! When `--metrics-prefix` is set, secret operations are also counted per
+//! tracked top-level path prefix, for attributing usage to teams. Secret
+//! reads that fail to decrypt are counted separately, so a wrong master key
+//! or corrupted storage shows up as a spike of its own.
+//! The endpoint is unauthenticated, like `/v1/sys/health`, and exposes nothing
+//! that `/v1/sys/status` does not already reveal.
+
+use std::fmt::Write as _;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+
+use crate::AppState;
+
+/// Content type of the Prometheus text exposition format, version 0.0.4.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Handles GET `/metrics`.
+pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let sv = state.status().await;
+    let mut out = render(sv.version, sv.initialized, sv.sealed);
+    counter(
+        &mut out,
+        "egide_secrets_decryption_failures_total",
+        "Secret reads that failed to decrypt or authenticate.",
+        state.secret_decrypt_failures.load(Ordering::Relaxed),
+    );
+    render_prefix_counters(&mut out, &state.secret_metrics.snapshot());
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], out)
+}
+
+/// Renders the gauges in the Prometheus text exposition format.
+fn render(version: &str, initialized: bool, sealed: bool) -> String {
+    let mut out = String::new();
+    gauge(
+        &mut out,
+        "egide_sealed",
+        "Whether the vault is sealed (1) or unsealed (0).",
+        "",
+        u8::from(sealed),
+    );
+    gauge(
+        &mut out,
+        "egide_initialized",
+        "Whether the vault has been initialized (1) or not (0).",
+        "",
+        u8::from(initialized),
+    );
+    gauge(
+        &mut out,
+        "egide_build_info",
+        "Build information; the value is always 1.",
+        &format!("{{version=\"{}\"}}", escape_label_value(version)),
+        1,
+    );
+    out
+}
+
+/// Appends the per-prefix secret operation counter, if any prefix is tracked.
+fn render_prefix_counters(out: &mut String, counts: &[(&str, u64)]) {
+    if counts.is_empty() {
+        return;
+    }
+    let name = "egide_secrets_operations_total";
+    // Writing to a String is infallible.
+    let _ = writeln!(
+        out,
+        "# HELP {name} Secret operations, by top-level path prefix."
+    );
+    let _ = writeln!(out, "# TYPE {name} counter");
+    for (prefix, count) in counts {
+        let _ = writeln!(
+            out,
+            "{name}{{prefix=\"{}\"}} {count}",
+            escape_label_value(prefix)
+        );
+    }
+}
+
+/// Appends one unlabelled counter with its `HELP` and `TYPE` lines.
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    // Writing to a String is infallible.
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} counter");
+    let _ = writeln!(out, "{name} {value}");
+}
+
+/// Appends one gauge with its `HELP` and `TYPE` lines.
+fn gauge(out: &mut String, name: &str, help: &str, labels: &str, value: u8) {
+    // Writing to a String is infallible.
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    let _ = writeln!(out, "{name}{labels} {value}");
+}
+
+/// Escapes a label value as the exposition format requires (`\`, `"`, newline).
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_reports_seal_state_as_zero_or_one() {
+        let unsealed = render("1.2.3", true, false);
+        assert!(unsealed.contains("\negide_sealed 0\n"));
+        assert!(unsealed.contains("\negide_initialized 1\n"));
+
+        let sealed = render("1.2.3", true, true);
+        assert!(sealed.contains("\negide_sealed 1\n"));
+    }
+
+    #[test]
+    fn render_labels_build_info_with_version() {
+        let out = render("1.2.3", false, true);
+        assert!(out.contains("# TYPE egide_build_info gauge\n"));
+        assert!(out.contains("egide_build_info{version=\"1.2.3\"} 1\n"));
+    }
+
+    #[test]
+    fn prefix_counters_are_labelled_and_omitted_when_untracked() {
+        let mut out = String::new();
+        render_prefix_counters(&mut out, &[]);
+        assert!(out.is_empty());
+
+        render_prefix_counters(&mut out, &[("teamA", 3), ("other", 1)]);
+        assert!(out.contains("# TYPE egide_secrets_operations_total counter\n"));
+        assert!(out.contains("egide_secrets_operations_total{prefix=\"teamA\"} 3\n"));
+        assert!(out.contains("egide_secrets_operations_total{prefix=\"other\"} 1\n"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label_value("a\"b\\c\nd"), r#"a\"b\\c\nd"#);
+    }
+}
@@ -17,6 +17,10 @@ pub struct Problem {
     pub status: u16,
     /// Human-readable explanation specific to this occurrence.
     pub detail: String,
+    /// Extension member naming the request field at fault, when one can be
+    /// singled out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
 }
 
 impl Problem {
@@ -27,8 +31,16 @@ impl Problem {
             title: status.canonical_reason().unwrap_or("Error").to_string(),
             status: status.as_u16(),
             detail: detail.into(),
+            field: None,
         }
     }
+
+    /// Names the request field the problem is about.
+    #[must_use]
+    pub fn with_field(mut self, name: impl Into<String>) -> Self {
+        self.field = Some(name.into());
+        self
+    }
 }
 
 impl IntoResponse for Problem {
@@ -54,7 +66,8 @@ impl From<egide_api::ServiceError> for Problem {
             E::Forbidden(m) => Problem::new(S::FORBIDDEN, m),
             E::Sealed => Problem::new(S::SERVICE_UNAVAILABLE, "Vault is sealed"),
             E::DecryptionFailed => Problem::new(S::BAD_REQUEST, "decryption failed"),
-            E::Internal(m) => Problem::new(S::INTERNAL_SERVER_ERROR, m),
+            E::RateLimited(m) => Problem::new(S::TOO_MANY_REQUESTS, m),
+            E::Undecryptable(m) | E::Internal(m) => Problem::new(S::INTERNAL_SERVER_ERROR, m),
         }
     }
 }
@@ -69,5 +82,16 @@ mod tests {
         assert_eq!(p.status, 403);
         assert_eq!(p.title, "Forbidden");
         assert_eq!(p.detail, "nope");
+        assert!(p.field.is_none());
+    }
+
+    #[test]
+    fn field_is_an_extension_member() {
+        let p = Problem::new(StatusCode::BAD_REQUEST, "bad").with_field("data");
+        let json = serde_json::to_value(&p).expect("serialize");
+        assert_eq!(json["field"], "data");
+        let json =
+            serde_json::to_value(Problem::new(StatusCode::BAD_REQUEST, "bad")).expect("serialize");
+        assert!(json.get("field").is_none());
     }
 }
@@ -56,11 +56,20 @@ pub struct KeyInfoResponse {
     latest_version: u32,
     min_encryption_version: u32,
     min_decryption_version: u32,
+    min_available_version: u32,
+    encryption_version_window: EncryptionVersionWindow,
     supports_encryption: bool,
     supports_decryption: bool,
     deletion_allowed: bool,
 }
 
+/// Inclusive range of versions a key currently encrypts under.
+#[derive(Serialize)]
+pub struct EncryptionVersionWindow {
+    min: u32,
+    max: u32,
+}
+
 /// Response for `POST /v1/transit/keys/{name}/rotate`.
 #[derive(Serialize)]
 pub struct RotateResponse {
@@ -72,6 +81,10 @@ pub struct RotateResponse {
 pub struct CiphertextRequest {
     /// Ciphertext produced by a previous encrypt/datakey/rewrap call.
     pub ciphertext: String,
+    /// Base64-encoded associated data the ciphertext was bound to at
+    /// encryption (decrypt and rewrap).
+    #[serde(default)]
+    pub associated_data: Option<String>,
 }
 
 /// Body carrying base64-encoded plaintext.
@@ -79,6 +92,10 @@ pub struct CiphertextRequest {
 pub struct PlaintextRequest {
     /// Base64-encoded plaintext.
     pub plaintext: String,
+    /// Optional base64-encoded associated data, authenticated but not
+    /// encrypted. Decryption must present the same value.
+    #[serde(default)]
+    pub associated_data: Option<String>,
 }
 
 /// Response carrying an opaque ciphertext.
@@ -146,12 +163,15 @@ pub async fn get_key_handler(
     Path(name): Path<String>,
 ) -> Result<Json<KeyInfoResponse>, Problem> {
     let key = state.get_key(&name).await.map_err(Problem::from)?;
+    let (min, max) = key.encryption_version_window();
     Ok(Json(KeyInfoResponse {
         name: key.name,
         key_type: key.key_type.to_string(),
         latest_version: key.latest_version,
         min_encryption_version: key.min_encryption_version,
         min_decryption_version: key.min_decryption_version,
+        min_available_version: key.min_available_version,
+        encryption_version_window: EncryptionVersionWindow { min, max },
         supports_encryption: key.supports_encryption,
         supports_decryption: key.supports_decryption,
         deletion_allowed: key.deletion_allowed,
@@ -195,10 +215,11 @@ pub async fn encrypt_handler(
     let plaintext = BASE64
         .decode(req.plaintext.as_bytes())
         .map_err(|_| Problem::new(StatusCode::BAD_REQUEST, "plaintext must be valid base64"))?;
-    let ciphertext = state
-        .encrypt(&name, &plaintext)
-        .await
-        .map_err(Problem::from)?;
+    let ciphertext = match decode_associated_data(req.associated_data.as_deref())? {
+        Some(aad) => state.encrypt_with_aad(&name, &plaintext, &aad).await,
+        None => state.encrypt(&name, &plaintext).await,
+    }
+    .map_err(Problem::from)?;
     Ok(Json(CiphertextResponse { ciphertext }))
 }
 
@@ -212,15 +233,30 @@ pub async fn decrypt_handler(
     Path(name): Path<String>,
     Json(req): Json<CiphertextRequest>,
 ) -> Result<Json<PlaintextResponse>, Problem> {
-    let plaintext = state
-        .decrypt(&name, &req.ciphertext)
-        .await
-        .map_err(Problem::from)?;
+    let plaintext = match decode_associated_data(req.associated_data.as_deref())? {
+        Some(aad) => state.decrypt_with_aad(&name, &req.ciphertext, &aad).await,
+        None => state.decrypt(&name, &req.ciphertext).await,
+    }
+    .map_err(Problem::from)?;
     Ok(Json(PlaintextResponse {
         plaintext: BASE64.encode(&plaintext),
     }))
 }
 
+/// Decodes the optional base64 `associated_data` of a data request.
+fn decode_associated_data(encoded: Option<&str>) -> Result<Option<Vec<u8>>, Problem> {
+    encoded
+        .map(|value| {
+            BASE64.decode(value.as_bytes()).map_err(|_| {
+                Problem::new(
+                    StatusCode::BAD_REQUEST,
+                    "associated_data must be valid base64",
+                )
+            })
+        })
+        .transpose()
+}
+
 /// Handles `POST /v1/transit/datakey/{name}`.
 ///
 /// The plaintext key bytes are base64-encoded in the response. Base64 is a REST concern.
@@ -238,15 +274,19 @@ pub async fn datakey_handler(
 }
 
 /// Handles `POST /v1/transit/rewrap/{name}`.
+///
+/// A ciphertext bound to `associated_data` must carry the same value; it
+/// stays bound to it after the rewrap.
 pub async fn rewrap_handler(
     Authenticated(_ctx): Authenticated,
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
     Json(req): Json<CiphertextRequest>,
 ) -> Result<Json<CiphertextResponse>, Problem> {
-    let ciphertext = state
-        .rewrap(&name, &req.ciphertext)
-        .await
-        .map_err(Problem::from)?;
+    let ciphertext = match decode_associated_data(req.associated_data.as_deref())? {
+        Some(aad) => state.rewrap_with_aad(&name, &req.ciphertext, &aad).await,
+        None => state.rewrap(&name, &req.ciphertext).await,
+    }
+    .map_err(Problem::from)?;
     Ok(Json(CiphertextResponse { ciphertext }))
 }
@@ -0,0 +1,108 @@
+//! Field-level validation of JSON request bodies.
+//!
+//! [`ValidatedJson`] stands in for axum's `Json` extractor on bodies whose
+//! shape clients commonly get wrong. Instead of a generic deserialize error, a
+//! malformed body is answered with a 400 [`Problem`] naming the offending
+//! field in its `field` extension member.
+
+use axum::extract::{FromRequest, Request};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::problem::Problem;
+
+/// A request body whose top-level fields can be checked one by one.
+pub trait RequestSchema: DeserializeOwned {
+    /// Checks the fields of `body`, reporting the first malformed one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`Problem`] naming the field that failed.
+    fn validate(body: &Map<String, Value>) -> Result<(), Problem>;
+}
+
+/// Checks that field `name` of `body` deserializes as `T`.
+///
+/// An absent field passes unless `required` is set.
+///
+/// # Errors
+///
+/// Returns a 400 [`Problem`] naming `name` if the field is missing but
+/// required, or holds a value of the wrong shape.
+pub fn check_field<T: DeserializeOwned>(
+    body: &Map<String, Value>,
+    name: &str,
+    required: bool,
+) -> Result<(), Problem> {
+    let invalid = |detail: String| Problem::new(StatusCode::BAD_REQUEST, detail).with_field(name);
+    match body.get(name) {
+        None if required => Err(invalid(format!("missing field `{name}`"))),
+        None => Ok(()),
+        Some(value) => serde_json::from_value::<T>(value.clone())
+            .map(|_| ())
+            .map_err(|e| invalid(format!("invalid field `{name}`: {e}"))),
+    }
+}
+
+/// JSON body extractor that validates `T` field by field.
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: RequestSchema,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<Value>::from_request(req, state)
+            .await
+            .map_err(|rejection| Problem::new(rejection.status(), rejection.body_text()))?;
+
+        let Value::Object(body) = &value else {
+            return Err(Problem::new(
+                StatusCode::BAD_REQUEST,
+                "request body must be a JSON object",
+            ));
+        };
+        T::validate(body)?;
+
+        serde_json::from_value(value)
+            .map(ValidatedJson)
+            .map_err(|e| Problem::new(StatusCode::BAD_REQUEST, e.to_string()))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn check_field_names_the_malformed_field() {
+        let body = object(json!({ "data": 123 }));
+
+        let err = check_field::<Map<String, Value>>(&body, "data", true).unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("data"));
+        assert_eq!(err.status, StatusCode::BAD_REQUEST.as_u16());
+    }
+
+    #[test]
+    fn check_field_only_requires_required_fields() {
+        let body = object(json!({}));
+
+        assert!(check_field::<u8>(&body, "shares", false).is_ok());
+        let err = check_field::<u8>(&body, "shares", true).unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("shares"));
+    }
+}
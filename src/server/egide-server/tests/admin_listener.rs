@@ -0,0 +1,145 @@
+//! Integration tests for the admin/data listener split.
+
+#![allow(clippy::disallowed_methods)] // tokio::time::sleep allowed in tests
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use egide_api::proto::{
+    secrets_service_client::SecretsServiceClient, sys_service_client::SysServiceClient,
+    GetSecretRequest, StatusRequest,
+};
+use egide_server::{build_admin_router, build_data_router, build_router, grpc, AppState};
+use tokio::sync::oneshot;
+use tonic::transport::Channel;
+use tonic::Code;
+use tower::ServiceExt;
+
+/// Builds the shared state of an initialized, sealed Egide server.
+async fn test_state() -> (tempfile::TempDir, Arc<AppState>) {
+    let (tmp, seal_manager, _) = common::sealed_vault().await;
+    let state = Arc::new(common::app_state(&tmp, seal_manager));
+    (tmp, state)
+}
+
+async fn status(app: &axum::Router, method: &str, uri: &str) -> StatusCode {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .expect("request");
+    app.clone().oneshot(req).await.expect("oneshot").status()
+}
+
+#[tokio::test]
+async fn admin_listener_serves_only_sys_routes() {
+    let (_tmp, state) = test_state().await;
+    let admin = build_admin_router(state);
+
+    assert_eq!(
+        status(&admin, "GET", "/v1/sys/status").await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status(&admin, "GET", "/v1/sys/health").await,
+        StatusCode::OK
+    );
+    assert_eq!(status(&admin, "GET", "/metrics").await, StatusCode::OK);
+    assert_eq!(
+        status(&admin, "GET", "/v1/secrets/app/db").await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        status(&admin, "GET", "/v1/transit/keys").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn data_listener_omits_sys_routes_but_keeps_health() {
+    let (_tmp, state) = test_state().await;
+    let data = build_data_router(state);
+
+    assert_eq!(status(&data, "GET", "/v1/sys/health").await, StatusCode::OK);
+    for uri in ["/v1/sys/status", "/v1/sys/seal-status", "/metrics"] {
+        assert_eq!(
+            status(&data, "GET", uri).await,
+            StatusCode::NOT_FOUND,
+            "{uri}"
+        );
+    }
+    assert_eq!(
+        status(&data, "POST", "/v1/sys/seal").await,
+        StatusCode::NOT_FOUND
+    );
+    // Data routes are routed: a sealed server answers, it does not 404.
+    assert_ne!(
+        status(&data, "GET", "/v1/secrets/app/db").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn single_listener_serves_both_planes() {
+    let (_tmp, state) = test_state().await;
+    let app = build_router(state);
+
+    assert_eq!(status(&app, "GET", "/v1/sys/status").await, StatusCode::OK);
+    assert_ne!(
+        status(&app, "GET", "/v1/secrets/app/db").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn data_grpc_listener_rejects_sys_rpcs() {
+    let (_tmp, state) = test_state().await;
+
+    // Bind first to discover the OS-assigned port, then release so tonic can rebind.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind ephemeral");
+    let addr = listener.local_addr().expect("local_addr");
+    drop(listener);
+    let (tx, rx) = oneshot::channel::<()>();
+    tokio::spawn(grpc::serve_data_plane(state, addr, async move {
+        let _ = rx.await;
+    }));
+
+    let endpoint = format!("http://{addr}");
+    let mut channel = None;
+    for _ in 0..20 {
+        if let Ok(ch) = Channel::from_shared(endpoint.clone())
+            .expect("valid endpoint")
+            .connect()
+            .await
+        {
+            channel = Some(ch);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let channel = channel.expect("gRPC server ready within 1 s");
+
+    let err = SysServiceClient::new(channel.clone())
+        .status(StatusRequest {})
+        .await
+        .expect_err("sys service must not be served");
+    assert_eq!(err.code(), Code::Unimplemented);
+
+    // The data services are there: an unauthenticated call is refused by
+    // auth, not by routing.
+    let err = SecretsServiceClient::new(channel)
+        .get(GetSecretRequest {
+            path: "app/db".into(),
+        })
+        .await
+        .expect_err("no token");
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let _ = tx.send(());
+}
@@ -0,0 +1,105 @@
+//! Fixture shared by the REST integration tests.
+//!
+//! Each test file compiles its own copy and only uses part of it.
+#![allow(dead_code)]
+
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request};
+use egide_auth::{RootTokenBackend, ServiceTokenBackend, ServiceTokenStore};
+use egide_seal::{InitResult, SealManager, ShamirConfig};
+use egide_server::{build_router, AppState, AuthService, PrefixMetrics, SecretGenerators};
+use egide_storage::StorageBackend;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+/// Number of shares [`unseal`] submits.
+pub const THRESHOLD: usize = 3;
+
+/// Initializes a vault with 5 shares and a threshold of [`THRESHOLD`] in a
+/// fresh directory, and leaves it sealed.
+pub async fn sealed_vault() -> (TempDir, SealManager, InitResult) {
+    let tmp = TempDir::new().expect("tempdir");
+    let mut seal_manager = SealManager::new(tmp.path()).await.expect("seal manager");
+    let init = seal_manager
+        .initialize(ShamirConfig {
+            shares: 5,
+            threshold: 3,
+        })
+        .await
+        .expect("initialize");
+    (tmp, seal_manager, init)
+}
+
+/// Unseals `seal_manager` with the first [`THRESHOLD`] shares of `init`.
+pub async fn unseal(seal_manager: &mut SealManager, init: &InitResult) {
+    let nonce = seal_manager.begin_unseal().expect("begin unseal");
+    for share in init.shares.iter().take(THRESHOLD) {
+        seal_manager.unseal(&nonce, share).await.expect("unseal");
+    }
+}
+
+/// Builds server state over `seal_manager` with the root and service token
+/// backends and default settings. No engine is opened.
+///
+/// Returned unshared so a test can adjust a field before wrapping it.
+pub fn app_state(tmp: &TempDir, seal_manager: SealManager) -> AppState {
+    let storage: Arc<dyn StorageBackend> = Arc::new(seal_manager.storage());
+    let service_store = ServiceTokenStore::new(storage);
+    let auth = AuthService::new(vec![
+        Box::new(RootTokenBackend::new(Arc::new(seal_manager.storage()))),
+        Box::new(ServiceTokenBackend::new(service_store.clone())),
+    ]);
+
+    AppState {
+        auth,
+        seal: RwLock::new(seal_manager),
+        secrets: RwLock::new(None),
+        transit: RwLock::new(None),
+        data_dir: tmp.path().to_path_buf(),
+        start_time: Instant::now(),
+        version: env!("CARGO_PKG_VERSION"),
+        service_tokens: service_store,
+        generators: SecretGenerators::default(),
+        encrypt_storage: false,
+        secret_metrics: PrefixMetrics::default(),
+        secret_decrypt_failures: AtomicU64::new(0),
+    }
+}
+
+/// Builds the state of an initialized, unsealed vault with its secrets
+/// engine open, and returns a usable root token.
+pub async fn unsealed_state() -> (TempDir, AppState, String) {
+    let (tmp, mut seal_manager, init) = sealed_vault().await;
+    unseal(&mut seal_manager, &init).await;
+    let state = app_state(&tmp, seal_manager);
+    state.ensure_secrets_engine().await.expect("secrets engine");
+    (tmp, state, init.root_token)
+}
+
+/// Builds an initialized + unsealed Egide router and returns a usable root token.
+pub async fn test_app() -> (TempDir, axum::Router, String) {
+    let (tmp, state, root_token) = unsealed_state().await;
+    (tmp, build_router(Arc::new(state)), root_token)
+}
+
+/// Builds a JSON request, with a bearer token if one is given.
+pub fn request(method: &str, uri: &str, token: Option<&str>, body: &str) -> Request<Body> {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(t) = token {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {t}"));
+    }
+    builder
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("request")
+}
+
+/// Reads a response body as JSON.
+pub async fn read_json(res: axum::response::Response) -> serde_json::Value {
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.expect("body");
+    serde_json::from_slice(&bytes).expect("json")
+}
@@ -0,0 +1,67 @@
+//! Integration tests for the REST concurrency limit.
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use egide_server::{limit_concurrency, OverloadPolicy};
+use tower::ServiceExt;
+
+const SLOW: Duration = Duration::from_millis(300);
+
+/// A router with one handler that holds its slot for [`SLOW`].
+fn slow_router(overload: OverloadPolicy) -> Router {
+    let router = Router::new().route(
+        "/slow",
+        get(|| async {
+            tokio::time::sleep(SLOW).await;
+            "done"
+        }),
+    );
+    limit_concurrency(router, 1, overload)
+}
+
+fn slow_request() -> Request<Body> {
+    Request::builder()
+        .uri("/slow")
+        .body(Body::empty())
+        .expect("request")
+}
+
+#[tokio::test]
+async fn excess_request_is_shed_with_503() {
+    let app = slow_router(OverloadPolicy::Shed);
+
+    let first = tokio::spawn(app.clone().oneshot(slow_request()));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let res = app.clone().oneshot(slow_request()).await.expect("oneshot");
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let res = first.await.expect("join").expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Once the slot is free, requests are served again.
+    let res = app.oneshot(slow_request()).await.expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn excess_request_is_queued() {
+    let app = slow_router(OverloadPolicy::Queue);
+    let start = Instant::now();
+
+    let first = tokio::spawn(app.clone().oneshot(slow_request()));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let res = app.oneshot(slow_request()).await.expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(
+        start.elapsed() >= SLOW * 2,
+        "the second request must wait for the first"
+    );
+
+    let res = first.await.expect("join").expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+}
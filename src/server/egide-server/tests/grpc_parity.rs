@@ -6,10 +6,11 @@
 
 #![allow(clippy::disallowed_methods)] // tokio::time::sleep allowed in tests
 
+mod common;
+
 use std::net::SocketAddr;
-use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use egide_api::proto::{
@@ -20,13 +21,9 @@ use egide_api::proto::{
     PutSecretRequest, StatusRequest,
 };
 use egide_api::ServiceContext;
-use egide_auth::{
-    AuthContext, AuthService, RootTokenBackend, ServiceTokenBackend, ServiceTokenStore,
-};
-use egide_seal::{SealManager, ShamirConfig};
+use egide_auth::AuthContext;
 use egide_server::grpc;
-use egide_storage::StorageBackend;
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::oneshot;
 use tonic::transport::Channel;
 use tonic::{Code, Request};
 
@@ -43,39 +40,9 @@ use tower::ServiceExt;
 /// Builds an initialized, fully unsealed [`ServiceContext`] AND an axum router
 /// backed by the same state. Returns `(tempdir, ctx, router, root_token)`.
 async fn unsealed_both() -> (tempfile::TempDir, Arc<ServiceContext>, axum::Router, String) {
-    let tmp = tempfile::TempDir::new().expect("tempdir");
-    let mut seal_manager = SealManager::new(tmp.path()).await.expect("seal manager");
-    let init = seal_manager
-        .initialize(ShamirConfig {
-            shares: 5,
-            threshold: 3,
-        })
-        .await
-        .expect("initialize");
-    let root_token = init.root_token.clone();
-    for share in init.shares.iter().take(3) {
-        seal_manager.unseal(share).await.expect("unseal");
-    }
-
-    let storage: Arc<dyn StorageBackend> = Arc::new(seal_manager.storage());
-    let service_store = ServiceTokenStore::new(storage);
-    let auth = AuthService::new(vec![
-        Box::new(RootTokenBackend::new(Arc::new(seal_manager.storage()))),
-        Box::new(ServiceTokenBackend::new(service_store.clone())),
-    ]);
-
-    let ctx = Arc::new(ServiceContext {
-        auth,
-        seal: RwLock::new(seal_manager),
-        secrets: RwLock::new(None),
-        transit: RwLock::new(None),
-        data_dir: PathBuf::from(tmp.path()),
-        start_time: Instant::now(),
-        version: "0.1.0-test",
-        service_tokens: service_store,
-    });
+    let (tmp, state, root_token) = common::unsealed_state().await;
+    let ctx = Arc::new(state);
     ctx.ensure_transit_engine().await.expect("transit engine");
-    ctx.ensure_secrets_engine().await.expect("secrets engine");
 
     let router = build_router(ctx.clone());
     (tmp, ctx, router, root_token)
@@ -83,38 +50,11 @@ async fn unsealed_both() -> (tempfile::TempDir, Arc<ServiceContext>, axum::Route
 
 /// Builds an initialized but still-sealed [`ServiceContext`] AND an axum router.
 async fn sealed_both() -> (tempfile::TempDir, Arc<ServiceContext>, axum::Router, String) {
-    let tmp = tempfile::TempDir::new().expect("tempdir");
-    let mut seal_manager = SealManager::new(tmp.path()).await.expect("seal manager");
-    let init = seal_manager
-        .initialize(ShamirConfig {
-            shares: 5,
-            threshold: 3,
-        })
-        .await
-        .expect("initialize");
-    let root_token = init.root_token.clone();
-    // Intentionally NOT unsealed.
-
-    let storage: Arc<dyn StorageBackend> = Arc::new(seal_manager.storage());
-    let service_store = ServiceTokenStore::new(storage);
-    let auth = AuthService::new(vec![
-        Box::new(RootTokenBackend::new(Arc::new(seal_manager.storage()))),
-        Box::new(ServiceTokenBackend::new(service_store.clone())),
-    ]);
-
-    let ctx = Arc::new(ServiceContext {
-        auth,
-        seal: RwLock::new(seal_manager),
-        secrets: RwLock::new(None),
-        transit: RwLock::new(None),
-        data_dir: PathBuf::from(tmp.path()),
-        start_time: Instant::now(),
-        version: "0.1.0-test",
-        service_tokens: service_store,
-    });
+    let (tmp, seal_manager, init) = common::sealed_vault().await;
+    let ctx = Arc::new(common::app_state(&tmp, seal_manager));
 
     let router = build_router(ctx.clone());
-    (tmp, ctx, router, root_token)
+    (tmp, ctx, router, init.root_token)
 }
 
 // ---------------------------------------------------------------------------
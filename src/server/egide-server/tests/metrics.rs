@@ -0,0 +1,77 @@
+//! Integration tests for the Prometheus `/metrics` endpoint.
+mod common;
+
+use std::sync::Arc;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use egide_server::build_router;
+use tower::ServiceExt;
+
+/// Builds a router over an initialized vault, unsealed or left sealed.
+///
+/// The unsealed variant stands in for dev mode: dev mode is an auto-unseal at
+/// startup, and its environment guard cannot be satisfied from a test without
+/// mutating process environment.
+async fn test_app(unseal: bool) -> (tempfile::TempDir, axum::Router) {
+    let (tmp, mut seal_manager, init) = common::sealed_vault().await;
+    if unseal {
+        common::unseal(&mut seal_manager, &init).await;
+    }
+    let state = common::app_state(&tmp, seal_manager);
+    (tmp, build_router(Arc::new(state)))
+}
+
+async fn scrape(app: axum::Router) -> (StatusCode, String, String) {
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/metrics")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("oneshot");
+    let status = res.status();
+    let content_type = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.expect("body");
+    (
+        status,
+        content_type,
+        String::from_utf8(bytes.to_vec()).expect("utf-8"),
+    )
+}
+
+#[tokio::test]
+async fn metrics_reports_unsealed_and_build_info_without_a_token() {
+    let (_tmp, app) = test_app(true).await;
+
+    let (status, content_type, body) = scrape(app).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.starts_with("text/plain; version=0.0.4"));
+    assert!(body.lines().any(|l| l == "egide_sealed 0"), "{body}");
+    assert!(body.lines().any(|l| l == "egide_initialized 1"), "{body}");
+    let build_info = format!(
+        "egide_build_info{{version=\"{}\"}} 1",
+        env!("CARGO_PKG_VERSION")
+    );
+    assert!(body.lines().any(|l| l == build_info), "{body}");
+}
+
+#[tokio::test]
+async fn metrics_reports_sealed_vault() {
+    let (_tmp, app) = test_app(false).await;
+
+    let (status, _, body) = scrape(app).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.lines().any(|l| l == "egide_sealed 1"), "{body}");
+    assert!(body.lines().any(|l| l == "egide_initialized 1"), "{body}");
+}
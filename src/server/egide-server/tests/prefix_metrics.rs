@@ -0,0 +1,67 @@
+//! Integration tests for per-prefix secret operation metrics.
+mod common;
+
+use std::sync::Arc;
+
+use axum::body::to_bytes;
+use axum::http::StatusCode;
+use common::{read_json, request};
+use egide_server::{build_router, PrefixMetrics};
+use tower::ServiceExt;
+
+/// Builds an initialized + unsealed Egide router tracking the `teamA` and
+/// `teamB` prefixes, and returns a usable root token.
+async fn test_app() -> (tempfile::TempDir, axum::Router, String) {
+    let (tmp, mut state, root_token) = common::unsealed_state().await;
+    state.secret_metrics = PrefixMetrics::new(["teamA", "teamB"]);
+    (tmp, build_router(Arc::new(state)), root_token)
+}
+
+async fn scrape(app: &axum::Router) -> String {
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/metrics", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.expect("body");
+    String::from_utf8(bytes.to_vec()).expect("utf-8")
+}
+
+fn counter(body: &str, prefix: &str) -> u64 {
+    let series = format!("egide_secrets_operations_total{{prefix=\"{prefix}\"}} ");
+    body.lines()
+        .find_map(|l| l.strip_prefix(series.as_str()))
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| panic!("no {prefix} series in {body}"))
+}
+
+#[tokio::test]
+async fn secret_operations_are_counted_per_prefix() {
+    let (_tmp, app, root) = test_app().await;
+
+    for path in ["teamA/db", "teamA/api", "teamB/db", "shared/cfg"] {
+        let res = app
+            .clone()
+            .oneshot(request(
+                "PUT",
+                &format!("/v1/secrets/{path}"),
+                Some(&root),
+                r#"{"data":{"k":"v"}}"#,
+            ))
+            .await
+            .expect("oneshot");
+        assert!(res.status().is_success(), "{path}: {}", res.status());
+    }
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/teamB/db", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(read_json(res).await["data"]["k"], "v");
+
+    let body = scrape(&app).await;
+    assert_eq!(counter(&body, "teamA"), 2);
+    assert_eq!(counter(&body, "teamB"), 2);
+    assert_eq!(counter(&body, "other"), 1);
+}
@@ -0,0 +1,91 @@
+//! Integration tests for anonymous reads of allowlisted secret paths.
+mod common;
+
+use std::sync::Arc;
+
+use axum::body::to_bytes;
+use axum::http::StatusCode;
+use common::request;
+use egide_server::build_router;
+use tower::ServiceExt;
+
+/// Builds an unsealed router whose only public-read prefix is `config/flags`.
+async fn test_app() -> (tempfile::TempDir, axum::Router, String) {
+    let (tmp, mut state, root_token) = common::unsealed_state().await;
+    state.auth = state.auth.with_public_read_prefixes(["config/flags"]);
+    (tmp, build_router(Arc::new(state)), root_token)
+}
+
+async fn put_secret(app: &axum::Router, token: &str, path: &str) {
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            &format!("/v1/secrets/{path}"),
+            Some(token),
+            r#"{"data":{"enabled":"true"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn allowlisted_path_reads_without_token_but_others_require_auth() {
+    let (_tmp, app, root) = test_app().await;
+    put_secret(&app, &root, "config/flags/beta").await;
+    put_secret(&app, &root, "app/db").await;
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/config/flags/beta", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.expect("body");
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+    assert_eq!(body["data"]["enabled"], "true");
+
+    let res = app
+        .oneshot(request("GET", "/v1/secrets/app/db", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn allowlisted_path_still_requires_auth_for_writes_and_bad_tokens() {
+    let (_tmp, app, root) = test_app().await;
+    put_secret(&app, &root, "config/flags/beta").await;
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/config/flags/beta",
+            None,
+            r#"{"data":{"enabled":"false"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = app
+        .clone()
+        .oneshot(request("DELETE", "/v1/secrets/config/flags/beta", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    // A presented token is validated even on a public path.
+    let res = app
+        .oneshot(request(
+            "GET",
+            "/v1/secrets/config/flags/beta",
+            Some("not-a-token"),
+            "",
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,116 @@
+//! Integration tests for `POST /v1/sys/maintenance/purge-deleted`.
+mod common;
+
+use axum::http::StatusCode;
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+/// Creates a service token via the root token and returns the bearer string.
+async fn service_token(app: &axum::Router, root: &str) -> String {
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/auth/service-tokens",
+            Some(root),
+            r#"{"service_name":"ops"}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::CREATED);
+    read_json(res).await["token"]
+        .as_str()
+        .expect("token")
+        .to_string()
+}
+
+async fn purge(app: &axum::Router, token: &str, older_than_secs: u64) -> axum::response::Response {
+    app.clone()
+        .oneshot(request(
+            "POST",
+            "/v1/sys/maintenance/purge-deleted",
+            Some(token),
+            &format!(r#"{{"older_than_secs":{older_than_secs}}}"#),
+        ))
+        .await
+        .expect("oneshot")
+}
+
+#[tokio::test]
+async fn purge_removes_secrets_deleted_before_cutoff() {
+    let (_tmp, app, root) = test_app().await;
+    for path in ["app/old", "app/live"] {
+        let res = app
+            .clone()
+            .oneshot(request(
+                "PUT",
+                &format!("/v1/secrets/{path}"),
+                Some(&root),
+                r#"{"data":{"password":"p1"}}"#,
+            ))
+            .await
+            .expect("oneshot");
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+    let res = app
+        .clone()
+        .oneshot(request("DELETE", "/v1/secrets/app/old", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    // The deletion is younger than an hour: nothing qualifies yet.
+    let res = purge(&app, &root, 3600).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["purged"], 0);
+
+    // Deletion timestamps have one-second resolution; wait until a zero
+    // retention puts the cutoff strictly after the deletion.
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let res = purge(&app, &root, 0).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["purged"], 1);
+
+    // The purged secret is gone for good: a new write starts over at v1.
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/old",
+            Some(&root),
+            r#"{"data":{"password":"p2"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["version"], 1);
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/app/live", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = purge(&app, &root, 0).await;
+    assert_eq!(read_json(res).await["purged"], 0);
+}
+
+#[tokio::test]
+async fn purge_requires_root() {
+    let (_tmp, app, root) = test_app().await;
+    let token = service_token(&app, &root).await;
+    let res = purge(&app, &token, 0).await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = app
+        .oneshot(request(
+            "POST",
+            "/v1/sys/maintenance/purge-deleted",
+            None,
+            r#"{"older_than_secs":0}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,80 @@
+//! Integration tests for field-level request body validation.
+mod common;
+
+use axum::http::{header, StatusCode};
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn put_with_non_object_data_names_the_field() {
+    let (_tmp, app, root) = test_app().await;
+
+    let res = app
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db",
+            Some(&root),
+            r#"{"data":123}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        res.headers()[header::CONTENT_TYPE],
+        "application/problem+json"
+    );
+    let body = read_json(res).await;
+    assert_eq!(body["status"], 400);
+    assert_eq!(body["field"], "data");
+    assert!(body["detail"]
+        .as_str()
+        .is_some_and(|d| d.contains("`data`")));
+}
+
+#[tokio::test]
+async fn put_without_data_names_the_field() {
+    let (_tmp, app, root) = test_app().await;
+
+    let res = app
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db",
+            Some(&root),
+            r#"{"cas":1}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(read_json(res).await["field"], "data");
+}
+
+#[tokio::test]
+async fn init_with_wrong_share_type_names_the_field() {
+    let (_tmp, app, _root) = test_app().await;
+
+    let res = app
+        .oneshot(request(
+            "POST",
+            "/v1/sys/init",
+            None,
+            r#"{"secret_shares":"five"}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(read_json(res).await["field"], "secret_shares");
+}
+
+#[tokio::test]
+async fn malformed_json_is_an_invalid_request_without_a_field() {
+    let (_tmp, app, root) = test_app().await;
+
+    let res = app
+        .oneshot(request("PUT", "/v1/secrets/app/db", Some(&root), "{"))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = read_json(res).await;
+    assert_eq!(body["status"], 400);
+    assert!(body.get("field").is_none());
+}
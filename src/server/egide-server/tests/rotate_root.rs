@@ -0,0 +1,66 @@
+//! Integration tests for root token rotation.
+mod common;
+
+use axum::http::StatusCode;
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+async fn rotate_root(app: &axum::Router, token: &str) -> axum::response::Response {
+    app.clone()
+        .oneshot(request("POST", "/v1/sys/rotate-root", Some(token), ""))
+        .await
+        .expect("oneshot")
+}
+
+async fn secrets_list_status(app: &axum::Router, token: &str) -> StatusCode {
+    app.clone()
+        .oneshot(request("GET", "/v1/secrets", Some(token), ""))
+        .await
+        .expect("oneshot")
+        .status()
+}
+
+#[tokio::test]
+async fn rotated_root_token_replaces_the_old_one() {
+    let (_tmp, app, old_root) = test_app().await;
+
+    let res = rotate_root(&app, &old_root).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let new_root = read_json(res).await["root_token"]
+        .as_str()
+        .expect("root_token")
+        .to_string();
+    assert_ne!(new_root, old_root);
+
+    assert_eq!(
+        secrets_list_status(&app, &old_root).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(secrets_list_status(&app, &new_root).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rotate_root_is_rate_limited() {
+    let (_tmp, app, root) = test_app().await;
+
+    let res = rotate_root(&app, &root).await;
+    let new_root = read_json(res).await["root_token"]
+        .as_str()
+        .expect("root_token")
+        .to_string();
+
+    let res = rotate_root(&app, &new_root).await;
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(secrets_list_status(&app, &new_root).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rotate_root_requires_a_token() {
+    let (_tmp, app, _root) = test_app().await;
+
+    let res = app
+        .oneshot(request("POST", "/v1/sys/rotate-root", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,134 @@
+//! Integration tests for the read-only seal status endpoint.
+mod common;
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use common::{read_json, request};
+use egide_server::build_router;
+use tower::ServiceExt;
+
+/// Builds an initialized but still sealed Egide router and returns its
+/// unseal shares in hex.
+async fn test_app() -> (tempfile::TempDir, axum::Router, Vec<String>) {
+    let (tmp, seal_manager, init) = common::sealed_vault().await;
+    let shares = init.shares.iter().map(egide_seal::Share::to_hex).collect();
+    let state = common::app_state(&tmp, seal_manager);
+    (tmp, build_router(Arc::new(state)), shares)
+}
+
+async fn seal_status(app: &axum::Router) -> serde_json::Value {
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/sys/seal-status", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    read_json(res).await
+}
+
+#[tokio::test]
+async fn seal_status_reports_progress_without_advancing_it() {
+    let (_tmp, app, shares) = test_app().await;
+
+    let body = seal_status(&app).await;
+    assert_eq!(body["sealed"], true);
+    assert_eq!(body["initialized"], true);
+    assert_eq!(body["total_shares"], 5);
+    assert_eq!(body["threshold"], 3);
+    assert_eq!(body["progress"], 0);
+    assert!(body["initialized_at"].is_u64());
+    assert_eq!(body["dev_mode"], false);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/sys/unseal",
+            None,
+            &format!(r#"{{"key":"{}"}}"#, shares[0]),
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Polling is read-only: progress stays at one share.
+    for _ in 0..2 {
+        let body = seal_status(&app).await;
+        assert_eq!(body["sealed"], true);
+        assert_eq!(body["progress"], 1);
+    }
+
+    // The next share continues from the polled progress.
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/sys/unseal",
+            None,
+            &format!(r#"{{"key":"{}"}}"#, shares[1]),
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(read_json(res).await["progress"], 2);
+}
+
+#[tokio::test]
+async fn unseal_nonce_goes_stale_after_reset() {
+    let (_tmp, app, shares) = test_app().await;
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/sys/unseal",
+            None,
+            &format!(r#"{{"key":"{}"}}"#, shares[0]),
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let stale = read_json(res).await["nonce"]
+        .as_str()
+        .expect("nonce")
+        .to_owned();
+
+    let res = app
+        .clone()
+        .oneshot(request("POST", "/v1/sys/unseal/reset", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["progress"], 0);
+    let nonce = body["nonce"].as_str().expect("nonce").to_owned();
+    assert_ne!(nonce, stale);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/sys/unseal",
+            None,
+            &format!(r#"{{"key":"{}","nonce":"{stale}"}}"#, shares[1]),
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    for share in &shares[..3] {
+        let res = app
+            .clone()
+            .oneshot(request(
+                "POST",
+                "/v1/sys/unseal",
+                None,
+                &format!(r#"{{"key":"{share}","nonce":"{nonce}"}}"#),
+            ))
+            .await
+            .expect("oneshot");
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+    let body = seal_status(&app).await;
+    assert_eq!(body["sealed"], false);
+}
@@ -0,0 +1,101 @@
+//! Integration tests for counting secrets.
+mod common;
+
+use axum::http::StatusCode;
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+async fn count(app: &axum::Router, root: &str, query: &str) -> u64 {
+    let res = app
+        .clone()
+        .oneshot(request(
+            "GET",
+            &format!("/v1/secrets-count{query}"),
+            Some(root),
+            "",
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    read_json(res).await["count"].as_u64().expect("count")
+}
+
+#[tokio::test]
+async fn count_matches_live_secrets_and_drops_after_delete() {
+    let (_tmp, app, root) = test_app().await;
+
+    for path in ["app/db", "app/cache", "app/queue", "other/key"] {
+        let res = app
+            .clone()
+            .oneshot(request(
+                "PUT",
+                &format!("/v1/secrets/{path}"),
+                Some(&root),
+                r#"{"data":{"k":"v"}}"#,
+            ))
+            .await
+            .expect("oneshot");
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    assert_eq!(count(&app, &root, "?prefix=app/").await, 3);
+    assert_eq!(count(&app, &root, "").await, 4);
+
+    let res = app
+        .clone()
+        .oneshot(request("DELETE", "/v1/secrets/app/cache", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    assert_eq!(count(&app, &root, "?prefix=app/").await, 2);
+    assert_eq!(
+        count(&app, &root, "?prefix=app/&include_deleted=true").await,
+        3
+    );
+}
+
+#[tokio::test]
+async fn count_requires_a_token() {
+    let (_tmp, app, _root) = test_app().await;
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets-count", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn secret_named_count_round_trips() {
+    let (_tmp, app, root) = test_app().await;
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/count",
+            Some(&root),
+            r#"{"data":{"k":"v"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/count", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["data"]["k"], "v");
+
+    let res = app
+        .clone()
+        .oneshot(request("DELETE", "/v1/secrets/count", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(count(&app, &root, "").await, 0);
+}
@@ -0,0 +1,72 @@
+//! Integration tests for reporting secrets that fail to decrypt.
+mod common;
+
+use std::sync::Arc;
+
+use axum::body::to_bytes;
+use axum::http::StatusCode;
+use common::{read_json, request};
+use egide_crypto::MasterKey;
+use egide_secrets::SecretsEngine;
+use egide_server::{build_router, AppState};
+use tower::ServiceExt;
+
+/// Builds an initialized + unsealed Egide router and returns its state and a
+/// usable root token.
+async fn test_app() -> (tempfile::TempDir, Arc<AppState>, axum::Router, String) {
+    let (tmp, state, root_token) = common::unsealed_state().await;
+    let state = Arc::new(state);
+    let app = build_router(state.clone());
+    (tmp, state, app, root_token)
+}
+
+#[tokio::test]
+async fn wrong_master_key_read_reports_decryption_failed() {
+    let (tmp, state, app, root) = test_app().await;
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db",
+            Some(&root),
+            r#"{"data":{"password":"hunter2"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Reopen the same database under another master key, as after a botched
+    // master-key change.
+    let wrong_key = MasterKey::generate().expect("master key");
+    let engine = SecretsEngine::new(tmp.path(), "default", wrong_key)
+        .await
+        .expect("secrets engine");
+    *state.secrets.write().await = Some(engine);
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/app/db", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = read_json(res).await;
+    assert_eq!(body["error_code"], "decryption_failed");
+
+    // A missing secret stays a plain 404 and is not counted.
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/app/none", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert!(read_json(res).await.get("error_code").is_none());
+
+    let res = app
+        .oneshot(request("GET", "/metrics", None, ""))
+        .await
+        .expect("oneshot");
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.expect("body");
+    let metrics = String::from_utf8(bytes.to_vec()).expect("utf-8");
+    assert!(metrics.contains("\negide_secrets_decryption_failures_total 1\n"));
+}
@@ -0,0 +1,69 @@
+//! Integration tests for check-and-set secret deletion.
+mod common;
+
+use axum::http::StatusCode;
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+async fn put(app: &axum::Router, root: &str, path: &str) {
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            &format!("/v1/secrets/{path}"),
+            Some(root),
+            r#"{"data":{"k":"v"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn delete_with_stale_cas_conflicts_and_matching_cas_deletes() {
+    let (_tmp, app, root) = test_app().await;
+    put(&app, &root, "app/db").await;
+    put(&app, &root, "app/db").await;
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "DELETE",
+            "/v1/secrets/app/db?cas=1",
+            Some(&root),
+            "",
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    assert!(read_json(res).await["error"]
+        .as_str()
+        .expect("error")
+        .contains("version mismatch"));
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/app/db", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "DELETE",
+            "/v1/secrets/app/db?cas=2",
+            Some(&root),
+            "",
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/app/db", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
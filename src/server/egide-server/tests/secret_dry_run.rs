@@ -0,0 +1,96 @@
+//! Integration tests for dry-run secret writes.
+mod common;
+
+use axum::http::StatusCode;
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn dry_run_put_returns_prospective_version_without_writing() {
+    let (_tmp, app, root) = test_app().await;
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db?dry_run=true",
+            Some(&root),
+            r#"{"data":{"password":"p1"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["version"], 1);
+    assert_eq!(body["dry_run"], true);
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets/app/db", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    // A real write is unaffected by the dry run and reports no dry_run flag.
+    let res = app
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db",
+            Some(&root),
+            r#"{"data":{"password":"p1"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["version"], 1);
+    assert!(body.get("dry_run").is_none());
+}
+
+#[tokio::test]
+async fn dry_run_put_reports_validation_failures() {
+    let (_tmp, app, root) = test_app().await;
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db",
+            Some(&root),
+            r#"{"data":{"password":"p1"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db?dry_run=true",
+            Some(&root),
+            r#"{"data":{"password":"p2"},"cas":5}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/bad%20path?dry_run=true",
+            Some(&root),
+            r#"{"data":{"password":"p2"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .oneshot(request("GET", "/v1/secrets/app/db", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    let body = read_json(res).await;
+    assert_eq!(body["metadata"]["version"], 1);
+    assert_eq!(body["data"]["password"], "p1");
+}
@@ -0,0 +1,124 @@
+//! Integration tests for rotating a secret field through a generator.
+mod common;
+
+use axum::http::StatusCode;
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn rotate_changes_the_field_and_increments_the_version() {
+    let (_tmp, app, root) = test_app().await;
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db",
+            Some(&root),
+            r#"{"data":{"username":"admin","password":"old"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/secrets-rotate/app/db",
+            Some(&root),
+            r#"{"generator":"random-password","field":"password"}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["version"], 2);
+    assert!(body.get("password").is_none());
+
+    let res = app
+        .oneshot(request("GET", "/v1/secrets/app/db", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["metadata"]["version"], 2);
+    assert_eq!(body["data"]["username"], "admin");
+    let password = body["data"]["password"].as_str().expect("password");
+    assert_ne!(password, "old");
+    assert!(!password.is_empty());
+}
+
+#[tokio::test]
+async fn rotate_rejects_unknown_generators_and_requires_auth() {
+    let (_tmp, app, root) = test_app().await;
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/db",
+            Some(&root),
+            r#"{"data":{"password":"old"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/secrets-rotate/app/db",
+            Some(&root),
+            r#"{"generator":"nope","field":"password"}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/secrets-rotate/app/db",
+            None,
+            r#"{"generator":"random-password","field":"password"}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = app
+        .oneshot(request(
+            "POST",
+            "/v1/secrets-rotate/app/missing",
+            Some(&root),
+            r#"{"generator":"random-password","field":"password"}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn a_secret_path_ending_in_rotate_stays_a_secret() {
+    let (_tmp, app, root) = test_app().await;
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/rotate",
+            Some(&root),
+            r#"{"data":{"k":"v"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .oneshot(request("GET", "/v1/secrets/app/rotate", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["data"]["k"], "v");
+}
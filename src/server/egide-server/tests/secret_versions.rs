@@ -0,0 +1,95 @@
+//! Integration tests for the secret version history endpoint.
+mod common;
+
+use axum::http::StatusCode;
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn versions_lists_every_write_with_its_actor() {
+    let (_tmp, app, root) = test_app().await;
+    for i in 1..=3 {
+        let res = app
+            .clone()
+            .oneshot(request(
+                "PUT",
+                "/v1/secrets/app/db",
+                Some(&root),
+                &format!(r#"{{"data":{{"password":"p{i}"}}}}"#),
+            ))
+            .await
+            .expect("oneshot");
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let res = app
+        .oneshot(request(
+            "GET",
+            "/v1/secrets-versions/app/db",
+            Some(&root),
+            "",
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    let versions = body["versions"].as_array().expect("versions array");
+    assert_eq!(versions.len(), 3);
+    let numbers: Vec<u64> = versions
+        .iter()
+        .map(|v| v["version"].as_u64().expect("version"))
+        .collect();
+    assert_eq!(numbers, vec![3, 2, 1]);
+    for v in versions {
+        assert_eq!(v["created_by"], "root");
+        assert_eq!(v["expired"], false);
+        assert!(v["created_at"].as_u64().expect("created_at") > 0);
+    }
+}
+
+#[tokio::test]
+async fn versions_requires_authentication_and_an_existing_secret() {
+    let (_tmp, app, root) = test_app().await;
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/secrets-versions/app/db", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = app
+        .oneshot(request(
+            "GET",
+            "/v1/secrets-versions/app/missing",
+            Some(&root),
+            "",
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn a_secret_path_ending_in_versions_stays_a_secret() {
+    let (_tmp, app, root) = test_app().await;
+    let res = app
+        .clone()
+        .oneshot(request(
+            "PUT",
+            "/v1/secrets/app/versions",
+            Some(&root),
+            r#"{"data":{"k":"v"}}"#,
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .oneshot(request("GET", "/v1/secrets/app/versions", Some(&root), ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["data"]["k"], "v");
+}
@@ -0,0 +1,32 @@
+//! Integration tests for the build/version endpoint.
+mod common;
+
+use axum::http::StatusCode;
+use common::{read_json, request, test_app};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn version_reports_crate_version_and_engines() {
+    let (_tmp, app, _root) = test_app().await;
+
+    let res = app
+        .oneshot(request("GET", "/v1/sys/version", None, ""))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = read_json(res).await;
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        body["enabled_engines"],
+        serde_json::Value::from(vec!["secrets", "transit"])
+    );
+    let algorithms = body["crypto_algorithms"].as_array().expect("algorithms");
+    assert!(algorithms.iter().any(|a| a == "aes256-gcm"));
+    let cpu = &body["cpu_features"];
+    assert_eq!(cpu["arch"], std::env::consts::ARCH);
+    for flag in ["aes", "clmul", "avx2"] {
+        assert!(cpu[flag].is_boolean(), "{flag} must be reported");
+    }
+    assert!(body["build_profile"].is_string());
+}
@@ -1,103 +1,28 @@
 //! Integration tests for the transit REST endpoints.
-use axum::body::{to_bytes, Body};
-use axum::http::{header, Request, StatusCode};
-use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use egide_auth::{RootTokenBackend, ServiceTokenBackend, ServiceTokenStore};
-use egide_seal::{SealManager, ShamirConfig};
-use egide_server::{build_router, AppState, AuthService};
-use egide_storage::StorageBackend;
+mod common;
+
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::RwLock;
+
+use axum::http::StatusCode;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use common::{read_json, request};
+use egide_server::build_router;
 use tower::ServiceExt;
 
 /// Builds an initialized, unsealed router with transit + secrets engines ready.
 async fn test_app() -> (tempfile::TempDir, axum::Router, String) {
-    let tmp = tempfile::TempDir::new().expect("tempdir");
-    let mut seal_manager = SealManager::new(tmp.path()).await.expect("seal manager");
-    let init = seal_manager
-        .initialize(ShamirConfig {
-            shares: 5,
-            threshold: 3,
-        })
-        .await
-        .expect("initialize");
-    let root_token = init.root_token.clone();
-    for share in init.shares.iter().take(3) {
-        seal_manager.unseal(share).await.expect("unseal");
-    }
-
-    let storage: Arc<dyn StorageBackend> = Arc::new(seal_manager.storage());
-    let service_store = ServiceTokenStore::new(storage);
-    let auth = AuthService::new(vec![
-        Box::new(RootTokenBackend::new(Arc::new(seal_manager.storage()))),
-        Box::new(ServiceTokenBackend::new(service_store.clone())),
-    ]);
-
-    let state = Arc::new(AppState {
-        auth,
-        seal: RwLock::new(seal_manager),
-        secrets: RwLock::new(None),
-        transit: RwLock::new(None),
-        data_dir: tmp.path().to_path_buf(),
-        start_time: Instant::now(),
-        version: "0.1.0",
-        service_tokens: service_store,
-    });
+    let (tmp, state, root_token) = common::unsealed_state().await;
     state.ensure_transit_engine().await.expect("transit engine");
 
-    (tmp, build_router(state), root_token)
+    (tmp, build_router(Arc::new(state)), root_token)
 }
 
 /// Builds an initialized but still-sealed router (transit engine stays None).
 async fn test_app_sealed() -> (tempfile::TempDir, axum::Router, String) {
-    let tmp = tempfile::TempDir::new().expect("tempdir");
-    let mut seal_manager = SealManager::new(tmp.path()).await.expect("seal manager");
-    let init = seal_manager
-        .initialize(ShamirConfig {
-            shares: 5,
-            threshold: 3,
-        })
-        .await
-        .expect("initialize");
-    let root_token = init.root_token.clone();
-    // Intentionally not unsealed: engines remain None.
-
-    let storage: Arc<dyn StorageBackend> = Arc::new(seal_manager.storage());
-    let service_store = ServiceTokenStore::new(storage);
-    let auth = AuthService::new(vec![
-        Box::new(RootTokenBackend::new(Arc::new(seal_manager.storage()))),
-        Box::new(ServiceTokenBackend::new(service_store.clone())),
-    ]);
-
-    let state = Arc::new(AppState {
-        auth,
-        seal: RwLock::new(seal_manager),
-        secrets: RwLock::new(None),
-        transit: RwLock::new(None),
-        data_dir: tmp.path().to_path_buf(),
-        start_time: Instant::now(),
-        version: "0.1.0",
-        service_tokens: service_store,
-    });
-
-    (tmp, build_router(state), root_token)
-}
+    let (tmp, seal_manager, init) = common::sealed_vault().await;
+    let state = common::app_state(&tmp, seal_manager);
 
-fn request(method: &str, uri: &str, token: Option<&str>, body: &str) -> Request<Body> {
-    let mut builder = Request::builder().method(method).uri(uri);
-    if let Some(t) = token {
-        builder = builder.header(header::AUTHORIZATION, format!("Bearer {t}"));
-    }
-    builder
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(Body::from(body.to_string()))
-        .expect("request")
-}
-
-async fn read_json(res: axum::response::Response) -> serde_json::Value {
-    let bytes = to_bytes(res.into_body(), usize::MAX).await.expect("body");
-    serde_json::from_slice(&bytes).expect("json")
+    (tmp, build_router(Arc::new(state)), init.root_token)
 }
 
 /// Creates a service token via the root token and returns the bearer string.
@@ -209,6 +134,8 @@ async fn list_and_get_key_allow_service_token() {
     let body = read_json(res).await;
     assert_eq!(body["name"], "app-kek");
     assert_eq!(body["latest_version"], 1);
+    assert_eq!(body["encryption_version_window"]["min"], 1);
+    assert_eq!(body["encryption_version_window"]["max"], 1);
 }
 
 #[tokio::test]
@@ -361,6 +288,63 @@ async fn encrypt_decrypt_roundtrip_with_service_token() {
     assert_eq!(read_json(res).await["plaintext"], plaintext);
 }
 
+#[tokio::test]
+async fn decrypt_requires_the_associated_data_used_at_encryption() {
+    let (_tmp, app, root) = test_app().await;
+    app.clone()
+        .oneshot(request(
+            "POST",
+            "/v1/transit/keys",
+            Some(&root),
+            r#"{"name":"k"}"#,
+        ))
+        .await
+        .expect("oneshot");
+
+    let plaintext = BASE64.encode(b"hello world");
+    let aad = BASE64.encode(b"tenant-1");
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/transit/encrypt/k",
+            Some(&root),
+            &format!(r#"{{"plaintext":"{plaintext}","associated_data":"{aad}"}}"#),
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let ciphertext = read_json(res).await["ciphertext"]
+        .as_str()
+        .expect("ciphertext")
+        .to_string();
+
+    let other = BASE64.encode(b"tenant-2");
+    for body in [
+        format!(r#"{{"ciphertext":"{ciphertext}"}}"#),
+        format!(r#"{{"ciphertext":"{ciphertext}","associated_data":"{other}"}}"#),
+    ] {
+        let res = app
+            .clone()
+            .oneshot(request("POST", "/v1/transit/decrypt/k", Some(&root), &body))
+            .await
+            .expect("oneshot");
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let res = app
+        .oneshot(request(
+            "POST",
+            "/v1/transit/decrypt/k",
+            Some(&root),
+            &format!(r#"{{"ciphertext":"{ciphertext}","associated_data":"{aad}"}}"#),
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["plaintext"], plaintext);
+}
+
 #[tokio::test]
 async fn encrypt_invalid_base64_is_400() {
     let (_tmp, app, root) = test_app().await;
@@ -506,3 +490,87 @@ async fn rewrap_upgrades_ciphertext_version() {
         .expect("oneshot");
     assert_eq!(read_json(res).await["plaintext"], plaintext);
 }
+
+#[tokio::test]
+async fn rewrap_keeps_the_associated_data_binding() {
+    let (_tmp, app, root) = test_app().await;
+    app.clone()
+        .oneshot(request(
+            "POST",
+            "/v1/transit/keys",
+            Some(&root),
+            r#"{"name":"k"}"#,
+        ))
+        .await
+        .expect("oneshot");
+
+    let plaintext = BASE64.encode(b"data");
+    let aad = BASE64.encode(b"tenant-1");
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/transit/encrypt/k",
+            Some(&root),
+            &format!(r#"{{"plaintext":"{plaintext}","associated_data":"{aad}"}}"#),
+        ))
+        .await
+        .expect("oneshot");
+    let ct_v1 = read_json(res).await["ciphertext"]
+        .as_str()
+        .expect("ciphertext")
+        .to_string();
+
+    app.clone()
+        .oneshot(request(
+            "POST",
+            "/v1/transit/keys/k/rotate",
+            Some(&root),
+            "",
+        ))
+        .await
+        .expect("oneshot");
+
+    let other = BASE64.encode(b"tenant-2");
+    for body in [
+        format!(r#"{{"ciphertext":"{ct_v1}"}}"#),
+        format!(r#"{{"ciphertext":"{ct_v1}","associated_data":"{other}"}}"#),
+        format!(r#"{{"ciphertext":"{ct_v1}","associated_data":"not base64!!"}}"#),
+    ] {
+        let res = app
+            .clone()
+            .oneshot(request("POST", "/v1/transit/rewrap/k", Some(&root), &body))
+            .await
+            .expect("oneshot");
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/transit/rewrap/k",
+            Some(&root),
+            &format!(r#"{{"ciphertext":"{ct_v1}","associated_data":"{aad}"}}"#),
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    let ct_v2 = read_json(res).await["ciphertext"]
+        .as_str()
+        .expect("ciphertext")
+        .to_string();
+    assert!(ct_v2.starts_with("egide:v2:"));
+
+    let res = app
+        .oneshot(request(
+            "POST",
+            "/v1/transit/decrypt/k",
+            Some(&root),
+            &format!(r#"{{"ciphertext":"{ct_v2}","associated_data":"{aad}"}}"#),
+        ))
+        .await
+        .expect("oneshot");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["plaintext"], plaintext);
+}
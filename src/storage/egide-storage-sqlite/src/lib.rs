@@ -2,17 +2,31 @@
 //!
 //! `SQLite` implementation of the storage backend with tenant isolation.
 //! Each tenant gets its own database file for maximum security.
+//!
+//! With the `sqlcipher` feature, a tenant database can also be encrypted at
+//! rest page by page, see [`SqliteBackend::open_encrypted`].
 
 #![forbid(unsafe_code)]
 
 use std::path::{Path, PathBuf};
 
 use async_trait::async_trait;
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
 use tracing::{debug, info};
 
 use egide_storage::{prefix_pattern, StorageBackend, StorageError};
 
+/// Encodes bytes as lowercase hex.
+fn hex_encode(bytes: &[u8]) -> String {
+    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(HEX_CHARS[usize::from(byte >> 4)] as char);
+        hex.push(HEX_CHARS[usize::from(byte & 0x0F)] as char);
+    }
+    hex
+}
+
 /// `SQLite` storage backend with tenant isolation.
 ///
 /// Each tenant gets its own database file at `{base_path}/{tenant}.db`.
@@ -40,9 +54,38 @@ impl SqliteBackend {
     /// - Directory cannot be created
     /// - Database connection fails
     pub async fn open(base_path: impl AsRef<Path>, tenant: &str) -> Result<Self, StorageError> {
+        Self::open_with_key(base_path.as_ref(), tenant, None).await
+    }
+
+    /// Opens or creates a tenant database encrypted at rest with `key`.
+    ///
+    /// The whole file (pages, schema, paths, timestamps) is encrypted by
+    /// `SQLCipher` under the raw 256-bit `key`, so nothing is readable on disk
+    /// without it. The format differs from plain `SQLite`: an encrypted
+    /// database can only be reopened through this method with the same key,
+    /// and an existing plain database cannot be opened here.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::ConnectionFailed`] if this build does not link
+    /// `SQLCipher` (the `sqlcipher` feature is off: the key would be silently
+    /// ignored, so this fails closed instead), if the key is wrong, or if the
+    /// file is a plain `SQLite` database. Otherwise fails like [`Self::open`].
+    pub async fn open_encrypted(
+        base_path: impl AsRef<Path>,
+        tenant: &str,
+        key: &[u8; 32],
+    ) -> Result<Self, StorageError> {
+        Self::open_with_key(base_path.as_ref(), tenant, Some(key)).await
+    }
+
+    async fn open_with_key(
+        base: &Path,
+        tenant: &str,
+        key: Option<&[u8; 32]>,
+    ) -> Result<Self, StorageError> {
         Self::validate_tenant(tenant)?;
 
-        let base = base_path.as_ref();
         std::fs::create_dir_all(base).map_err(|e| {
             StorageError::ConnectionFailed(format!("failed to create directory: {e}"))
         })?;
@@ -52,12 +95,26 @@ impl SqliteBackend {
 
         debug!(tenant = %tenant, path = %db_path.display(), "Opening SQLite database");
 
+        let mut options: SqliteConnectOptions = db_url
+            .parse()
+            .map_err(|e: sqlx::Error| StorageError::ConnectionFailed(e.to_string()))?;
+        if let Some(key) = key {
+            // A raw key (`x'..'`) skips SQLCipher's own passphrase KDF; the
+            // caller already derived a uniform key. sqlx runs `key` before
+            // any other pragma, as SQLCipher requires.
+            options = options.pragma("key", format!("\"x'{}'\"", hex_encode(key)));
+        }
+
         let pool = SqlitePoolOptions::new()
             .max_connections(5)
-            .connect(&db_url)
+            .connect_with(options)
             .await
             .map_err(|e| StorageError::ConnectionFailed(e.to_string()))?;
 
+        if key.is_some() {
+            Self::check_encryption(&pool).await?;
+        }
+
         let backend = Self {
             pool,
             actor: None,
@@ -71,6 +128,33 @@ impl SqliteBackend {
         Ok(backend)
     }
 
+    /// Fails unless the pool is backed by `SQLCipher` and the key opens the file.
+    async fn check_encryption(pool: &SqlitePool) -> Result<(), StorageError> {
+        // Plain SQLite ignores unknown pragmas: no row means no SQLCipher,
+        // and the `key` pragma was a no-op.
+        let cipher_version: Option<String> = sqlx::query_scalar("PRAGMA cipher_version")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| StorageError::ConnectionFailed(e.to_string()))?;
+        if cipher_version.is_none() {
+            return Err(StorageError::ConnectionFailed(
+                "storage encryption requires egide-storage-sqlite built with the `sqlcipher` feature"
+                    .into(),
+            ));
+        }
+
+        // The key is only checked when the first page is read.
+        sqlx::query("SELECT count(*) FROM sqlite_master")
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                StorageError::ConnectionFailed(format!(
+                    "cannot open encrypted database (wrong key or not encrypted): {e}"
+                ))
+            })?;
+        Ok(())
+    }
+
     /// Sets the actor for audit logging.
     ///
     /// Returns a new instance with the actor set. All operations
@@ -233,6 +317,73 @@ impl SqliteBackend {
         Ok(())
     }
 
+    /// Writes several keys atomically, like [`StorageBackend::put`] for each.
+    ///
+    /// Every value and its history entry are committed together in one
+    /// transaction, or none of them are.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::QueryFailed`] if any write or the commit
+    /// fails; the database is then left unchanged.
+    pub async fn put_all(&self, entries: &[(&str, &[u8])]) -> Result<(), StorageError> {
+        let now = Self::now();
+
+        // BEGIN IMMEDIATE takes the write lock up front. A deferred
+        // transaction promoted to a writer later would race and surface as
+        // SQLITE_BUSY under contention.
+        let mut transaction = self
+            .pool
+            .begin_with("BEGIN IMMEDIATE")
+            .await
+            .map_err(|e| StorageError::QueryFailed(e.to_string()))?;
+
+        for (key, value) in entries {
+            // The database owns the version counter. Computing it in a prior
+            // SELECT would leave a window for a concurrent writer to reuse it.
+            let (version,): (i64,) = sqlx::query_as(
+                r"
+                INSERT INTO kv_store (key, value, version, created_at, updated_at)
+                VALUES (?, ?, 1, ?, ?)
+                ON CONFLICT(key) DO UPDATE SET
+                    value = excluded.value,
+                    version = kv_store.version + 1,
+                    updated_at = excluded.updated_at
+                RETURNING version
+                ",
+            )
+            .bind(*key)
+            .bind(*value)
+            .bind(now)
+            .bind(now)
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|e| StorageError::QueryFailed(e.to_string()))?;
+
+            let operation = if version == 1 { "create" } else { "update" };
+
+            sqlx::query(
+                "INSERT INTO kv_history (key, value, version, operation, actor, timestamp) VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(*key)
+            .bind(*value)
+            .bind(version)
+            .bind(operation)
+            .bind(self.actor.as_deref())
+            .bind(now)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| StorageError::QueryFailed(e.to_string()))?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::QueryFailed(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Queries a single row with typed results.
     pub async fn query_one<T>(&self, sql: &str, params: &[&str]) -> Result<Option<T>, StorageError>
     where
@@ -277,59 +428,7 @@ impl StorageBackend for SqliteBackend {
     }
 
     async fn put(&self, key: &str, value: &[u8]) -> Result<(), StorageError> {
-        let now = Self::now();
-
-        // BEGIN IMMEDIATE takes the write lock up front. A deferred
-        // transaction promoted to a writer later would race and surface as
-        // SQLITE_BUSY under contention.
-        let mut transaction = self
-            .pool
-            .begin_with("BEGIN IMMEDIATE")
-            .await
-            .map_err(|e| StorageError::QueryFailed(e.to_string()))?;
-
-        // The database owns the version counter. Computing it in a prior
-        // SELECT would leave a window for a concurrent writer to reuse it.
-        let (version,): (i64,) = sqlx::query_as(
-            r"
-            INSERT INTO kv_store (key, value, version, created_at, updated_at)
-            VALUES (?, ?, 1, ?, ?)
-            ON CONFLICT(key) DO UPDATE SET
-                value = excluded.value,
-                version = kv_store.version + 1,
-                updated_at = excluded.updated_at
-            RETURNING version
-            ",
-        )
-        .bind(key)
-        .bind(value)
-        .bind(now)
-        .bind(now)
-        .fetch_one(&mut *transaction)
-        .await
-        .map_err(|e| StorageError::QueryFailed(e.to_string()))?;
-
-        let operation = if version == 1 { "create" } else { "update" };
-
-        sqlx::query(
-            "INSERT INTO kv_history (key, value, version, operation, actor, timestamp) VALUES (?, ?, ?, ?, ?, ?)",
-        )
-        .bind(key)
-        .bind(value)
-        .bind(version)
-        .bind(operation)
-        .bind(self.actor.as_deref())
-        .bind(now)
-        .execute(&mut *transaction)
-        .await
-        .map_err(|e| StorageError::QueryFailed(e.to_string()))?;
-
-        transaction
-            .commit()
-            .await
-            .map_err(|e| StorageError::QueryFailed(e.to_string()))?;
-
-        Ok(())
+        self.put_all(&[(key, value)]).await
     }
 
     async fn delete(&self, key: &str) -> Result<(), StorageError> {
@@ -583,6 +682,33 @@ mod tests {
         assert_eq!(rows[2], ("delete".to_string(), 3));
     }
 
+    #[tokio::test]
+    async fn test_put_all_writes_every_key() {
+        let (_tmp, backend) = setup().await;
+        backend.put("b", b"old").await.unwrap();
+
+        backend
+            .put_all(&[("a", b"one".as_slice()), ("b", b"two".as_slice())])
+            .await
+            .unwrap();
+
+        assert_eq!(backend.get("a").await.unwrap(), Some(b"one".to_vec()));
+        assert_eq!(backend.get("b").await.unwrap(), Some(b"two".to_vec()));
+        let rows: Vec<(String, String, i64)> =
+            sqlx::query_as("SELECT key, operation, version FROM kv_history ORDER BY id")
+                .fetch_all(&backend.pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("b".to_string(), "create".to_string(), 1),
+                ("a".to_string(), "create".to_string(), 1),
+                ("b".to_string(), "update".to_string(), 2),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_version_increments() {
         let (_tmp, backend) = setup().await;
@@ -728,4 +854,56 @@ mod tests {
             .unwrap();
         assert_eq!(count[0].0, 0);
     }
+
+    #[cfg(feature = "sqlcipher")]
+    #[tokio::test]
+    async fn test_encrypted_db_requires_key() {
+        let tmp = TempDir::new().unwrap();
+        let key = [7u8; 32];
+        {
+            let backend = SqliteBackend::open_encrypted(tmp.path(), "sealed", &key)
+                .await
+                .unwrap();
+            backend.put("secret/a", b"value").await.unwrap();
+            backend.pool.close().await;
+        }
+
+        // Opened as plain SQLite, the file is not a database.
+        let db_url = format!("sqlite:{}", tmp.path().join("sealed.db").display());
+        let plain = SqlitePool::connect(&db_url).await;
+        let readable = match plain {
+            Ok(pool) => sqlx::query("SELECT count(*) FROM sqlite_master")
+                .execute(&pool)
+                .await
+                .is_ok(),
+            Err(_) => false,
+        };
+        assert!(
+            !readable,
+            "encrypted database must not open as plain SQLite"
+        );
+
+        assert!(SqliteBackend::open(tmp.path(), "sealed").await.is_err());
+        assert!(
+            SqliteBackend::open_encrypted(tmp.path(), "sealed", &[8u8; 32])
+                .await
+                .is_err()
+        );
+
+        let backend = SqliteBackend::open_encrypted(tmp.path(), "sealed", &key)
+            .await
+            .unwrap();
+        assert_eq!(
+            backend.get("secret/a").await.unwrap().as_deref(),
+            Some(&b"value"[..])
+        );
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    #[tokio::test]
+    async fn test_open_encrypted_fails_closed_without_sqlcipher() {
+        let tmp = TempDir::new().unwrap();
+        let result = SqliteBackend::open_encrypted(tmp.path(), "sealed", &[7u8; 32]).await;
+        assert!(matches!(result, Err(StorageError::ConnectionFailed(_))));
+    }
 }